@@ -22,6 +22,37 @@ pub struct RefreshTokenClaims {
     pub exp: usize,      // Expiration (timestamp)
 }
 
+/// What a print link renders
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PrintKind {
+    /// A voting guide as the author's sample ballot, `sub` is the voting guide
+    SampleBallot,
+    /// An organization's candidate guide answers for `race_id`, `sub` is the candidate guide
+    CandidateGuide,
+}
+
+/// Claims of a shareable print link. Anyone holding the link can view the printout without
+/// signing in, so links expire.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PrintTokenClaims {
+    pub sub: uuid::Uuid,
+    pub kind: PrintKind,
+    pub race_id: Option<uuid::Uuid>,
+    pub exp: usize,
+}
+
+/// Claims of a user's calendar feed link. Calendar apps fetch feeds without signing in, so the
+/// link is its own credential and lasts longer than print links.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CalendarTokenClaims {
+    pub sub: uuid::Uuid,
+    /// Always `CALENDAR_AUDIENCE`, so other tokens can't be used as calendar links
+    pub aud: String,
+    pub exp: usize,
+}
+
+const CALENDAR_AUDIENCE: &str = "calendar";
+
 pub fn create_power_token() -> Result<String, Error> {
     let key = std::env::var("JWT_SECRET")?;
     let expiration = chrono::Utc::now()
@@ -134,6 +165,88 @@ pub fn validate_refresh_token(token: &str) -> Result<TokenData<RefreshTokenClaim
     }
 }
 
+pub fn create_print_token(
+    kind: PrintKind,
+    id: uuid::Uuid,
+    race_id: Option<uuid::Uuid>,
+) -> Result<String, Error> {
+    let key = std::env::var("JWT_SECRET")?;
+
+    let expiration = chrono::Utc::now()
+        .checked_add_signed(chrono::Duration::try_days(120).unwrap())
+        .expect("valid timestamp")
+        .timestamp();
+
+    let claims = PrintTokenClaims {
+        sub: id,
+        kind,
+        race_id,
+        exp: expiration as usize,
+    };
+
+    let token = match encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(key.as_bytes()),
+    ) {
+        Ok(t) => t,
+        Err(e) => panic!("Something went wrong encoding a JWT: {}", e),
+    };
+
+    Ok(token)
+}
+
+pub fn validate_print_token(token: &str) -> Result<TokenData<PrintTokenClaims>, Error> {
+    let key = std::env::var("JWT_SECRET")?;
+
+    match decode::<PrintTokenClaims>(
+        token,
+        &DecodingKey::from_secret(key.as_ref()),
+        &Validation::default(),
+    ) {
+        Ok(token_data) => Ok(token_data),
+        Err(err) => Err(Error::JwtError(err)),
+    }
+}
+
+pub fn create_calendar_token(user_id: uuid::Uuid) -> Result<String, Error> {
+    let key = std::env::var("JWT_SECRET")?;
+
+    let expiration = chrono::Utc::now()
+        .checked_add_signed(chrono::Duration::try_days(365).unwrap())
+        .expect("valid timestamp")
+        .timestamp();
+
+    let claims = CalendarTokenClaims {
+        sub: user_id,
+        aud: CALENDAR_AUDIENCE.to_string(),
+        exp: expiration as usize,
+    };
+
+    let token = match encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(key.as_bytes()),
+    ) {
+        Ok(t) => t,
+        Err(e) => panic!("Something went wrong encoding a JWT: {}", e),
+    };
+
+    Ok(token)
+}
+
+pub fn validate_calendar_token(token: &str) -> Result<TokenData<CalendarTokenClaims>, Error> {
+    let key = std::env::var("JWT_SECRET")?;
+    let mut validation = Validation::default();
+    validation.set_audience(&[CALENDAR_AUDIENCE]);
+
+    match decode::<CalendarTokenClaims>(token, &DecodingKey::from_secret(key.as_ref()), &validation)
+    {
+        Ok(token_data) => Ok(token_data),
+        Err(err) => Err(Error::JwtError(err)),
+    }
+}
+
 pub fn create_random_token() -> Result<String, Error> {
     let rand_string: String = thread_rng()
         .sample_iter(&Alphanumeric)
@@ -69,6 +69,13 @@ pub fn format_auth_cookie(token_type: TokenType, token: &str) -> String {
     )
 }
 
+/// Sets the secret once for every test, so no test writes the environment while another reads it
+#[cfg(test)]
+fn set_test_jwt_secret() {
+    static SET: std::sync::Once = std::sync::Once::new();
+    SET.call_once(|| std::env::set_var("JWT_SECRET", "test"));
+}
+
 #[test]
 fn test_create_temporary_username() {
     let input = "lai.henry+69@gmail.com";
@@ -80,6 +87,7 @@ fn test_create_temporary_username() {
 
 #[test]
 fn test_format_auth_cookie() {
+    set_test_jwt_secret();
     let token = "test";
     let result = format_auth_cookie(TokenType::Access, token);
     assert_eq!(
@@ -91,3 +99,33 @@ fn test_format_auth_cookie() {
         )
     );
 }
+
+#[test]
+fn test_print_token() {
+    set_test_jwt_secret();
+    let guide_id = uuid::Uuid::new_v4();
+    let race_id = uuid::Uuid::new_v4();
+    let token = create_print_token(PrintKind::CandidateGuide, guide_id, Some(race_id)).unwrap();
+    let claims = validate_print_token(&token).unwrap().claims;
+    assert_eq!(claims.kind, PrintKind::CandidateGuide);
+    assert_eq!(claims.sub, guide_id);
+    assert_eq!(claims.race_id, Some(race_id));
+
+    // Print links can't be used as access tokens, or the other way around
+    assert!(validate_access_token(&token).is_err());
+    assert!(validate_print_token(&create_power_token().unwrap()).is_err());
+}
+
+#[test]
+fn test_calendar_token() {
+    set_test_jwt_secret();
+    let user_id = uuid::Uuid::new_v4();
+    let token = create_calendar_token(user_id).unwrap();
+    assert_eq!(validate_calendar_token(&token).unwrap().claims.sub, user_id);
+
+    assert!(validate_access_token(&token).is_err());
+    assert!(validate_print_token(&token).is_err());
+    let print_token = create_print_token(PrintKind::SampleBallot, user_id, None).unwrap();
+    assert!(validate_calendar_token(&print_token).is_err());
+    assert!(validate_calendar_token(&create_power_token().unwrap()).is_err());
+}
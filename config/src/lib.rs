@@ -8,6 +8,8 @@ use url::Url;
 pub struct Config {
     pub environment: Environment,
     pub web_app_url: Url,
+    /// Where this server is reachable, for links that point back at it. `API_URL` overrides it.
+    pub api_url: Url,
     pub root_domain: String,
     pub same_site: String,
 }
@@ -21,6 +23,18 @@ impl Default for Config {
             Environment::Staging => Url::parse("https://staging.populist.us").unwrap(),
             _ => Url::parse("http://localhost:3030").unwrap(),
         };
+        let api_url = match env::var("API_URL") {
+            Ok(api_url) => Url::parse(&api_url).expect("API_URL is a valid URL"),
+            Err(_) => match environment {
+                Environment::Production => Url::parse("https://api.populist.us").unwrap(),
+                Environment::Staging => Url::parse("https://api.staging.populist.us").unwrap(),
+                _ => Url::parse(&format!(
+                    "http://localhost:{}",
+                    env::var("PORT").unwrap_or_else(|_| "1234".into())
+                ))
+                .unwrap(),
+            },
+        };
         let root_domain = match environment {
             Environment::Production => "populist.us".to_string(),
             Environment::Staging => "staging.populist.us".to_string(),
@@ -34,6 +48,7 @@ impl Default for Config {
         Config {
             environment,
             web_app_url,
+            api_url,
             root_domain,
             same_site,
         }
@@ -141,6 +156,7 @@ mod tests {
         let config = Config::default();
         assert_eq!(config.environment, Environment::Local);
         assert_eq!(config.web_app_url.to_string(), "http://localhost:3030/");
+        assert_eq!(config.api_url.to_string(), "http://localhost:1234/");
     }
 
     #[test]
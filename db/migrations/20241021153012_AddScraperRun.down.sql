@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS scraper_run_error;
+DROP TABLE IF EXISTS scraper_run;
+DROP TYPE IF EXISTS scraper_run_status;
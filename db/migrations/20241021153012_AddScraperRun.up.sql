@@ -0,0 +1,33 @@
+-- Add up migration script here
+CREATE TYPE scraper_run_status AS ENUM (
+    'running', 'succeeded', 'failed'
+);
+
+CREATE TABLE scraper_run (
+    id uuid NOT NULL DEFAULT gen_random_uuid() PRIMARY KEY,
+    source_id TEXT NOT NULL,
+    status SCRAPER_RUN_STATUS NOT NULL DEFAULT 'running',
+    -- Inserted, updated, unchanged and failed row counts keyed by entity type
+    stats JSONB NOT NULL DEFAULT '{}'::jsonb,
+    error TEXT,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    finished_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_scraper_run_source_id ON scraper_run (source_id, started_at);
+
+CREATE TRIGGER set_updated_at BEFORE UPDATE ON scraper_run FOR EACH ROW EXECUTE PROCEDURE set_updated_at();
+
+CREATE TABLE scraper_run_error (
+    id uuid NOT NULL DEFAULT gen_random_uuid() PRIMARY KEY,
+    scraper_run_id uuid NOT NULL REFERENCES scraper_run (id) ON DELETE CASCADE,
+    entity_type TEXT,
+    -- Where in the source the failing record came from, e.g. a table row
+    source_ref TEXT,
+    message TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_scraper_run_error_scraper_run_id ON scraper_run_error (scraper_run_id);
@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS scraper_proposed_change;
+ALTER TABLE scraper_run DROP COLUMN IF EXISTS is_staged;
+DROP TYPE IF EXISTS proposed_change_status;
+DROP TYPE IF EXISTS proposed_change_kind;
+DROP TYPE IF EXISTS scraper_entity_type;
@@ -0,0 +1,42 @@
+-- Add up migration script here
+CREATE TYPE scraper_entity_type AS ENUM (
+    'election', 'office', 'party', 'politician', 'race', 'race_candidate'
+);
+
+CREATE TYPE proposed_change_kind AS ENUM (
+    'insert', 'update'
+);
+
+CREATE TYPE proposed_change_status AS ENUM (
+    'pending', 'approved', 'rejected'
+);
+
+ALTER TABLE scraper_run ADD COLUMN is_staged BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE scraper_proposed_change (
+    id uuid NOT NULL DEFAULT gen_random_uuid() PRIMARY KEY,
+    scraper_run_id uuid NOT NULL REFERENCES scraper_run (id) ON DELETE CASCADE,
+    -- Order the change was produced in, applying in this order satisfies foreign keys
+    position INT NOT NULL,
+    entity_type SCRAPER_ENTITY_TYPE NOT NULL,
+    -- Unique key columns identifying the row, e.g. {"slug": "..."}
+    entity_key JSONB NOT NULL,
+    change_kind PROPOSED_CHANGE_KIND NOT NULL,
+    -- The full row as the scraper would have written it
+    record JSONB NOT NULL,
+    -- Changed fields for updates, [{"field", "old", "new"}]
+    changes JSONB NOT NULL DEFAULT '[]'::jsonb,
+    status PROPOSED_CHANGE_STATUS NOT NULL DEFAULT 'pending',
+    rejection_reason TEXT,
+    -- Set when approving failed to apply the change, the change stays pending
+    apply_error TEXT,
+    reviewed_by uuid REFERENCES populist_user (id) ON DELETE SET NULL,
+    reviewed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (scraper_run_id, position)
+);
+
+CREATE INDEX idx_scraper_proposed_change_status ON scraper_proposed_change (status);
+
+CREATE TRIGGER set_updated_at BEFORE UPDATE ON scraper_proposed_change FOR EACH ROW EXECUTE PROCEDURE set_updated_at();
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS slug_history;
+DROP TYPE IF EXISTS slug_entity_type;
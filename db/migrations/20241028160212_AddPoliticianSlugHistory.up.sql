@@ -0,0 +1,15 @@
+-- Add up migration script here
+CREATE TYPE slug_entity_type AS ENUM ('politician');
+
+-- Slugs an entity was previously published under, so old links resolve to the current record.
+-- Merged politicians keep their slug here.
+CREATE TABLE slug_history (
+    id uuid NOT NULL DEFAULT gen_random_uuid() PRIMARY KEY,
+    entity_type SLUG_ENTITY_TYPE NOT NULL,
+    entity_id uuid NOT NULL,
+    slug TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (entity_type, slug)
+);
+
+CREATE INDEX idx_slug_history_entity ON slug_history (entity_type, entity_id);
@@ -0,0 +1,21 @@
+-- Add down migration script here
+DROP TRIGGER IF EXISTS sync_external_ids ON politician;
+DROP TRIGGER IF EXISTS sync_external_ids ON organization;
+DROP TRIGGER IF EXISTS sync_external_ids ON bill;
+DROP TRIGGER IF EXISTS sync_external_ids ON committee;
+DROP TRIGGER IF EXISTS sync_external_ids ON party;
+DROP TRIGGER IF EXISTS sync_external_ids ON office;
+DROP TRIGGER IF EXISTS delete_external_ids ON politician;
+DROP TRIGGER IF EXISTS delete_external_ids ON organization;
+DROP TRIGGER IF EXISTS delete_external_ids ON bill;
+DROP TRIGGER IF EXISTS delete_external_ids ON committee;
+DROP TRIGGER IF EXISTS delete_external_ids ON party;
+DROP TRIGGER IF EXISTS delete_external_ids ON office;
+DROP TRIGGER IF EXISTS delete_external_ids ON race;
+DROP TRIGGER IF EXISTS delete_external_ids ON election;
+DROP TRIGGER IF EXISTS delete_external_ids ON ballot_measure;
+DROP FUNCTION IF EXISTS sync_external_ids();
+DROP FUNCTION IF EXISTS delete_external_ids();
+DROP TABLE IF EXISTS external_id;
+DROP TYPE IF EXISTS external_id_source;
+DROP TYPE IF EXISTS external_id_entity_type;
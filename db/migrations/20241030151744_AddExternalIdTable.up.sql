@@ -0,0 +1,153 @@
+-- Add up migration script here
+CREATE TYPE external_id_entity_type AS ENUM (
+    'politician', 'organization', 'bill', 'committee', 'party', 'office', 'race', 'election', 'ballot_measure'
+);
+
+CREATE TYPE external_id_source AS ENUM (
+    'ref_key', 'votesmart', 'legiscan', 'open_secrets', 'fec', 'ocd', 'ballotpedia'
+);
+
+-- Crosswalk of ids other systems use for our records. An entity may have several ids from the
+-- same source (e.g. after a merge), but an id belongs to exactly one entity.
+CREATE TABLE external_id (
+    id uuid NOT NULL DEFAULT gen_random_uuid() PRIMARY KEY,
+    entity_type EXTERNAL_ID_ENTITY_TYPE NOT NULL,
+    entity_id uuid NOT NULL,
+    source EXTERNAL_ID_SOURCE NOT NULL,
+    value TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (entity_type, source, value)
+);
+
+CREATE INDEX idx_external_id_entity ON external_id (entity_type, entity_id);
+
+CREATE TRIGGER set_updated_at BEFORE UPDATE ON external_id FOR EACH ROW EXECUTE PROCEDURE set_updated_at();
+
+-- Legacy id columns are still written by imports, mirror them into the crosswalk. A changed id
+-- replaces the old one, and moves to this record if another one held it.
+CREATE OR REPLACE FUNCTION sync_external_ids() RETURNS TRIGGER AS $$
+DECLARE
+    target_type EXTERNAL_ID_ENTITY_TYPE := TG_ARGV[0]::EXTERNAL_ID_ENTITY_TYPE;
+    target_source EXTERNAL_ID_SOURCE;
+    old_value TEXT;
+    new_value TEXT;
+    i INT := 1;
+BEGIN
+    -- Remaining trigger arguments are pairs of column name and source
+    WHILE i < TG_NARGS LOOP
+        target_source := TG_ARGV[i + 1]::EXTERNAL_ID_SOURCE;
+        new_value := to_jsonb(NEW)->>TG_ARGV[i];
+        old_value := CASE WHEN TG_OP = 'UPDATE' THEN to_jsonb(OLD)->>TG_ARGV[i] END;
+
+        IF old_value IS NOT NULL AND old_value IS DISTINCT FROM new_value THEN
+            DELETE FROM external_id
+            WHERE entity_type = target_type AND entity_id = NEW.id
+            AND source = target_source AND value = old_value;
+        END IF;
+
+        IF new_value IS NOT NULL AND new_value IS DISTINCT FROM old_value THEN
+            INSERT INTO external_id (entity_type, entity_id, source, value)
+            VALUES (target_type, NEW.id, target_source, new_value)
+            ON CONFLICT (entity_type, source, value) DO UPDATE SET entity_id = EXCLUDED.entity_id;
+        END IF;
+        i := i + 2;
+    END LOOP;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION delete_external_ids() RETURNS TRIGGER AS $$
+BEGIN
+    DELETE FROM external_id
+    WHERE entity_type = TG_ARGV[0]::EXTERNAL_ID_ENTITY_TYPE AND entity_id = OLD.id;
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER sync_external_ids AFTER INSERT OR UPDATE ON politician FOR EACH ROW
+EXECUTE PROCEDURE sync_external_ids(
+    'politician',
+    'ref_key', 'ref_key',
+    'votesmart_candidate_id', 'votesmart',
+    'legiscan_people_id', 'legiscan',
+    'crp_candidate_id', 'open_secrets',
+    'fec_candidate_id', 'fec'
+);
+CREATE TRIGGER sync_external_ids AFTER INSERT OR UPDATE ON organization FOR EACH ROW
+EXECUTE PROCEDURE sync_external_ids('organization', 'votesmart_sig_id', 'votesmart');
+CREATE TRIGGER sync_external_ids AFTER INSERT OR UPDATE ON bill FOR EACH ROW
+EXECUTE PROCEDURE sync_external_ids(
+    'bill',
+    'legiscan_bill_id', 'legiscan',
+    'votesmart_bill_id', 'votesmart'
+);
+CREATE TRIGGER sync_external_ids AFTER INSERT OR UPDATE ON committee FOR EACH ROW
+EXECUTE PROCEDURE sync_external_ids('committee', 'legiscan_committee_id', 'legiscan');
+CREATE TRIGGER sync_external_ids AFTER INSERT OR UPDATE ON party FOR EACH ROW
+EXECUTE PROCEDURE sync_external_ids('party', 'fec_code', 'fec');
+CREATE TRIGGER sync_external_ids AFTER INSERT OR UPDATE ON office FOR EACH ROW
+EXECUTE PROCEDURE sync_external_ids('office', 'ref_key', 'ref_key');
+
+CREATE TRIGGER delete_external_ids AFTER DELETE ON politician FOR EACH ROW
+EXECUTE PROCEDURE delete_external_ids('politician');
+CREATE TRIGGER delete_external_ids AFTER DELETE ON organization FOR EACH ROW
+EXECUTE PROCEDURE delete_external_ids('organization');
+CREATE TRIGGER delete_external_ids AFTER DELETE ON bill FOR EACH ROW
+EXECUTE PROCEDURE delete_external_ids('bill');
+CREATE TRIGGER delete_external_ids AFTER DELETE ON committee FOR EACH ROW
+EXECUTE PROCEDURE delete_external_ids('committee');
+CREATE TRIGGER delete_external_ids AFTER DELETE ON party FOR EACH ROW
+EXECUTE PROCEDURE delete_external_ids('party');
+CREATE TRIGGER delete_external_ids AFTER DELETE ON office FOR EACH ROW
+EXECUTE PROCEDURE delete_external_ids('office');
+CREATE TRIGGER delete_external_ids AFTER DELETE ON race FOR EACH ROW
+EXECUTE PROCEDURE delete_external_ids('race');
+CREATE TRIGGER delete_external_ids AFTER DELETE ON election FOR EACH ROW
+EXECUTE PROCEDURE delete_external_ids('election');
+CREATE TRIGGER delete_external_ids AFTER DELETE ON ballot_measure FOR EACH ROW
+EXECUTE PROCEDURE delete_external_ids('ballot_measure');
+
+-- Backfill from the legacy columns, first writer wins where ids were duplicated
+INSERT INTO external_id (entity_type, entity_id, source, value)
+SELECT 'politician', id, source::EXTERNAL_ID_SOURCE, value
+FROM politician,
+LATERAL (VALUES
+    ('ref_key', ref_key),
+    ('votesmart', votesmart_candidate_id::TEXT),
+    ('legiscan', legiscan_people_id::TEXT),
+    ('open_secrets', crp_candidate_id),
+    ('fec', fec_candidate_id)
+) AS ids (source, value)
+WHERE value IS NOT NULL
+ON CONFLICT DO NOTHING;
+
+INSERT INTO external_id (entity_type, entity_id, source, value)
+SELECT 'organization', id, 'votesmart', votesmart_sig_id::TEXT
+FROM organization WHERE votesmart_sig_id IS NOT NULL
+ON CONFLICT DO NOTHING;
+
+INSERT INTO external_id (entity_type, entity_id, source, value)
+SELECT 'bill', id, source::EXTERNAL_ID_SOURCE, value
+FROM bill,
+LATERAL (VALUES
+    ('legiscan', legiscan_bill_id::TEXT),
+    ('votesmart', votesmart_bill_id::TEXT)
+) AS ids (source, value)
+WHERE value IS NOT NULL
+ON CONFLICT DO NOTHING;
+
+INSERT INTO external_id (entity_type, entity_id, source, value)
+SELECT 'committee', id, 'legiscan', legiscan_committee_id::TEXT
+FROM committee WHERE legiscan_committee_id IS NOT NULL
+ON CONFLICT DO NOTHING;
+
+INSERT INTO external_id (entity_type, entity_id, source, value)
+SELECT 'party', id, 'fec', fec_code
+FROM party WHERE fec_code IS NOT NULL
+ON CONFLICT DO NOTHING;
+
+INSERT INTO external_id (entity_type, entity_id, source, value)
+SELECT 'office', id, 'ref_key', ref_key
+FROM office WHERE ref_key IS NOT NULL
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,21 @@
+-- Add down migration script here
+DROP TRIGGER IF EXISTS record_slug_history ON politician;
+DROP TRIGGER IF EXISTS record_slug_history ON office;
+DROP TRIGGER IF EXISTS record_slug_history ON race;
+DROP TRIGGER IF EXISTS record_slug_history ON bill;
+DROP TRIGGER IF EXISTS record_slug_history ON organization;
+DROP TRIGGER IF EXISTS delete_slug_history ON politician;
+DROP TRIGGER IF EXISTS delete_slug_history ON office;
+DROP TRIGGER IF EXISTS delete_slug_history ON race;
+DROP TRIGGER IF EXISTS delete_slug_history ON bill;
+DROP TRIGGER IF EXISTS delete_slug_history ON organization;
+DROP FUNCTION IF EXISTS record_slug_history();
+DROP FUNCTION IF EXISTS delete_slug_history();
+
+-- Enum values can't be dropped, so the type is recreated with only merged politicians' slugs
+DELETE FROM slug_history WHERE entity_type <> 'politician';
+ALTER TABLE slug_history ALTER COLUMN entity_type TYPE TEXT;
+DROP TYPE slug_entity_type;
+CREATE TYPE slug_entity_type AS ENUM ('politician');
+ALTER TABLE slug_history ALTER COLUMN entity_type TYPE slug_entity_type
+USING entity_type::slug_entity_type;
@@ -0,0 +1,52 @@
+-- Add up migration script here
+-- Slugs of other renamed entities are kept alongside those of merged politicians
+ALTER TYPE slug_entity_type ADD VALUE 'office';
+ALTER TYPE slug_entity_type ADD VALUE 'race';
+ALTER TYPE slug_entity_type ADD VALUE 'bill';
+ALTER TYPE slug_entity_type ADD VALUE 'organization';
+
+CREATE OR REPLACE FUNCTION record_slug_history() RETURNS TRIGGER AS $$
+BEGIN
+    IF OLD.slug IS DISTINCT FROM NEW.slug THEN
+        INSERT INTO slug_history (entity_type, entity_id, slug)
+        VALUES (TG_ARGV[0]::SLUG_ENTITY_TYPE, OLD.id, OLD.slug)
+        ON CONFLICT (entity_type, slug) DO UPDATE
+        SET entity_id = EXCLUDED.entity_id, created_at = CURRENT_TIMESTAMP;
+
+        -- The new slug is live again, it no longer needs to redirect anywhere
+        DELETE FROM slug_history
+        WHERE entity_type = TG_ARGV[0]::SLUG_ENTITY_TYPE AND slug = NEW.slug;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION delete_slug_history() RETURNS TRIGGER AS $$
+BEGIN
+    DELETE FROM slug_history
+    WHERE entity_type = TG_ARGV[0]::SLUG_ENTITY_TYPE AND entity_id = OLD.id;
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER record_slug_history AFTER UPDATE OF slug ON politician FOR EACH ROW
+EXECUTE PROCEDURE record_slug_history('politician');
+CREATE TRIGGER record_slug_history AFTER UPDATE OF slug ON office FOR EACH ROW
+EXECUTE PROCEDURE record_slug_history('office');
+CREATE TRIGGER record_slug_history AFTER UPDATE OF slug ON race FOR EACH ROW
+EXECUTE PROCEDURE record_slug_history('race');
+CREATE TRIGGER record_slug_history AFTER UPDATE OF slug ON bill FOR EACH ROW
+EXECUTE PROCEDURE record_slug_history('bill');
+CREATE TRIGGER record_slug_history AFTER UPDATE OF slug ON organization FOR EACH ROW
+EXECUTE PROCEDURE record_slug_history('organization');
+
+CREATE TRIGGER delete_slug_history AFTER DELETE ON politician FOR EACH ROW
+EXECUTE PROCEDURE delete_slug_history('politician');
+CREATE TRIGGER delete_slug_history AFTER DELETE ON office FOR EACH ROW
+EXECUTE PROCEDURE delete_slug_history('office');
+CREATE TRIGGER delete_slug_history AFTER DELETE ON race FOR EACH ROW
+EXECUTE PROCEDURE delete_slug_history('race');
+CREATE TRIGGER delete_slug_history AFTER DELETE ON bill FOR EACH ROW
+EXECUTE PROCEDURE delete_slug_history('bill');
+CREATE TRIGGER delete_slug_history AFTER DELETE ON organization FOR EACH ROW
+EXECUTE PROCEDURE delete_slug_history('organization');
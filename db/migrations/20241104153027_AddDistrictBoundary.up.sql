@@ -0,0 +1,31 @@
+-- Add up migration script here
+
+-- Polygons for every kind of district an office can be elected from, imported from Census
+-- TIGER/Line and state shapefiles. Sub-districts of a county or city (commissioner districts,
+-- wards) carry the name of their parent in `county` / `municipality`; rows for a whole county
+-- or city leave those empty. `layer` names the map a boundary is one vintage of, which stays the
+-- same when a file is renamed between vintages (`tiger:cd118` and `tiger:cd119` are both `tiger:cd`).
+CREATE TABLE district_boundary (
+    id uuid NOT NULL DEFAULT gen_random_uuid() PRIMARY KEY,
+    state STATE NOT NULL,
+    district_type DISTRICT_TYPE NOT NULL,
+    code TEXT NOT NULL,
+    name TEXT,
+    county TEXT,
+    municipality TEXT,
+    vintage INTEGER NOT NULL,
+    geoid TEXT,
+    source TEXT NOT NULL,
+    layer TEXT NOT NULL,
+    geom GEOMETRY(MultiPolygon, 4326) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX uq_district_boundary ON district_boundary (
+    state, district_type, code, (COALESCE(county, '')), (COALESCE(municipality, '')), vintage
+);
+CREATE INDEX idx_district_boundary_layer ON district_boundary (state, layer, vintage);
+CREATE INDEX idx_district_boundary_geom ON district_boundary USING gist (geom);
+
+CREATE TRIGGER set_updated_at BEFORE UPDATE ON district_boundary FOR EACH ROW EXECUTE PROCEDURE set_updated_at();
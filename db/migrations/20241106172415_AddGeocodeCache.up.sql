@@ -0,0 +1,17 @@
+-- Add up migration script here
+
+-- Geocoder responses keyed by normalized address. A NULL result records that the address
+-- couldn't be matched, so bad input isn't sent to the geocoder again until it expires.
+CREATE TABLE geocode_cache (
+    geocoder TEXT NOT NULL,
+    normalized_address TEXT NOT NULL,
+    result JSONB,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (geocoder, normalized_address)
+);
+
+CREATE INDEX idx_geocode_cache_expires_at ON geocode_cache (expires_at);
+
+CREATE TRIGGER set_updated_at BEFORE UPDATE ON geocode_cache FOR EACH ROW EXECUTE PROCEDURE set_updated_at();
@@ -0,0 +1,5 @@
+-- Add down migration script here
+ALTER TABLE office DROP COLUMN IF EXISTS district_vintage;
+DROP TABLE IF EXISTS address_district;
+DROP FUNCTION IF EXISTS district_vintage_in_force;
+DROP TABLE IF EXISTS district_vintage;
@@ -0,0 +1,49 @@
+-- Add up migration script here
+
+-- When each vintage of a state's district maps takes effect, per boundary layer since several
+-- layers can hold districts of one type (places and county subdivisions are both cities). A
+-- vintage is in force for elections from its effective_from until the next vintage's.
+CREATE TABLE district_vintage (
+    id uuid NOT NULL DEFAULT gen_random_uuid() PRIMARY KEY,
+    state STATE NOT NULL,
+    layer TEXT NOT NULL,
+    district_type DISTRICT_TYPE NOT NULL,
+    vintage INTEGER NOT NULL,
+    effective_from DATE NOT NULL,
+    -- NULL until the refresh_address_districts job has reassigned addresses to this vintage
+    addresses_refreshed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (state, layer, vintage)
+);
+
+CREATE TRIGGER set_updated_at BEFORE UPDATE ON district_vintage FOR EACH ROW EXECUTE PROCEDURE set_updated_at();
+
+INSERT INTO district_vintage (state, layer, district_type, vintage, effective_from)
+SELECT DISTINCT state, layer, district_type, vintage, MAKE_DATE(vintage, 1, 1)
+FROM district_boundary;
+
+CREATE OR REPLACE FUNCTION district_vintage_in_force(s STATE, l TEXT, d DATE) RETURNS INTEGER AS $$
+    SELECT vintage FROM district_vintage
+    WHERE state = s AND layer = l AND effective_from <= d
+    ORDER BY effective_from DESC, vintage DESC
+    LIMIT 1
+$$ LANGUAGE sql STABLE;
+
+-- The districts an address falls in under each vintage
+CREATE TABLE address_district (
+    address_id uuid NOT NULL REFERENCES address(id) ON DELETE CASCADE,
+    district_boundary_id uuid NOT NULL REFERENCES district_boundary(id) ON DELETE CASCADE,
+    district_type DISTRICT_TYPE NOT NULL,
+    vintage INTEGER NOT NULL,
+    code TEXT NOT NULL,
+    county TEXT,
+    municipality TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (address_id, district_boundary_id)
+);
+
+CREATE INDEX idx_address_district_vintage ON address_district (address_id, district_type, vintage);
+
+-- Vintage of the district map an office is elected from, NULL matches whichever is in force
+ALTER TABLE office ADD COLUMN district_vintage INTEGER;
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS ranked_choice_round_candidate;
+DROP TABLE IF EXISTS ranked_choice_round;
+DROP TYPE IF EXISTS ranked_choice_status;
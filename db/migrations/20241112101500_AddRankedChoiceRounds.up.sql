@@ -0,0 +1,36 @@
+-- Add up migration script here
+
+CREATE TYPE ranked_choice_status AS ENUM ('continuing', 'elected', 'eliminated');
+
+-- One row per round of a ranked choice tabulation. Re-importing a race's results replaces its rounds.
+CREATE TABLE ranked_choice_round (
+    id uuid NOT NULL DEFAULT gen_random_uuid() PRIMARY KEY,
+    race_id uuid NOT NULL REFERENCES race(id) ON DELETE CASCADE,
+    round INTEGER NOT NULL,
+    -- Votes needed to win, NULL when the source doesn't say
+    threshold DOUBLE PRECISION,
+    -- Ballots with no continuing candidate ranked, cumulative across rounds
+    exhausted DOUBLE PRECISION NOT NULL DEFAULT 0,
+    -- 'cvr' when tabulated from cast vote records, otherwise the summary format imported
+    source TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (race_id, round)
+);
+
+CREATE TRIGGER set_updated_at BEFORE UPDATE ON ranked_choice_round FOR EACH ROW EXECUTE PROCEDURE set_updated_at();
+
+-- A candidate's votes in a round. Votes are fractional once surpluses are transferred.
+CREATE TABLE ranked_choice_round_candidate (
+    round_id uuid NOT NULL REFERENCES ranked_choice_round(id) ON DELETE CASCADE,
+    -- NULL for write-ins and names that didn't match a candidate in the race
+    candidate_id uuid REFERENCES politician(id) ON DELETE SET NULL,
+    candidate_name TEXT NOT NULL,
+    votes DOUBLE PRECISION NOT NULL,
+    -- Change from the previous round
+    transfer DOUBLE PRECISION NOT NULL DEFAULT 0,
+    status RANKED_CHOICE_STATUS NOT NULL DEFAULT 'continuing',
+    PRIMARY KEY (round_id, candidate_name)
+);
+
+CREATE INDEX idx_ranked_choice_round_candidate_candidate_id ON ranked_choice_round_candidate(candidate_id);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE race_candidates DROP COLUMN is_winner;
+ALTER TABLE race DROP COLUMN results_finalized_at;
@@ -0,0 +1,6 @@
+-- Add up migration script here
+ALTER TABLE race ADD COLUMN results_finalized_at TIMESTAMPTZ;
+
+-- Outcome counted in the candidate's race_wins or race_losses, NULL until the race is finalized.
+-- Finalizing again only applies the difference, so wins are never counted twice.
+ALTER TABLE race_candidates ADD COLUMN is_winner BOOLEAN;
@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS precinct_statistics;
+DROP TABLE IF EXISTS precinct_result;
+DROP FUNCTION IF EXISTS precinct_vintage_on;
+DROP TABLE IF EXISTS precinct_boundary;
+DROP TABLE IF EXISTS precinct;
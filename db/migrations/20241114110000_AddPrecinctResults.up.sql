@@ -0,0 +1,75 @@
+-- Add up migration script here
+
+-- Voting precincts as results files identify them
+CREATE TABLE precinct (
+    id uuid NOT NULL DEFAULT gen_random_uuid() PRIMARY KEY,
+    state STATE NOT NULL,
+    county_code TEXT NOT NULL,
+    precinct_code TEXT NOT NULL,
+    name TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (state, county_code, precinct_code)
+);
+
+CREATE TRIGGER set_updated_at BEFORE UPDATE ON precinct FOR EACH ROW EXECUTE PROCEDURE set_updated_at();
+
+-- Precinct polygons from each year's precinct shapefile (`vintage`). Precincts are redrawn after
+-- redistricting and whenever cities split or merge them, so an election uses the state's latest
+-- vintage from its year or earlier.
+CREATE TABLE precinct_boundary (
+    precinct_id uuid NOT NULL REFERENCES precinct(id) ON DELETE CASCADE,
+    vintage INTEGER NOT NULL,
+    geom GEOMETRY(MultiPolygon, 4326) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (precinct_id, vintage)
+);
+
+CREATE INDEX idx_precinct_boundary_geom ON precinct_boundary USING gist (geom);
+
+CREATE TRIGGER set_updated_at BEFORE UPDATE ON precinct_boundary FOR EACH ROW EXECUTE PROCEDURE set_updated_at();
+
+CREATE OR REPLACE FUNCTION precinct_vintage_on(s STATE, d DATE) RETURNS INTEGER AS $$
+    SELECT MAX(b.vintage) FROM precinct_boundary b
+    JOIN precinct p ON p.id = b.precinct_id
+    WHERE p.state = s AND b.vintage <= EXTRACT(YEAR FROM d)
+$$ LANGUAGE sql STABLE;
+
+-- Votes for each candidate in each precinct. Write-ins and candidates we don't track have no
+-- candidate_id.
+CREATE TABLE precinct_result (
+    race_id uuid NOT NULL REFERENCES race(id) ON DELETE CASCADE,
+    precinct_id uuid NOT NULL REFERENCES precinct(id) ON DELETE CASCADE,
+    candidate_id uuid REFERENCES politician(id) ON DELETE SET NULL,
+    candidate_name TEXT NOT NULL,
+    votes INTEGER NOT NULL,
+    -- Votes for every candidate in the race in this precinct
+    total_votes INTEGER,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (race_id, precinct_id, candidate_name)
+);
+
+CREATE INDEX idx_precinct_result_precinct_id ON precinct_result (precinct_id);
+
+CREATE TRIGGER set_updated_at BEFORE UPDATE ON precinct_result FOR EACH ROW EXECUTE PROCEDURE set_updated_at();
+
+-- Turnout reported by each precinct for an election
+CREATE TABLE precinct_statistics (
+    election_id uuid NOT NULL REFERENCES election(id) ON DELETE CASCADE,
+    precinct_id uuid NOT NULL REFERENCES precinct(id) ON DELETE CASCADE,
+    has_reported BOOLEAN NOT NULL DEFAULT false,
+    registered_voters INTEGER,
+    election_day_registrations INTEGER,
+    roster_signatures INTEGER,
+    military_overseas_absentee_ballots INTEGER,
+    federal_only_absentee_ballots INTEGER,
+    president_only_absentee_ballots INTEGER,
+    total_voted INTEGER,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (election_id, precinct_id)
+);
+
+CREATE TRIGGER set_updated_at BEFORE UPDATE ON precinct_statistics FOR EACH ROW EXECUTE PROCEDURE set_updated_at();
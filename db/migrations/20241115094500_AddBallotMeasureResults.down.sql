@@ -0,0 +1,6 @@
+-- Add down migration script here
+ALTER TABLE ballot_measure
+    DROP COLUMN passage_threshold,
+    DROP COLUMN results_finalized_at;
+
+DROP TYPE passage_threshold;
@@ -0,0 +1,12 @@
+-- Add up migration script here
+CREATE TYPE passage_threshold AS ENUM (
+    'simple_majority',
+    'fifty_five_percent',
+    'three_fifths',
+    'two_thirds',
+    'three_quarters'
+);
+
+ALTER TABLE ballot_measure
+    ADD COLUMN passage_threshold passage_threshold NOT NULL DEFAULT 'simple_majority',
+    ADD COLUMN results_finalized_at TIMESTAMPTZ;
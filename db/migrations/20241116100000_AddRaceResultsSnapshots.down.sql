@@ -0,0 +1,9 @@
+-- Add down migration script here
+DROP TABLE race_results_snapshot_candidate;
+DROP TABLE race_results_snapshot;
+
+ALTER TABLE race
+    DROP COLUMN results_status,
+    DROP COLUMN results_updated_at;
+
+DROP TYPE results_status;
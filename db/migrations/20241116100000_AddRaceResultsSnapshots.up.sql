@@ -0,0 +1,34 @@
+-- Add up migration script here
+
+CREATE TYPE results_status AS ENUM ('unofficial', 'canvassed', 'certified', 'recount');
+
+ALTER TABLE race
+    ADD COLUMN results_status results_status NOT NULL DEFAULT 'unofficial',
+    -- When vote counts or status last changed, i.e. when the latest snapshot was taken
+    ADD COLUMN results_updated_at TIMESTAMPTZ;
+
+-- A race's results each time they change, whether from a results file or a status change by
+-- staff. Snapshots are never updated so they double as the race's correction history.
+CREATE TABLE race_results_snapshot (
+    id uuid NOT NULL DEFAULT gen_random_uuid() PRIMARY KEY,
+    race_id uuid NOT NULL REFERENCES race(id) ON DELETE CASCADE,
+    results_status results_status NOT NULL,
+    total_votes INTEGER,
+    num_precincts_reporting INTEGER,
+    total_precincts INTEGER,
+    -- File the numbers were loaded from and the SHA-256 of its contents, NULL for changes by staff
+    source_url TEXT,
+    source_hash TEXT,
+    created_by uuid REFERENCES populist_user(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_race_results_snapshot_race_id ON race_results_snapshot(race_id, created_at);
+
+CREATE TABLE race_results_snapshot_candidate (
+    snapshot_id uuid NOT NULL REFERENCES race_results_snapshot(id) ON DELETE CASCADE,
+    -- Merged politicians are re-pointed rather than cascading, so history outlives duplicates
+    candidate_id uuid NOT NULL REFERENCES politician(id),
+    votes INTEGER,
+    PRIMARY KEY (snapshot_id, candidate_id)
+);
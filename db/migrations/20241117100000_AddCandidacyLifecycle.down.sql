@@ -0,0 +1,14 @@
+-- Add down migration script here
+ALTER TABLE race DROP COLUMN write_in_votes;
+
+ALTER TABLE race_candidates
+    DROP CONSTRAINT if_withdrawn_or_disqualified_then_is_running_false,
+    DROP COLUMN status,
+    DROP COLUMN is_write_in,
+    DROP COLUMN is_incumbent,
+    DROP COLUMN filing_date,
+    DROP COLUMN ballot_designation,
+    DROP COLUMN party_on_ballot,
+    DROP COLUMN ballot_order;
+
+DROP TYPE candidacy_status;
@@ -0,0 +1,28 @@
+-- Add up migration script here
+
+CREATE TYPE candidacy_status AS ENUM ('filed', 'qualified', 'withdrawn', 'disqualified');
+
+ALTER TABLE race_candidates
+    ADD COLUMN status candidacy_status NOT NULL DEFAULT 'filed',
+    ADD COLUMN is_write_in BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN is_incumbent BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN filing_date DATE,
+    -- Occupation or title printed under the name where states allow one, e.g. "Incumbent"
+    ADD COLUMN ballot_designation TEXT,
+    -- Party as printed on the ballot, which may differ from the politician's party
+    ADD COLUMN party_on_ballot TEXT,
+    -- Position on the ballot, NULL where the state rotates names or for write-ins
+    ADD COLUMN ballot_order INTEGER;
+
+UPDATE race_candidates SET status = CASE
+    WHEN NOT is_running THEN 'withdrawn'::candidacy_status
+    WHEN date_qualified IS NOT NULL THEN 'qualified'::candidacy_status
+    ELSE 'filed'::candidacy_status
+END;
+
+ALTER TABLE race_candidates
+    ADD CONSTRAINT if_withdrawn_or_disqualified_then_is_running_false
+    CHECK (status NOT IN ('withdrawn', 'disqualified') OR is_running = false);
+
+-- Votes for write-ins that results files don't name, e.g. Minnesota's WRITE-IN line
+ALTER TABLE race ADD COLUMN write_in_votes INTEGER;
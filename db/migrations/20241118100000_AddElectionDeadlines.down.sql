@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE election_deadline;
+DROP TYPE deadline_type;
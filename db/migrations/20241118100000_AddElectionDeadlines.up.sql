@@ -0,0 +1,34 @@
+-- Add up migration script here
+
+CREATE TYPE deadline_type AS ENUM (
+    'registration',
+    'online_registration',
+    'absentee_request',
+    'absentee_return',
+    'early_voting_begins',
+    'early_voting_ends',
+    'canvass',
+    'certification',
+    'other'
+);
+
+-- Dates voters and staff need ahead of and after an election. A deadline applies to the whole
+-- election when state is NULL, otherwise to the state, or to a county or city in it.
+CREATE TABLE election_deadline (
+    id uuid NOT NULL DEFAULT gen_random_uuid() PRIMARY KEY,
+    election_id uuid NOT NULL REFERENCES election(id) ON DELETE CASCADE,
+    deadline_type deadline_type NOT NULL,
+    date DATE NOT NULL,
+    state state,
+    election_scope election_scope NOT NULL DEFAULT 'state',
+    county TEXT,
+    municipality TEXT,
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_election_deadline_election_id ON election_deadline(election_id, date);
+CREATE INDEX idx_election_deadline_state ON election_deadline(state, date);
+
+CREATE TRIGGER set_updated_at BEFORE UPDATE ON election_deadline FOR EACH ROW EXECUTE PROCEDURE set_updated_at();
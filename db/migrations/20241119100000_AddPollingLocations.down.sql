@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP TABLE polling_location_precinct;
+DROP TABLE polling_location_hours;
+DROP TABLE polling_location;
+DROP TYPE polling_location_type;
@@ -0,0 +1,44 @@
+-- Add up migration script here
+
+CREATE TYPE polling_location_type AS ENUM ('election_day', 'early_voting');
+
+-- Where voters cast ballots in an election. Election day locations serve the precincts linked
+-- in polling_location_precinct. Early voting locations serve every voter in their county, or
+-- the whole state when county_code is NULL.
+CREATE TABLE polling_location (
+    id uuid NOT NULL DEFAULT gen_random_uuid() PRIMARY KEY,
+    election_id uuid NOT NULL REFERENCES election(id) ON DELETE CASCADE,
+    location_type polling_location_type NOT NULL,
+    state STATE NOT NULL,
+    county_code TEXT,
+    name TEXT NOT NULL,
+    line_1 TEXT NOT NULL,
+    line_2 TEXT,
+    city TEXT NOT NULL,
+    postal_code TEXT,
+    accessibility_notes TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (election_id, location_type, state, name, line_1, city)
+);
+
+CREATE INDEX idx_polling_location_county ON polling_location(election_id, state, county_code);
+
+CREATE TRIGGER set_updated_at BEFORE UPDATE ON polling_location FOR EACH ROW EXECUTE PROCEDURE set_updated_at();
+
+-- Opening hours by date, early voting locations usually have several
+CREATE TABLE polling_location_hours (
+    polling_location_id uuid NOT NULL REFERENCES polling_location(id) ON DELETE CASCADE,
+    date DATE NOT NULL,
+    opens_at TIME NOT NULL,
+    closes_at TIME NOT NULL,
+    PRIMARY KEY (polling_location_id, date)
+);
+
+CREATE TABLE polling_location_precinct (
+    polling_location_id uuid NOT NULL REFERENCES polling_location(id) ON DELETE CASCADE,
+    precinct_id uuid NOT NULL REFERENCES precinct(id) ON DELETE CASCADE,
+    PRIMARY KEY (polling_location_id, precinct_id)
+);
+
+CREATE INDEX idx_polling_location_precinct_precinct_id ON polling_location_precinct(precinct_id);
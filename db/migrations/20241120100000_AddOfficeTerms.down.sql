@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE office_term;
+DROP TYPE office_term_source;
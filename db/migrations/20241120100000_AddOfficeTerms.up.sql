@@ -0,0 +1,75 @@
+-- Add up migration script here
+
+CREATE TYPE office_term_source AS ENUM ('elected', 'special_election', 'appointed', 'other');
+
+-- Who held each office and when. Terms won in races are added when the race is finalized.
+-- end_date is the day the term ends, when the next one begins, and is moved up when a term ends
+-- early.
+CREATE TABLE office_term (
+    id uuid NOT NULL DEFAULT gen_random_uuid() PRIMARY KEY,
+    office_id uuid NOT NULL REFERENCES office(id) ON DELETE CASCADE,
+    politician_id uuid NOT NULL REFERENCES politician(id) ON DELETE CASCADE,
+    race_id uuid REFERENCES race(id) ON DELETE SET NULL,
+    obtained_by office_term_source NOT NULL,
+    start_date DATE,
+    end_date DATE,
+    notes TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (race_id, politician_id),
+    CONSTRAINT end_date_after_start_date CHECK (end_date >= start_date)
+);
+
+CREATE INDEX idx_office_term_office_id ON office_term(office_id, start_date);
+CREATE INDEX idx_office_term_politician_id ON office_term(politician_id, start_date);
+
+CREATE TRIGGER set_updated_at BEFORE UPDATE ON office_term FOR EACH ROW EXECUTE PROCEDURE set_updated_at();
+
+-- Terms won in races finalized so far, dated the way OfficeTerm::record_race_winners dates them
+INSERT INTO office_term (office_id, politician_id, race_id, obtained_by, start_date, end_date)
+SELECT
+    r.office_id,
+    rc.candidate_id,
+    r.id,
+    CASE WHEN r.is_special_election THEN 'special_election' ELSE 'elected' END::office_term_source,
+    t.start_date,
+    t.start_date + make_interval(years => o.term_length)
+FROM race r
+JOIN election e ON e.id = r.election_id
+JOIN office o ON o.id = r.office_id
+JOIN race_candidates rc ON rc.race_id = r.id AND rc.is_winner
+CROSS JOIN LATERAL (
+    SELECT CASE
+        WHEN r.is_special_election OR EXTRACT(MONTH FROM e.election_date) < 7
+        THEN e.election_date
+        ELSE make_date(EXTRACT(YEAR FROM e.election_date)::INTEGER + 1, 1, 1)
+    END AS start_date
+) t
+WHERE r.race_type = 'general' AND r.results_finalized_at IS NOT NULL;
+
+-- Current office holders without a term that has begun. They're taken to have started in the
+-- year of the earliest VoteSmart experience they still hold, e.g. "2017-Present", and their
+-- start is left unknown without one.
+INSERT INTO office_term (office_id, politician_id, obtained_by, start_date)
+SELECT p.office_id, p.id, 'other', (
+    SELECT MIN(make_date(SUBSTRING(x->>'span' FROM '^\s*(\d{4})')::INTEGER, 1, 1))
+    FROM jsonb_array_elements(
+        CASE jsonb_typeof(p.votesmart_candidate_bio #> '{candidate,political,experience}')
+            WHEN 'array' THEN p.votesmart_candidate_bio #> '{candidate,political,experience}'
+            WHEN 'object' THEN jsonb_build_array(
+                p.votesmart_candidate_bio #> '{candidate,political,experience}'
+            )
+            ELSE '[]'::jsonb
+        END
+    ) x
+    WHERE x->>'title' IS DISTINCT FROM 'Candidate'
+    AND x->>'span' ~* '^\s*\d{4}\s*-\s*present\s*$'
+)
+FROM politician p
+WHERE p.office_id IS NOT NULL
+AND NOT EXISTS (
+    SELECT 1 FROM office_term t
+    WHERE t.politician_id = p.id
+    AND t.office_id = p.office_id
+    AND t.start_date <= CURRENT_DATE
+);
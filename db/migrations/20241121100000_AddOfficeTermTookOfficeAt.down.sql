@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE office_term DROP COLUMN replaces_term_id;
+ALTER TABLE office_term DROP COLUMN took_office_at;
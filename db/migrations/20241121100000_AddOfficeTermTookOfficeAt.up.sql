@@ -0,0 +1,11 @@
+-- Add up migration script here
+
+-- Set when the term's holder was made the office's incumbent, see transition_office_holders
+ALTER TABLE office_term ADD COLUMN took_office_at TIMESTAMPTZ;
+
+-- The term an appointment or other term without a race ends when it begins
+ALTER TABLE office_term
+ADD COLUMN replaces_term_id uuid REFERENCES office_term(id) ON DELETE SET NULL;
+
+-- Terms that have already begun were seated by hand before the transition job existed
+UPDATE office_term SET took_office_at = start_date WHERE start_date <= CURRENT_DATE;
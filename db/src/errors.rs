@@ -3,6 +3,9 @@ pub enum Error {
     #[error(transparent)]
     DatabaseError(#[from] sqlx::Error),
 
+    #[error(transparent)]
+    GeocoderError(#[from] crate::geocoder::GeocoderError),
+
     #[error("Your email or username was not found in our database")]
     EmailOrUsernameNotFound,
 
@@ -0,0 +1,115 @@
+use super::{normalize_address, GeocodedAddress, Geocoder, GeocoderError};
+use crate::{AddressInput, DateTime};
+use sqlx::{types::Json, PgPool};
+
+/// Days a geocoded address is reused, override with `GEOCODE_CACHE_TTL_DAYS`
+const DEFAULT_TTL_DAYS: i64 = 90;
+/// Addresses that didn't match are retried sooner, the geocoder's data may have caught up
+const NOT_FOUND_TTL_DAYS: i64 = 1;
+
+/// Wraps a geocoder with the `geocode_cache` table. The cache never fails a lookup: errors
+/// reading or writing it are logged, and when the geocoder itself is down an expired entry
+/// is served rather than nothing.
+pub struct CachedGeocoder<G> {
+    db_pool: PgPool,
+    geocoder: G,
+    ttl: chrono::Duration,
+}
+
+struct CacheEntry {
+    result: Option<Json<GeocodedAddress>>,
+    expires_at: DateTime,
+}
+
+impl<G: Geocoder + Sync> CachedGeocoder<G> {
+    pub fn new(db_pool: PgPool, geocoder: G) -> Self {
+        let ttl_days = std::env::var("GEOCODE_CACHE_TTL_DAYS")
+            .ok()
+            .and_then(|days| days.parse().ok())
+            .unwrap_or(DEFAULT_TTL_DAYS);
+        Self {
+            db_pool,
+            geocoder,
+            ttl: chrono::Duration::days(ttl_days),
+        }
+    }
+
+    async fn read(&self, key: &str) -> Option<CacheEntry> {
+        let entry = sqlx::query_as!(
+            CacheEntry,
+            r#"
+                SELECT result AS "result:Json<GeocodedAddress>", expires_at
+                FROM geocode_cache
+                WHERE geocoder = $1 AND normalized_address = $2
+            "#,
+            self.geocoder.name(),
+            key,
+        )
+        .fetch_optional(&self.db_pool)
+        .await;
+
+        entry.unwrap_or_else(|err| {
+            tracing::warn!("Failed to read geocode cache: {}", err);
+            None
+        })
+    }
+
+    async fn write(&self, key: &str, result: Option<&GeocodedAddress>) {
+        let ttl = match result {
+            Some(_) => self.ttl,
+            None => chrono::Duration::days(NOT_FOUND_TTL_DAYS),
+        };
+        let written = sqlx::query!(
+            r#"
+                INSERT INTO geocode_cache (geocoder, normalized_address, result, expires_at)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (geocoder, normalized_address) DO UPDATE SET
+                    result = EXCLUDED.result,
+                    expires_at = EXCLUDED.expires_at
+            "#,
+            self.geocoder.name(),
+            key,
+            result.map(Json) as Option<Json<&GeocodedAddress>>,
+            chrono::Utc::now() + ttl,
+        )
+        .execute(&self.db_pool)
+        .await;
+
+        if let Err(err) = written {
+            tracing::warn!("Failed to write geocode cache: {}", err);
+        }
+    }
+}
+
+impl<G: Geocoder + Sync> Geocoder for CachedGeocoder<G> {
+    fn name(&self) -> &'static str {
+        self.geocoder.name()
+    }
+
+    async fn geocode(
+        &self,
+        address: &AddressInput,
+    ) -> Result<Option<GeocodedAddress>, GeocoderError> {
+        let key = normalize_address(address);
+        let cached = self.read(&key).await;
+        if let Some(entry) = &cached {
+            if entry.expires_at > chrono::Utc::now() {
+                return Ok(entry.result.as_ref().map(|result| result.0.clone()));
+            }
+        }
+
+        match self.geocoder.geocode(address).await {
+            Ok(result) => {
+                self.write(&key, result.as_ref()).await;
+                Ok(result)
+            }
+            Err(err) => match cached.and_then(|entry| entry.result) {
+                Some(stale) => {
+                    tracing::warn!("Geocoder failed, using expired cache entry: {}", err);
+                    Ok(Some(stale.0))
+                }
+                None => Err(err),
+            },
+        }
+    }
+}
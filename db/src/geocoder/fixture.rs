@@ -0,0 +1,54 @@
+use super::{normalize_address, GeocodedAddress, Geocoder, GeocoderError};
+use crate::AddressInput;
+use std::collections::HashMap;
+
+/// Answers from a JSON file of addresses instead of a real backend, for tests and local
+/// development. Keys are one-line addresses, e.g. `"100 N 6th St, Minneapolis, MN, 55403"`.
+pub struct FixtureGeocoder {
+    addresses: HashMap<String, GeocodedAddress>,
+}
+
+impl FixtureGeocoder {
+    pub fn new(addresses: HashMap<String, GeocodedAddress>) -> Self {
+        Self {
+            addresses: addresses
+                .into_iter()
+                .map(|(address, geocoded)| (normalize_fixture_key(&address), geocoded))
+                .collect(),
+        }
+    }
+
+    pub fn from_file(path: &str) -> Result<Self, GeocoderError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|err| GeocoderError::Unavailable(format!("{}: {}", path, err)))?;
+        let addresses = serde_json::from_str(&contents)
+            .map_err(|err| GeocoderError::Unavailable(format!("{}: {}", path, err)))?;
+        Ok(Self::new(addresses))
+    }
+
+    pub fn lookup(&self, address: &AddressInput) -> Option<GeocodedAddress> {
+        self.addresses.get(&normalize_address(address)).cloned()
+    }
+}
+
+fn normalize_fixture_key(address: &str) -> String {
+    address
+        .split(',')
+        .map(super::normalize_component)
+        .filter(|component| !component.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl Geocoder for FixtureGeocoder {
+    fn name(&self) -> &'static str {
+        "fixture"
+    }
+
+    async fn geocode(
+        &self,
+        address: &AddressInput,
+    ) -> Result<Option<GeocodedAddress>, GeocoderError> {
+        Ok(self.lookup(address))
+    }
+}
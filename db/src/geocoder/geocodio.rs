@@ -0,0 +1,80 @@
+use super::{GeocodedAddress, Geocoder, GeocoderError};
+use crate::{AddressInput, Coordinates};
+use geocodio::{AddressParams, GeocodioProxy};
+
+/// Geocodio fields holding the districts we store on an address. The unversioned fields follow
+/// the maps currently in force, districts from other vintages come from `district_boundary`.
+const FIELDS: [&str; 2] = ["cd", "stateleg"];
+
+pub struct GeocodioGeocoder {
+    proxy: GeocodioProxy,
+}
+
+impl GeocodioGeocoder {
+    pub fn new() -> Result<Self, GeocoderError> {
+        let proxy =
+            GeocodioProxy::new().map_err(|err| GeocoderError::Unavailable(format!("{:?}", err)))?;
+        Ok(Self { proxy })
+    }
+}
+
+impl Geocoder for GeocodioGeocoder {
+    fn name(&self) -> &'static str {
+        "geocodio"
+    }
+
+    async fn geocode(
+        &self,
+        address: &AddressInput,
+    ) -> Result<Option<GeocodedAddress>, GeocoderError> {
+        let response = self
+            .proxy
+            .geocode(
+                AddressParams::AddressInput(geocodio::AddressInput {
+                    line_1: address.line_1.clone(),
+                    line_2: address.line_2.clone(),
+                    city: address.city.clone(),
+                    state: address.state.to_string(),
+                    country: address.country.clone(),
+                    postal_code: address.postal_code.clone(),
+                }),
+                Some(&FIELDS),
+            )
+            .await;
+
+        let response = match response {
+            Ok(response) => response,
+            Err(geocodio::Error::BadAddress(_)) => return Ok(None),
+            Err(err) => return Err(GeocoderError::Unavailable(err.to_string())),
+        };
+
+        let Some(result) = response.results.into_iter().next() else {
+            return Ok(None);
+        };
+        let fields = result.fields.as_ref();
+        let congressional_district = fields
+            .and_then(|f| f.congressional_districts.as_ref())
+            .and_then(|districts| districts.first())
+            .map(|d| d.district_number.to_string());
+        let state_legislative_districts =
+            fields.and_then(|f| f.state_legislative_districts.as_ref());
+        let state_house_district = state_legislative_districts
+            .and_then(|districts| districts.house.first())
+            .map(|d| d.district_number.to_string());
+        let state_senate_district = state_legislative_districts
+            .and_then(|districts| districts.senate.first())
+            .map(|d| d.district_number.to_string());
+
+        Ok(Some(GeocodedAddress {
+            coordinates: Coordinates {
+                latitude: result.location.latitude,
+                longitude: result.location.longitude,
+            },
+            city: result.address_components.city.clone(),
+            county: result.address_components.county.clone(),
+            congressional_district,
+            state_senate_district,
+            state_house_district,
+        }))
+    }
+}
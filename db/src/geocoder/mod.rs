@@ -0,0 +1,282 @@
+//! Turning a voter's address into coordinates and districts. Which backend answers is picked
+//! with the `GEOCODER` env var, every lookup goes through the `geocode_cache` table, and
+//! districts the backend doesn't return are filled in from `district_boundary`.
+
+mod cache;
+mod fixture;
+mod geocodio;
+mod postgis;
+
+pub use self::cache::CachedGeocoder;
+pub use self::fixture::FixtureGeocoder;
+pub use self::geocodio::GeocodioGeocoder;
+pub use self::postgis::PostgisGeocoder;
+
+use crate::{AddressInput, Coordinates, DistrictBoundary, DistrictType};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::future::Future;
+
+#[derive(thiserror::Error, Debug)]
+pub enum GeocoderError {
+    #[error("This is not a valid voting address")]
+    NotFound,
+
+    #[error("Address lookup is unavailable: {0}")]
+    Unavailable(String),
+
+    #[error(transparent)]
+    DatabaseError(#[from] sqlx::Error),
+}
+
+/// What a geocoder knows about an address. District fields are `None` when the backend
+/// doesn't cover them.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GeocodedAddress {
+    pub coordinates: Coordinates,
+    pub city: Option<String>,
+    pub county: Option<String>,
+    pub congressional_district: Option<String>,
+    pub state_senate_district: Option<String>,
+    pub state_house_district: Option<String>,
+}
+
+pub trait Geocoder {
+    /// Identifies the backend in the cache, results from different backends aren't mixed
+    fn name(&self) -> &'static str;
+
+    /// `Ok(None)` when the address couldn't be matched, errors are reserved for the backend
+    /// itself failing
+    fn geocode(
+        &self,
+        address: &AddressInput,
+    ) -> impl Future<Output = Result<Option<GeocodedAddress>, GeocoderError>> + Send;
+}
+
+/// The backend configured with `GEOCODER`: `geocodio` (default), `postgis` or `fixture`
+pub enum ConfiguredGeocoder {
+    Geocodio(GeocodioGeocoder),
+    Postgis(PostgisGeocoder),
+    Fixture(FixtureGeocoder),
+}
+
+impl ConfiguredGeocoder {
+    pub fn from_env(db_pool: &PgPool) -> Result<Self, GeocoderError> {
+        match std::env::var("GEOCODER").as_deref() {
+            Ok("postgis") => Ok(Self::Postgis(PostgisGeocoder::new(db_pool.clone()))),
+            Ok("fixture") => {
+                let path = std::env::var("GEOCODER_FIXTURE").map_err(|_| {
+                    GeocoderError::Unavailable("GEOCODER_FIXTURE is not set".to_string())
+                })?;
+                Ok(Self::Fixture(FixtureGeocoder::from_file(&path)?))
+            }
+            Ok("geocodio") | Err(_) => Ok(Self::Geocodio(GeocodioGeocoder::new()?)),
+            Ok(other) => Err(GeocoderError::Unavailable(format!(
+                "Unknown geocoder {}",
+                other
+            ))),
+        }
+    }
+}
+
+impl Geocoder for ConfiguredGeocoder {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Geocodio(geocoder) => geocoder.name(),
+            Self::Postgis(geocoder) => geocoder.name(),
+            Self::Fixture(geocoder) => geocoder.name(),
+        }
+    }
+
+    async fn geocode(
+        &self,
+        address: &AddressInput,
+    ) -> Result<Option<GeocodedAddress>, GeocoderError> {
+        match self {
+            Self::Geocodio(geocoder) => geocoder.geocode(address).await,
+            Self::Postgis(geocoder) => geocoder.geocode(address).await,
+            Self::Fixture(geocoder) => geocoder.geocode(address).await,
+        }
+    }
+}
+
+/// Geocodes an address with the configured backend, through the cache, and fills in any
+/// districts it didn't return from imported boundaries
+pub async fn geocode_address(
+    db_pool: &PgPool,
+    address: &AddressInput,
+) -> Result<GeocodedAddress, GeocoderError> {
+    let geocoder = CachedGeocoder::new(db_pool.clone(), ConfiguredGeocoder::from_env(db_pool)?);
+    let mut geocoded = geocoder
+        .geocode(address)
+        .await?
+        .ok_or(GeocoderError::NotFound)?;
+    fill_districts(db_pool, &mut geocoded).await;
+    Ok(geocoded)
+}
+
+/// Fills districts and county the geocoder left empty by point-in-polygon against the maps in
+/// force today. A failed lookup only leaves them empty, the address itself is still usable.
+pub async fn fill_districts(db_pool: &PgPool, geocoded: &mut GeocodedAddress) {
+    if geocoded.congressional_district.is_some()
+        && geocoded.state_senate_district.is_some()
+        && geocoded.state_house_district.is_some()
+        && geocoded.county.is_some()
+    {
+        return;
+    }
+
+    let today = chrono::Utc::now().date_naive();
+    let boundaries =
+        match DistrictBoundary::containing_point_on(db_pool, &geocoded.coordinates, today).await {
+            Ok(boundaries) => crate::AddressDistricts { boundaries },
+            Err(err) => {
+                tracing::warn!("Failed to look up districts for geocoded address: {}", err);
+                return;
+            }
+        };
+
+    let code = |district_type| boundaries.code(district_type).map(str::to_string);
+    geocoded.congressional_district = geocoded
+        .congressional_district
+        .take()
+        .or_else(|| code(DistrictType::UsCongressional));
+    geocoded.state_senate_district = geocoded
+        .state_senate_district
+        .take()
+        .or_else(|| code(DistrictType::StateSenate));
+    geocoded.state_house_district = geocoded
+        .state_house_district
+        .take()
+        .or_else(|| code(DistrictType::StateHouse));
+    geocoded.county = geocoded
+        .county
+        .take()
+        .or_else(|| boundaries.county().map(str::to_string));
+}
+
+const ABBREVIATIONS: [(&str, &str); 24] = [
+    ("STREET", "ST"),
+    ("SAINT", "ST"),
+    ("AVENUE", "AVE"),
+    ("ROAD", "RD"),
+    ("DRIVE", "DR"),
+    ("BOULEVARD", "BLVD"),
+    ("LANE", "LN"),
+    ("COURT", "CT"),
+    ("PLACE", "PL"),
+    ("TERRACE", "TER"),
+    ("PARKWAY", "PKWY"),
+    ("HIGHWAY", "HWY"),
+    ("CIRCLE", "CIR"),
+    ("TRAIL", "TRL"),
+    ("NORTH", "N"),
+    ("SOUTH", "S"),
+    ("EAST", "E"),
+    ("WEST", "W"),
+    ("NORTHEAST", "NE"),
+    ("NORTHWEST", "NW"),
+    ("SOUTHEAST", "SE"),
+    ("SOUTHWEST", "SW"),
+    ("APARTMENT", "APT"),
+    ("SUITE", "STE"),
+];
+
+fn normalize_component(component: &str) -> String {
+    component
+        .to_uppercase()
+        .replace(['.', ',', '\''], " ")
+        .split_whitespace()
+        .map(|word| {
+            ABBREVIATIONS
+                .iter()
+                .find(|(long, _)| *long == word)
+                .map(|(_, short)| *short)
+                .unwrap_or(word)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Cache key for an address, so spelling variations of the same address share one lookup
+pub fn normalize_address(address: &AddressInput) -> String {
+    let postal_code = address.postal_code.trim();
+    [
+        address.line_1.as_str(),
+        address.line_2.as_deref().unwrap_or_default(),
+        address.city.as_str(),
+        address.state.as_ref(),
+        postal_code.get(..5).unwrap_or(postal_code),
+    ]
+    .iter()
+    .map(|component| normalize_component(component))
+    .filter(|component| !component.is_empty())
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::enums::State;
+
+    fn address(line_1: &str, line_2: Option<&str>, city: &str, postal_code: &str) -> AddressInput {
+        AddressInput {
+            line_1: line_1.to_string(),
+            line_2: line_2.map(str::to_string),
+            city: city.to_string(),
+            county: None,
+            state: State::MN,
+            country: "USA".to_string(),
+            postal_code: postal_code.to_string(),
+            coordinates: None,
+            congressional_district: None,
+            state_senate_district: None,
+            state_house_district: None,
+        }
+    }
+
+    #[test]
+    fn test_normalize_address() {
+        let expected = "1234 N MAIN ST, APT 4, ST PAUL, MN, 55104";
+        assert_eq!(
+            normalize_address(&address(
+                "1234 North Main Street",
+                Some("Apartment 4"),
+                "Saint Paul",
+                "55104"
+            )),
+            expected
+        );
+        assert_eq!(
+            normalize_address(&address(
+                " 1234  n. main st ",
+                Some("apt. 4"),
+                "St. Paul",
+                "55104-1234"
+            )),
+            expected
+        );
+        assert_eq!(
+            normalize_address(&address("1234 Main St", Some(""), "Minneapolis", "55401")),
+            "1234 MAIN ST, MINNEAPOLIS, MN, 55401"
+        );
+    }
+
+    #[test]
+    fn test_fixture_geocoder() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/geocoder.json");
+        let geocoder = FixtureGeocoder::from_file(path).unwrap();
+
+        let geocoded = geocoder
+            .lookup(&address("100 N 6th Street", None, "Minneapolis", "55403"))
+            .unwrap();
+        assert_eq!(geocoded.county.as_deref(), Some("Hennepin County"));
+        assert_eq!(geocoded.congressional_district.as_deref(), Some("5"));
+        assert_eq!(geocoded.state_house_district.as_deref(), Some("59B"));
+
+        assert!(geocoder
+            .lookup(&address("1 Nowhere Ln", None, "Minneapolis", "55401"))
+            .is_none());
+    }
+}
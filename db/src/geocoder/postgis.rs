@@ -0,0 +1,73 @@
+use super::{GeocodedAddress, Geocoder, GeocoderError};
+use crate::{AddressInput, Coordinates};
+use sqlx::{PgPool, Row};
+
+/// Ratings above this are guesses at the street or zip code rather than the address
+const MAX_RATING: i32 = 20;
+
+/// Geocodes against Census TIGER data loaded into the `postgis_tiger_geocoder` extension, so
+/// lookups don't leave the database. Districts come from imported boundaries.
+pub struct PostgisGeocoder {
+    db_pool: PgPool,
+}
+
+impl PostgisGeocoder {
+    pub fn new(db_pool: PgPool) -> Self {
+        Self { db_pool }
+    }
+}
+
+impl Geocoder for PostgisGeocoder {
+    fn name(&self) -> &'static str {
+        "postgis"
+    }
+
+    async fn geocode(
+        &self,
+        address: &AddressInput,
+    ) -> Result<Option<GeocodedAddress>, GeocoderError> {
+        let one_line = format!(
+            "{} {}, {}, {} {}",
+            address.line_1,
+            address.line_2.as_deref().unwrap_or_default(),
+            address.city,
+            address.state,
+            address.postal_code
+        );
+
+        // The extension is optional, so this isn't checked at compile time like other queries
+        let record = sqlx::query(
+            r#"
+                SELECT
+                    ST_X(ST_Transform(g.geomout, 4326)) AS longitude,
+                    ST_Y(ST_Transform(g.geomout, 4326)) AS latitude,
+                    (g.addy).location AS city,
+                    g.rating
+                FROM geocode($1, 1) AS g
+            "#,
+        )
+        .bind(one_line)
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(|err| GeocoderError::Unavailable(err.to_string()))?;
+
+        let Some(record) = record else {
+            return Ok(None);
+        };
+        if record.try_get::<i32, _>("rating")? > MAX_RATING {
+            return Ok(None);
+        }
+
+        Ok(Some(GeocodedAddress {
+            coordinates: Coordinates {
+                latitude: record.try_get("latitude")?,
+                longitude: record.try_get("longitude")?,
+            },
+            city: record.try_get("city")?,
+            county: None,
+            congressional_district: None,
+            state_senate_district: None,
+            state_house_district: None,
+        }))
+    }
+}
@@ -1,6 +1,8 @@
 pub mod errors;
+pub mod geocoder;
 pub mod loaders;
 pub mod models;
+pub mod pagination;
 pub mod pool;
 pub mod util;
 
@@ -10,20 +12,38 @@ pub use errors::Error;
 
 pub use models::address::*;
 pub use models::argument::*;
+pub use models::ballot::*;
 pub use models::ballot_measure::*;
 pub use models::bill::*;
+pub use models::district_boundary::*;
+pub use models::district_vintage::*;
 pub use models::election::*;
+pub use models::election_deadline::*;
 pub use models::embed::*;
 pub use models::enums::*;
+pub use models::external_id::*;
 pub use models::issue_tag::*;
 pub use models::office::*;
+pub use models::office_term::*;
+pub use models::office_transition::*;
 pub use models::organization::*;
 pub use models::organization_politician_note::*;
 pub use models::party::*;
 pub use models::politician::*;
+pub use models::politician_match::*;
 pub use models::poll::*;
+pub use models::polling_location::*;
+pub use models::precinct::*;
 pub use models::question::*;
 pub use models::race::*;
+pub use models::race_finalization::*;
+pub use models::race_results_snapshot::*;
+pub use models::ranked_choice::*;
 pub use models::respondent::*;
+pub use models::sample_ballot::*;
+pub use models::scraper_proposed_change::*;
+pub use models::scraper_run::*;
+pub use models::slug_history::*;
 pub use models::user::*;
+pub use pagination::*;
 pub use pool::*;
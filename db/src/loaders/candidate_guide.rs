@@ -0,0 +1,40 @@
+use async_graphql::dataloader::Loader;
+use async_graphql::futures_util::TryStreamExt;
+use async_graphql::FieldError;
+use itertools::Itertools;
+
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+use crate::models::candidate_guide::CandidateGuide;
+
+pub struct CandidateGuideLoader(PgPool);
+
+impl CandidateGuideLoader {
+    pub fn new(pool: PgPool) -> Self {
+        Self(pool)
+    }
+}
+
+impl Loader<uuid::Uuid> for CandidateGuideLoader {
+    type Value = CandidateGuide;
+    type Error = FieldError;
+
+    async fn load(
+        &self,
+        keys: &[uuid::Uuid],
+    ) -> Result<HashMap<uuid::Uuid, Self::Value>, Self::Error> {
+        let query = format!(
+            r#"SELECT * FROM candidate_guide WHERE id IN ({})"#,
+            keys.iter().map(|k| format!("'{}'", k)).join(",")
+        );
+
+        let cache = sqlx::query_as(&query)
+            .fetch(&self.0)
+            .map_ok(|candidate_guide: CandidateGuide| (candidate_guide.id, candidate_guide))
+            .try_collect()
+            .await?;
+
+        Ok(cache)
+    }
+}
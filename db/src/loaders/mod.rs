@@ -1,5 +1,11 @@
+pub mod bill;
+pub mod candidate_guide;
+pub mod election;
 pub mod issue_tag;
 pub mod office;
 pub mod organization;
+pub mod party;
 pub mod politician;
+pub mod poll;
+pub mod question;
 pub mod race;
@@ -3,7 +3,7 @@ use async_graphql::futures_util::TryStreamExt;
 use async_graphql::FieldError;
 use itertools::Itertools;
 
-use sqlx::PgPool;
+use sqlx::{FromRow, PgPool, Row};
 use std::collections::HashMap;
 
 use crate::Politician;
@@ -54,14 +54,27 @@ impl Loader<PoliticianSlug> for PoliticianLoader {
         &self,
         keys: &[PoliticianSlug],
     ) -> Result<HashMap<PoliticianSlug, Self::Value>, Self::Error> {
+        // Previous slugs, including those of merged politicians, resolve to the current record
+        let slugs = keys.iter().map(|k| format!("'{}'", k.0)).join(",");
         let query = format!(
-            r#"SELECT * FROM politician WHERE slug IN ({})"#,
-            keys.iter().map(|k| format!("'{}'", k.0)).join(",")
+            r#"
+            SELECT p.slug AS requested_slug, p.* FROM politician p WHERE p.slug IN ({slugs})
+            UNION ALL
+            SELECT h.slug AS requested_slug, p.* FROM slug_history h
+            JOIN politician p ON p.id = h.entity_id
+            WHERE h.entity_type = 'politician' AND h.slug IN ({slugs})
+            AND h.slug NOT IN (SELECT slug FROM politician)
+            "#
         );
 
-        let cache = sqlx::query_as(&query)
+        let cache = sqlx::query(&query)
             .fetch(&self.0)
-            .map_ok(|politician: Politician| (PoliticianSlug(politician.slug.clone()), politician))
+            .and_then(|row| async move {
+                Ok((
+                    PoliticianSlug(row.try_get("requested_slug")?),
+                    Politician::from_row(&row)?,
+                ))
+            })
             .try_collect()
             .await?;
 
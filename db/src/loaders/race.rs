@@ -3,13 +3,19 @@ use async_graphql::futures_util::TryStreamExt;
 use async_graphql::FieldError;
 use itertools::Itertools;
 
-use sqlx::PgPool;
+use sqlx::{FromRow, PgPool, Row};
 use std::collections::HashMap;
 
-use crate::Race;
+use crate::{Politician, Race, RaceCandidate};
 
 pub struct RaceLoader(PgPool);
 
+/// Loads every candidacy in each race with its candidate, in ballot order, keyed by race id
+pub struct RaceCandidatesLoader(PgPool);
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RaceId(pub uuid::Uuid);
+
 impl RaceLoader {
     pub fn new(pool: PgPool) -> Self {
         Self(pool)
@@ -38,3 +44,60 @@ impl Loader<uuid::Uuid> for RaceLoader {
         Ok(cache)
     }
 }
+
+impl RaceCandidatesLoader {
+    pub fn new(pool: PgPool) -> Self {
+        Self(pool)
+    }
+}
+
+impl Loader<RaceId> for RaceCandidatesLoader {
+    type Value = Vec<(RaceCandidate, Politician)>;
+    type Error = FieldError;
+
+    async fn load(&self, keys: &[RaceId]) -> Result<HashMap<RaceId, Self::Value>, Self::Error> {
+        let query = format!(
+            r#"
+                SELECT
+                    rc.race_id,
+                    rc.candidate_id,
+                    rc.status,
+                    rc.is_running,
+                    rc.is_write_in,
+                    rc.is_incumbent,
+                    rc.filing_date,
+                    rc.ballot_designation,
+                    rc.party_on_ballot,
+                    rc.ballot_order,
+                    p.*
+                FROM race_candidates rc
+                JOIN politician p ON p.id = rc.candidate_id
+                WHERE rc.race_id IN ({})
+                ORDER BY rc.ballot_order NULLS LAST
+            "#,
+            keys.iter().map(|k| format!("'{}'", k.0)).join(",")
+        );
+
+        let cache = sqlx::query(&query)
+            .fetch(&self.0)
+            .map_ok(|row| {
+                (
+                    row.get("race_id"),
+                    RaceCandidate::from_row(&row),
+                    Politician::from_row(&row),
+                )
+            })
+            .try_fold(
+                HashMap::<RaceId, Self::Value>::new(),
+                |mut acc, (race_id, candidacy, politician)| async move {
+                    acc.entry(RaceId(race_id))
+                        .or_default()
+                        .push((candidacy?, politician?));
+                    Ok(acc)
+                },
+            )
+            .await?;
+
+        Ok(cache)
+    }
+}
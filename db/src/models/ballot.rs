@@ -0,0 +1,957 @@
+use crate::{
+    models::enums::{BallotMeasureStatus, PassageThreshold, RaceType, State, VoteType},
+    Address, BallotMeasure, Chamber, DistrictBoundary, DistrictType, Election, ElectionScope,
+    Office, PoliticalScope, Race,
+};
+use async_graphql::Enum;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use strum_macros::Display;
+
+/// Sections of a ballot, in the order they're printed
+#[derive(
+    Enum, Display, Debug, Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub enum BallotSection {
+    Federal,
+    State,
+    County,
+    City,
+    School,
+    Judicial,
+    Measures,
+}
+
+/// Why a race is on an address's ballot
+#[derive(Enum, Display, Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum BallotMatchReason {
+    Nationwide,
+    Statewide,
+    Countywide,
+    Citywide,
+    District,
+    SchoolDistrict,
+}
+
+/// A district an address votes in, from imported boundaries or a state's own address data
+#[derive(Debug, Clone, PartialEq)]
+pub struct BallotDistrict {
+    pub district_type: DistrictType,
+    pub code: String,
+    pub name: Option<String>,
+    /// Census GEOID of the boundary, school districts are often identified by it
+    pub geoid: Option<String>,
+    pub county: Option<String>,
+    pub municipality: Option<String>,
+    /// School district a school board subdistrict belongs to
+    pub school_district: Option<String>,
+    /// Vintage of the boundary the district came from, `None` for other sources
+    pub vintage: Option<i32>,
+    /// Whether the district is from the maps in force on election day
+    pub in_force: bool,
+}
+
+/// Where a ballot measure is voted on
+#[derive(Debug, Clone)]
+pub struct BallotMeasureArea {
+    pub id: uuid::Uuid,
+    pub election_scope: Option<ElectionScope>,
+    pub county: Option<String>,
+    pub municipality: Option<String>,
+    pub school_district: Option<String>,
+}
+
+/// Everything about an address that decides which offices are on its ballot
+#[derive(Debug, Clone)]
+pub struct BallotAddress {
+    pub state: State,
+    pub county: Option<String>,
+    pub municipality: Option<String>,
+    pub districts: Vec<BallotDistrict>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BallotMatch {
+    pub reason: BallotMatchReason,
+    /// Shown to voters, e.g. "Address is in State House District 59B (2022 map)"
+    pub explanation: String,
+    pub district: Option<BallotDistrict>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BallotRace {
+    pub race: Race,
+    pub office: Office,
+    pub section: BallotSection,
+    pub ballot_match: BallotMatch,
+}
+
+#[derive(Debug, Clone)]
+pub struct BallotSectionEntries {
+    pub section: BallotSection,
+    pub races: Vec<BallotRace>,
+    pub ballot_measures: Vec<BallotMeasure>,
+}
+
+/// The races and measures on the ballot at an address, in ballot order
+#[derive(Debug, Clone)]
+pub struct Ballot {
+    pub election: Election,
+    pub address_id: uuid::Uuid,
+    pub sections: Vec<BallotSectionEntries>,
+}
+
+impl Ballot {
+    pub async fn build(
+        db_pool: &PgPool,
+        election_id: &uuid::Uuid,
+        address_id: &uuid::Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let election = Election::find_by_id(db_pool, *election_id).await?;
+        let address = BallotAddress::load(db_pool, address_id, election.election_date).await?;
+
+        let races = sqlx::query_as!(
+            Race,
+            r#"
+                SELECT
+                    r.id,
+                    r.slug,
+                    r.title,
+                    r.office_id,
+                    r.race_type AS "race_type:RaceType",
+                    r.vote_type AS "vote_type:VoteType",
+                    r.party_id,
+                    r.state AS "state:State",
+                    r.description,
+                    r.ballotpedia_link,
+                    r.early_voting_begins_date,
+                    r.winner_ids,
+                    r.total_votes,
+                    r.num_precincts_reporting,
+                    r.total_precincts,
+                    r.official_website,
+                    r.election_id,
+                    r.is_special_election,
+                    r.num_elect,
+                    r.created_at,
+                    r.updated_at
+                FROM race r
+                JOIN office o ON o.id = r.office_id
+                WHERE r.election_id = $1
+                AND (o.election_scope = 'national' OR o.state = $2)
+            "#,
+            election_id,
+            address.state as State,
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        let office_ids: Vec<uuid::Uuid> = races.iter().map(|r| r.office_id).collect();
+        let offices = sqlx::query_as!(
+            Office,
+            r#"
+                SELECT id, slug, title, subtitle, subtitle_short, name, office_type, district, district_type AS "district_type:DistrictType", district_vintage, hospital_district, school_district, chamber AS "chamber:Chamber", election_scope as "election_scope:ElectionScope", political_scope AS "political_scope:PoliticalScope", state AS "state:State", county, municipality, term_length, seat, priority, created_at, updated_at FROM office
+                WHERE id = ANY($1)
+            "#,
+            &office_ids,
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        let mut ballot_races: Vec<BallotRace> = races
+            .into_iter()
+            .filter_map(|race| {
+                let office = offices.iter().find(|o| o.id == race.office_id)?.clone();
+                let ballot_match = address.match_office(&office)?;
+                Some(BallotRace {
+                    section: BallotSection::of_office(&office),
+                    race,
+                    office,
+                    ballot_match,
+                })
+            })
+            .collect();
+        ballot_races.sort_by(|a, b| {
+            a.section
+                .cmp(&b.section)
+                .then(
+                    a.office
+                        .priority
+                        .unwrap_or(i32::MAX)
+                        .cmp(&b.office.priority.unwrap_or(i32::MAX)),
+                )
+                .then(b.race.title.cmp(&a.race.title))
+        });
+
+        // Local measures are only on the ballot where they're voted on
+        let measure_areas = sqlx::query_as!(
+            BallotMeasureArea,
+            r#"
+                SELECT
+                    bm.id,
+                    bm.election_scope AS "election_scope:ElectionScope",
+                    bm.county,
+                    bm.municipality,
+                    bm.school_district
+                FROM ballot_measure bm
+                WHERE bm.election_id = $1 AND bm.state = $2
+            "#,
+            election_id,
+            address.state as State,
+        )
+        .fetch_all(db_pool)
+        .await?;
+        let measure_ids: Vec<uuid::Uuid> = measure_areas
+            .iter()
+            .filter(|area| address.match_measure(area))
+            .map(|area| area.id)
+            .collect();
+
+        let ballot_measures = sqlx::query_as!(
+            BallotMeasure,
+            r#"
+                SELECT
+                    bm.id,
+                    bm.slug,
+                    bm.title,
+                    bm.description,
+                    bm.status AS "status:BallotMeasureStatus",
+                    bm.ballot_measure_code,
+                    bm.measure_type,
+                    bm.definitions,
+                    bm.passage_threshold AS "passage_threshold:PassageThreshold",
+                    bm.official_summary,
+                    bm.populist_summary,
+                    bm.full_text_url,
+                    bm.election_id,
+                    bm.state AS "state:State",
+                    bm.yes_votes,
+                    bm.no_votes,
+                    bm.num_precincts_reporting,
+                    bm.total_precincts,
+                    bm.created_at,
+                    bm.updated_at
+                FROM ballot_measure bm
+                WHERE bm.election_id = $1 AND bm.state = $2 AND bm.id = ANY($3)
+                ORDER BY bm.ballot_measure_code, bm.title
+            "#,
+            election_id,
+            address.state as State,
+            &measure_ids,
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        let mut sections: Vec<BallotSectionEntries> = vec![];
+        for ballot_race in ballot_races {
+            match sections.last_mut() {
+                Some(entries) if entries.section == ballot_race.section => {
+                    entries.races.push(ballot_race)
+                }
+                _ => sections.push(BallotSectionEntries {
+                    section: ballot_race.section,
+                    races: vec![ballot_race],
+                    ballot_measures: vec![],
+                }),
+            }
+        }
+        if !ballot_measures.is_empty() {
+            sections.push(BallotSectionEntries {
+                section: BallotSection::Measures,
+                races: vec![],
+                ballot_measures,
+            });
+        }
+
+        Ok(Self {
+            election,
+            address_id: *address_id,
+            sections,
+        })
+    }
+
+    /// Every race on the ballot, in ballot order
+    pub fn races(&self) -> impl Iterator<Item = &BallotRace> {
+        self.sections.iter().flat_map(|s| s.races.iter())
+    }
+
+    pub fn ballot_measures(&self) -> impl Iterator<Item = &BallotMeasure> {
+        self.sections.iter().flat_map(|s| s.ballot_measures.iter())
+    }
+}
+
+impl BallotSection {
+    pub fn of_office(office: &Office) -> Self {
+        let is_court = [office.name.as_deref(), Some(office.title.as_str())]
+            .into_iter()
+            .flatten()
+            .any(|t| t.contains("Judge") || t.contains("Justice") || t.contains("Court"));
+
+        if office.election_scope == ElectionScope::National
+            || office.political_scope == PoliticalScope::Federal
+            || office.district_type == Some(DistrictType::UsCongressional)
+        {
+            Self::Federal
+        } else if office.district_type == Some(DistrictType::Judicial) || is_court {
+            Self::Judicial
+        } else if office.district_type == Some(DistrictType::School)
+            || office.school_district.is_some()
+        {
+            Self::School
+        } else {
+            match (office.election_scope, office.district_type) {
+                (ElectionScope::State, _) => Self::State,
+                (ElectionScope::County, _) => Self::County,
+                (ElectionScope::City, _) => Self::City,
+                (_, Some(DistrictType::StateSenate | DistrictType::StateHouse)) => Self::State,
+                (_, Some(DistrictType::City)) => Self::City,
+                _ => Self::County,
+            }
+        }
+    }
+}
+
+impl BallotAddress {
+    /// Gathers an address's districts under the maps in force on `date`, plus every other
+    /// vintage it's been assigned, for offices elected from older or newer maps
+    pub async fn load(
+        db_pool: &PgPool,
+        address_id: &uuid::Uuid,
+        date: chrono::NaiveDate,
+    ) -> Result<Self, sqlx::Error> {
+        let address = sqlx::query!(
+            r#"
+                SELECT
+                    state AS "state:State",
+                    county,
+                    city,
+                    congressional_district,
+                    state_senate_district,
+                    state_house_district
+                FROM address
+                WHERE id = $1
+            "#,
+            address_id,
+        )
+        .fetch_one(db_pool)
+        .await?;
+
+        let in_force = Address::districts_on(db_pool, address_id, date).await?;
+        let assigned = sqlx::query_as!(
+            DistrictBoundary,
+            r#"
+                SELECT
+                    b.id,
+                    b.state AS "state:State",
+                    b.district_type AS "district_type:DistrictType",
+                    b.code,
+                    b.name,
+                    b.county,
+                    b.municipality,
+                    b.vintage,
+                    b.geoid,
+                    b.source,
+                    b.layer,
+                    b.created_at,
+                    b.updated_at
+                FROM address_district ad
+                JOIN district_boundary b ON b.id = ad.district_boundary_id
+                WHERE ad.address_id = $1
+            "#,
+            address_id,
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        let county = in_force
+            .county()
+            .map(str::to_string)
+            .or(address.county.clone());
+        let mut municipality = in_force.municipality().map(str::to_string);
+
+        let mut districts: Vec<BallotDistrict> = in_force
+            .boundaries
+            .iter()
+            .map(|b| (b, true))
+            .chain(
+                assigned
+                    .iter()
+                    .filter(|a| !in_force.boundaries.iter().any(|b| b.id == a.id))
+                    .map(|b| (b, false)),
+            )
+            // Whole counties and cities are matched by name, not as districts
+            .filter(|(b, _)| match b.district_type {
+                DistrictType::County => b.county.is_some(),
+                DistrictType::City => b.municipality.is_some(),
+                _ => true,
+            })
+            .map(|(b, in_force)| BallotDistrict {
+                district_type: b.district_type,
+                code: normalize_district_code(&b.code),
+                name: b.name.clone(),
+                geoid: b.geoid.clone(),
+                county: b.county.clone(),
+                municipality: b.municipality.clone(),
+                school_district: None,
+                vintage: Some(b.vintage),
+                in_force,
+            })
+            .collect();
+
+        for (district_type, code) in [
+            (
+                DistrictType::UsCongressional,
+                address.congressional_district,
+            ),
+            (DistrictType::StateSenate, address.state_senate_district),
+            (DistrictType::StateHouse, address.state_house_district),
+        ] {
+            push_fallback(
+                &mut districts,
+                BallotDistrict::from_code(district_type, code),
+            );
+        }
+
+        // Minnesota's voting district and school district files cover districts we don't
+        // have boundaries for, and are only used for those
+        let uncovered = [
+            DistrictType::Judicial,
+            DistrictType::SoilAndWater,
+            DistrictType::County,
+            DistrictType::City,
+            DistrictType::School,
+        ]
+        .into_iter()
+        .any(|t| !districts.iter().any(|d| d.in_force && d.district_type == t));
+        if address.state == State::MN && (uncovered || municipality.is_none()) {
+            if let Some(mn) = Address::extended_mn_by_address_id(db_pool, address_id).await? {
+                municipality =
+                    municipality.or(mn.municipality_name.map(|m| m.replace("Twp", "Township")));
+
+                for (district_type, code) in [
+                    (DistrictType::Judicial, mn.judicial_district),
+                    (DistrictType::SoilAndWater, mn.soil_and_water_district),
+                    (DistrictType::County, mn.county_commissioner_district),
+                    (DistrictType::City, mn.ward),
+                ] {
+                    push_fallback(
+                        &mut districts,
+                        BallotDistrict::from_code(district_type, code).map(|d| BallotDistrict {
+                            county: county
+                                .clone()
+                                .filter(|_| district_type == DistrictType::County),
+                            municipality: municipality
+                                .clone()
+                                .filter(|_| district_type == DistrictType::City),
+                            ..d
+                        }),
+                    );
+                }
+
+                // Numbered like the offices, e.g. ISD #1, which TIGER's school districts aren't
+                let school_district = mn.school_district_number.map(|number| {
+                    let prefix = match mn.school_district_type.as_deref() {
+                        Some("03") => "SSD",
+                        _ => "ISD",
+                    };
+                    format!("{} #{}", prefix, number.trim_start_matches('0'))
+                });
+                let has_school_boundaries = districts
+                    .iter()
+                    .any(|d| d.in_force && d.district_type == DistrictType::School);
+                if let Some(school_district) = school_district.filter(|_| !has_school_boundaries) {
+                    if let Some(subdistrict) =
+                        BallotDistrict::from_code(DistrictType::School, mn.school_subdistrict_code)
+                    {
+                        districts.push(BallotDistrict {
+                            name: mn.school_subdistrict_name,
+                            school_district: Some(school_district.clone()),
+                            ..subdistrict
+                        });
+                    }
+                    if let Some(district) =
+                        BallotDistrict::from_code(DistrictType::School, Some(school_district))
+                    {
+                        districts.push(BallotDistrict {
+                            name: mn.school_district_name,
+                            ..district
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            state: address.state,
+            county,
+            municipality: municipality.or(Some(address.city)),
+            districts,
+        })
+    }
+
+    /// Whether an office is elected by this address, and why
+    pub fn match_office(&self, office: &Office) -> Option<BallotMatch> {
+        if office.election_scope == ElectionScope::National {
+            return Some(BallotMatch {
+                reason: BallotMatchReason::Nationwide,
+                explanation: "Nationwide office".to_string(),
+                district: None,
+            });
+        }
+        if office.state != Some(self.state) {
+            return None;
+        }
+
+        match office.election_scope {
+            ElectionScope::National => None,
+            ElectionScope::State => Some(BallotMatch {
+                reason: BallotMatchReason::Statewide,
+                explanation: format!("Statewide office in {}", self.state),
+                district: None,
+            }),
+            ElectionScope::County => {
+                let county = self.county.as_deref()?;
+                names_match(office.county.as_deref()?, county, " County").then(|| BallotMatch {
+                    reason: BallotMatchReason::Countywide,
+                    explanation: format!("Countywide office in {}", county_name(county)),
+                    district: None,
+                })
+            }
+            ElectionScope::City => {
+                let municipality = self.municipality.as_deref()?;
+                names_match(office.municipality.as_deref()?, municipality, "").then(|| {
+                    BallotMatch {
+                        reason: BallotMatchReason::Citywide,
+                        explanation: format!("Citywide office in {}", municipality),
+                        district: None,
+                    }
+                })
+            }
+            ElectionScope::District => match office.district_type? {
+                DistrictType::School => self.match_school_district(office),
+                district_type => {
+                    let code = normalize_district_code(office.district.as_deref()?);
+                    let district = self.districts.iter().find(|d| {
+                        d.district_type == district_type
+                            && d.school_district.is_none()
+                            && d.code == code
+                            && match office.district_vintage {
+                                Some(vintage) => d.vintage == Some(vintage),
+                                None => d.in_force,
+                            }
+                            && parent_matches(office.county.as_deref(), d.county.as_deref())
+                            && parent_matches(
+                                office.municipality.as_deref(),
+                                d.municipality.as_deref(),
+                            )
+                    })?;
+                    Some(BallotMatch {
+                        reason: BallotMatchReason::District,
+                        explanation: district_explanation(district),
+                        district: Some(district.clone()),
+                    })
+                }
+            },
+        }
+    }
+
+    /// Whether a ballot measure is voted on at this address. Measures without a scope are
+    /// scoped by the narrowest place they name.
+    pub fn match_measure(&self, area: &BallotMeasureArea) -> bool {
+        let election_scope = area.election_scope.unwrap_or(
+            match (&area.municipality, &area.county, &area.school_district) {
+                (Some(_), _, _) => ElectionScope::City,
+                (None, Some(_), _) => ElectionScope::County,
+                (None, None, Some(_)) => ElectionScope::District,
+                (None, None, None) => ElectionScope::State,
+            },
+        );
+        let place_matches = |measure: &Option<String>, address: &Option<String>, suffix| match (
+            measure.as_deref(),
+            address.as_deref(),
+        ) {
+            (Some(measure), Some(address)) => names_match(measure, address, suffix),
+            _ => false,
+        };
+
+        match election_scope {
+            ElectionScope::National | ElectionScope::State => true,
+            ElectionScope::County => place_matches(&area.county, &self.county, " County"),
+            ElectionScope::City => place_matches(&area.municipality, &self.municipality, ""),
+            ElectionScope::District => area
+                .school_district
+                .as_deref()
+                .is_some_and(|school_district| self.school_district(school_district).is_some()),
+        }
+    }
+
+    fn match_school_district(&self, office: &Office) -> Option<BallotMatch> {
+        let school_district = office.school_district.as_deref()?;
+        let district = self.school_district(school_district)?;
+        let school_name = district.name.clone().unwrap_or(school_district.to_string());
+
+        match office.district.as_deref() {
+            None => Some(BallotMatch {
+                reason: BallotMatchReason::SchoolDistrict,
+                explanation: format!("Address is in {}", school_name),
+                district: Some(district.clone()),
+            }),
+            Some(subdistrict) => {
+                let code = normalize_district_code(subdistrict);
+                let subdistrict = self.districts.iter().find(|d| {
+                    d.district_type == DistrictType::School
+                        && d.school_district.as_deref() == Some(district.code.as_str())
+                        && d.code == code
+                })?;
+                Some(BallotMatch {
+                    reason: BallotMatchReason::SchoolDistrict,
+                    explanation: format!(
+                        "Address is in {} subdistrict {}",
+                        school_name, subdistrict.code
+                    ),
+                    district: Some(subdistrict.clone()),
+                })
+            }
+        }
+    }
+
+    /// Finds a school district by Minnesota's numbering (ISD #1), a Census GEOID, the NCES
+    /// district id the GEOID ends in, or its name
+    fn school_district(&self, school_district: &str) -> Option<&BallotDistrict> {
+        self.districts.iter().find(|d| {
+            d.district_type == DistrictType::School
+                && d.in_force
+                && d.school_district.is_none()
+                && (d.code == normalize_district_code(school_district)
+                    || d.geoid.as_deref().is_some_and(|geoid| {
+                        geoid == school_district.trim()
+                            || geoid.get(2..) == Some(school_district.trim())
+                    })
+                    || d.name
+                        .as_deref()
+                        .is_some_and(|n| names_match(n, school_district, " School District")))
+        })
+    }
+}
+
+/// Reduces the ways a district gets written ("District 03", "Ward 3", "W-03", "Soil and
+/// Water Supervisor (District 3)") to the code itself, e.g. `3`
+pub fn normalize_district_code(code: &str) -> String {
+    if code.contains('#') {
+        // School districts are told apart by their prefix, e.g. ISD #1 and SSD #1
+        return code.trim().to_uppercase();
+    }
+    let code = match (code.find('('), code.rfind(')')) {
+        (Some(start), Some(end)) if start < end => &code[start + 1..end],
+        _ => code,
+    };
+    let token = code.split_whitespace().last().unwrap_or_default();
+    let token: String = token
+        .rsplit('-')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect();
+    let token = if token.chars().any(|c| c.is_ascii_digit()) {
+        token.trim_start_matches(|c: char| !c.is_ascii_digit())
+    } else {
+        token.as_str()
+    };
+    let trimmed = token.trim_start_matches('0');
+    if trimmed.is_empty() && !token.is_empty() {
+        "0".to_string()
+    } else {
+        trimmed.to_uppercase()
+    }
+}
+
+/// Compares place names ignoring case and an optional suffix, e.g. "Hennepin" and "Hennepin County"
+fn names_match(a: &str, b: &str, suffix: &str) -> bool {
+    let strip = |name: &str| {
+        let name = name.trim().to_lowercase();
+        let suffix = suffix.to_lowercase();
+        name.strip_suffix(suffix.as_str())
+            .map(str::to_string)
+            .unwrap_or(name)
+            .replace("twp", "township")
+    };
+    strip(a) == strip(b)
+}
+
+fn parent_matches(office: Option<&str>, district: Option<&str>) -> bool {
+    match district {
+        None => true,
+        Some(district) => office
+            .is_some_and(|o| names_match(o, district, " County") || names_match(o, district, "")),
+    }
+}
+
+fn county_name(county: &str) -> String {
+    if county.to_lowercase().ends_with(" county") {
+        county.to_string()
+    } else {
+        format!("{} County", county)
+    }
+}
+
+fn district_explanation(district: &BallotDistrict) -> String {
+    let name = district.name.clone().unwrap_or_else(|| {
+        let code = &district.code;
+        match district.district_type {
+            DistrictType::UsCongressional => format!("Congressional District {}", code),
+            DistrictType::StateSenate => format!("State Senate District {}", code),
+            DistrictType::StateHouse => format!("State House District {}", code),
+            DistrictType::County => match &district.county {
+                Some(county) => format!("{} Commissioner District {}", county_name(county), code),
+                None => format!("Commissioner District {}", code),
+            },
+            DistrictType::City => match &district.municipality {
+                Some(municipality) => format!("{} Ward {}", municipality, code),
+                None => format!("Ward {}", code),
+            },
+            DistrictType::Judicial => format!("Judicial District {}", code),
+            DistrictType::SoilAndWater => {
+                format!("Soil and Water Conservation District {}", code)
+            }
+            DistrictType::Hospital => format!("Hospital District {}", code),
+            DistrictType::Transportation => format!("Transportation District {}", code),
+            DistrictType::School => format!("School District {}", code),
+        }
+    });
+    match district.vintage {
+        Some(vintage) => format!("Address is in {} ({} map)", name, vintage),
+        None => format!("Address is in {}", name),
+    }
+}
+
+impl BallotDistrict {
+    /// A district from an address's own data rather than a boundary file
+    fn from_code(district_type: DistrictType, code: Option<String>) -> Option<Self> {
+        let code = code.filter(|c| !c.trim().is_empty())?;
+        Some(Self {
+            district_type,
+            code: normalize_district_code(&code),
+            name: None,
+            geoid: None,
+            county: None,
+            municipality: None,
+            school_district: None,
+            vintage: None,
+            in_force: true,
+        })
+    }
+}
+
+/// Adds a district from address data unless boundaries already cover its type
+fn push_fallback(districts: &mut Vec<BallotDistrict>, district: Option<BallotDistrict>) {
+    if let Some(district) = district {
+        if !districts
+            .iter()
+            .any(|d| d.in_force && d.district_type == district.district_type)
+        {
+            districts.push(district);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn office(election_scope: ElectionScope, district_type: Option<DistrictType>) -> Office {
+        Office {
+            id: uuid::Uuid::new_v4(),
+            slug: "office".to_string(),
+            title: "Office".to_string(),
+            subtitle: None,
+            subtitle_short: None,
+            name: None,
+            office_type: None,
+            district: None,
+            district_type,
+            district_vintage: None,
+            hospital_district: None,
+            school_district: None,
+            chamber: None,
+            political_scope: PoliticalScope::Local,
+            election_scope,
+            state: Some(State::MN),
+            county: None,
+            municipality: None,
+            term_length: None,
+            seat: None,
+            priority: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    fn district(
+        district_type: DistrictType,
+        code: &str,
+        vintage: i32,
+        in_force: bool,
+    ) -> BallotDistrict {
+        BallotDistrict {
+            district_type,
+            code: code.to_string(),
+            name: None,
+            geoid: None,
+            county: None,
+            municipality: None,
+            school_district: None,
+            vintage: Some(vintage),
+            in_force,
+        }
+    }
+
+    #[test]
+    fn test_normalize_district_code() {
+        assert_eq!(normalize_district_code("District 03"), "3");
+        assert_eq!(normalize_district_code("Ward 3"), "3");
+        assert_eq!(normalize_district_code("MPLS W-03"), "3");
+        assert_eq!(normalize_district_code("07a"), "7A");
+        assert_eq!(
+            normalize_district_code("Soil and Water Supervisor (District 4)"),
+            "4"
+        );
+        assert_eq!(normalize_district_code("East"), "EAST");
+        assert_eq!(normalize_district_code("00"), "0");
+        assert_eq!(normalize_district_code("ssd #1"), "SSD #1");
+    }
+
+    #[test]
+    fn test_match_office() {
+        let address = BallotAddress {
+            state: State::MN,
+            county: Some("Hennepin County".to_string()),
+            municipality: Some("Minneapolis".to_string()),
+            districts: vec![
+                district(DistrictType::StateHouse, "59B", 2022, true),
+                district(DistrictType::StateHouse, "60A", 2012, false),
+                BallotDistrict {
+                    county: Some("Hennepin County".to_string()),
+                    ..district(DistrictType::County, "3", 2022, true)
+                },
+            ],
+        };
+
+        let mut house = office(ElectionScope::District, Some(DistrictType::StateHouse));
+        house.district = Some("59B".to_string());
+        let house_match = address.match_office(&house).unwrap();
+        assert_eq!(house_match.reason, BallotMatchReason::District);
+        assert_eq!(
+            house_match.explanation,
+            "Address is in State House District 59B (2022 map)"
+        );
+
+        // Offices still elected from the old map only match under it
+        house.district = Some("60A".to_string());
+        assert!(address.match_office(&house).is_none());
+        house.district_vintage = Some(2012);
+        assert!(address.match_office(&house).is_some());
+
+        let mut commissioner = office(ElectionScope::District, Some(DistrictType::County));
+        commissioner.district = Some("District 3".to_string());
+        commissioner.county = Some("Ramsey".to_string());
+        assert!(address.match_office(&commissioner).is_none());
+        commissioner.county = Some("Hennepin".to_string());
+        assert!(address.match_office(&commissioner).is_some());
+
+        let mut sheriff = office(ElectionScope::County, None);
+        sheriff.county = Some("Hennepin".to_string());
+        assert_eq!(
+            address.match_office(&sheriff).unwrap().explanation,
+            "Countywide office in Hennepin County"
+        );
+
+        let mut governor = office(ElectionScope::State, None);
+        assert_eq!(
+            address.match_office(&governor).unwrap().reason,
+            BallotMatchReason::Statewide
+        );
+        governor.state = Some(State::WI);
+        assert!(address.match_office(&governor).is_none());
+    }
+
+    #[test]
+    fn test_match_school_district() {
+        let address = BallotAddress {
+            state: State::CO,
+            county: Some("Denver County".to_string()),
+            municipality: Some("Denver".to_string()),
+            districts: vec![BallotDistrict {
+                name: Some("Denver County School District 1".to_string()),
+                geoid: Some("0803360".to_string()),
+                ..district(DistrictType::School, "0803360", 2024, true)
+            }],
+        };
+
+        let mut school_board = office(ElectionScope::District, Some(DistrictType::School));
+        school_board.state = Some(State::CO);
+        for school_district in ["0803360", "03360", "Denver County School District 1"] {
+            school_board.school_district = Some(school_district.to_string());
+            assert_eq!(
+                address.match_office(&school_board).unwrap().explanation,
+                "Address is in Denver County School District 1"
+            );
+        }
+        school_board.school_district = Some("0803330".to_string());
+        assert!(address.match_office(&school_board).is_none());
+    }
+
+    #[test]
+    fn test_match_measure() {
+        let address = BallotAddress {
+            state: State::MN,
+            county: Some("Hennepin County".to_string()),
+            municipality: Some("Minneapolis".to_string()),
+            districts: vec![district(DistrictType::School, "ISD #1", 2024, true)],
+        };
+        let area =
+            |election_scope, county: Option<&str>, municipality: Option<&str>| BallotMeasureArea {
+                id: uuid::Uuid::new_v4(),
+                election_scope,
+                county: county.map(str::to_string),
+                municipality: municipality.map(str::to_string),
+                school_district: None,
+            };
+
+        assert!(address.match_measure(&area(Some(ElectionScope::State), None, None)));
+        assert!(address.match_measure(&area(None, None, None)));
+        assert!(address.match_measure(&area(None, Some("Hennepin"), None)));
+        assert!(!address.match_measure(&area(None, Some("Ramsey"), None)));
+        assert!(address.match_measure(&area(None, Some("Hennepin"), Some("Minneapolis"))));
+        assert!(!address.match_measure(&area(None, Some("Hennepin"), Some("Edina"))));
+        assert!(!address.match_measure(&area(Some(ElectionScope::City), None, None)));
+
+        let mut school_question = area(Some(ElectionScope::District), None, None);
+        assert!(!address.match_measure(&school_question));
+        school_question.school_district = Some("ISD #1".to_string());
+        assert!(address.match_measure(&school_question));
+        school_question.school_district = Some("ISD #728".to_string());
+        assert!(!address.match_measure(&school_question));
+    }
+
+    #[test]
+    fn test_ballot_section_of_office() {
+        let mut senator = office(ElectionScope::State, None);
+        senator.political_scope = PoliticalScope::Federal;
+        assert_eq!(BallotSection::of_office(&senator), BallotSection::Federal);
+
+        let mut judge = office(ElectionScope::State, None);
+        judge.title = "Supreme Court Justice".to_string();
+        assert_eq!(BallotSection::of_office(&judge), BallotSection::Judicial);
+
+        let mut school_board = office(ElectionScope::District, Some(DistrictType::School));
+        school_board.school_district = Some("ISD #1".to_string());
+        assert_eq!(
+            BallotSection::of_office(&school_board),
+            BallotSection::School
+        );
+
+        let ward = office(ElectionScope::District, Some(DistrictType::City));
+        assert_eq!(BallotSection::of_office(&ward), BallotSection::City);
+    }
+}
@@ -1,5 +1,5 @@
-use super::enums::{BallotMeasureStatus, State};
-use crate::{DateTime, IssueTag, PoliticalScope, PopularitySort};
+use super::enums::{BallotMeasureStatus, PassageThreshold, State};
+use crate::{DateTime, IssueTag, Page, PageRequest, PoliticalScope, PopularitySort, SortDirection};
 use async_graphql::InputObject;
 use sqlx::postgres::PgPool;
 use sqlx::FromRow;
@@ -17,6 +17,7 @@ pub struct BallotMeasure {
     pub ballot_measure_code: String,
     pub measure_type: String, //perhaps make enum later
     pub definitions: String,  // markdown list of bulleted items
+    pub passage_threshold: PassageThreshold,
     //optional fields
     pub yes_votes: Option<i32>,
     pub no_votes: Option<i32>,
@@ -40,12 +41,23 @@ pub struct UpsertBallotMeasureInput {
     pub ballot_measure_code: Option<String>,
     pub measure_type: Option<String>,
     pub definitions: Option<String>,
+    pub passage_threshold: Option<PassageThreshold>,
     pub description: Option<String>,
     pub official_summary: Option<String>,
     pub populist_summary: Option<String>,
     pub full_text_url: Option<String>,
 }
 
+/// Results for one measure from a results file, matched on `ballot_measure_code`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BallotMeasureResultRow {
+    pub ballot_measure_code: String,
+    pub yes_votes: i32,
+    pub no_votes: i32,
+    pub num_precincts_reporting: Option<i32>,
+    pub total_precincts: Option<i32>,
+}
+
 #[derive(InputObject, Default, Debug)]
 pub struct BallotMeasureFilter {
     query: Option<String>,
@@ -74,8 +86,8 @@ impl BallotMeasure {
                 INSERT INTO ballot_measure 
                 (id, election_id, slug, title, status, description, official_summary, 
                 populist_summary, full_text_url, state, ballot_measure_code, 
-                measure_type, definitions) 
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+                measure_type, definitions, passage_threshold) 
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, COALESCE($14, 'simple_majority'::passage_threshold))
                 ON CONFLICT (id) DO UPDATE SET
                     slug = COALESCE($3, ballot_measure.slug),
                     title = COALESCE($4, ballot_measure.title),
@@ -87,8 +99,9 @@ impl BallotMeasure {
                     state = COALESCE($10, ballot_measure.state),
                     ballot_measure_code = COALESCE($11, ballot_measure.ballot_measure_code),
                     measure_type = COALESCE($12, ballot_measure.measure_type),
-                    definitions = COALESCE($13, ballot_measure.definitions)
-                RETURNING id, election_id, slug, title, status AS "status: BallotMeasureStatus", description, official_summary, populist_summary, full_text_url, state AS "state:State", ballot_measure_code, measure_type, definitions, yes_votes, no_votes, num_precincts_reporting, total_precincts, created_at, updated_at
+                    definitions = COALESCE($13, ballot_measure.definitions),
+                    passage_threshold = COALESCE($14, ballot_measure.passage_threshold)
+                RETURNING id, election_id, slug, title, status AS "status: BallotMeasureStatus", description, official_summary, populist_summary, full_text_url, state AS "state:State", ballot_measure_code, measure_type, definitions, passage_threshold AS "passage_threshold:PassageThreshold", yes_votes, no_votes, num_precincts_reporting, total_precincts, created_at, updated_at
             "#,
             id,
             election_id,
@@ -102,7 +115,8 @@ impl BallotMeasure {
             input.state as Option<State>,
             input.ballot_measure_code,
             input.measure_type,
-            input.definitions
+            input.definitions,
+            input.passage_threshold as Option<PassageThreshold>
         )
         .fetch_one(db_pool)
         .await?;
@@ -110,6 +124,45 @@ impl BallotMeasure {
         Ok(record)
     }
 
+    /// Updates vote counts and reporting for the election's measures. Codes are compared
+    /// ignoring case, and the codes that matched are returned so callers can report the rest.
+    pub async fn update_results(
+        db_pool: &PgPool,
+        election_id: uuid::Uuid,
+        rows: &[BallotMeasureResultRow],
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let codes: Vec<String> = rows.iter().map(|r| r.ballot_measure_code.clone()).collect();
+        let yes_votes: Vec<i32> = rows.iter().map(|r| r.yes_votes).collect();
+        let no_votes: Vec<i32> = rows.iter().map(|r| r.no_votes).collect();
+        let reporting: Vec<Option<i32>> = rows.iter().map(|r| r.num_precincts_reporting).collect();
+        let total_precincts: Vec<Option<i32>> = rows.iter().map(|r| r.total_precincts).collect();
+
+        let matched = sqlx::query_scalar!(
+            r#"
+                UPDATE ballot_measure bm SET
+                    yes_votes = r.yes_votes,
+                    no_votes = r.no_votes,
+                    num_precincts_reporting = r.num_precincts_reporting,
+                    total_precincts = r.total_precincts
+                FROM UNNEST($2::text[], $3::int[], $4::int[], $5::int[], $6::int[])
+                    AS r(code, yes_votes, no_votes, num_precincts_reporting, total_precincts)
+                WHERE bm.election_id = $1
+                AND UPPER(bm.ballot_measure_code) = UPPER(TRIM(r.code))
+                RETURNING r.code AS "code!"
+            "#,
+            election_id,
+            &codes,
+            &yes_votes,
+            &no_votes,
+            &reporting as &[Option<i32>],
+            &total_precincts as &[Option<i32>],
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        Ok(matched)
+    }
+
     pub async fn delete(db_pool: &PgPool, id: uuid::Uuid) -> Result<(), sqlx::Error> {
         sqlx::query!("DELETE FROM ballot_measure WHERE id=$1", id)
             .execute(db_pool)
@@ -131,6 +184,7 @@ impl BallotMeasure {
                     ballot_measure_code, 
                     measure_type, 
                     definitions, 
+                    passage_threshold AS "passage_threshold:PassageThreshold",
                     ballot_measure.description, 
                     official_summary, 
                     populist_summary, 
@@ -151,12 +205,8 @@ impl BallotMeasure {
         Ok(record)
     }
 
-    pub async fn filter(
-        db_pool: &PgPool,
-        filter: &BallotMeasureFilter,
-        sort: &BallotMeasureSort,
-    ) -> Result<Vec<Self>, sqlx::Error> {
-        let query = format!(
+    fn filter_query(sort: &BallotMeasureSort) -> String {
+        format!(
             r#"
                 SELECT 
                     ballot_measure.id, 
@@ -168,6 +218,7 @@ impl BallotMeasure {
                     ballot_measure_code, 
                     measure_type, 
                     definitions, 
+                    passage_threshold,
                     ballot_measure.description, 
                     official_summary, 
                     populist_summary, 
@@ -177,7 +228,8 @@ impl BallotMeasure {
                     num_precincts_reporting, 
                     total_precincts, 
                     ballot_measure.created_at, 
-                    ballot_measure.updated_at 
+                    ballot_measure.updated_at,
+                    {sort_value} AS sort_value
                 FROM ballot_measure
                 LEFT JOIN ballot_measure_public_votes bpv ON bpv.ballot_measure_id = ballot_measure.id
                 JOIN election e ON e.id = ballot_measure.election_id,
@@ -214,29 +266,56 @@ impl BallotMeasure {
                     rank_description,
                     t.tag_array
                 )
-                ORDER BY {order_by}
-                LIMIT 20
-                
             "#,
-            order_by = match sort.popularity {
-                Some(PopularitySort::MostPopular) => "rank_ballot_measure_code, rank_title, rank_description, COUNT(bpv.*) DESC NULLS LAST",
+            sort_value = match sort.popularity {
+                Some(PopularitySort::MostPopular) => "COUNT(bpv.*)::float8",
                 Some(PopularitySort::MostSupported) =>
-                    "rank_ballot_measure_code, rank_title, rank_description, SUM(CASE WHEN bpv.position = 'support' THEN 1 ELSE 0 END)DESC NULLS LAST",
+                    "SUM(CASE WHEN bpv.position = 'support' THEN 1 ELSE 0 END)::float8",
                 Some(PopularitySort::MostOpposed) =>
-                    "rank_ballot_measure_code, rank_title, rank_description, SUM(CASE WHEN bpv.position = 'oppose' THEN 1 ELSE 0 END) DESC NULLS LAST",
-                None => "rank_ballot_measure_code, rank_title, rank_description DESC NULLS LAST",
+                    "SUM(CASE WHEN bpv.position = 'oppose' THEN 1 ELSE 0 END)::float8",
+                None => "(COALESCE(rank_ballot_measure_code, 0) + COALESCE(rank_title, 0) + COALESCE(rank_description, 0))::float8",
             }
+        )
+    }
+
+    /// Returns a single page of ballot measures, most popular or most relevant first
+    pub async fn filter(
+        db_pool: &PgPool,
+        filter: &BallotMeasureFilter,
+        sort: &BallotMeasureSort,
+        page: &PageRequest,
+    ) -> Result<Page<Self>, sqlx::Error> {
+        let query = page.paginate(&Self::filter_query(sort), "float8", SortDirection::Desc, 6);
+
+        let rows = page
+            .bind(
+                sqlx::query(&query)
+                    .bind(filter.query.to_owned())
+                    .bind(filter.status)
+                    .bind(filter.state)
+                    .bind(filter.year)
+                    .bind(filter.issue_tag.to_owned()),
+            )
+            .fetch_all(db_pool)
+            .await?;
+
+        Page::from_rows(rows, page)
+    }
+
+    pub async fn count(db_pool: &PgPool, filter: &BallotMeasureFilter) -> Result<i64, sqlx::Error> {
+        let query = format!(
+            "SELECT COUNT(*) FROM ({}) AS filtered",
+            Self::filter_query(&BallotMeasureSort::default())
         );
 
-        let records = sqlx::query_as::<_, BallotMeasure>(&query)
+        sqlx::query_scalar(&query)
             .bind(filter.query.to_owned())
             .bind(filter.status)
             .bind(filter.state)
             .bind(filter.year)
             .bind(filter.issue_tag.to_owned())
-            .fetch_all(db_pool)
-            .await?;
-        Ok(records)
+            .fetch_one(db_pool)
+            .await
     }
 
     pub async fn issue_tags(
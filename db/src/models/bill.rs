@@ -1,6 +1,7 @@
 use crate::{
     models::enums::{ArgumentPosition, AuthorType, BillStatus},
-    Argument, Chamber, CreateArgumentInput, DateTime, IssueTag, Politician,
+    Argument, Chamber, CreateArgumentInput, DateTime, ExternalId, ExternalIdEntityType,
+    ExternalIdSource, IssueTag, Page, PageRequest, Politician, SortDirection,
 };
 use async_graphql::{Enum, InputObject, SimpleObject};
 use chrono::NaiveDate;
@@ -257,14 +258,8 @@ impl Bill {
         Ok(())
     }
 
-    pub async fn filter(
-        db_pool: &PgPool,
-        filter: &BillFilter,
-        sort: &BillSort,
-    ) -> Result<Vec<Self>, sqlx::Error> {
-        let search_query = filter.query.to_owned();
-
-        let query = format!(
+    fn filter_query(sort: &BillSort) -> String {
+        format!(
             r#"
             SELECT
                 bill.id,
@@ -293,9 +288,7 @@ impl Bill {
                 bill.attributes,
                 bill.created_at,
                 bill.updated_at,
-                rank_bill_number,
-                rank_title,
-                rank_description
+                {sort_value} AS sort_value
             FROM
             bill
             LEFT JOIN bill_public_votes bpv ON bill.id = bpv.bill_id
@@ -336,30 +329,58 @@ impl Bill {
                 rank_description,
                 t.tag_array
             )
-            ORDER BY {order_by}
-            LIMIT 20
         "#,
-            order_by = match sort.popularity {
-                Some(PopularitySort::MostPopular) => "rank_bill_number, rank_title, rank_description, COUNT(bpv.*) DESC NULLS LAST",
+            sort_value = match sort.popularity {
+                Some(PopularitySort::MostPopular) => "COUNT(bpv.*)::float8",
                 Some(PopularitySort::MostSupported) =>
-                    "rank_bill_number, rank_title, rank_description, SUM(CASE WHEN bpv.position = 'support' THEN 1 ELSE 0 END)DESC NULLS LAST",
+                    "SUM(CASE WHEN bpv.position = 'support' THEN 1 ELSE 0 END)::float8",
                 Some(PopularitySort::MostOpposed) =>
-                    "rank_bill_number, rank_title, rank_description, SUM(CASE WHEN bpv.position = 'oppose' THEN 1 ELSE 0 END) DESC NULLS LAST",
-                None => "rank_bill_number, rank_title, rank_description DESC NULLS LAST",
+                    "SUM(CASE WHEN bpv.position = 'oppose' THEN 1 ELSE 0 END)::float8",
+                None => "(COALESCE(rank_bill_number, 0) + COALESCE(rank_title, 0) + COALESCE(rank_description, 0))::float8",
             }
+        )
+    }
+
+    /// Returns a single page of bills, most popular or most relevant first
+    pub async fn filter(
+        db_pool: &PgPool,
+        filter: &BillFilter,
+        sort: &BillSort,
+        page: &PageRequest,
+    ) -> Result<Page<Self>, sqlx::Error> {
+        let query = page.paginate(&Self::filter_query(sort), "float8", SortDirection::Desc, 7);
+
+        let rows = page
+            .bind(
+                sqlx::query(&query)
+                    .bind(filter.query.to_owned())
+                    .bind(filter.status as Option<BillStatus>)
+                    .bind(filter.political_scope)
+                    .bind(filter.state)
+                    .bind(filter.year)
+                    .bind(filter.issue_tag.clone()),
+            )
+            .fetch_all(db_pool)
+            .await?;
+
+        Page::from_rows(rows, page)
+    }
+
+    pub async fn count(db_pool: &PgPool, filter: &BillFilter) -> Result<i64, sqlx::Error> {
+        let query = format!(
+            "SELECT COUNT(*) FROM ({}) AS filtered",
+            Self::filter_query(&BillSort::default())
         );
 
-        let records = sqlx::query_as::<_, Bill>(&query)
-            .bind(search_query)
+        sqlx::query_scalar(&query)
+            .bind(filter.query.to_owned())
             .bind(filter.status as Option<BillStatus>)
             .bind(filter.political_scope)
             .bind(filter.state)
             .bind(filter.year)
             .bind(filter.issue_tag.clone())
-            .fetch_all(db_pool)
-            .await?;
-
-        Ok(records)
+            .fetch_one(db_pool)
+            .await
     }
 
     pub async fn find_by_id(db_pool: &PgPool, id: uuid::Uuid) -> Result<Self, sqlx::Error> {
@@ -393,12 +414,26 @@ impl Bill {
         Ok(records)
     }
 
+    /// Looks up a bill by its LegiScan or VoteSmart bill id
+    pub async fn find_by_external_id(
+        db_pool: &PgPool,
+        source: ExternalIdSource,
+        value: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        ExternalId::find_entity(db_pool, ExternalIdEntityType::Bill, source, value).await
+    }
+
+    /// Also resolves slugs the record had before it was renamed
     pub async fn find_by_slug(db_pool: &PgPool, slug: &str) -> Result<Self, sqlx::Error> {
         let record = sqlx::query_as!(
             Bill,
             r#"
                 SELECT id, slug, title, populist_title, bill_number, status AS "status: BillStatus", description, session_id, official_summary, populist_summary, full_text_url, legiscan_bill_id, legiscan_committee, legiscan_last_action, legiscan_last_action_date, legiscan_data, history, state AS "state: State", votesmart_bill_id, political_scope AS "political_scope: PoliticalScope", bill_type, chamber AS "chamber: Chamber", attributes, created_at, updated_at FROM bill
-                WHERE slug = $1
+                WHERE slug = $1 OR id = (
+                    SELECT entity_id FROM slug_history WHERE entity_type = 'bill' AND slug = $1
+                )
+                ORDER BY slug = $1 DESC
+                LIMIT 1
             "#,
             slug
         )
@@ -0,0 +1,192 @@
+use crate::{models::enums::State, Address, Coordinates, DateTime, DistrictType};
+use sqlx::{FromRow, PgConnection, PgPool};
+
+/// A district's polygon for one vintage of the boundary files. The geometry itself stays in
+/// the database and is only used for point-in-polygon lookups.
+#[derive(FromRow, Debug, Clone)]
+pub struct DistrictBoundary {
+    pub id: uuid::Uuid,
+    pub state: State,
+    pub district_type: DistrictType,
+    pub code: String,
+    pub name: Option<String>,
+    /// County a sub-county district (e.g. a commissioner district) belongs to
+    pub county: Option<String>,
+    /// City a sub-city district (e.g. a ward) belongs to
+    pub municipality: Option<String>,
+    pub vintage: i32,
+    pub geoid: Option<String>,
+    /// File layer the boundary was imported from, e.g. `tiger:sldu`
+    pub source: String,
+    /// Map the boundary is one vintage of, e.g. `tiger:cd` for `tiger:cd118` and `tiger:cd119`
+    pub layer: String,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Debug, Clone)]
+pub struct UpsertDistrictBoundaryInput {
+    pub state: State,
+    pub district_type: DistrictType,
+    pub code: String,
+    pub name: Option<String>,
+    pub county: Option<String>,
+    pub municipality: Option<String>,
+    pub vintage: i32,
+    pub geoid: Option<String>,
+    pub source: String,
+    pub layer: String,
+    /// GeoJSON Polygon or MultiPolygon in WGS 84
+    pub geometry: String,
+}
+
+impl DistrictBoundary {
+    pub async fn upsert(
+        conn: &mut PgConnection,
+        input: &UpsertDistrictBoundaryInput,
+    ) -> Result<uuid::Uuid, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+                INSERT INTO district_boundary (state, district_type, code, name, county, municipality, vintage, geoid, source, layer, geom)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, ST_Multi(ST_Force2D(ST_SetSRID(ST_GeomFromGeoJSON($11), 4326))))
+                ON CONFLICT (state, district_type, code, (COALESCE(county, '')), (COALESCE(municipality, '')), vintage) DO UPDATE SET
+                    name = EXCLUDED.name,
+                    geoid = EXCLUDED.geoid,
+                    source = EXCLUDED.source,
+                    layer = EXCLUDED.layer,
+                    geom = EXCLUDED.geom
+                RETURNING id
+            "#,
+            input.state as State,
+            input.district_type as DistrictType,
+            input.code,
+            input.name,
+            input.county,
+            input.municipality,
+            input.vintage,
+            input.geoid,
+            input.source,
+            input.layer,
+            input.geometry,
+        )
+        .fetch_one(conn)
+        .await
+    }
+
+    /// Every district containing a point. Each imported layer answers from its latest vintage,
+    /// or the latest one no newer than `vintage` when given.
+    pub async fn containing_point(
+        db_pool: &PgPool,
+        coordinates: &Coordinates,
+        vintage: Option<i32>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DistrictBoundary,
+            r#"
+                SELECT
+                    b.id,
+                    b.state AS "state:State",
+                    b.district_type AS "district_type:DistrictType",
+                    b.code,
+                    b.name,
+                    b.county,
+                    b.municipality,
+                    b.vintage,
+                    b.geoid,
+                    b.source,
+                    b.layer,
+                    b.created_at,
+                    b.updated_at
+                FROM district_boundary b
+                WHERE ST_Contains(b.geom, ST_SetSRID(ST_MakePoint($1, $2), 4326))
+                AND b.vintage = (
+                    SELECT MAX(v.vintage) FROM district_boundary v
+                    WHERE v.state = b.state AND v.layer = b.layer
+                    AND ($3::INTEGER IS NULL OR v.vintage <= $3)
+                )
+                ORDER BY b.district_type, b.county NULLS FIRST, b.municipality NULLS FIRST, b.code
+            "#,
+            coordinates.longitude,
+            coordinates.latitude,
+            vintage,
+        )
+        .fetch_all(db_pool)
+        .await
+    }
+}
+
+/// The districts an address falls in, across every `DistrictType`
+#[derive(Debug, Clone, Default)]
+pub struct AddressDistricts {
+    pub boundaries: Vec<DistrictBoundary>,
+}
+
+impl AddressDistricts {
+    /// Name of the county the address is in
+    pub fn county(&self) -> Option<&str> {
+        self.boundaries
+            .iter()
+            .find(|b| b.district_type == DistrictType::County && b.county.is_none())
+            .and_then(|b| b.name.as_deref())
+    }
+
+    /// Name of the city or township the address is in
+    pub fn municipality(&self) -> Option<&str> {
+        self.boundaries
+            .iter()
+            .find(|b| b.district_type == DistrictType::City && b.municipality.is_none())
+            .and_then(|b| b.name.as_deref())
+    }
+
+    /// Districts of a type. For counties and cities these are the districts within them,
+    /// such as commissioner districts and wards, not the county or city itself.
+    pub fn of_type(&self, district_type: DistrictType) -> Vec<&DistrictBoundary> {
+        self.boundaries
+            .iter()
+            .filter(|b| b.district_type == district_type)
+            .filter(|b| match district_type {
+                DistrictType::County => b.county.is_some(),
+                DistrictType::City => b.municipality.is_some(),
+                _ => true,
+            })
+            .collect()
+    }
+
+    pub fn code(&self, district_type: DistrictType) -> Option<&str> {
+        self.of_type(district_type).first().map(|b| b.code.as_str())
+    }
+}
+
+impl Address {
+    /// Resolves the districts an address falls in from imported boundaries
+    pub async fn districts(
+        db_pool: &PgPool,
+        address_id: &uuid::Uuid,
+        vintage: Option<i32>,
+    ) -> Result<AddressDistricts, sqlx::Error> {
+        let location = sqlx::query!(
+            r#"
+                SELECT lon, lat FROM address WHERE id = $1
+            "#,
+            address_id,
+        )
+        .fetch_one(db_pool)
+        .await?;
+
+        let (Some(longitude), Some(latitude)) = (location.lon, location.lat) else {
+            return Ok(AddressDistricts::default());
+        };
+
+        let boundaries = DistrictBoundary::containing_point(
+            db_pool,
+            &Coordinates {
+                latitude,
+                longitude,
+            },
+            vintage,
+        )
+        .await?;
+
+        Ok(AddressDistricts { boundaries })
+    }
+}
@@ -0,0 +1,497 @@
+use crate::{
+    models::enums::State, Address, AddressDistricts, Coordinates, DateTime, DistrictBoundary,
+    DistrictType,
+};
+use chrono::{Datelike, NaiveDate};
+use sqlx::{FromRow, PgConnection, PgPool};
+
+/// When one vintage of a state's district maps takes effect for elections, per boundary layer
+#[derive(FromRow, Debug, Clone)]
+pub struct DistrictVintage {
+    pub id: uuid::Uuid,
+    pub state: State,
+    /// Boundary layer the vintage is of, see `DistrictBoundary::layer`
+    pub layer: String,
+    pub district_type: DistrictType,
+    pub vintage: i32,
+    pub effective_from: NaiveDate,
+    pub addresses_refreshed_at: Option<DateTime>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+impl DistrictVintage {
+    /// Records that boundaries for a vintage were (re)loaded, flagging addresses in the state
+    /// for reassignment. Without `effective_from` a new vintage takes effect January 1st of
+    /// its year.
+    pub async fn upsert(
+        conn: &mut PgConnection,
+        state: State,
+        layer: &str,
+        district_type: DistrictType,
+        vintage: i32,
+        effective_from: Option<NaiveDate>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            DistrictVintage,
+            r#"
+                INSERT INTO district_vintage (state, layer, district_type, vintage, effective_from)
+                VALUES ($1, $2, $3, $4, COALESCE($5, MAKE_DATE($4, 1, 1)))
+                ON CONFLICT (state, layer, vintage) DO UPDATE SET
+                    district_type = EXCLUDED.district_type,
+                    effective_from = COALESCE($5, district_vintage.effective_from),
+                    addresses_refreshed_at = NULL
+                RETURNING id, state AS "state:State", layer, district_type AS "district_type:DistrictType", vintage, effective_from, addresses_refreshed_at, created_at, updated_at
+            "#,
+            state as State,
+            layer,
+            district_type as DistrictType,
+            vintage,
+            effective_from,
+        )
+        .fetch_one(conn)
+        .await
+    }
+
+    /// Vintage of a state's maps in a layer in force on a date, e.g. an election date
+    pub async fn in_force(
+        db_pool: &PgPool,
+        state: State,
+        layer: &str,
+        date: NaiveDate,
+    ) -> Result<Option<i32>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT district_vintage_in_force($1, $2, $3) AS "vintage""#,
+            state as State,
+            layer,
+            date,
+        )
+        .fetch_one(db_pool)
+        .await
+    }
+
+    /// Vintages loaded since addresses were last reassigned, and vintages that have taken
+    /// effect since, so the districts stored on addresses switch over to them
+    pub async fn pending_refresh(db_pool: &PgPool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DistrictVintage,
+            r#"
+                SELECT id, state AS "state:State", layer, district_type AS "district_type:DistrictType", vintage, effective_from, addresses_refreshed_at, created_at, updated_at
+                FROM district_vintage
+                WHERE addresses_refreshed_at IS NULL
+                OR (addresses_refreshed_at < effective_from AND effective_from <= CURRENT_DATE)
+                ORDER BY state, layer, vintage
+            "#,
+        )
+        .fetch_all(db_pool)
+        .await
+    }
+
+    /// Reassigns every address in the state to this vintage's districts in its layer. When
+    /// the vintage is the one in force today, the districts stored on the address itself are
+    /// updated too, and cleared for addresses outside every district of the vintage. Returns
+    /// the number of addresses assigned a district.
+    pub async fn refresh_addresses(&self, db_pool: &PgPool) -> Result<u64, sqlx::Error> {
+        let mut tx = db_pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+                DELETE FROM address_district ad
+                USING address a, district_boundary b
+                WHERE ad.address_id = a.id
+                AND ad.district_boundary_id = b.id
+                AND a.state = $1::state::text
+                AND b.layer = $2
+                AND ad.vintage = $3
+            "#,
+            self.state as State,
+            self.layer,
+            self.vintage,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let assigned = sqlx::query!(
+            r#"
+                INSERT INTO address_district (address_id, district_boundary_id, district_type, vintage, code, county, municipality)
+                SELECT a.id, b.id, b.district_type, b.vintage, b.code, b.county, b.municipality
+                FROM address a
+                JOIN district_boundary b ON b.state = $1
+                    AND b.layer = $2
+                    AND b.vintage = $3
+                    AND ST_Contains(b.geom, ST_SetSRID(ST_MakePoint(a.lon, a.lat), 4326))
+                WHERE a.state = $1::state::text AND a.lon IS NOT NULL AND a.lat IS NOT NULL
+                ON CONFLICT DO NOTHING
+            "#,
+            self.state as State,
+            self.layer,
+            self.vintage,
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        sqlx::query!(
+            r#"
+                WITH district AS (
+                    SELECT a.id, (
+                        SELECT ad.code FROM address_district ad
+                        JOIN district_boundary b ON b.id = ad.district_boundary_id
+                        WHERE ad.address_id = a.id AND b.layer = $2 AND ad.vintage = $4
+                        ORDER BY ad.code
+                        LIMIT 1
+                    ) AS code
+                    FROM address a
+                    WHERE a.state = $1::state::text AND a.lon IS NOT NULL AND a.lat IS NOT NULL
+                )
+                UPDATE address a SET
+                    congressional_district = CASE WHEN $3 = 'us_congressional' THEN d.code ELSE a.congressional_district END,
+                    state_senate_district = CASE WHEN $3 = 'state_senate' THEN d.code ELSE a.state_senate_district END,
+                    state_house_district = CASE WHEN $3 = 'state_house' THEN d.code ELSE a.state_house_district END
+                FROM district d
+                WHERE d.id = a.id
+                AND $3 IN ('us_congressional', 'state_senate', 'state_house')
+                AND district_vintage_in_force($1, $2, CURRENT_DATE) = $4
+            "#,
+            self.state as State,
+            self.layer,
+            self.district_type as DistrictType,
+            self.vintage,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE district_vintage SET addresses_refreshed_at = NOW() WHERE id = $1",
+            self.id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(assigned)
+    }
+}
+
+impl DistrictBoundary {
+    /// Every district containing a point under the maps in force on `date`. Layers without a
+    /// vintage in force fall back to the latest one from `date`'s year or earlier.
+    pub async fn containing_point_on(
+        db_pool: &PgPool,
+        coordinates: &Coordinates,
+        date: NaiveDate,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DistrictBoundary,
+            r#"
+                SELECT
+                    b.id,
+                    b.state AS "state:State",
+                    b.district_type AS "district_type:DistrictType",
+                    b.code,
+                    b.name,
+                    b.county,
+                    b.municipality,
+                    b.vintage,
+                    b.geoid,
+                    b.source,
+                    b.layer,
+                    b.created_at,
+                    b.updated_at
+                FROM district_boundary b
+                WHERE ST_Contains(b.geom, ST_SetSRID(ST_MakePoint($1, $2), 4326))
+                AND b.vintage = COALESCE(
+                    district_vintage_in_force(b.state, b.layer, $3),
+                    (
+                        SELECT MAX(v.vintage) FROM district_boundary v
+                        WHERE v.state = b.state AND v.layer = b.layer
+                        AND v.vintage <= $4
+                    )
+                )
+                ORDER BY b.district_type, b.county NULLS FIRST, b.municipality NULLS FIRST, b.code
+            "#,
+            coordinates.longitude,
+            coordinates.latitude,
+            date,
+            date.year(),
+        )
+        .fetch_all(db_pool)
+        .await
+    }
+}
+
+impl Address {
+    /// Assigns an address to its districts under every loaded vintage, for addresses saved
+    /// after the vintage's refresh job ran
+    pub async fn refresh_districts(
+        db_pool: &PgPool,
+        address_id: &uuid::Uuid,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = db_pool.begin().await?;
+
+        sqlx::query!(
+            "DELETE FROM address_district WHERE address_id = $1",
+            address_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+                INSERT INTO address_district (address_id, district_boundary_id, district_type, vintage, code, county, municipality)
+                SELECT a.id, b.id, b.district_type, b.vintage, b.code, b.county, b.municipality
+                FROM address a
+                JOIN district_boundary b ON b.state::text = a.state
+                    AND ST_Contains(b.geom, ST_SetSRID(ST_MakePoint(a.lon, a.lat), 4326))
+                WHERE a.id = $1 AND a.lon IS NOT NULL AND a.lat IS NOT NULL
+                ON CONFLICT DO NOTHING
+            "#,
+            address_id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// The districts an address falls in under the maps in force on `date`, e.g. an election
+    /// date. Uses the stored assignments, or looks them up when the address has none yet.
+    pub async fn districts_on(
+        db_pool: &PgPool,
+        address_id: &uuid::Uuid,
+        date: NaiveDate,
+    ) -> Result<AddressDistricts, sqlx::Error> {
+        let boundaries = sqlx::query_as!(
+            DistrictBoundary,
+            r#"
+                SELECT
+                    b.id,
+                    b.state AS "state:State",
+                    b.district_type AS "district_type:DistrictType",
+                    b.code,
+                    b.name,
+                    b.county,
+                    b.municipality,
+                    b.vintage,
+                    b.geoid,
+                    b.source,
+                    b.layer,
+                    b.created_at,
+                    b.updated_at
+                FROM address_district ad
+                JOIN district_boundary b ON b.id = ad.district_boundary_id
+                WHERE ad.address_id = $1
+                AND ad.vintage = COALESCE(
+                    district_vintage_in_force(b.state, b.layer, $2),
+                    (
+                        SELECT MAX(v.vintage) FROM district_boundary v
+                        WHERE v.state = b.state AND v.layer = b.layer
+                        AND v.vintage <= $3
+                    )
+                )
+                ORDER BY b.district_type, b.county NULLS FIRST, b.municipality NULLS FIRST, b.code
+            "#,
+            address_id,
+            date,
+            date.year(),
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        if !boundaries.is_empty() {
+            return Ok(AddressDistricts { boundaries });
+        }
+
+        let location = sqlx::query!("SELECT lon, lat FROM address WHERE id = $1", address_id)
+            .fetch_one(db_pool)
+            .await?;
+        let (Some(longitude), Some(latitude)) = (location.lon, location.lat) else {
+            return Ok(AddressDistricts::default());
+        };
+        let boundaries = DistrictBoundary::containing_point_on(
+            db_pool,
+            &Coordinates {
+                latitude,
+                longitude,
+            },
+            date,
+        )
+        .await?;
+        Ok(AddressDistricts { boundaries })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UpsertDistrictBoundaryInput;
+
+    /// A square around a point, as a GeoJSON Polygon
+    fn square(longitude: f64, latitude: f64) -> String {
+        let (w, e, s, n) = (
+            longitude - 0.1,
+            longitude + 0.1,
+            latitude - 0.1,
+            latitude + 0.1,
+        );
+        format!(
+            r#"{{"type":"Polygon","coordinates":[[[{w},{s}],[{e},{s}],[{e},{n}],[{w},{n}],[{w},{s}]]]}}"#
+        )
+    }
+
+    async fn import(
+        db_pool: &PgPool,
+        layer: &str,
+        district_type: DistrictType,
+        code: &str,
+        vintage: i32,
+        geometry: String,
+    ) -> DistrictVintage {
+        let mut conn = db_pool.acquire().await.unwrap();
+        DistrictBoundary::upsert(
+            &mut conn,
+            &UpsertDistrictBoundaryInput {
+                state: State::WY,
+                district_type,
+                code: code.to_string(),
+                name: None,
+                county: None,
+                municipality: None,
+                vintage,
+                geoid: None,
+                source: layer.to_string(),
+                layer: layer.to_string(),
+                geometry,
+            },
+        )
+        .await
+        .unwrap();
+        DistrictVintage::upsert(&mut conn, State::WY, layer, district_type, vintage, None)
+            .await
+            .unwrap()
+    }
+
+    async fn insert_address(
+        db_pool: &PgPool,
+        line_1: &str,
+        longitude: f64,
+        latitude: f64,
+    ) -> uuid::Uuid {
+        sqlx::query_scalar!(
+            r#"
+                INSERT INTO address (line_1, city, state, country, postal_code, lon, lat, state_house_district)
+                VALUES ($1, 'Casper', 'WY', 'USA', '82601', $2, $3, '7')
+                RETURNING id
+            "#,
+            line_1,
+            longitude,
+            latitude,
+        )
+        .fetch_one(db_pool)
+        .await
+        .unwrap()
+    }
+
+    async fn clean_up(db_pool: &PgPool, address_id: uuid::Uuid, layers: &[&str]) {
+        let layers: Vec<String> = layers.iter().map(|l| l.to_string()).collect();
+        sqlx::query!("DELETE FROM address WHERE id = $1", address_id)
+            .execute(db_pool)
+            .await
+            .unwrap();
+        sqlx::query!(
+            "DELETE FROM district_boundary WHERE layer = ANY($1)",
+            &layers
+        )
+        .execute(db_pool)
+        .await
+        .unwrap();
+        sqlx::query!(
+            "DELETE FROM district_vintage WHERE layer = ANY($1)",
+            &layers
+        )
+        .execute(db_pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_layers_of_one_district_type_keep_their_own_vintage() {
+        let db_pool = crate::DatabasePool::new().await.unwrap().connection;
+        let address_id = insert_address(&db_pool, "1 Test Layers St", -106.3, 42.85).await;
+
+        let place = import(
+            &db_pool,
+            "test:place",
+            DistrictType::City,
+            "5613900",
+            2024,
+            square(-106.3, 42.85),
+        )
+        .await;
+        let cousub = import(
+            &db_pool,
+            "test:cousub",
+            DistrictType::City,
+            "5602590",
+            2020,
+            square(-106.3, 42.85),
+        )
+        .await;
+        place.refresh_addresses(&db_pool).await.unwrap();
+        cousub.refresh_addresses(&db_pool).await.unwrap();
+
+        let date = NaiveDate::from_ymd_opt(2024, 11, 5).unwrap();
+        let districts = Address::districts_on(&db_pool, &address_id, date).await;
+        clean_up(&db_pool, address_id, &["test:place", "test:cousub"]).await;
+
+        let mut codes: Vec<_> = districts
+            .unwrap()
+            .boundaries
+            .into_iter()
+            .filter(|b| b.layer.starts_with("test:"))
+            .map(|b| b.code)
+            .collect();
+        codes.sort();
+        assert_eq!(codes, vec!["5602590", "5613900"]);
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_refresh_clears_districts_outside_the_vintage_in_force() {
+        let db_pool = crate::DatabasePool::new().await.unwrap().connection;
+        let address_id = insert_address(&db_pool, "2 Test Layers St", -106.3, 42.85).await;
+
+        let old = import(
+            &db_pool,
+            "test:sldl",
+            DistrictType::StateHouse,
+            "7",
+            2012,
+            square(-106.3, 42.85),
+        )
+        .await;
+        let new = import(
+            &db_pool,
+            "test:sldl",
+            DistrictType::StateHouse,
+            "8",
+            2022,
+            square(-104.8, 41.1),
+        )
+        .await;
+        old.refresh_addresses(&db_pool).await.unwrap();
+        new.refresh_addresses(&db_pool).await.unwrap();
+
+        let district = sqlx::query_scalar!(
+            "SELECT state_house_district FROM address WHERE id = $1",
+            address_id
+        )
+        .fetch_one(&db_pool)
+        .await;
+        clean_up(&db_pool, address_id, &["test:sldl"]).await;
+
+        assert_eq!(district.unwrap(), None);
+    }
+}
@@ -1,8 +1,9 @@
 use async_graphql::InputObject;
-use sqlx::postgres::PgPool;
 use sqlx::FromRow;
+use sqlx::{postgres::PgPool, PgConnection};
 
 use super::enums::State;
+use crate::{ExternalId, ExternalIdEntityType, ExternalIdSource};
 
 #[derive(FromRow, Debug, Clone)]
 pub struct Election {
@@ -38,6 +39,15 @@ pub struct ElectionFilter {
 }
 
 impl Election {
+    /// Looks up an election by its OCD or Ballotpedia id
+    pub async fn find_by_external_id(
+        db_pool: &PgPool,
+        source: ExternalIdSource,
+        value: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        ExternalId::find_entity(db_pool, ExternalIdEntityType::Election, source, value).await
+    }
+
     pub async fn upsert(
         db_pool: &PgPool,
         input: &UpsertElectionInput,
@@ -73,7 +83,7 @@ impl Election {
     }
 
     pub async fn upsert_from_source(
-        db_pool: &PgPool,
+        conn: &mut PgConnection,
         input: &UpsertElectionInput,
     ) -> Result<Self, sqlx::Error> {
         input
@@ -103,7 +113,7 @@ impl Election {
             input.municipality,
             input.election_date
         )
-        .fetch_one(db_pool)
+        .fetch_one(&mut *conn)
         .await
     }
 
@@ -0,0 +1,476 @@
+use super::{election::Election, enums::State, office::ElectionScope};
+use crate::DateTime;
+use async_graphql::{Enum, InputObject};
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use strum_macros::Display;
+
+/// Feeds keep elections this many days after election day, so canvass and certification dates
+/// stay on subscribers' calendars
+const PAST_ELECTION_DAYS: i32 = 90;
+
+#[derive(Enum, Display, Debug, Copy, Clone, Eq, PartialEq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "deadline_type", rename_all = "snake_case")]
+pub enum DeadlineType {
+    /// Last day to register by mail or in person before election day
+    Registration,
+    OnlineRegistration,
+    AbsenteeRequest,
+    /// Absentee ballots must be received, or postmarked where the state allows it, by this date
+    AbsenteeReturn,
+    EarlyVotingBegins,
+    EarlyVotingEnds,
+    Canvass,
+    Certification,
+    Other,
+}
+
+impl DeadlineType {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DeadlineType::Registration => "Voter registration deadline",
+            DeadlineType::OnlineRegistration => "Online voter registration deadline",
+            DeadlineType::AbsenteeRequest => "Absentee ballot request deadline",
+            DeadlineType::AbsenteeReturn => "Absentee ballot return deadline",
+            DeadlineType::EarlyVotingBegins => "Early voting begins",
+            DeadlineType::EarlyVotingEnds => "Early voting ends",
+            DeadlineType::Canvass => "Canvass",
+            DeadlineType::Certification => "Results certified",
+            DeadlineType::Other => "Election deadline",
+        }
+    }
+}
+
+#[derive(FromRow, Debug, Clone)]
+pub struct ElectionDeadline {
+    pub id: uuid::Uuid,
+    pub election_id: uuid::Uuid,
+    pub deadline_type: DeadlineType,
+    pub date: NaiveDate,
+    /// Empty when the deadline applies everywhere the election is held
+    pub state: Option<State>,
+    pub election_scope: ElectionScope,
+    pub county: Option<String>,
+    pub municipality: Option<String>,
+    pub description: Option<String>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(InputObject, Default, Debug)]
+pub struct UpsertElectionDeadlineInput {
+    pub id: Option<uuid::Uuid>,
+    pub election_id: Option<uuid::Uuid>,
+    pub deadline_type: Option<DeadlineType>,
+    pub date: Option<NaiveDate>,
+    pub state: Option<State>,
+    pub election_scope: Option<ElectionScope>,
+    pub county: Option<String>,
+    pub municipality: Option<String>,
+    pub description: Option<String>,
+}
+
+impl ElectionDeadline {
+    pub async fn upsert(
+        db_pool: &PgPool,
+        input: &UpsertElectionDeadlineInput,
+    ) -> Result<Self, sqlx::Error> {
+        let id = input.id.unwrap_or_else(uuid::Uuid::new_v4);
+        sqlx::query_as!(
+            ElectionDeadline,
+            r#"
+                INSERT INTO election_deadline
+                (id, election_id, deadline_type, date, state, election_scope, county, municipality, description)
+                VALUES ($1, $2, $3, $4, $5, COALESCE($6, 'state'::election_scope), $7, $8, $9)
+                ON CONFLICT (id) DO UPDATE SET
+                    election_id = COALESCE($2, election_deadline.election_id),
+                    deadline_type = COALESCE($3, election_deadline.deadline_type),
+                    date = COALESCE($4, election_deadline.date),
+                    state = COALESCE($5, election_deadline.state),
+                    election_scope = COALESCE($6, election_deadline.election_scope),
+                    county = COALESCE($7, election_deadline.county),
+                    municipality = COALESCE($8, election_deadline.municipality),
+                    description = COALESCE($9, election_deadline.description)
+                RETURNING
+                    id,
+                    election_id,
+                    deadline_type AS "deadline_type:DeadlineType",
+                    date,
+                    state AS "state:State",
+                    election_scope AS "election_scope:ElectionScope",
+                    county,
+                    municipality,
+                    description,
+                    created_at,
+                    updated_at
+            "#,
+            id,
+            input.election_id,
+            input.deadline_type as Option<DeadlineType>,
+            input.date,
+            input.state as Option<State>,
+            input.election_scope as Option<ElectionScope>,
+            input.county,
+            input.municipality,
+            input.description,
+        )
+        .fetch_one(db_pool)
+        .await
+    }
+
+    pub async fn delete(db_pool: &PgPool, id: uuid::Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM election_deadline WHERE id = $1", id)
+            .execute(db_pool)
+            .await?;
+        Ok(())
+    }
+
+    /// An election's deadlines by date, only those that apply in `state` when one is given
+    pub async fn find_by_election_id(
+        db_pool: &PgPool,
+        election_id: uuid::Uuid,
+        state: Option<State>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ElectionDeadline,
+            r#"
+                SELECT
+                    id,
+                    election_id,
+                    deadline_type AS "deadline_type:DeadlineType",
+                    date,
+                    state AS "state:State",
+                    election_scope AS "election_scope:ElectionScope",
+                    county,
+                    municipality,
+                    description,
+                    created_at,
+                    updated_at
+                FROM election_deadline
+                WHERE election_id = $1
+                AND ($2::state IS NULL OR state IS NULL OR state = $2)
+                ORDER BY date, deadline_type
+            "#,
+            election_id,
+            state as Option<State>,
+        )
+        .fetch_all(db_pool)
+        .await
+    }
+}
+
+/// Elections and their deadlines for an iCalendar feed
+#[derive(Debug, Clone)]
+pub struct ElectionCalendar {
+    pub name: String,
+    pub elections: Vec<Election>,
+    pub deadlines: Vec<ElectionDeadline>,
+}
+
+impl ElectionCalendar {
+    /// Every deadline in an election
+    pub async fn for_election(
+        db_pool: &PgPool,
+        election_id: uuid::Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let election = Election::find_by_id(db_pool, election_id).await?;
+        let deadlines = ElectionDeadline::find_by_election_id(db_pool, election_id, None).await?;
+        Ok(Self {
+            name: election.title.clone(),
+            elections: vec![election],
+            deadlines,
+        })
+    }
+
+    /// Upcoming and recent elections held in a state, with the deadlines that apply there
+    pub async fn for_state(db_pool: &PgPool, state: State) -> Result<Self, sqlx::Error> {
+        let elections = sqlx::query_as!(
+            Election,
+            r#"
+                SELECT id, slug, title, description, state AS "state:State", municipality, election_date
+                FROM election
+                WHERE (state IS NULL OR state = $1)
+                AND election_date >= CURRENT_DATE - $2::integer
+                ORDER BY election_date
+            "#,
+            state as State,
+            PAST_ELECTION_DAYS,
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        let deadlines = sqlx::query_as!(
+            ElectionDeadline,
+            r#"
+                SELECT
+                    id,
+                    election_id,
+                    deadline_type AS "deadline_type:DeadlineType",
+                    date,
+                    state AS "state:State",
+                    election_scope AS "election_scope:ElectionScope",
+                    county,
+                    municipality,
+                    description,
+                    created_at,
+                    updated_at
+                FROM election_deadline
+                WHERE election_id = ANY($1)
+                AND (state IS NULL OR state = $2)
+                ORDER BY date, deadline_type
+            "#,
+            &elections.iter().map(|e| e.id).collect::<Vec<_>>(),
+            state as State,
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        Ok(Self {
+            name: format!("{} Elections", state),
+            elections,
+            deadlines,
+        })
+    }
+
+    /// Upcoming and recent elections at the user's address and those they have voting guides
+    /// for, with the deadlines that apply at their address. Users without an address get
+    /// every deadline in their voting guides' elections.
+    pub async fn for_user(db_pool: &PgPool, user_id: uuid::Uuid) -> Result<Self, sqlx::Error> {
+        let elections = sqlx::query_as!(
+            Election,
+            r#"
+                WITH home AS (
+                    SELECT a.state, a.city
+                    FROM user_profile up
+                    JOIN address a ON a.id = up.address_id
+                    WHERE up.user_id = $1
+                )
+                SELECT e.id, e.slug, e.title, e.description, e.state AS "state:State", e.municipality, e.election_date
+                FROM election e
+                WHERE e.election_date >= CURRENT_DATE - $2::integer
+                AND (
+                    EXISTS (
+                        SELECT 1 FROM voting_guide vg
+                        WHERE vg.user_id = $1 AND vg.election_id = e.id
+                    )
+                    OR EXISTS (
+                        SELECT 1 FROM home h
+                        WHERE (e.state IS NULL OR e.state::text = h.state)
+                        AND (e.municipality IS NULL OR e.municipality ILIKE h.city)
+                    )
+                )
+                ORDER BY e.election_date
+            "#,
+            user_id,
+            PAST_ELECTION_DAYS,
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        let deadlines = sqlx::query_as!(
+            ElectionDeadline,
+            r#"
+                WITH home AS (
+                    SELECT a.state, a.county, a.city
+                    FROM user_profile up
+                    JOIN address a ON a.id = up.address_id
+                    WHERE up.user_id = $2
+                )
+                SELECT
+                    d.id,
+                    d.election_id,
+                    d.deadline_type AS "deadline_type:DeadlineType",
+                    d.date,
+                    d.state AS "state:State",
+                    d.election_scope AS "election_scope:ElectionScope",
+                    d.county,
+                    d.municipality,
+                    d.description,
+                    d.created_at,
+                    d.updated_at
+                FROM election_deadline d
+                WHERE d.election_id = ANY($1)
+                AND (
+                    NOT EXISTS (SELECT 1 FROM home)
+                    OR EXISTS (
+                        SELECT 1 FROM home h
+                        WHERE (d.state IS NULL OR d.state::text = h.state)
+                        AND (
+                            d.county IS NULL
+                            OR h.county ILIKE d.county
+                            OR h.county ILIKE d.county || ' County'
+                        )
+                        AND (d.municipality IS NULL OR h.city ILIKE d.municipality)
+                    )
+                )
+                ORDER BY d.date, d.deadline_type
+            "#,
+            &elections.iter().map(|e| e.id).collect::<Vec<_>>(),
+            user_id,
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        Ok(Self {
+            name: "My Elections".to_string(),
+            elections,
+            deadlines,
+        })
+    }
+
+    /// Renders the calendar as iCalendar (RFC 5545) with an all-day event for each election
+    /// day and deadline
+    pub fn to_icalendar(&self) -> String {
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//Populist//Election Calendar//EN".to_string(),
+            "CALSCALE:GREGORIAN".to_string(),
+            "METHOD:PUBLISH".to_string(),
+            format!("X-WR-CALNAME:{}", escape_text(&self.name)),
+        ];
+
+        for election in &self.elections {
+            lines.extend(event(
+                &format!("election-{}@populist.us", election.id),
+                election.election_date,
+                &format!("Election Day: {}", election.title),
+                election.description.as_deref(),
+            ));
+        }
+
+        for deadline in &self.deadlines {
+            let Some(election) = self.elections.iter().find(|e| e.id == deadline.election_id)
+            else {
+                continue;
+            };
+            let place = deadline
+                .municipality
+                .as_deref()
+                .or(deadline.county.as_deref())
+                .map(|place| format!(" ({})", place))
+                .unwrap_or_default();
+            lines.extend(event(
+                &format!("deadline-{}@populist.us", deadline.id),
+                deadline.date,
+                &format!(
+                    "{}{}: {}",
+                    deadline.deadline_type.label(),
+                    place,
+                    election.title
+                ),
+                deadline.description.as_deref(),
+            ));
+        }
+
+        lines.push("END:VCALENDAR".to_string());
+        lines
+            .iter()
+            .map(|line| fold_line(line))
+            .collect::<Vec<_>>()
+            .join("\r\n")
+            + "\r\n"
+    }
+}
+
+fn event(uid: &str, date: NaiveDate, summary: &str, description: Option<&str>) -> Vec<String> {
+    let mut lines = vec![
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}", uid),
+        // Events don't change on a schedule, so their date stands in for when they were created
+        format!("DTSTAMP:{}T000000Z", date.format("%Y%m%d")),
+        format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")),
+        format!(
+            "DTEND;VALUE=DATE:{}",
+            (date + Duration::days(1)).format("%Y%m%d")
+        ),
+        format!("SUMMARY:{}", escape_text(summary)),
+    ];
+    if let Some(description) = description {
+        lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+    }
+    lines.push("TRANSP:TRANSPARENT".to_string());
+    lines.push("END:VEVENT".to_string());
+    lines
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Lines longer than 75 bytes continue on the next line after a space, without splitting
+/// characters
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_icalendar() {
+        let election = Election {
+            id: uuid::Uuid::new_v4(),
+            slug: "general-election-2024".to_string(),
+            title: "General Election 2024".to_string(),
+            description: None,
+            state: None,
+            municipality: None,
+            election_date: NaiveDate::from_ymd_opt(2024, 11, 5).unwrap(),
+        };
+        let deadline = ElectionDeadline {
+            id: uuid::Uuid::new_v4(),
+            election_id: election.id,
+            deadline_type: DeadlineType::Registration,
+            date: NaiveDate::from_ymd_opt(2024, 10, 15).unwrap(),
+            state: Some(State::MN),
+            election_scope: ElectionScope::County,
+            county: Some("Hennepin".to_string()),
+            municipality: None,
+            description: Some("Register in person, by mail; or online, then vote".repeat(2)),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+        let calendar = ElectionCalendar {
+            name: "MN Elections".to_string(),
+            elections: vec![election],
+            deadlines: vec![deadline],
+        };
+
+        let ics = calendar.to_icalendar();
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ics.contains("DTSTART;VALUE=DATE:20241105\r\nDTEND;VALUE=DATE:20241106\r\n"));
+        assert!(ics
+            .contains("SUMMARY:Voter registration deadline (Hennepin): General Election 2024\r\n"));
+        assert!(ics.lines().all(|line| line.len() <= 76));
+
+        let unfolded = ics.replace("\r\n ", "");
+        assert!(unfolded.contains("DESCRIPTION:Register in person\\, by mail\\; or online"));
+    }
+
+    #[test]
+    fn test_fold_line() {
+        let line = format!("SUMMARY:{}", "é".repeat(40));
+        let folded = fold_line(&line);
+        assert!(folded.split("\r\n").all(|part| part.len() <= 75));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
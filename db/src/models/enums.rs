@@ -238,6 +238,34 @@ pub enum BallotMeasureStatus {
     Unknown,
 }
 
+/// Share of yes votes a ballot measure needs to pass
+#[derive(Enum, Debug, Display, Copy, Clone, Eq, PartialEq, sqlx::Type, Default)]
+#[strum(serialize_all = "snake_case")]
+#[sqlx(type_name = "passage_threshold", rename_all = "snake_case")]
+pub enum PassageThreshold {
+    /// More yes than no votes, a tie fails
+    #[default]
+    SimpleMajority,
+    FiftyFivePercent,
+    ThreeFifths,
+    TwoThirds,
+    ThreeQuarters,
+}
+
+impl PassageThreshold {
+    /// Compares whole vote counts so thresholds like two thirds are exact
+    pub fn passes(&self, yes_votes: i32, no_votes: i32) -> bool {
+        let (yes, total) = (yes_votes as i64, yes_votes as i64 + no_votes as i64);
+        match self {
+            PassageThreshold::SimpleMajority => yes * 2 > total,
+            PassageThreshold::FiftyFivePercent => yes * 20 >= total * 11 && yes > 0,
+            PassageThreshold::ThreeFifths => yes * 5 >= total * 3 && yes > 0,
+            PassageThreshold::TwoThirds => yes * 3 >= total * 2 && yes > 0,
+            PassageThreshold::ThreeQuarters => yes * 4 >= total * 3 && yes > 0,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "action_type", rename_all = "camelCase")]
 pub enum LegislationAction {
@@ -355,3 +383,135 @@ pub enum BillType {
 
     None,
 }
+
+#[derive(Enum, Debug, Display, Copy, Clone, Eq, PartialEq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "scraper_run_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ScraperRunStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+#[derive(
+    Enum,
+    Debug,
+    Display,
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    PartialOrd,
+    Ord,
+    sqlx::Type,
+    Serialize,
+    Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[sqlx(type_name = "scraper_entity_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ScraperEntityType {
+    Election,
+    Office,
+    Party,
+    Politician,
+    Race,
+    RaceCandidate,
+}
+
+impl ScraperEntityType {
+    pub fn table(&self) -> &'static str {
+        match self {
+            ScraperEntityType::Election => "election",
+            ScraperEntityType::Office => "office",
+            ScraperEntityType::Party => "party",
+            ScraperEntityType::Politician => "politician",
+            ScraperEntityType::Race => "race",
+            ScraperEntityType::RaceCandidate => "race_candidates",
+        }
+    }
+}
+
+#[derive(Enum, Debug, Display, Copy, Clone, Eq, PartialEq, sqlx::Type, Serialize, Deserialize)]
+#[strum(serialize_all = "snake_case")]
+#[sqlx(type_name = "proposed_change_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ProposedChangeKind {
+    Insert,
+    Update,
+}
+
+#[derive(Enum, Debug, Display, Copy, Clone, Eq, PartialEq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "proposed_change_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ProposedChangeStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+#[derive(
+    Enum, Debug, Display, Copy, Clone, Eq, PartialEq, EnumString, sqlx::Type, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[sqlx(type_name = "external_id_entity_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ExternalIdEntityType {
+    Politician,
+    Organization,
+    Bill,
+    Committee,
+    Party,
+    Office,
+    Race,
+    Election,
+    BallotMeasure,
+}
+
+/// Systems whose identifiers we store in the `external_id` crosswalk
+#[derive(
+    Enum, Debug, Display, Copy, Clone, Eq, PartialEq, EnumString, sqlx::Type, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[sqlx(type_name = "external_id_source", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ExternalIdSource {
+    /// Keys generated by our own scrapers, e.g. `MN-SOS-...`
+    RefKey,
+    Votesmart,
+    Legiscan,
+    OpenSecrets,
+    Fec,
+    /// Open Civic Data identifiers, e.g. `ocd-person/...` or `ocd-division/...`
+    Ocd,
+    Ballotpedia,
+}
+
+/// Entities whose previous slugs are kept in `slug_history`
+#[derive(Enum, Debug, Display, Copy, Clone, Eq, PartialEq, sqlx::Type, Serialize, Deserialize)]
+#[strum(serialize_all = "snake_case")]
+#[sqlx(type_name = "slug_entity_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SlugEntityType {
+    Politician,
+    Office,
+    Race,
+    Bill,
+    Organization,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_passage_thresholds() {
+        assert!(!PassageThreshold::SimpleMajority.passes(500, 500));
+        assert!(PassageThreshold::SimpleMajority.passes(501, 500));
+        assert!(PassageThreshold::TwoThirds.passes(200, 100));
+        assert!(!PassageThreshold::TwoThirds.passes(199, 100));
+        assert!(PassageThreshold::FiftyFivePercent.passes(55, 45));
+        assert!(!PassageThreshold::ThreeFifths.passes(59, 41));
+        assert!(!PassageThreshold::ThreeQuarters.passes(0, 0));
+    }
+}
@@ -0,0 +1,193 @@
+use crate::{DateTime, ExternalIdEntityType, ExternalIdSource};
+use async_graphql::InputObject;
+use sqlx::{
+    postgres::{PgPool, PgRow},
+    FromRow,
+};
+
+/// An identifier another system uses for one of our records
+#[derive(FromRow, Debug, Clone)]
+pub struct ExternalId {
+    pub id: uuid::Uuid,
+    pub entity_type: ExternalIdEntityType,
+    pub entity_id: uuid::Uuid,
+    pub source: ExternalIdSource,
+    pub value: String,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(InputObject, Debug, Clone)]
+pub struct UpsertExternalIdInput {
+    pub entity_type: ExternalIdEntityType,
+    pub entity_id: uuid::Uuid,
+    pub source: ExternalIdSource,
+    pub value: String,
+}
+
+impl ExternalIdEntityType {
+    pub fn table(&self) -> &'static str {
+        match self {
+            ExternalIdEntityType::Politician => "politician",
+            ExternalIdEntityType::Organization => "organization",
+            ExternalIdEntityType::Bill => "bill",
+            ExternalIdEntityType::Committee => "committee",
+            ExternalIdEntityType::Party => "party",
+            ExternalIdEntityType::Office => "office",
+            ExternalIdEntityType::Race => "race",
+            ExternalIdEntityType::Election => "election",
+            ExternalIdEntityType::BallotMeasure => "ballot_measure",
+        }
+    }
+}
+
+impl ExternalId {
+    /// Records an external id, moving it to `entity_id` if another record held it
+    pub async fn upsert(
+        db_pool: &PgPool,
+        input: &UpsertExternalIdInput,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ExternalId,
+            r#"
+                INSERT INTO external_id (entity_type, entity_id, source, value)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (entity_type, source, value) DO UPDATE SET entity_id = EXCLUDED.entity_id
+                RETURNING id, entity_type AS "entity_type:ExternalIdEntityType", entity_id, source AS "source:ExternalIdSource", value, created_at, updated_at
+            "#,
+            input.entity_type as ExternalIdEntityType,
+            input.entity_id,
+            input.source as ExternalIdSource,
+            input.value.trim(),
+        )
+        .fetch_one(db_pool)
+        .await
+    }
+
+    pub async fn delete(db_pool: &PgPool, id: uuid::Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM external_id WHERE id = $1", id)
+            .execute(db_pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Every external id recorded for an entity
+    pub async fn find_by_entity(
+        db_pool: &PgPool,
+        entity_type: ExternalIdEntityType,
+        entity_id: uuid::Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExternalId,
+            r#"
+                SELECT id, entity_type AS "entity_type:ExternalIdEntityType", entity_id, source AS "source:ExternalIdSource", value, created_at, updated_at
+                FROM external_id
+                WHERE entity_type = $1 AND entity_id = $2
+                ORDER BY source, value
+            "#,
+            entity_type as ExternalIdEntityType,
+            entity_id,
+        )
+        .fetch_all(db_pool)
+        .await
+    }
+
+    /// Every id a source has for entities of `entity_type`, e.g. all VoteSmart candidate ids
+    pub async fn find_by_source(
+        db_pool: &PgPool,
+        entity_type: ExternalIdEntityType,
+        source: ExternalIdSource,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExternalId,
+            r#"
+                SELECT id, entity_type AS "entity_type:ExternalIdEntityType", entity_id, source AS "source:ExternalIdSource", value, created_at, updated_at
+                FROM external_id
+                WHERE entity_type = $1 AND source = $2
+                ORDER BY entity_id, created_at
+            "#,
+            entity_type as ExternalIdEntityType,
+            source as ExternalIdSource,
+        )
+        .fetch_all(db_pool)
+        .await
+    }
+
+    /// Loads the record of `entity_type` a source's identifier belongs to
+    pub async fn find_entity<T>(
+        db_pool: &PgPool,
+        entity_type: ExternalIdEntityType,
+        source: ExternalIdSource,
+        value: &str,
+    ) -> Result<Option<T>, sqlx::Error>
+    where
+        T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+    {
+        let sql = format!(
+            r#"
+            SELECT t.* FROM {} t
+            JOIN external_id e ON e.entity_id = t.id
+            WHERE e.entity_type = $1 AND e.source = $2 AND e.value = $3
+            "#,
+            entity_type.table()
+        );
+        sqlx::query_as::<_, T>(&sql)
+            .bind(entity_type)
+            .bind(source)
+            .bind(value.trim())
+            .fetch_optional(db_pool)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    /// Legacy id columns are mirrored into the crosswalk as they're set, changed and cleared
+    #[tokio::test]
+    #[ignore]
+    async fn test_sync_external_ids_trigger() {
+        let db_pool = crate::DatabasePool::new().await.unwrap().connection;
+        let mut tx = db_pool.begin().await.unwrap();
+
+        let id = sqlx::query_scalar::<_, uuid::Uuid>(
+            r#"
+            INSERT INTO politician (slug, first_name, last_name, full_name, votesmart_candidate_id)
+            VALUES ('test-sync-external-ids', 'Test', 'Politician', 'Test Politician', 111)
+            RETURNING id
+            "#,
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .unwrap();
+
+        let mut votesmart_ids = Vec::new();
+        for value in [Some(222), Some(222), None] {
+            votesmart_ids.push(
+                sqlx::query_scalar::<_, String>(
+                    "SELECT value FROM external_id WHERE entity_id = $1 AND source = 'votesmart'",
+                )
+                .bind(id)
+                .fetch_all(&mut *tx)
+                .await
+                .unwrap(),
+            );
+            sqlx::query("UPDATE politician SET votesmart_candidate_id = $2 WHERE id = $1")
+                .bind(id)
+                .bind(value)
+                .execute(&mut *tx)
+                .await
+                .unwrap();
+        }
+        let cleared = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM external_id WHERE entity_id = $1 AND source = 'votesmart'",
+        )
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await
+        .unwrap();
+        tx.rollback().await.unwrap();
+
+        assert_eq!(votesmart_ids, vec![vec!["111"], vec!["222"], vec!["222"]]);
+        assert_eq!(cleared, 0);
+    }
+}
@@ -1,22 +1,39 @@
 pub mod address;
 pub mod argument;
+pub mod ballot;
 pub mod ballot_measure;
 pub mod bill;
 pub mod candidate_guide;
 pub mod committee;
+pub mod district_boundary;
+pub mod district_vintage;
 pub mod election;
+pub mod election_deadline;
 pub mod embed;
 pub mod enums;
+pub mod external_id;
 pub mod issue_tag;
 pub mod office;
+pub mod office_term;
+pub mod office_transition;
 pub mod organization;
 pub mod organization_politician_note;
 pub mod party;
 pub mod politician;
+pub mod politician_match;
 pub mod poll;
+pub mod polling_location;
+pub mod precinct;
 pub mod question;
 pub mod race;
+pub mod race_finalization;
+pub mod race_results_snapshot;
+pub mod ranked_choice;
 pub mod respondent;
+pub mod sample_ballot;
+pub mod scraper_proposed_change;
+pub mod scraper_run;
+pub mod slug_history;
 pub mod user;
 pub mod vote;
 pub mod voting_guide;
@@ -1,10 +1,10 @@
 use super::enums::{PoliticalScope, State};
-use crate::DateTime;
+use crate::{DateTime, ExternalId, ExternalIdEntityType, ExternalIdSource};
 use async_graphql::{Enum, InputObject};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use slugify::slugify;
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
 use strum_macros::{Display, EnumString};
 
 #[derive(sqlx::FromRow, Debug, Clone)]
@@ -18,6 +18,8 @@ pub struct Office {
     pub office_type: Option<String>,
     pub district: Option<String>,
     pub district_type: Option<DistrictType>,
+    /// Year of the district map the office is elected from
+    pub district_vintage: Option<i32>,
     pub hospital_district: Option<String>,
     pub school_district: Option<String>,
     pub chamber: Option<Chamber>,
@@ -45,6 +47,8 @@ pub struct UpsertOfficeInput {
     pub office_type: Option<String>,
     pub district: Option<String>,
     pub district_type: Option<DistrictType>,
+    /// Year of the district map the office is elected from
+    pub district_vintage: Option<i32>,
     pub hospital_district: Option<String>,
     pub school_district: Option<String>,
     pub chamber: Option<Chamber>,
@@ -156,8 +160,8 @@ impl Office {
         let record = sqlx::query_as!(
             Office,
             r#"
-                INSERT INTO office (id, slug, title, subtitle, subtitle_short, name, office_type, district, district_type, hospital_district, school_district, chamber, political_scope, election_scope, state, county, municipality, term_length, seat, priority)
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20)
+                INSERT INTO office (id, slug, title, subtitle, subtitle_short, name, office_type, district, district_type, hospital_district, school_district, chamber, political_scope, election_scope, state, county, municipality, term_length, seat, priority, district_vintage)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21)
                 ON CONFLICT (id) DO UPDATE
                 SET
                     slug = COALESCE($2, office.slug),
@@ -178,8 +182,9 @@ impl Office {
                     municipality = COALESCE($17, office.municipality),
                     term_length = COALESCE($18, office.term_length),
                     seat = COALESCE($19, office.seat),
-                    priority = COALESCE($20, office.priority)
-                RETURNING id, slug, title, subtitle, subtitle_short, name, office_type, district, district_type AS "district_type:DistrictType", hospital_district, school_district, chamber AS "chamber:Chamber", political_scope AS "political_scope:PoliticalScope", election_scope as "election_scope:ElectionScope", state AS "state:State", county, municipality, term_length, seat, priority, created_at, updated_at
+                    priority = COALESCE($20, office.priority),
+                    district_vintage = COALESCE($21, office.district_vintage)
+                RETURNING id, slug, title, subtitle, subtitle_short, name, office_type, district, district_type AS "district_type:DistrictType", district_vintage, hospital_district, school_district, chamber AS "chamber:Chamber", political_scope AS "political_scope:PoliticalScope", election_scope as "election_scope:ElectionScope", state AS "state:State", county, municipality, term_length, seat, priority, created_at, updated_at
             "#,
             id,
             slug,
@@ -201,6 +206,7 @@ impl Office {
             input.term_length,
             input.seat,
             input.priority,
+            input.district_vintage,
         )
         .fetch_one(db_pool)
         .await?;
@@ -209,7 +215,7 @@ impl Office {
     }
 
     pub async fn upsert_from_source(
-        db_pool: &PgPool,
+        conn: &mut PgConnection,
         input: &UpsertOfficeInput,
     ) -> Result<Self, sqlx::Error> {
         input
@@ -221,8 +227,8 @@ impl Office {
         sqlx::query_as!(
             Office,
             r#"
-                INSERT INTO office (slug, title, subtitle, subtitle_short, name, office_type, district, district_type, hospital_district, school_district, chamber, political_scope, election_scope, state, county, municipality, term_length, seat, priority)
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19)
+                INSERT INTO office (slug, title, subtitle, subtitle_short, name, office_type, district, district_type, hospital_district, school_district, chamber, political_scope, election_scope, state, county, municipality, term_length, seat, priority, district_vintage)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20)
                 ON CONFLICT (slug) DO UPDATE
                 SET
                     title = COALESCE($2, office.title),
@@ -242,8 +248,9 @@ impl Office {
                     municipality = COALESCE($16, office.municipality),
                     term_length = COALESCE($17, office.term_length),
                     seat = COALESCE($18, office.seat),
-                    priority = COALESCE($19, office.priority)
-                RETURNING id, slug, title, subtitle, subtitle_short, name, office_type, district, district_type AS "district_type:DistrictType", hospital_district, school_district, chamber AS "chamber:Chamber", political_scope AS "political_scope:PoliticalScope", election_scope as "election_scope:ElectionScope", state AS "state:State", county, municipality, term_length, seat, priority, created_at, updated_at
+                    priority = COALESCE($19, office.priority),
+                    district_vintage = COALESCE($20, office.district_vintage)
+                RETURNING id, slug, title, subtitle, subtitle_short, name, office_type, district, district_type AS "district_type:DistrictType", district_vintage, hospital_district, school_district, chamber AS "chamber:Chamber", political_scope AS "political_scope:PoliticalScope", election_scope as "election_scope:ElectionScope", state AS "state:State", county, municipality, term_length, seat, priority, created_at, updated_at
             "#,
             input.slug,
             input.title,
@@ -264,8 +271,9 @@ impl Office {
             input.term_length,
             input.seat,
             input.priority,
+            input.district_vintage,
         )
-        .fetch_one(db_pool)
+        .fetch_one(&mut *conn)
         .await
     }
 
@@ -280,7 +288,7 @@ impl Office {
         let record = sqlx::query_as!(
             Office,
             r#"
-                SELECT id, slug, title, subtitle, subtitle_short, name, office_type, district, district_type AS "district_type:DistrictType", hospital_district, school_district, chamber AS "chamber:Chamber", election_scope as "election_scope:ElectionScope", political_scope AS "political_scope:PoliticalScope", state AS "state:State", county, municipality, term_length, seat, priority, created_at, updated_at FROM office
+                SELECT id, slug, title, subtitle, subtitle_short, name, office_type, district, district_type AS "district_type:DistrictType", district_vintage, hospital_district, school_district, chamber AS "chamber:Chamber", election_scope as "election_scope:ElectionScope", political_scope AS "political_scope:PoliticalScope", state AS "state:State", county, municipality, term_length, seat, priority, created_at, updated_at FROM office
                 WHERE id = $1
             "#,
             id
@@ -291,12 +299,26 @@ impl Office {
         Ok(record)
     }
 
+    /// Looks up an office by its id in another system, e.g. its scraper ref key or OCD id
+    pub async fn find_by_external_id(
+        db_pool: &PgPool,
+        source: ExternalIdSource,
+        value: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        ExternalId::find_entity(db_pool, ExternalIdEntityType::Office, source, value).await
+    }
+
+    /// Also resolves slugs the record had before it was renamed
     pub async fn find_by_slug(db_pool: &PgPool, slug: String) -> Result<Self, sqlx::Error> {
         let record = sqlx::query_as!(
             Office,
             r#"
-                SELECT id, slug, title, subtitle, subtitle_short, name, office_type, district, district_type AS "district_type:DistrictType", hospital_district, school_district, chamber AS "chamber:Chamber", election_scope as "election_scope:ElectionScope", political_scope AS "political_scope:PoliticalScope", state AS "state:State", county, municipality, term_length, seat, priority, created_at, updated_at FROM office
-                WHERE slug = $1
+                SELECT id, slug, title, subtitle, subtitle_short, name, office_type, district, district_type AS "district_type:DistrictType", district_vintage, hospital_district, school_district, chamber AS "chamber:Chamber", election_scope as "election_scope:ElectionScope", political_scope AS "political_scope:PoliticalScope", state AS "state:State", county, municipality, term_length, seat, priority, created_at, updated_at FROM office
+                WHERE slug = $1 OR id = (
+                    SELECT entity_id FROM slug_history WHERE entity_type = 'office' AND slug = $1
+                )
+                ORDER BY slug = $1 DESC
+                LIMIT 1
             "#,
             slug
         )
@@ -322,6 +344,7 @@ impl Office {
                 office_type,
                 district,
                 district_type AS "district_type:DistrictType",
+                district_vintage,
                 hospital_district,
                 school_district,
                 chamber AS "chamber:Chamber",
@@ -0,0 +1,413 @@
+use super::{enums::RaceType, race::CandidacyStatus};
+use crate::DateTime;
+use async_graphql::{Enum, InputObject};
+use chrono::{Datelike, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection, PgPool};
+use strum_macros::Display;
+
+#[derive(Enum, Display, Debug, Copy, Clone, Eq, PartialEq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "office_term_source", rename_all = "snake_case")]
+pub enum OfficeTermSource {
+    Elected,
+    SpecialElection,
+    Appointed,
+    /// Includes office holders entered before terms were tracked
+    Other,
+}
+
+#[derive(FromRow, Debug, Clone)]
+pub struct OfficeTerm {
+    pub id: uuid::Uuid,
+    pub office_id: uuid::Uuid,
+    pub politician_id: uuid::Uuid,
+    /// The race the term was won in
+    pub race_id: Option<uuid::Uuid>,
+    pub obtained_by: OfficeTermSource,
+    /// Empty when we don't know when the term began
+    pub start_date: Option<NaiveDate>,
+    /// The day the term ends, or ended if it ended early. Empty when the office has no term
+    /// length.
+    pub end_date: Option<NaiveDate>,
+    pub notes: Option<String>,
+    /// When the holder was made the office's incumbent
+    pub took_office_at: Option<DateTime>,
+    /// For terms without a race, the term that ends when this one begins
+    pub replaces_term_id: Option<uuid::Uuid>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(InputObject, Default, Debug)]
+pub struct UpsertOfficeTermInput {
+    pub id: Option<uuid::Uuid>,
+    pub office_id: Option<uuid::Uuid>,
+    pub politician_id: Option<uuid::Uuid>,
+    pub race_id: Option<uuid::Uuid>,
+    pub obtained_by: Option<OfficeTermSource>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub notes: Option<String>,
+    /// For appointments and other terms without a race, the term that ends when this one
+    /// begins. Terms without a race don't end anyone else's.
+    pub replaces_term_id: Option<uuid::Uuid>,
+}
+
+/// A race a politician ran in and how it went
+#[derive(FromRow, Debug, Clone)]
+pub struct ElectoralHistoryRace {
+    pub race_id: uuid::Uuid,
+    pub race_title: String,
+    pub race_type: RaceType,
+    pub office_id: uuid::Uuid,
+    pub election_date: Option<NaiveDate>,
+    pub is_special_election: bool,
+    pub status: CandidacyStatus,
+    pub votes: Option<i32>,
+    /// Votes for every candidate in the race
+    pub total_votes: Option<i32>,
+    /// Empty until the race is finalized
+    pub is_winner: Option<bool>,
+}
+
+/// A politician's races and terms in office, newest first
+#[derive(Debug, Clone)]
+pub struct ElectoralHistory {
+    pub races: Vec<ElectoralHistoryRace>,
+    pub terms: Vec<OfficeTerm>,
+}
+
+/// When a term won in an election begins. Winners of special elections and elections in the
+/// first half of the year take office right away, the rest at the start of the next year.
+/// Terms that begin on another date can be corrected by staff.
+pub fn term_start_date(election_date: NaiveDate, is_special_election: bool) -> NaiveDate {
+    if is_special_election || election_date.month() < 7 {
+        election_date
+    } else {
+        NaiveDate::from_ymd_opt(election_date.year() + 1, 1, 1).unwrap()
+    }
+}
+
+/// When a term beginning on `start_date` ends, empty when the office's term length isn't known
+pub fn term_end_date(start_date: NaiveDate, term_length: Option<i32>) -> Option<NaiveDate> {
+    let months = u32::try_from(term_length?).ok()? * 12;
+    start_date.checked_add_months(Months::new(months))
+}
+
+impl OfficeTerm {
+    pub fn is_current(&self, today: NaiveDate) -> bool {
+        self.start_date.is_some_and(|start| start <= today)
+            && self.end_date.is_none_or(|end| end > today)
+    }
+
+    pub async fn upsert(
+        db_pool: &PgPool,
+        input: &UpsertOfficeTermInput,
+    ) -> Result<Self, sqlx::Error> {
+        let id = input.id.unwrap_or_else(uuid::Uuid::new_v4);
+        sqlx::query_as!(
+            OfficeTerm,
+            r#"
+                INSERT INTO office_term
+                (id, office_id, politician_id, race_id, obtained_by, start_date, end_date, notes, replaces_term_id)
+                VALUES ($1, $2, $3, $4, COALESCE($5, 'other'::office_term_source), $6, $7, $8, $9)
+                ON CONFLICT (id) DO UPDATE SET
+                    office_id = COALESCE($2, office_term.office_id),
+                    politician_id = COALESCE($3, office_term.politician_id),
+                    race_id = COALESCE($4, office_term.race_id),
+                    obtained_by = COALESCE($5, office_term.obtained_by),
+                    start_date = COALESCE($6, office_term.start_date),
+                    end_date = COALESCE($7, office_term.end_date),
+                    notes = COALESCE($8, office_term.notes),
+                    replaces_term_id = COALESCE($9, office_term.replaces_term_id)
+                RETURNING
+                    id,
+                    office_id,
+                    politician_id,
+                    race_id,
+                    obtained_by AS "obtained_by:OfficeTermSource",
+                    start_date,
+                    end_date,
+                    notes,
+                    took_office_at,
+                    replaces_term_id,
+                    created_at,
+                    updated_at
+            "#,
+            id,
+            input.office_id,
+            input.politician_id,
+            input.race_id,
+            input.obtained_by as Option<OfficeTermSource>,
+            input.start_date,
+            input.end_date,
+            input.notes,
+            input.replaces_term_id,
+        )
+        .fetch_one(db_pool)
+        .await
+    }
+
+    pub async fn delete(db_pool: &PgPool, id: uuid::Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM office_term WHERE id = $1", id)
+            .execute(db_pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Adds a term for each winner of a finalized general race, dated from the election and the
+    /// office's term length, and removes terms from the race for candidates who are no longer
+    /// winners after a recount. Terms already recorded are left alone so staff corrections stick.
+    pub async fn record_race_winners(
+        conn: &mut PgConnection,
+        race_id: uuid::Uuid,
+        winner_ids: &[uuid::Uuid],
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM office_term WHERE race_id = $1 AND NOT politician_id = ANY($2)",
+            race_id,
+            winner_ids,
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        let race = sqlx::query!(
+            r#"
+                SELECT r.office_id, r.is_special_election, e.election_date, o.term_length
+                FROM race r
+                JOIN election e ON e.id = r.election_id
+                JOIN office o ON o.id = r.office_id
+                WHERE r.id = $1
+            "#,
+            race_id,
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+
+        let start_date = term_start_date(race.election_date, race.is_special_election);
+        let obtained_by = match race.is_special_election {
+            true => OfficeTermSource::SpecialElection,
+            false => OfficeTermSource::Elected,
+        };
+        sqlx::query!(
+            r#"
+                INSERT INTO office_term
+                (office_id, politician_id, race_id, obtained_by, start_date, end_date)
+                SELECT $1, UNNEST($2::uuid[]), $3, $4, $5, $6
+                ON CONFLICT (race_id, politician_id) DO NOTHING
+            "#,
+            race.office_id,
+            winner_ids,
+            race_id,
+            obtained_by as OfficeTermSource,
+            start_date,
+            term_end_date(start_date, race.term_length),
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Everyone who has held an office, latest term first
+    pub async fn find_by_office_id(
+        db_pool: &PgPool,
+        office_id: uuid::Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            OfficeTerm,
+            r#"
+                SELECT
+                    id,
+                    office_id,
+                    politician_id,
+                    race_id,
+                    obtained_by AS "obtained_by:OfficeTermSource",
+                    start_date,
+                    end_date,
+                    notes,
+                    took_office_at,
+                    replaces_term_id,
+                    created_at,
+                    updated_at
+                FROM office_term
+                WHERE office_id = $1
+                ORDER BY start_date DESC NULLS FIRST, created_at DESC
+            "#,
+            office_id,
+        )
+        .fetch_all(db_pool)
+        .await
+    }
+
+    /// Every office a politician has held, latest term first
+    pub async fn find_by_politician_id(
+        db_pool: &PgPool,
+        politician_id: uuid::Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            OfficeTerm,
+            r#"
+                SELECT
+                    id,
+                    office_id,
+                    politician_id,
+                    race_id,
+                    obtained_by AS "obtained_by:OfficeTermSource",
+                    start_date,
+                    end_date,
+                    notes,
+                    took_office_at,
+                    replaces_term_id,
+                    created_at,
+                    updated_at
+                FROM office_term
+                WHERE politician_id = $1
+                ORDER BY start_date DESC NULLS FIRST, created_at DESC
+            "#,
+            politician_id,
+        )
+        .fetch_all(db_pool)
+        .await
+    }
+}
+
+impl ElectoralHistory {
+    pub async fn for_politician(
+        db_pool: &PgPool,
+        politician_id: uuid::Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let races = sqlx::query_as!(
+            ElectoralHistoryRace,
+            r#"
+                SELECT
+                    r.id AS race_id,
+                    r.title AS race_title,
+                    r.race_type AS "race_type:RaceType",
+                    r.office_id,
+                    e.election_date AS "election_date?",
+                    r.is_special_election,
+                    rc.status AS "status:CandidacyStatus",
+                    rc.votes,
+                    r.total_votes,
+                    rc.is_winner
+                FROM race_candidates rc
+                JOIN race r ON r.id = rc.race_id
+                LEFT JOIN election e ON e.id = r.election_id
+                WHERE rc.candidate_id = $1
+                ORDER BY e.election_date DESC NULLS FIRST, r.title
+            "#,
+            politician_id,
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        let terms = OfficeTerm::find_by_politician_id(db_pool, politician_id).await?;
+        Ok(Self { races, terms })
+    }
+
+    pub fn race_wins(&self) -> i32 {
+        self.races
+            .iter()
+            .filter(|r| r.is_winner == Some(true))
+            .count() as i32
+    }
+
+    pub fn race_losses(&self) -> i32 {
+        self.races
+            .iter()
+            .filter(|r| r.is_winner == Some(false))
+            .count() as i32
+    }
+
+    /// Whole years in office up to `today`, counting overlapping terms once. Terms without a
+    /// start date aren't counted, and `None` when no term with a start date has begun.
+    pub fn years_in_office(&self, today: NaiveDate) -> Option<i32> {
+        let mut spans = self
+            .terms
+            .iter()
+            .filter_map(|t| {
+                let start = t.start_date.filter(|start| *start <= today)?;
+                Some((start, t.end_date.map_or(today, |end| end.min(today))))
+            })
+            .collect::<Vec<_>>();
+        if spans.is_empty() {
+            return None;
+        }
+        spans.sort();
+
+        let mut days = 0;
+        let mut counted_until: Option<NaiveDate> = None;
+        for (start, end) in spans {
+            let start = counted_until.map_or(start, |until| start.max(until));
+            if end > start {
+                days += (end - start).num_days();
+                counted_until = Some(end);
+            }
+        }
+        Some((days as f64 / 365.25) as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn term(start_date: Option<NaiveDate>, end_date: Option<NaiveDate>) -> OfficeTerm {
+        OfficeTerm {
+            id: uuid::Uuid::new_v4(),
+            office_id: uuid::Uuid::new_v4(),
+            politician_id: uuid::Uuid::new_v4(),
+            race_id: None,
+            obtained_by: OfficeTermSource::Elected,
+            start_date,
+            end_date,
+            notes: None,
+            took_office_at: None,
+            replaces_term_id: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_term_dates() {
+        let general = term_start_date(date(2024, 11, 5), false);
+        assert_eq!(general, date(2025, 1, 1));
+        assert_eq!(term_end_date(general, Some(4)), Some(date(2029, 1, 1)));
+        assert_eq!(term_end_date(general, None), None);
+        assert_eq!(term_start_date(date(2024, 11, 5), true), date(2024, 11, 5));
+        assert_eq!(term_start_date(date(2025, 4, 8), false), date(2025, 4, 8));
+        assert_eq!(
+            term_end_date(date(2024, 2, 29), Some(2)),
+            Some(date(2026, 2, 28))
+        );
+    }
+
+    #[test]
+    fn test_years_in_office() {
+        let history = ElectoralHistory {
+            races: vec![],
+            terms: vec![
+                // Overlaps the term below by two years
+                term(Some(date(2019, 1, 1)), Some(date(2023, 1, 1))),
+                term(Some(date(2015, 1, 1)), Some(date(2021, 1, 1))),
+                term(Some(date(2025, 1, 1)), Some(date(2027, 1, 1))),
+                term(None, None),
+            ],
+        };
+        assert_eq!(history.years_in_office(date(2024, 6, 1)), Some(8));
+        assert_eq!(history.years_in_office(date(2026, 1, 2)), Some(9));
+
+        // Holders whose start isn't known haven't been in office zero years
+        let unknown = ElectoralHistory {
+            races: vec![],
+            terms: vec![term(None, None)],
+        };
+        assert_eq!(unknown.years_in_office(date(2024, 6, 1)), None);
+    }
+}
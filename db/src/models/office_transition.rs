@@ -0,0 +1,702 @@
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+/// A term whose holder is, or will be, made the office's incumbent when it begins
+#[derive(Debug, Clone)]
+pub struct OfficeTransition {
+    pub office_term_id: uuid::Uuid,
+    pub office_id: uuid::Uuid,
+    pub office_title: String,
+    pub politician_id: uuid::Uuid,
+    pub race_id: Option<uuid::Uuid>,
+    pub start_date: NaiveDate,
+    /// Holders whose terms end when this one begins
+    pub outgoing_politician_ids: Vec<uuid::Uuid>,
+    /// Set when the transition can't happen yet, e.g. the race isn't certified
+    pub waiting_reason: Option<String>,
+    /// Whether the transition happened in this run, or would have in a dry run
+    pub is_applied: bool,
+}
+
+/// A holder removed from an office because their term ended and they have no other term in it
+#[derive(Debug, Clone)]
+pub struct EndedOfficeTerm {
+    pub office_term_id: uuid::Uuid,
+    pub office_id: uuid::Uuid,
+    pub office_title: String,
+    pub politician_id: uuid::Uuid,
+    pub end_date: NaiveDate,
+}
+
+/// What a run of `transition_office_holders` did, or would do in a dry run
+#[derive(Debug, Clone, Default)]
+pub struct OfficeTransitions {
+    /// Pending transitions by start date, including those that aren't due yet
+    pub transitions: Vec<OfficeTransition>,
+    /// Holders whose terms ran out without a transition replacing them, e.g. board members who
+    /// didn't run again
+    pub ended_terms: Vec<EndedOfficeTerm>,
+    /// Candidates whose `upcoming_race_id` was cleared because their race was certified
+    pub finished_race_candidates: u64,
+}
+
+struct PendingTerm {
+    id: uuid::Uuid,
+    office_id: uuid::Uuid,
+    office_title: String,
+    politician_id: uuid::Uuid,
+    race_id: Option<uuid::Uuid>,
+    replaces_term_id: Option<uuid::Uuid>,
+    start_date: NaiveDate,
+    is_certified: bool,
+}
+
+/// Makes the holders of terms that have begun the incumbents of their offices. Terms won in
+/// races wait for the race to be certified. A new term ends the current terms of everyone who
+/// ran in its race, or of every holder when the race is for a single seat. Terms without a race,
+/// e.g. appointments, only end the term they replace. Holders whose terms ended without a new
+/// term in the office are removed from it. Candidates in certified races that have been held no
+/// longer have them as their upcoming race. Nothing is written when `dry_run` is set.
+pub async fn transition_office_holders(
+    db_pool: &PgPool,
+    today: NaiveDate,
+    dry_run: bool,
+) -> Result<OfficeTransitions, sqlx::Error> {
+    let mut tx = db_pool.begin().await?;
+
+    let pending = sqlx::query_as!(
+        PendingTerm,
+        r#"
+            SELECT
+                t.id,
+                t.office_id,
+                o.title AS office_title,
+                t.politician_id,
+                t.race_id,
+                t.replaces_term_id,
+                t.start_date AS "start_date!",
+                (t.race_id IS NULL OR r.results_status = 'certified') AS "is_certified!"
+            FROM office_term t
+            JOIN office o ON o.id = t.office_id
+            LEFT JOIN race r ON r.id = t.race_id
+            WHERE t.took_office_at IS NULL
+            AND t.start_date IS NOT NULL
+            AND (t.end_date IS NULL OR t.end_date > $1)
+            ORDER BY t.start_date, t.created_at
+        "#,
+        today,
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut transitions = Vec::with_capacity(pending.len());
+    for term in pending {
+        let outgoing = sqlx::query!(
+            r#"
+                SELECT t.id, t.politician_id
+                FROM office_term t
+                LEFT JOIN race r ON r.id = $3
+                WHERE t.office_id = $1
+                AND t.id <> $2
+                AND (t.start_date IS NULL OR t.start_date < $4)
+                AND (t.end_date IS NULL OR t.end_date >= $4)
+                AND (
+                    t.id = $5
+                    OR (r.id IS NOT NULL AND COALESCE(r.num_elect, 1) = 1)
+                    OR EXISTS (
+                        SELECT 1 FROM race_candidates
+                        WHERE race_id = r.id AND candidate_id = t.politician_id
+                    )
+                )
+                -- Holders who won another seat in the race stay, their own new term ends this one
+                AND NOT EXISTS (
+                    SELECT 1 FROM office_term n
+                    WHERE n.race_id = r.id AND n.politician_id = t.politician_id AND n.id <> $2
+                )
+            "#,
+            term.office_id,
+            term.id,
+            term.race_id,
+            term.start_date,
+            term.replaces_term_id,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let waiting_reason = if !term.is_certified {
+            Some("Race results aren't certified".to_string())
+        } else if term.start_date > today {
+            Some(format!("Term begins {}", term.start_date))
+        } else {
+            None
+        };
+
+        if !dry_run && waiting_reason.is_none() {
+            let outgoing_term_ids = outgoing.iter().map(|t| t.id).collect::<Vec<_>>();
+            let outgoing_politician_ids =
+                outgoing.iter().map(|t| t.politician_id).collect::<Vec<_>>();
+
+            sqlx::query!(
+                "UPDATE office_term SET end_date = $2 WHERE id = ANY($1)",
+                &outgoing_term_ids,
+                term.start_date,
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query!(
+                r#"
+                    UPDATE politician SET office_id = NULL
+                    WHERE id = ANY($1) AND id <> $2 AND office_id = $3
+                "#,
+                &outgoing_politician_ids,
+                term.politician_id,
+                term.office_id,
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query!(
+                "UPDATE politician SET office_id = $2 WHERE id = $1",
+                term.politician_id,
+                term.office_id,
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query!(
+                "UPDATE office_term SET took_office_at = NOW() WHERE id = $1",
+                term.id,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        transitions.push(OfficeTransition {
+            office_term_id: term.id,
+            office_id: term.office_id,
+            office_title: term.office_title,
+            politician_id: term.politician_id,
+            race_id: term.race_id,
+            start_date: term.start_date,
+            outgoing_politician_ids: outgoing
+                .iter()
+                .map(|t| t.politician_id)
+                .filter(|id| *id != term.politician_id)
+                .collect(),
+            is_applied: waiting_reason.is_none(),
+            waiting_reason,
+        });
+    }
+
+    // Seats the transitions above didn't hand over, e.g. of board members who didn't run again
+    let ended_terms = sqlx::query_as!(
+        EndedOfficeTerm,
+        r#"
+            SELECT DISTINCT ON (t.politician_id)
+                t.id AS office_term_id,
+                t.office_id,
+                o.title AS office_title,
+                t.politician_id,
+                t.end_date AS "end_date!"
+            FROM office_term t
+            JOIN office o ON o.id = t.office_id
+            JOIN politician p ON p.id = t.politician_id AND p.office_id = t.office_id
+            WHERE t.end_date <= $1
+            AND NOT EXISTS (
+                SELECT 1 FROM office_term n
+                WHERE n.politician_id = t.politician_id
+                AND n.office_id = t.office_id
+                AND n.id <> t.id
+                AND (n.end_date IS NULL OR n.end_date > $1)
+            )
+            ORDER BY t.politician_id, t.end_date DESC
+        "#,
+        today,
+    )
+    .fetch_all(&mut *tx)
+    .await?
+    .into_iter()
+    // A dry run hasn't removed the holders that due transitions replace
+    .filter(|ended| {
+        !transitions.iter().any(|t| {
+            t.is_applied
+                && t.office_id == ended.office_id
+                && t.outgoing_politician_ids.contains(&ended.politician_id)
+        })
+    })
+    .collect::<Vec<_>>();
+
+    if !dry_run {
+        let politician_ids = ended_terms
+            .iter()
+            .map(|t| t.politician_id)
+            .collect::<Vec<_>>();
+        sqlx::query!(
+            "UPDATE politician SET office_id = NULL WHERE id = ANY($1)",
+            &politician_ids,
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    let finished_race_candidates = if dry_run {
+        let count = sqlx::query_scalar!(
+            r#"
+                SELECT COUNT(*) AS "count!"
+                FROM politician p
+                JOIN race r ON r.id = p.upcoming_race_id
+                JOIN election e ON e.id = r.election_id
+                WHERE r.results_status = 'certified' AND e.election_date < $1
+            "#,
+            today,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        count as u64
+    } else {
+        sqlx::query!(
+            r#"
+                UPDATE politician p SET upcoming_race_id = NULL
+                FROM race r
+                JOIN election e ON e.id = r.election_id
+                WHERE r.id = p.upcoming_race_id
+                AND r.results_status = 'certified'
+                AND e.election_date < $1
+            "#,
+            today,
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+    };
+
+    if !dry_run {
+        tx.commit().await?;
+    }
+    Ok(OfficeTransitions {
+        transitions,
+        ended_terms,
+        finished_race_candidates,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Transitions touch every pending term, so tests mustn't run them at the same time
+    static TRANSITIONS: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    async fn office(db_pool: &PgPool, slug: &str) -> uuid::Uuid {
+        sqlx::query_scalar!(
+            "INSERT INTO office (slug, title, political_scope) VALUES ($1, 'Council Member', 'local') RETURNING id",
+            slug,
+        )
+        .fetch_one(db_pool)
+        .await
+        .unwrap()
+    }
+
+    async fn politician(db_pool: &PgPool, slug: &str, office_id: Option<uuid::Uuid>) -> uuid::Uuid {
+        sqlx::query_scalar!(
+            r#"
+                INSERT INTO politician (slug, full_name, first_name, last_name, office_id)
+                VALUES ($1, $1, $1, $1, $2)
+                RETURNING id
+            "#,
+            slug,
+            office_id,
+        )
+        .fetch_one(db_pool)
+        .await
+        .unwrap()
+    }
+
+    /// A certified race for `num_elect` seats
+    async fn race(
+        db_pool: &PgPool,
+        slug: &str,
+        office_id: uuid::Uuid,
+        num_elect: i32,
+        candidate_ids: &[uuid::Uuid],
+    ) -> uuid::Uuid {
+        let race_id = sqlx::query_scalar!(
+            r#"
+                INSERT INTO race (slug, title, office_id, num_elect, results_status)
+                VALUES ($1, $1, $2, $3, 'certified')
+                RETURNING id
+            "#,
+            slug,
+            office_id,
+            num_elect,
+        )
+        .fetch_one(db_pool)
+        .await
+        .unwrap();
+        sqlx::query!(
+            "INSERT INTO race_candidates (race_id, candidate_id) SELECT $1, UNNEST($2::uuid[])",
+            race_id,
+            candidate_ids,
+        )
+        .execute(db_pool)
+        .await
+        .unwrap();
+        race_id
+    }
+
+    /// A term, already taken up by its holder when `seated` is set
+    async fn term(
+        db_pool: &PgPool,
+        office_id: uuid::Uuid,
+        politician_id: uuid::Uuid,
+        race_id: Option<uuid::Uuid>,
+        dates: (Option<NaiveDate>, Option<NaiveDate>),
+        seated: bool,
+    ) -> uuid::Uuid {
+        sqlx::query_scalar!(
+            r#"
+                INSERT INTO office_term (office_id, politician_id, race_id, obtained_by, start_date, end_date, took_office_at)
+                VALUES ($1, $2, $3, 'elected', $4, $5, CASE WHEN $6 THEN $4::DATE::TIMESTAMPTZ END)
+                RETURNING id
+            "#,
+            office_id,
+            politician_id,
+            race_id,
+            dates.0,
+            dates.1,
+            seated,
+        )
+        .fetch_one(db_pool)
+        .await
+        .unwrap()
+    }
+
+    async fn office_of(db_pool: &PgPool, politician_id: uuid::Uuid) -> Option<uuid::Uuid> {
+        sqlx::query_scalar!(
+            "SELECT office_id FROM politician WHERE id = $1",
+            politician_id
+        )
+        .fetch_one(db_pool)
+        .await
+        .unwrap()
+    }
+
+    async fn end_of(db_pool: &PgPool, term_id: uuid::Uuid) -> Option<NaiveDate> {
+        sqlx::query_scalar!("SELECT end_date FROM office_term WHERE id = $1", term_id)
+            .fetch_one(db_pool)
+            .await
+            .unwrap()
+    }
+
+    async fn clean_up(db_pool: &PgPool, prefix: &str) {
+        let pattern = format!("{}%", prefix);
+        sqlx::query!("DELETE FROM race WHERE slug LIKE $1", pattern)
+            .execute(db_pool)
+            .await
+            .unwrap();
+        sqlx::query!("DELETE FROM politician WHERE slug LIKE $1", pattern)
+            .execute(db_pool)
+            .await
+            .unwrap();
+        sqlx::query!("DELETE FROM office WHERE slug LIKE $1", pattern)
+            .execute(db_pool)
+            .await
+            .unwrap();
+    }
+
+    fn transition(run: &OfficeTransitions, term_id: uuid::Uuid) -> &OfficeTransition {
+        run.transitions
+            .iter()
+            .find(|t| t.office_term_id == term_id)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_2020_holder_hands_over_to_2024_winner() {
+        let _lock = TRANSITIONS.lock().await;
+        let db_pool = crate::DatabasePool::new().await.unwrap().connection;
+        let prefix = "test-transition-handover";
+        let office_id = office(&db_pool, prefix).await;
+        let holder = politician(&db_pool, &format!("{}-holder", prefix), Some(office_id)).await;
+        let winner = politician(&db_pool, &format!("{}-winner", prefix), None).await;
+        let race_2020 = race(
+            &db_pool,
+            &format!("{}-2020", prefix),
+            office_id,
+            1,
+            &[holder],
+        )
+        .await;
+        let race_2024 = race(
+            &db_pool,
+            &format!("{}-2024", prefix),
+            office_id,
+            1,
+            &[winner],
+        )
+        .await;
+        let today = date(2025, 1, 6);
+        let old_term = term(
+            &db_pool,
+            office_id,
+            holder,
+            Some(race_2020),
+            (Some(date(2021, 1, 1)), Some(date(2025, 1, 1))),
+            true,
+        )
+        .await;
+        let new_term = term(
+            &db_pool,
+            office_id,
+            winner,
+            Some(race_2024),
+            (Some(date(2025, 1, 1)), Some(date(2029, 1, 1))),
+            false,
+        )
+        .await;
+
+        let run = transition_office_holders(&db_pool, today, false)
+            .await
+            .unwrap();
+        let handover = transition(&run, new_term);
+        assert!(handover.is_applied);
+        // The old term ends the day the new one begins
+        assert_eq!(handover.outgoing_politician_ids, vec![holder]);
+        assert_eq!(end_of(&db_pool, old_term).await, Some(date(2025, 1, 1)));
+        assert_eq!(office_of(&db_pool, winner).await, Some(office_id));
+        assert_eq!(office_of(&db_pool, holder).await, None);
+
+        clean_up(&db_pool, prefix).await;
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_single_seat_winner_replaces_holder_who_did_not_run() {
+        let _lock = TRANSITIONS.lock().await;
+        let db_pool = crate::DatabasePool::new().await.unwrap().connection;
+        let prefix = "test-transition-single-seat";
+        let office_id = office(&db_pool, prefix).await;
+        let holder = politician(&db_pool, &format!("{}-holder", prefix), Some(office_id)).await;
+        let winner = politician(&db_pool, &format!("{}-winner", prefix), None).await;
+        let race_id = race(&db_pool, prefix, office_id, 1, &[winner]).await;
+        let today = date(2025, 1, 6);
+        // A current holder backfilled without dates
+        let old_term = term(&db_pool, office_id, holder, None, (None, None), true).await;
+        let new_term = term(
+            &db_pool,
+            office_id,
+            winner,
+            Some(race_id),
+            (Some(date(2025, 1, 6)), Some(date(2029, 1, 1))),
+            false,
+        )
+        .await;
+
+        let run = transition_office_holders(&db_pool, today, false)
+            .await
+            .unwrap();
+        assert_eq!(
+            transition(&run, new_term).outgoing_politician_ids,
+            vec![holder]
+        );
+        assert_eq!(end_of(&db_pool, old_term).await, Some(date(2025, 1, 6)));
+        assert_eq!(office_of(&db_pool, winner).await, Some(office_id));
+        assert_eq!(office_of(&db_pool, holder).await, None);
+
+        clean_up(&db_pool, prefix).await;
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_multi_seat_race_removes_losers_and_retirees() {
+        let _lock = TRANSITIONS.lock().await;
+        let db_pool = crate::DatabasePool::new().await.unwrap().connection;
+        let prefix = "test-transition-multi-seat";
+        let office_id = office(&db_pool, prefix).await;
+        let reelected =
+            politician(&db_pool, &format!("{}-reelected", prefix), Some(office_id)).await;
+        let loser = politician(&db_pool, &format!("{}-loser", prefix), Some(office_id)).await;
+        let retiree = politician(&db_pool, &format!("{}-retiree", prefix), Some(office_id)).await;
+        let newcomer = politician(&db_pool, &format!("{}-newcomer", prefix), None).await;
+        let race_id = race(
+            &db_pool,
+            prefix,
+            office_id,
+            2,
+            &[reelected, loser, newcomer],
+        )
+        .await;
+        let today = date(2025, 1, 6);
+        let old_dates = (Some(date(2021, 1, 1)), Some(date(2025, 1, 1)));
+        let new_dates = (Some(date(2025, 1, 1)), Some(date(2029, 1, 1)));
+        for holder in [reelected, loser, retiree] {
+            term(&db_pool, office_id, holder, None, old_dates, true).await;
+        }
+        let reelected_term = term(
+            &db_pool,
+            office_id,
+            reelected,
+            Some(race_id),
+            new_dates,
+            false,
+        )
+        .await;
+        let newcomer_term = term(
+            &db_pool,
+            office_id,
+            newcomer,
+            Some(race_id),
+            new_dates,
+            false,
+        )
+        .await;
+
+        let run = transition_office_holders(&db_pool, today, false)
+            .await
+            .unwrap();
+        assert_eq!(
+            transition(&run, newcomer_term).outgoing_politician_ids,
+            vec![loser]
+        );
+        assert!(transition(&run, reelected_term).is_applied);
+        let ended = run
+            .ended_terms
+            .iter()
+            .filter(|t| t.office_id == office_id)
+            .map(|t| t.politician_id)
+            .collect::<Vec<_>>();
+        assert_eq!(ended, vec![retiree]);
+        assert_eq!(office_of(&db_pool, reelected).await, Some(office_id));
+        assert_eq!(office_of(&db_pool, newcomer).await, Some(office_id));
+        assert_eq!(office_of(&db_pool, loser).await, None);
+        assert_eq!(office_of(&db_pool, retiree).await, None);
+
+        clean_up(&db_pool, prefix).await;
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_appointment_only_ends_the_term_it_replaces() {
+        let _lock = TRANSITIONS.lock().await;
+        let db_pool = crate::DatabasePool::new().await.unwrap().connection;
+        let prefix = "test-transition-appointment";
+        let office_id = office(&db_pool, prefix).await;
+        let resigning =
+            politician(&db_pool, &format!("{}-resigning", prefix), Some(office_id)).await;
+        let colleague =
+            politician(&db_pool, &format!("{}-colleague", prefix), Some(office_id)).await;
+        let appointee = politician(&db_pool, &format!("{}-appointee", prefix), None).await;
+        let today = date(2025, 3, 3);
+        let term_dates = (Some(date(2023, 1, 1)), Some(date(2027, 1, 1)));
+        let resigning_term = term(&db_pool, office_id, resigning, None, term_dates, true).await;
+        let colleague_term = term(&db_pool, office_id, colleague, None, term_dates, true).await;
+        // Staff filling in when a backfilled term began doesn't end anyone else's
+        let backfilled_term = term(
+            &db_pool,
+            office_id,
+            colleague,
+            None,
+            (Some(date(2019, 1, 1)), None),
+            false,
+        )
+        .await;
+        let appointment = sqlx::query_scalar!(
+            r#"
+                INSERT INTO office_term (office_id, politician_id, obtained_by, start_date, end_date, replaces_term_id)
+                VALUES ($1, $2, 'appointed', $3, $4, $5)
+                RETURNING id
+            "#,
+            office_id,
+            appointee,
+            date(2025, 3, 1),
+            date(2027, 1, 1),
+            resigning_term,
+        )
+        .fetch_one(&db_pool)
+        .await
+        .unwrap();
+
+        let run = transition_office_holders(&db_pool, today, false)
+            .await
+            .unwrap();
+        assert_eq!(
+            transition(&run, appointment).outgoing_politician_ids,
+            vec![resigning]
+        );
+        assert!(transition(&run, backfilled_term)
+            .outgoing_politician_ids
+            .is_empty());
+        assert_eq!(
+            end_of(&db_pool, resigning_term).await,
+            Some(date(2025, 3, 1))
+        );
+        assert_eq!(
+            end_of(&db_pool, colleague_term).await,
+            Some(date(2027, 1, 1))
+        );
+        assert_eq!(office_of(&db_pool, appointee).await, Some(office_id));
+        assert_eq!(office_of(&db_pool, colleague).await, Some(office_id));
+        assert_eq!(office_of(&db_pool, resigning).await, None);
+
+        clean_up(&db_pool, prefix).await;
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_dry_run_reports_without_writing() {
+        let _lock = TRANSITIONS.lock().await;
+        let db_pool = crate::DatabasePool::new().await.unwrap().connection;
+        let prefix = "test-transition-dry-run";
+        let office_id = office(&db_pool, prefix).await;
+        let holder = politician(&db_pool, &format!("{}-holder", prefix), Some(office_id)).await;
+        let winner = politician(&db_pool, &format!("{}-winner", prefix), None).await;
+        let race_id = race(&db_pool, prefix, office_id, 1, &[winner]).await;
+        let today = date(2025, 1, 6);
+        let old_term = term(
+            &db_pool,
+            office_id,
+            holder,
+            None,
+            (Some(date(2021, 1, 1)), Some(date(2025, 1, 1))),
+            true,
+        )
+        .await;
+        let new_term = term(
+            &db_pool,
+            office_id,
+            winner,
+            Some(race_id),
+            (Some(date(2025, 1, 1)), Some(date(2029, 1, 1))),
+            false,
+        )
+        .await;
+
+        let run = transition_office_holders(&db_pool, today, true)
+            .await
+            .unwrap();
+        let handover = transition(&run, new_term);
+        assert!(handover.is_applied);
+        assert_eq!(handover.outgoing_politician_ids, vec![holder]);
+        // The holder is reported once, as replaced rather than as a term that ran out
+        assert!(!run.ended_terms.iter().any(|t| t.politician_id == holder));
+        assert_eq!(office_of(&db_pool, holder).await, Some(office_id));
+        assert_eq!(office_of(&db_pool, winner).await, None);
+        assert_eq!(end_of(&db_pool, old_term).await, Some(date(2025, 1, 1)));
+        let took_office_at = sqlx::query_scalar!(
+            "SELECT took_office_at FROM office_term WHERE id = $1",
+            new_term
+        )
+        .fetch_one(&db_pool)
+        .await
+        .unwrap();
+        assert!(took_office_at.is_none());
+
+        clean_up(&db_pool, prefix).await;
+    }
+}
@@ -2,6 +2,7 @@ use crate::CreateOrConnectIssueTagInput;
 use crate::DateTime;
 use crate::IssueTag;
 use crate::IssueTagIdentifier;
+use crate::{ExternalId, ExternalIdEntityType, ExternalIdSource};
 use async_graphql::InputObject;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JSON;
@@ -219,12 +220,26 @@ impl Organization {
         Ok(record)
     }
 
+    /// Looks up an organization by its VoteSmart special interest group id or another system's id
+    pub async fn find_by_external_id(
+        db_pool: &PgPool,
+        source: ExternalIdSource,
+        value: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        ExternalId::find_entity(db_pool, ExternalIdEntityType::Organization, source, value).await
+    }
+
+    /// Also resolves slugs the record had before it was renamed
     pub async fn find_by_slug(db_pool: &PgPool, slug: String) -> Result<Self, sqlx::Error> {
         let record = sqlx::query_as!(
             Organization,
             r#"
                 SELECT * FROM organization
-                WHERE slug = $1
+                WHERE slug = $1 OR id = (
+                    SELECT entity_id FROM slug_history WHERE entity_type = 'organization' AND slug = $1
+                )
+                ORDER BY slug = $1 DESC
+                LIMIT 1
             "#,
             slug
         )
@@ -1,6 +1,7 @@
 use async_graphql::InputObject;
-use sqlx::postgres::PgPool;
-use sqlx::FromRow;
+use sqlx::{FromRow, PgConnection, PgPool};
+
+use crate::{ExternalId, ExternalIdEntityType, ExternalIdSource};
 
 #[derive(FromRow, Debug, Clone)]
 pub struct Party {
@@ -23,8 +24,17 @@ pub struct UpsertPartyInput {
 }
 
 impl Party {
-    pub async fn upsert_from_source(
+    /// Looks up a party by its id in another system, e.g. its FEC party code
+    pub async fn find_by_external_id(
         db_pool: &PgPool,
+        source: ExternalIdSource,
+        value: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        ExternalId::find_entity(db_pool, ExternalIdEntityType::Party, source, value).await
+    }
+
+    pub async fn upsert_from_source(
+        conn: &mut PgConnection,
         input: &UpsertPartyInput,
     ) -> Result<Self, sqlx::Error> {
         input
@@ -52,7 +62,7 @@ impl Party {
             input.fec_code,
             input.notes,
         )
-        .fetch_one(db_pool)
+        .fetch_one(&mut *conn)
         .await
     }
 }
@@ -1,13 +1,14 @@
 use crate::{
     models::enums::State, CreateOrConnectIssueTagInput, CreateOrConnectOrganizationInput, DateTime,
-    IssueTag, Organization, OrganizationIdentifier,
+    ExternalId, ExternalIdEntityType, ExternalIdSource, IssueTag, Organization,
+    OrganizationIdentifier, Page, PageRequest, SortDirection,
 };
 use async_graphql::InputObject;
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JSON;
 use slugify::slugify;
-use sqlx::{postgres::PgPool, FromRow};
+use sqlx::{postgres::PgPool, FromRow, PgConnection};
 
 use super::enums::{Chambers, PoliticalScope};
 
@@ -305,7 +306,7 @@ impl Politician {
     }
 
     pub async fn upsert_from_source(
-        db_pool: &PgPool,
+        conn: &mut PgConnection,
         input: &UpsertPoliticianInput,
     ) -> Result<Self, sqlx::Error> {
         input
@@ -320,6 +321,20 @@ impl Politician {
             .ok_or("slug is required")
             .map_err(|err| sqlx::Error::AnyDriverError(err.into()))?;
 
+        // The ref key may have been merged into another politician, upsert that record instead
+        let ref_key = sqlx::query_scalar!(
+            r#"
+            SELECT p.ref_key FROM external_id e
+            JOIN politician p ON p.id = e.entity_id
+            WHERE e.entity_type = 'politician' AND e.source = 'ref_key' AND e.value = $1
+            "#,
+            input.ref_key,
+        )
+        .fetch_optional(&mut *conn)
+        .await?
+        .flatten()
+        .or_else(|| input.ref_key.clone());
+
         let slug = sqlx::query_scalar!(
             r#"
             SELECT CASE
@@ -348,10 +363,10 @@ impl Politician {
                 $2
             END
             "#,
-            input.ref_key,
+            ref_key,
             input.slug,
         )
-        .fetch_one(db_pool)
+        .fetch_one(&mut *conn)
         .await?
         .ok_or_else(|| {
             sqlx::Error::AnyDriverError("Failure determining slug for Politician".into())
@@ -438,7 +453,7 @@ impl Politician {
                 updated_at
 
             "#, 
-            input.ref_key,
+            ref_key,
             &slug,
             input.first_name,
             input.middle_name,
@@ -473,7 +488,7 @@ impl Politician {
             input.fec_candidate_id,
             input.race_wins,
             input.race_losses,
-        ).fetch_one(db_pool).await
+        ).fetch_one(&mut *conn).await
     }
 
     pub async fn update(
@@ -600,12 +615,26 @@ impl Politician {
         Ok(record)
     }
 
+    /// Removes a politician from their office, ending their current term in it today
     pub async fn remove_office(
         db_pool: &PgPool,
         politician_id: uuid::Uuid,
     ) -> Result<(), sqlx::Error> {
         sqlx::query!(
-            "UPDATE politician SET office_id = NULL WHERE id = $1",
+            r#"
+                WITH removed AS (
+                    UPDATE politician p SET office_id = NULL
+                    FROM politician previous
+                    WHERE p.id = $1 AND previous.id = p.id
+                    RETURNING previous.office_id
+                )
+                UPDATE office_term t SET end_date = CURRENT_DATE
+                FROM removed
+                WHERE t.politician_id = $1
+                AND t.office_id = removed.office_id
+                AND (t.start_date IS NULL OR t.start_date <= CURRENT_DATE)
+                AND (t.end_date IS NULL OR t.end_date > CURRENT_DATE)
+            "#,
             politician_id
         )
         .execute(db_pool)
@@ -718,6 +747,15 @@ impl Politician {
         Ok(record)
     }
 
+    /// Looks up a politician by their id in another system, e.g. a VoteSmart or FEC candidate id
+    pub async fn find_by_external_id(
+        db_pool: &PgPool,
+        source: ExternalIdSource,
+        value: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        ExternalId::find_entity(db_pool, ExternalIdEntityType::Politician, source, value).await
+    }
+
     pub async fn find_by_slug(db_pool: &PgPool, slug: String) -> Result<Self, sqlx::Error> {
         let record = sqlx::query_as!(
             Politician,
@@ -825,88 +863,112 @@ impl Politician {
         Ok(record)
     }
 
+    fn filter_query() -> &'static str {
+        r#"
+            SELECT  p.id,
+                    p.slug,
+                    p.ref_key,
+                    first_name,
+                    middle_name,
+                    last_name,
+                    suffix,
+                    preferred_name,
+                    full_name,
+                    biography,
+                    biography_source,
+                    home_state,
+                    date_of_birth,
+                    office_id,
+                    upcoming_race_id,
+                    thumbnail_image_url,
+                    assets,
+                    official_website_url,
+                    campaign_website_url,
+                    facebook_url,
+                    twitter_url,
+                    instagram_url,
+                    youtube_url,
+                    linkedin_url,
+                    tiktok_url,
+                    email,
+                    phone,
+                    party_id,
+                    votesmart_candidate_id,
+                    votesmart_candidate_bio,
+                    votesmart_candidate_ratings,
+                    legiscan_people_id,
+                    crp_candidate_id,
+                    fec_candidate_id,
+                    race_wins,
+                    race_losses,
+                    p.created_at,
+                    p.updated_at,
+                    last_name AS sort_value
+            FROM politician p
+            LEFT JOIN office o ON office_id = o.id,
+            to_tsvector(
+                first_name || ' ' || last_name || ' ' || COALESCE(preferred_name, '') || COALESCE(o.title, '')
+             ) document,
+            websearch_to_tsquery($1::text) query,
+            NULLIF(ts_rank(to_tsvector(first_name), query), 0) rank_first_name,
+            NULLIF(ts_rank(to_tsvector(last_name), query), 0) rank_last_name,
+            NULLIF(ts_rank(to_tsvector(preferred_name), query), 0) rank_preferred_name,
+            NULLIF(ts_rank(to_tsvector(o.title), query), 0) rank_office_title
+            WHERE (($1::text = '') IS NOT FALSE OR query @@ document)
+            AND ($2::state IS NULL OR home_state = $2)
+            AND ($3::political_scope IS NULL OR political_scope = $3)
+            AND ($4::text IS NULL OR $4 = 'All' OR (
+                ($4 = 'Senate' AND o.title ILIKE '%Senator') OR
+                ($4 = 'House' AND o.title ILIKE '%Representative')
+            ))
+            GROUP BY (
+                p.id,
+                rank_last_name,
+                rank_preferred_name,
+                rank_first_name,
+                rank_office_title,
+                o.title
+            )
+        "#
+    }
+
+    /// Returns a single page of politicians sorted by last name
     pub async fn filter(
         db_pool: &PgPool,
         filter: &PoliticianFilter,
-    ) -> Result<Vec<Self>, sqlx::Error> {
+        page: &PageRequest,
+    ) -> Result<Page<Self>, sqlx::Error> {
         let search_query = filter.query.to_owned().unwrap_or_default();
-        let records = sqlx::query_as!(
-            Politician,
-            r#"
-                SELECT  p.id,
-                        p.slug,
-                        p.ref_key,
-                        first_name,
-                        middle_name,
-                        last_name,
-                        suffix,
-                        preferred_name,
-                        full_name,
-                        biography,
-                        biography_source,
-                        home_state AS "home_state:State",
-                        date_of_birth,
-                        office_id,
-                        upcoming_race_id,
-                        thumbnail_image_url,
-                        assets,
-                        official_website_url,
-                        campaign_website_url,
-                        facebook_url,
-                        twitter_url,
-                        instagram_url,
-                        youtube_url,
-                        linkedin_url,
-                        tiktok_url,
-                        email,
-                        phone,
-                        party_id,
-                        votesmart_candidate_id,
-                        votesmart_candidate_bio,
-                        votesmart_candidate_ratings,
-                        legiscan_people_id,
-                        crp_candidate_id,
-                        fec_candidate_id,
-                        race_wins,
-                        race_losses,
-                        p.created_at,
-                        p.updated_at 
-                FROM politician p
-                LEFT JOIN office o ON office_id = o.id,
-                to_tsvector(
-                    first_name || ' ' || last_name || ' ' || COALESCE(preferred_name, '') || COALESCE(o.title, '')
-                 ) document,
-                websearch_to_tsquery($1::text) query,
-                NULLIF(ts_rank(to_tsvector(first_name), query), 0) rank_first_name,
-                NULLIF(ts_rank(to_tsvector(last_name), query), 0) rank_last_name,
-                NULLIF(ts_rank(to_tsvector(preferred_name), query), 0) rank_preferred_name,
-                NULLIF(ts_rank(to_tsvector(o.title), query), 0) rank_office_title
-                WHERE (($1::text = '') IS NOT FALSE OR query @@ document)
-                AND ($2::state IS NULL OR home_state = $2)
-                AND ($3::political_scope IS NULL OR political_scope = $3)
-                AND ($4::text IS NULL OR $4 = 'All' OR (
-                    ($4 = 'Senate' AND o.title ILIKE '%Senator') OR
-                    ($4 = 'House' AND o.title ILIKE '%Representative')
-                ))
-                GROUP BY (
-                    p.id,
-                    rank_last_name,
-                    rank_preferred_name,
-                    rank_first_name,
-                    rank_office_title,
-                    o.title
-                )
-                ORDER BY last_name ASC
-                LIMIT 50
-            "#,
-            search_query,
-            filter.home_state as Option<State>,
-            filter.political_scope as Option<PoliticalScope>,
-            filter.chambers.map(|c| c.to_string()) as Option<String>
-        )
-        .fetch_all(db_pool)
-        .await?;
-        Ok(records)
+        let query = page.paginate(Self::filter_query(), "text", SortDirection::Asc, 5);
+
+        let rows = page
+            .bind(
+                sqlx::query(&query)
+                    .bind(search_query)
+                    .bind(filter.home_state)
+                    .bind(filter.political_scope)
+                    .bind(filter.chambers.map(|c| c.to_string())),
+            )
+            .fetch_all(db_pool)
+            .await?;
+
+        Page::from_rows(rows, page)
+    }
+
+    pub async fn count(db_pool: &PgPool, filter: &PoliticianFilter) -> Result<i64, sqlx::Error> {
+        let search_query = filter.query.to_owned().unwrap_or_default();
+        let query = format!(
+            "SELECT COUNT(*) FROM ({}) AS filtered",
+            Self::filter_query()
+        );
+
+        sqlx::query_scalar(&query)
+            .bind(search_query)
+            .bind(filter.home_state)
+            .bind(filter.political_scope)
+            .bind(filter.chambers.map(|c| c.to_string()))
+            .fetch_one(db_pool)
+            .await
     }
 
     pub async fn organization_endorsements(
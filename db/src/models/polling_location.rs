@@ -0,0 +1,338 @@
+use super::{enums::State, precinct::Precinct};
+use crate::DateTime;
+use async_graphql::Enum;
+use chrono::{NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use strum_macros::Display;
+
+#[derive(Enum, Display, Debug, Copy, Clone, Eq, PartialEq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "polling_location_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PollingLocationType {
+    ElectionDay,
+    EarlyVoting,
+}
+
+#[derive(FromRow, Debug, Clone)]
+pub struct PollingLocation {
+    pub id: uuid::Uuid,
+    pub election_id: uuid::Uuid,
+    pub location_type: PollingLocationType,
+    pub state: State,
+    /// Empty for early voting locations open to the whole state
+    pub county_code: Option<String>,
+    pub name: String,
+    pub line_1: String,
+    pub line_2: Option<String>,
+    pub city: String,
+    pub postal_code: Option<String>,
+    pub accessibility_notes: Option<String>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(FromRow, Debug, Clone)]
+pub struct PollingLocationHours {
+    pub polling_location_id: uuid::Uuid,
+    pub date: NaiveDate,
+    pub opens_at: NaiveTime,
+    pub closes_at: NaiveTime,
+}
+
+/// A row of a polling place file. Locations repeat once per precinct they serve and date they're
+/// open, rows for the same location are combined.
+#[derive(Debug, Clone)]
+pub struct PollingLocationRow {
+    pub location_type: PollingLocationType,
+    pub county_code: Option<String>,
+    /// Required for election day locations. Early voting rows without one serve their county.
+    /// Rows with one need its `county_code`.
+    pub precinct_code: Option<String>,
+    pub name: String,
+    pub line_1: String,
+    pub line_2: Option<String>,
+    pub city: String,
+    pub postal_code: Option<String>,
+    pub date: Option<NaiveDate>,
+    pub opens_at: Option<NaiveTime>,
+    pub closes_at: Option<NaiveTime>,
+    pub accessibility_notes: Option<String>,
+}
+
+/// Where the voter at an address can vote in an election
+#[derive(Debug, Clone, Default)]
+pub struct PollingPlace {
+    /// Empty when the address isn't geocoded or no precinct polygon contains it
+    pub precinct: Option<Precinct>,
+    pub election_day: Option<PollingLocation>,
+    pub early_voting: Vec<PollingLocation>,
+    /// Hours of every location above
+    pub hours: Vec<PollingLocationHours>,
+}
+
+/// Reads opening and closing times as polling place files write them, e.g. `07:00`, `7:00 AM`
+/// or `19:00:00`
+pub fn parse_polling_time(value: &str) -> Option<NaiveTime> {
+    let value = value.trim().to_uppercase();
+    ["%H:%M", "%H:%M:%S", "%I:%M %p", "%I:%M%p"]
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(&value, format).ok())
+}
+
+impl PollingLocation {
+    /// Replaces an election's polling locations in a state with those in `rows`. Only location
+    /// types present in the file are replaced, so election day and early voting files can be
+    /// imported separately. Precincts the file mentions are added if results or boundaries
+    /// haven't added them yet. Returns the number of locations imported.
+    pub async fn import(
+        db_pool: &PgPool,
+        election_id: uuid::Uuid,
+        state: State,
+        rows: &[PollingLocationRow],
+    ) -> Result<u64, sqlx::Error> {
+        let mut location_types = vec![];
+        for row in rows {
+            if !location_types.contains(&row.location_type) {
+                location_types.push(row.location_type);
+            }
+        }
+
+        let mut tx = db_pool.begin().await?;
+        for location_type in location_types {
+            sqlx::query!(
+                r#"
+                    DELETE FROM polling_location
+                    WHERE election_id = $1 AND state = $2 AND location_type = $3
+                "#,
+                election_id,
+                state as State,
+                location_type as PollingLocationType,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let mut location_ids = vec![];
+        for row in rows {
+            let location_id = sqlx::query_scalar!(
+                r#"
+                    INSERT INTO polling_location
+                    (election_id, location_type, state, county_code, name, line_1, line_2, city, postal_code, accessibility_notes)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                    ON CONFLICT (election_id, location_type, state, name, line_1, city) DO UPDATE SET
+                        line_2 = COALESCE(polling_location.line_2, EXCLUDED.line_2),
+                        postal_code = COALESCE(polling_location.postal_code, EXCLUDED.postal_code),
+                        accessibility_notes = COALESCE(polling_location.accessibility_notes, EXCLUDED.accessibility_notes)
+                    RETURNING id
+                "#,
+                election_id,
+                row.location_type as PollingLocationType,
+                state as State,
+                row.county_code,
+                row.name,
+                row.line_1,
+                row.line_2,
+                row.city,
+                row.postal_code,
+                row.accessibility_notes,
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+            location_ids.push(location_id);
+
+            if let (Some(county_code), Some(precinct_code)) = (&row.county_code, &row.precinct_code)
+            {
+                sqlx::query!(
+                    r#"
+                        INSERT INTO precinct (state, county_code, precinct_code)
+                        VALUES ($1, $2, $3)
+                        ON CONFLICT (state, county_code, precinct_code) DO NOTHING
+                    "#,
+                    state as State,
+                    county_code,
+                    precinct_code,
+                )
+                .execute(&mut *tx)
+                .await?;
+
+                sqlx::query!(
+                    r#"
+                        INSERT INTO polling_location_precinct (polling_location_id, precinct_id)
+                        SELECT $1, id FROM precinct
+                        WHERE state = $2 AND county_code = $3 AND precinct_code = $4
+                        ON CONFLICT DO NOTHING
+                    "#,
+                    location_id,
+                    state as State,
+                    county_code,
+                    precinct_code,
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+
+            if let (Some(date), Some(opens_at), Some(closes_at)) =
+                (row.date, row.opens_at, row.closes_at)
+            {
+                sqlx::query!(
+                    r#"
+                        INSERT INTO polling_location_hours (polling_location_id, date, opens_at, closes_at)
+                        VALUES ($1, $2, $3, $4)
+                        ON CONFLICT (polling_location_id, date) DO UPDATE SET
+                            opens_at = EXCLUDED.opens_at,
+                            closes_at = EXCLUDED.closes_at
+                    "#,
+                    location_id,
+                    date,
+                    opens_at,
+                    closes_at,
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+        tx.commit().await?;
+
+        location_ids.sort();
+        location_ids.dedup();
+        Ok(location_ids.len() as u64)
+    }
+
+    /// Opening hours of the given locations by date
+    pub async fn hours(
+        db_pool: &PgPool,
+        polling_location_ids: &[uuid::Uuid],
+    ) -> Result<Vec<PollingLocationHours>, sqlx::Error> {
+        sqlx::query_as!(
+            PollingLocationHours,
+            r#"
+                SELECT polling_location_id, date, opens_at, closes_at
+                FROM polling_location_hours
+                WHERE polling_location_id = ANY($1)
+                ORDER BY date, opens_at
+            "#,
+            polling_location_ids,
+        )
+        .fetch_all(db_pool)
+        .await
+    }
+}
+
+impl PollingPlace {
+    /// Finds the precinct containing an address, on the precinct maps in use for an election,
+    /// and the places its voters can vote in it. Election day voters go to their precinct's polling place. Early voting
+    /// locations linked to precincts serve only those, the rest serve their county or state.
+    pub async fn for_address(
+        db_pool: &PgPool,
+        election_id: uuid::Uuid,
+        address_id: uuid::Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let precinct = sqlx::query_as!(
+            Precinct,
+            r#"
+                SELECT
+                    p.id,
+                    p.state AS "state:State",
+                    p.county_code,
+                    p.precinct_code,
+                    p.name,
+                    p.created_at,
+                    p.updated_at
+                FROM precinct p
+                JOIN address a ON a.id = $1 AND p.state::text = a.state
+                JOIN precinct_boundary b ON b.precinct_id = p.id
+                    AND b.vintage = precinct_vintage_on(p.state, (SELECT election_date FROM election WHERE id = $2))
+                WHERE ST_Contains(b.geom, a.geom)
+                LIMIT 1
+            "#,
+            address_id,
+            election_id,
+        )
+        .fetch_optional(db_pool)
+        .await?;
+
+        let Some(precinct) = precinct else {
+            return Ok(Self::default());
+        };
+
+        let locations = sqlx::query_as!(
+            PollingLocation,
+            r#"
+                SELECT
+                    pl.id,
+                    pl.election_id,
+                    pl.location_type AS "location_type:PollingLocationType",
+                    pl.state AS "state:State",
+                    pl.county_code,
+                    pl.name,
+                    pl.line_1,
+                    pl.line_2,
+                    pl.city,
+                    pl.postal_code,
+                    pl.accessibility_notes,
+                    pl.created_at,
+                    pl.updated_at
+                FROM polling_location pl
+                WHERE pl.election_id = $1
+                AND pl.state = $2
+                AND (
+                    EXISTS (
+                        SELECT 1 FROM polling_location_precinct
+                        WHERE polling_location_id = pl.id AND precinct_id = $3
+                    )
+                    OR (
+                        pl.location_type = 'early_voting'
+                        AND (pl.county_code IS NULL OR pl.county_code = $4)
+                        AND NOT EXISTS (
+                            SELECT 1 FROM polling_location_precinct
+                            WHERE polling_location_id = pl.id
+                        )
+                    )
+                )
+                ORDER BY pl.updated_at DESC, pl.name
+            "#,
+            election_id,
+            precinct.state as State,
+            precinct.id,
+            precinct.county_code,
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        let hours =
+            PollingLocation::hours(db_pool, &locations.iter().map(|l| l.id).collect::<Vec<_>>())
+                .await?;
+        let (election_day, early_voting): (Vec<_>, Vec<_>) = locations
+            .into_iter()
+            .partition(|l| l.location_type == PollingLocationType::ElectionDay);
+
+        Ok(Self {
+            precinct: Some(precinct),
+            election_day: election_day.into_iter().next(),
+            early_voting,
+            hours,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_polling_time() {
+        let seven_am = NaiveTime::from_hms_opt(7, 0, 0);
+        assert_eq!(parse_polling_time("07:00"), seven_am);
+        assert_eq!(parse_polling_time("7:00 am"), seven_am);
+        assert_eq!(
+            parse_polling_time("20:00:00"),
+            NaiveTime::from_hms_opt(20, 0, 0)
+        );
+        assert_eq!(
+            parse_polling_time("8:00PM"),
+            NaiveTime::from_hms_opt(20, 0, 0)
+        );
+        assert_eq!(parse_polling_time("noon"), None);
+    }
+}
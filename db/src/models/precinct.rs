@@ -0,0 +1,388 @@
+use crate::{models::enums::State, DateTime};
+use sqlx::{FromRow, PgConnection, PgPool};
+
+#[derive(FromRow, Debug, Clone)]
+pub struct Precinct {
+    pub id: uuid::Uuid,
+    pub state: State,
+    pub county_code: String,
+    pub precinct_code: String,
+    pub name: Option<String>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+/// A candidate's votes in a precinct, as read from a results file
+#[derive(Debug, Clone)]
+pub struct PrecinctResultRow {
+    pub county_code: String,
+    pub precinct_code: String,
+    pub office_name: String,
+    pub candidate_name: String,
+    pub votes: i32,
+    /// Votes for every candidate for the office in the precinct
+    pub total_votes: Option<i32>,
+}
+
+/// A precinct's turnout, as read from a statistics file
+#[derive(Debug, Clone, Default)]
+pub struct PrecinctStatisticsRow {
+    pub county_code: String,
+    pub precinct_code: String,
+    pub precinct_name: Option<String>,
+    pub has_reported: bool,
+    pub registered_voters: Option<i32>,
+    pub election_day_registrations: Option<i32>,
+    pub roster_signatures: Option<i32>,
+    pub military_overseas_absentee_ballots: Option<i32>,
+    pub federal_only_absentee_ballots: Option<i32>,
+    pub president_only_absentee_ballots: Option<i32>,
+    pub total_voted: Option<i32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PrecinctCandidateVotes {
+    pub candidate_id: Option<uuid::Uuid>,
+    pub candidate_name: String,
+    pub votes: i32,
+}
+
+/// A race's results in one precinct with the precinct's turnout
+#[derive(Debug, Clone)]
+pub struct PrecinctRaceResult {
+    pub precinct_id: uuid::Uuid,
+    pub county_code: String,
+    pub precinct_code: String,
+    pub name: Option<String>,
+    pub total_votes: Option<i32>,
+    pub registered_voters: Option<i32>,
+    /// Empty until the precinct reports its turnout
+    pub total_voted: Option<i32>,
+    /// Most votes first
+    pub candidates: Vec<PrecinctCandidateVotes>,
+}
+
+impl Precinct {
+    /// Sets a precinct's polygon for a vintage of the precinct maps from a GeoJSON Polygon or
+    /// MultiPolygon in WGS 84, adding the precinct if results haven't mentioned it yet
+    pub async fn upsert_geometry(
+        conn: &mut PgConnection,
+        state: State,
+        county_code: &str,
+        precinct_code: &str,
+        name: Option<&str>,
+        vintage: i32,
+        geometry: &str,
+    ) -> Result<uuid::Uuid, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+                WITH p AS (
+                    INSERT INTO precinct (state, county_code, precinct_code, name)
+                    VALUES ($1, $2, $3, $4)
+                    ON CONFLICT (state, county_code, precinct_code) DO UPDATE SET
+                        name = COALESCE(precinct.name, EXCLUDED.name)
+                    RETURNING id
+                )
+                INSERT INTO precinct_boundary (precinct_id, vintage, geom)
+                SELECT id, $5, ST_Multi(ST_Force2D(ST_SetSRID(ST_GeomFromGeoJSON($6), 4326))) FROM p
+                ON CONFLICT (precinct_id, vintage) DO UPDATE SET geom = EXCLUDED.geom
+                RETURNING precinct_id
+            "#,
+            state as State,
+            county_code,
+            precinct_code,
+            name,
+            vintage,
+            geometry,
+        )
+        .fetch_one(conn)
+        .await
+    }
+}
+
+impl PrecinctRaceResult {
+    /// Stores precinct results for an election. Each office in the file is matched to the race
+    /// its candidates are running in, so write-ins are kept for races we know about and offices
+    /// with none of our candidates are skipped. Returns the number of rows stored.
+    pub async fn import(
+        db_pool: &PgPool,
+        election_id: uuid::Uuid,
+        state: State,
+        rows: &[PrecinctResultRow],
+    ) -> Result<u64, sqlx::Error> {
+        let column = |f: fn(&PrecinctResultRow) -> String| rows.iter().map(f).collect::<Vec<_>>();
+        let county_codes = column(|r| r.county_code.clone());
+        let precinct_codes = column(|r| r.precinct_code.clone());
+        let office_names = column(|r| r.office_name.clone());
+        let candidate_names = column(|r| r.candidate_name.clone());
+        let votes: Vec<i32> = rows.iter().map(|r| r.votes).collect();
+        let total_votes: Vec<Option<i32>> = rows.iter().map(|r| r.total_votes).collect();
+
+        let mut tx = db_pool.begin().await?;
+        upsert_precincts(&mut tx, state, &county_codes, &precinct_codes, None).await?;
+
+        let result = sqlx::query!(
+            r#"
+                WITH source AS (
+                    SELECT * FROM UNNEST($3::TEXT[], $4::TEXT[], $5::TEXT[], $6::TEXT[], $7::INTEGER[], $8::INTEGER[])
+                    AS s(county_code, precinct_code, office_name, candidate_name, votes, total_votes)
+                ),
+                candidates AS (
+                    SELECT DISTINCT ON (s.office_name, s.candidate_name)
+                        s.office_name,
+                        s.candidate_name,
+                        p.id AS candidate_id,
+                        rc.race_id
+                    FROM (SELECT DISTINCT office_name, candidate_name FROM source) s
+                    LEFT JOIN slug_history sh ON sh.entity_type = 'politician'
+                        AND sh.slug = SLUGIFY(s.candidate_name)
+                    JOIN politician p ON p.slug = SLUGIFY(s.candidate_name) OR p.id = sh.entity_id
+                    JOIN race_candidates rc ON rc.candidate_id = p.id
+                    JOIN race r ON r.id = rc.race_id AND r.election_id = $1
+                    ORDER BY s.office_name, s.candidate_name, rc.race_id
+                ),
+                offices AS (
+                    SELECT DISTINCT ON (office_name) office_name, race_id
+                    FROM candidates
+                    ORDER BY office_name, race_id
+                )
+                INSERT INTO precinct_result (race_id, precinct_id, candidate_id, candidate_name, votes, total_votes)
+                SELECT DISTINCT ON (o.race_id, pr.id, s.candidate_name)
+                    o.race_id, pr.id, c.candidate_id, s.candidate_name, s.votes, s.total_votes
+                FROM source s
+                JOIN offices o ON o.office_name = s.office_name
+                JOIN precinct pr ON pr.state = $2
+                    AND pr.county_code = s.county_code
+                    AND pr.precinct_code = s.precinct_code
+                LEFT JOIN candidates c ON c.office_name = s.office_name
+                    AND c.candidate_name = s.candidate_name
+                    AND c.race_id = o.race_id
+                ON CONFLICT (race_id, precinct_id, candidate_name) DO UPDATE SET
+                    candidate_id = EXCLUDED.candidate_id,
+                    votes = EXCLUDED.votes,
+                    total_votes = EXCLUDED.total_votes
+            "#,
+            election_id,
+            state as State,
+            &county_codes,
+            &precinct_codes,
+            &office_names,
+            &candidate_names,
+            &votes,
+            &total_votes as &[Option<i32>],
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Stores each precinct's turnout for an election, naming precincts along the way
+    pub async fn import_statistics(
+        db_pool: &PgPool,
+        election_id: uuid::Uuid,
+        state: State,
+        rows: &[PrecinctStatisticsRow],
+    ) -> Result<u64, sqlx::Error> {
+        let county_codes: Vec<String> = rows.iter().map(|r| r.county_code.clone()).collect();
+        let precinct_codes: Vec<String> = rows.iter().map(|r| r.precinct_code.clone()).collect();
+        let names: Vec<Option<String>> = rows.iter().map(|r| r.precinct_name.clone()).collect();
+        let number = |f: fn(&PrecinctStatisticsRow) -> Option<i32>| {
+            rows.iter().map(f).collect::<Vec<Option<i32>>>()
+        };
+
+        let mut tx = db_pool.begin().await?;
+        upsert_precincts(&mut tx, state, &county_codes, &precinct_codes, Some(&names)).await?;
+
+        let result = sqlx::query!(
+            r#"
+                INSERT INTO precinct_statistics (
+                    election_id,
+                    precinct_id,
+                    has_reported,
+                    registered_voters,
+                    election_day_registrations,
+                    roster_signatures,
+                    military_overseas_absentee_ballots,
+                    federal_only_absentee_ballots,
+                    president_only_absentee_ballots,
+                    total_voted
+                )
+                SELECT DISTINCT ON (pr.id) $1, pr.id, s.has_reported, s.registered_voters, s.election_day_registrations,
+                    s.roster_signatures, s.military_overseas_absentee_ballots, s.federal_only_absentee_ballots,
+                    s.president_only_absentee_ballots, s.total_voted
+                FROM UNNEST($3::TEXT[], $4::TEXT[], $5::BOOLEAN[], $6::INTEGER[], $7::INTEGER[], $8::INTEGER[], $9::INTEGER[], $10::INTEGER[], $11::INTEGER[], $12::INTEGER[])
+                    AS s(county_code, precinct_code, has_reported, registered_voters, election_day_registrations,
+                        roster_signatures, military_overseas_absentee_ballots, federal_only_absentee_ballots,
+                        president_only_absentee_ballots, total_voted)
+                JOIN precinct pr ON pr.state = $2
+                    AND pr.county_code = s.county_code
+                    AND pr.precinct_code = s.precinct_code
+                ON CONFLICT (election_id, precinct_id) DO UPDATE SET
+                    has_reported = EXCLUDED.has_reported,
+                    registered_voters = EXCLUDED.registered_voters,
+                    election_day_registrations = EXCLUDED.election_day_registrations,
+                    roster_signatures = EXCLUDED.roster_signatures,
+                    military_overseas_absentee_ballots = EXCLUDED.military_overseas_absentee_ballots,
+                    federal_only_absentee_ballots = EXCLUDED.federal_only_absentee_ballots,
+                    president_only_absentee_ballots = EXCLUDED.president_only_absentee_ballots,
+                    total_voted = EXCLUDED.total_voted
+            "#,
+            election_id,
+            state as State,
+            &county_codes,
+            &precinct_codes,
+            &rows.iter().map(|r| r.has_reported).collect::<Vec<_>>(),
+            &number(|r| r.registered_voters) as &[Option<i32>],
+            &number(|r| r.election_day_registrations) as &[Option<i32>],
+            &number(|r| r.roster_signatures) as &[Option<i32>],
+            &number(|r| r.military_overseas_absentee_ballots) as &[Option<i32>],
+            &number(|r| r.federal_only_absentee_ballots) as &[Option<i32>],
+            &number(|r| r.president_only_absentee_ballots) as &[Option<i32>],
+            &number(|r| r.total_voted) as &[Option<i32>],
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Every precinct with results for a race, ordered by county and precinct
+    pub async fn for_race(db_pool: &PgPool, race_id: uuid::Uuid) -> Result<Vec<Self>, sqlx::Error> {
+        let records = sqlx::query!(
+            r#"
+                SELECT
+                    p.id,
+                    p.county_code,
+                    p.precinct_code,
+                    p.name,
+                    s.registered_voters,
+                    CASE WHEN s.has_reported THEN s.total_voted END AS total_voted,
+                    pr.candidate_id,
+                    pr.candidate_name,
+                    pr.votes,
+                    pr.total_votes
+                FROM precinct_result pr
+                JOIN precinct p ON p.id = pr.precinct_id
+                JOIN race r ON r.id = pr.race_id
+                LEFT JOIN precinct_statistics s ON s.precinct_id = p.id AND s.election_id = r.election_id
+                WHERE pr.race_id = $1
+                ORDER BY p.county_code, p.precinct_code, pr.votes DESC, pr.candidate_name
+            "#,
+            race_id,
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        let mut results: Vec<Self> = vec![];
+        for record in records {
+            let candidate = PrecinctCandidateVotes {
+                candidate_id: record.candidate_id,
+                candidate_name: record.candidate_name,
+                votes: record.votes,
+            };
+            match results.last_mut() {
+                Some(result) if result.precinct_id == record.id => {
+                    result.candidates.push(candidate)
+                }
+                _ => results.push(Self {
+                    precinct_id: record.id,
+                    county_code: record.county_code,
+                    precinct_code: record.precinct_code,
+                    name: record.name,
+                    total_votes: record.total_votes,
+                    registered_voters: record.registered_voters,
+                    total_voted: record.total_voted,
+                    candidates: vec![candidate],
+                }),
+            }
+        }
+        Ok(results)
+    }
+
+    /// A race's precinct results as a GeoJSON FeatureCollection, one feature per precinct with
+    /// the votes for each candidate, the leader and turnout as properties. Polygons are from the
+    /// precinct maps in use for the race's election, precincts without one have a null geometry.
+    pub async fn geojson_for_race(
+        db_pool: &PgPool,
+        race_id: uuid::Uuid,
+    ) -> Result<serde_json::Value, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+                WITH results AS (
+                    SELECT
+                        precinct_id,
+                        SUM(votes) AS votes_cast,
+                        MAX(total_votes) AS total_votes,
+                        JSON_OBJECT_AGG(candidate_name, votes ORDER BY votes DESC) AS votes,
+                        (ARRAY_AGG(candidate_name ORDER BY votes DESC))[1] AS leader,
+                        (ARRAY_AGG(candidate_id ORDER BY votes DESC))[1] AS leader_id
+                    FROM precinct_result
+                    WHERE race_id = $1
+                    GROUP BY precinct_id
+                )
+                SELECT JSON_BUILD_OBJECT(
+                    'type', 'FeatureCollection',
+                    'features', COALESCE(JSON_AGG(JSON_BUILD_OBJECT(
+                        'type', 'Feature',
+                        'id', p.id,
+                        'geometry', ST_AsGeoJSON(b.geom, 6)::JSON,
+                        'properties', JSON_BUILD_OBJECT(
+                            'county_code', p.county_code,
+                            'precinct_code', p.precinct_code,
+                            'name', p.name,
+                            'total_votes', COALESCE(r.total_votes, r.votes_cast),
+                            'leader', r.leader,
+                            'leader_id', r.leader_id,
+                            'votes', r.votes,
+                            'registered_voters', s.registered_voters,
+                            'total_voted', CASE WHEN s.has_reported THEN s.total_voted END
+                        )
+                    ) ORDER BY p.county_code, p.precinct_code), '[]'::JSON)
+                ) AS "collection!"
+                FROM results r
+                JOIN precinct p ON p.id = r.precinct_id
+                LEFT JOIN precinct_boundary b ON b.precinct_id = p.id
+                    AND b.vintage = precinct_vintage_on(p.state, (
+                        SELECT e.election_date FROM race ra
+                        JOIN election e ON e.id = ra.election_id
+                        WHERE ra.id = $1
+                    ))
+                LEFT JOIN precinct_statistics s ON s.precinct_id = p.id
+                    AND s.election_id = (SELECT election_id FROM race WHERE id = $1)
+            "#,
+            race_id,
+        )
+        .fetch_one(db_pool)
+        .await
+    }
+}
+
+async fn upsert_precincts(
+    conn: &mut PgConnection,
+    state: State,
+    county_codes: &[String],
+    precinct_codes: &[String],
+    names: Option<&[Option<String>]>,
+) -> Result<(), sqlx::Error> {
+    let names = names.map_or_else(|| vec![None; county_codes.len()], |n| n.to_vec());
+    sqlx::query!(
+        r#"
+            INSERT INTO precinct (state, county_code, precinct_code, name)
+            SELECT DISTINCT ON (county_code, precinct_code) $1, county_code, precinct_code, name
+            FROM UNNEST($2::TEXT[], $3::TEXT[], $4::TEXT[]) AS s(county_code, precinct_code, name)
+            ON CONFLICT (state, county_code, precinct_code) DO UPDATE SET
+                name = COALESCE(EXCLUDED.name, precinct.name)
+            WHERE EXCLUDED.name IS DISTINCT FROM precinct.name AND EXCLUDED.name IS NOT NULL
+        "#,
+        state as State,
+        county_codes,
+        precinct_codes,
+        &names as &[Option<String>],
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
@@ -1,12 +1,16 @@
 use super::enums::{PoliticalScope, RaceType, State, VoteType};
-use crate::{DateTime, ElectionScope};
-use async_graphql::InputObject;
+use crate::{
+    DateTime, ElectionScope, ExternalId, ExternalIdEntityType, ExternalIdSource,
+    RaceResultsSnapshot,
+};
+use async_graphql::{Enum, InputObject};
 use chrono::NaiveDate;
 use itertools::Itertools;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use slugify::slugify;
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
+use strum_macros::Display;
 
 #[derive(sqlx::FromRow, Debug, Clone)]
 pub struct Race {
@@ -49,7 +53,10 @@ pub struct UpsertRaceInput {
     pub state: Option<State>,
     pub election_id: Option<uuid::Uuid>,
     pub winner_ids: Option<Vec<uuid::Uuid>>,
+    /// Written with a results snapshot, see `RaceResultsSnapshot::update_votes`
     pub total_votes: Option<i32>,
+    /// Changes `total_votes` even when the race's results are certified
+    pub force_results: Option<bool>,
     pub is_special_election: bool,
     pub num_elect: Option<i32>,
 }
@@ -66,16 +73,54 @@ pub struct RaceFilter {
     year: Option<i32>,
 }
 
+#[derive(
+    Enum, Display, Debug, Copy, Clone, Eq, PartialEq, sqlx::Type, Serialize, Deserialize, Default,
+)]
+#[sqlx(type_name = "candidacy_status", rename_all = "snake_case")]
+pub enum CandidacyStatus {
+    #[default]
+    Filed,
+    /// On the ballot, or certified as a write-in
+    Qualified,
+    Withdrawn,
+    Disqualified,
+}
+
+impl CandidacyStatus {
+    /// Withdrawn and disqualified candidates are no longer running
+    pub fn is_running(&self) -> bool {
+        !matches!(
+            self,
+            CandidacyStatus::Withdrawn | CandidacyStatus::Disqualified
+        )
+    }
+}
+
 #[derive(sqlx::FromRow, Debug, Clone)]
 pub struct RaceCandidate {
     pub race_id: uuid::Uuid,
     pub candidate_id: uuid::Uuid,
+    pub status: CandidacyStatus,
+    pub is_running: bool,
+    pub is_write_in: bool,
+    pub is_incumbent: bool,
+    pub filing_date: Option<NaiveDate>,
+    pub ballot_designation: Option<String>,
+    pub party_on_ballot: Option<String>,
+    pub ballot_order: Option<i32>,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize, InputObject)]
 pub struct UpsertRaceCandidateInput {
     pub race_id: uuid::Uuid,
     pub candidate_id: uuid::Uuid,
+    pub status: Option<CandidacyStatus>,
+    pub is_write_in: Option<bool>,
+    pub is_incumbent: Option<bool>,
+    pub filing_date: Option<NaiveDate>,
+    pub ballot_designation: Option<String>,
+    pub party_on_ballot: Option<String>,
+    pub ballot_order: Option<i32>,
 }
 
 impl Race {
@@ -104,10 +149,11 @@ impl Race {
             slug = format!("{}-{}", r.slug, rando);
         }
 
-        let record = sqlx::query_as!(Race,
+        let mut tx = db_pool.begin().await?;
+        let mut record = sqlx::query_as!(Race,
             r#"
-                INSERT INTO race (id, slug, title, office_id, race_type, vote_type, party_id, state, description, ballotpedia_link, early_voting_begins_date, winner_ids, official_website, election_id, total_votes, is_special_election, num_elect)
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+                INSERT INTO race (id, slug, title, office_id, race_type, vote_type, party_id, state, description, ballotpedia_link, early_voting_begins_date, winner_ids, official_website, election_id, is_special_election, num_elect)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
                 ON CONFLICT (id) DO UPDATE
                 SET
                     slug = COALESCE($2, race.slug),
@@ -123,9 +169,8 @@ impl Race {
                     winner_ids = COALESCE($12, race.winner_ids),
                     official_website = COALESCE($13, race.official_website),
                     election_id = COALESCE($14, race.election_id),
-                    total_votes = COALESCE($15, race.total_votes),
-                    is_special_election = COALESCE($16, race.is_special_election),
-                    num_elect = COALESCE($17, race.num_elect)
+                    is_special_election = COALESCE($15, race.is_special_election),
+                    num_elect = COALESCE($16, race.num_elect)
                 RETURNING id, slug, title,  office_id, race_type AS "race_type:RaceType", vote_type AS "vote_type:VoteType", party_id, state AS "state:State", description, ballotpedia_link, early_voting_begins_date, winner_ids, official_website, election_id, total_votes, num_precincts_reporting, total_precincts, is_special_election, num_elect, created_at, updated_at
             "#,
             id,
@@ -142,18 +187,20 @@ impl Race {
             input.winner_ids.as_ref().map(|v| v.as_slice()),
             input.official_website,
             input.election_id,
-            input.total_votes,
             input.is_special_election,
             input.num_elect,
         )
-        .fetch_one(db_pool)
+        .fetch_one(&mut *tx)
         .await?;
+        record.set_total_votes(&mut tx, input).await?;
+        tx.commit().await?;
 
         Ok(record)
     }
 
+    /// New races without a vote type use the one from the office's latest race
     pub async fn upsert_from_source(
-        db_pool: &PgPool,
+        conn: &mut PgConnection,
         input: &UpsertRaceInput,
     ) -> Result<Self, sqlx::Error> {
         input
@@ -162,10 +209,10 @@ impl Race {
             .ok_or("slug is required")
             .map_err(|err| sqlx::Error::AnyDriverError(err.into()))?;
 
-        sqlx::query_as!(Race,
+        let mut record = sqlx::query_as!(Race,
             r#"
-                INSERT INTO race (slug, title, office_id, race_type, vote_type, party_id, state, description, ballotpedia_link, early_voting_begins_date, winner_ids, official_website, election_id, total_votes, is_special_election, num_elect)
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+                INSERT INTO race (slug, title, office_id, race_type, vote_type, party_id, state, description, ballotpedia_link, early_voting_begins_date, winner_ids, official_website, election_id, is_special_election, num_elect)
+                VALUES ($1, $2, $3, $4, COALESCE($5, (SELECT vote_type FROM race WHERE office_id = $3 ORDER BY created_at DESC LIMIT 1), 'plurality'), $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
                 ON CONFLICT (slug) DO UPDATE
                 SET
                     title = COALESCE($2, race.title), 
@@ -180,9 +227,8 @@ impl Race {
                     winner_ids = COALESCE($11, race.winner_ids),
                     official_website = COALESCE($12, race.official_website),
                     election_id = COALESCE($13, race.election_id),
-                    total_votes = COALESCE($14, race.total_votes),
-                    is_special_election = COALESCE($15, race.is_special_election),
-                    num_elect = COALESCE($16, race.num_elect)
+                    is_special_election = COALESCE($14, race.is_special_election),
+                    num_elect = COALESCE($15, race.num_elect)
                 RETURNING id, slug, title,  office_id, race_type AS "race_type:RaceType", vote_type AS "vote_type:VoteType", party_id, state AS "state:State", description, ballotpedia_link, early_voting_begins_date, winner_ids, official_website, election_id, total_votes, num_precincts_reporting, total_precincts, is_special_election, num_elect, created_at, updated_at
             "#,
             input.slug,
@@ -198,12 +244,34 @@ impl Race {
             input.winner_ids.as_ref().map(|v| v.as_slice()),
             input.official_website,
             input.election_id,
-            input.total_votes,
             input.is_special_election,
             input.num_elect,
         )
-        .fetch_one(db_pool)
-        .await
+        .fetch_one(&mut *conn)
+        .await?;
+        record.set_total_votes(conn, input).await?;
+        Ok(record)
+    }
+
+    /// Writes the input's total votes through the snapshotting update path
+    async fn set_total_votes(
+        &mut self,
+        conn: &mut PgConnection,
+        input: &UpsertRaceInput,
+    ) -> Result<(), sqlx::Error> {
+        if let Some(total_votes) = input.total_votes {
+            RaceResultsSnapshot::update_votes(
+                conn,
+                self.id,
+                Some(total_votes),
+                &[],
+                None,
+                input.force_results.unwrap_or(false),
+            )
+            .await?;
+            self.total_votes = Some(total_votes);
+        }
+        Ok(())
     }
 
     pub async fn delete(db_pool: &PgPool, id: uuid::Uuid) -> Result<(), sqlx::Error> {
@@ -228,12 +296,26 @@ impl Race {
         Ok(record)
     }
 
+    /// Looks up a race by the id a ballot data source such as Ballotpedia gives it
+    pub async fn find_by_external_id(
+        db_pool: &PgPool,
+        source: ExternalIdSource,
+        value: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        ExternalId::find_entity(db_pool, ExternalIdEntityType::Race, source, value).await
+    }
+
+    /// Also resolves slugs the record had before it was renamed
     pub async fn find_by_slug(db_pool: &PgPool, slug: String) -> Result<Self, sqlx::Error> {
         let record = sqlx::query_as!(
             Race,
             r#"
                 SELECT id, slug, title, office_id, race_type AS "race_type:RaceType", vote_type AS "vote_type:VoteType", party_id, state AS "state:State", description, ballotpedia_link, early_voting_begins_date, winner_ids, total_votes, num_precincts_reporting, total_precincts, official_website, election_id, is_special_election, num_elect, created_at, updated_at FROM race
-                WHERE slug = $1
+                WHERE slug = $1 OR id = (
+                    SELECT entity_id FROM slug_history WHERE entity_type = 'race' AND slug = $1
+                )
+                ORDER BY slug = $1 DESC
+                LIMIT 1
             "#,
             slug
         )
@@ -243,6 +325,25 @@ impl Race {
         Ok(record)
     }
 
+    /// An office's races with elections that have already happened, latest first
+    pub async fn find_past_by_office_id(
+        db_pool: &PgPool,
+        office_id: uuid::Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Race,
+            r#"
+                SELECT r.id, r.slug, r.title, r.office_id, r.race_type AS "race_type:RaceType", r.vote_type AS "vote_type:VoteType", r.party_id, r.state AS "state:State", r.description, r.ballotpedia_link, r.early_voting_begins_date, r.winner_ids, r.total_votes, r.num_precincts_reporting, r.total_precincts, r.official_website, r.election_id, r.is_special_election, r.num_elect, r.created_at, r.updated_at FROM race r
+                JOIN election e ON e.id = r.election_id
+                WHERE r.office_id = $1 AND e.election_date < CURRENT_DATE
+                ORDER BY e.election_date DESC, r.race_type DESC, r.title
+            "#,
+            office_id
+        )
+        .fetch_all(db_pool)
+        .await
+    }
+
     pub async fn filter(db_pool: &PgPool, input: RaceFilter) -> Result<Vec<Self>, sqlx::Error> {
         let office_titles = match input.office_titles {
             Some(office_titles) => office_titles.iter().map(|t| format!("'{}'", t)).join(","),
@@ -356,22 +457,82 @@ impl Race {
 }
 
 impl RaceCandidate {
+    /// Adds a candidate to a race or updates their filing details. Candidates staff have
+    /// withdrawn or disqualified keep that status, sources can't put them back on the ballot.
     pub async fn upsert_from_source(
+        conn: &mut PgConnection,
+        input: &UpsertRaceCandidateInput,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            RaceCandidate,
+            r#"
+                INSERT INTO race_candidates (race_id, candidate_id, status, is_running, is_write_in, is_incumbent, filing_date, ballot_designation, party_on_ballot, ballot_order)
+                VALUES ($1, $2, COALESCE($3, 'filed'::candidacy_status), COALESCE($3, 'filed'::candidacy_status) NOT IN ('withdrawn', 'disqualified'), COALESCE($4, false), COALESCE($5, false), $6, $7, $8, $9)
+                ON CONFLICT (race_id, candidate_id) DO UPDATE
+                SET
+                    status = CASE
+                        WHEN race_candidates.status IN ('withdrawn', 'disqualified') THEN race_candidates.status
+                        ELSE COALESCE($3, race_candidates.status)
+                    END,
+                    is_running = race_candidates.is_running AND EXCLUDED.is_running,
+                    is_write_in = COALESCE($4, race_candidates.is_write_in),
+                    is_incumbent = COALESCE($5, race_candidates.is_incumbent),
+                    filing_date = COALESCE($6, race_candidates.filing_date),
+                    ballot_designation = COALESCE($7, race_candidates.ballot_designation),
+                    party_on_ballot = COALESCE($8, race_candidates.party_on_ballot),
+                    ballot_order = COALESCE($9, race_candidates.ballot_order)
+                RETURNING race_id, candidate_id, status AS "status:CandidacyStatus", is_running, is_write_in, is_incumbent, filing_date, ballot_designation, party_on_ballot, ballot_order
+            "#,
+            input.race_id,
+            input.candidate_id,
+            input.status as Option<CandidacyStatus>,
+            input.is_write_in,
+            input.is_incumbent,
+            input.filing_date,
+            input.ballot_designation,
+            input.party_on_ballot,
+            input.ballot_order,
+        )
+        .fetch_one(&mut *conn)
+        .await
+    }
+
+    /// Updates a candidacy, e.g. to withdraw or disqualify a candidate or to put them back on
+    /// the ballot. Fields left empty are unchanged.
+    pub async fn update(
         db_pool: &PgPool,
         input: &UpsertRaceCandidateInput,
-    ) -> Result<Option<Self>, sqlx::Error> {
+    ) -> Result<Self, sqlx::Error> {
         sqlx::query_as!(
             RaceCandidate,
             r#"
-                INSERT INTO race_candidates (race_id, candidate_id)
-                VALUES ($1, $2)
-                ON CONFLICT (race_id, candidate_id) DO NOTHING
-                RETURNING race_id, candidate_id
+                UPDATE race_candidates
+                SET
+                    status = COALESCE($3, status),
+                    is_running = COALESCE($3, status) NOT IN ('withdrawn', 'disqualified'),
+                    date_dropped = CASE
+                        WHEN COALESCE($3, status) IN ('withdrawn', 'disqualified') THEN COALESCE(date_dropped, CURRENT_DATE)
+                    END,
+                    is_write_in = COALESCE($4, is_write_in),
+                    is_incumbent = COALESCE($5, is_incumbent),
+                    filing_date = COALESCE($6, filing_date),
+                    ballot_designation = COALESCE($7, ballot_designation),
+                    party_on_ballot = COALESCE($8, party_on_ballot),
+                    ballot_order = COALESCE($9, ballot_order)
+                WHERE race_id = $1 AND candidate_id = $2
+                RETURNING race_id, candidate_id, status AS "status:CandidacyStatus", is_running, is_write_in, is_incumbent, filing_date, ballot_designation, party_on_ballot, ballot_order
             "#,
             input.race_id,
             input.candidate_id,
+            input.status as Option<CandidacyStatus>,
+            input.is_write_in,
+            input.is_incumbent,
+            input.filing_date,
+            input.ballot_designation,
+            input.party_on_ballot,
+            input.ballot_order,
         )
-        .fetch_optional(db_pool)
+        .fetch_one(db_pool)
         .await
     }
 }
@@ -0,0 +1,371 @@
+use super::enums::{BallotMeasureStatus, PassageThreshold, RaceType, State, VoteType};
+use super::office_term::OfficeTerm;
+use crate::util::advancement::{self, AdvancementRule, Outcome};
+use sqlx::PgPool;
+
+/// What finalizing a race does, or would do in a dry run
+#[derive(Debug, Clone)]
+pub struct RaceFinalization {
+    pub race_id: uuid::Uuid,
+    pub race_title: String,
+    pub rule: AdvancementRule,
+    /// Winners most votes first, or the nominees and candidates advancing from a primary
+    pub winner_ids: Vec<uuid::Uuid>,
+    pub loser_ids: Vec<uuid::Uuid>,
+    /// General race primary winners are added to and primary losers are removed from
+    pub general_race_id: Option<uuid::Uuid>,
+    /// Set when the race is left alone, e.g. it has no results yet or ends in a tie
+    pub skipped_reason: Option<String>,
+    pub previously_finalized: bool,
+}
+
+/// What finalizing a ballot measure does, or would do in a dry run
+#[derive(Debug, Clone)]
+pub struct BallotMeasureFinalization {
+    pub ballot_measure_id: uuid::Uuid,
+    pub title: String,
+    pub ballot_measure_code: String,
+    pub passage_threshold: PassageThreshold,
+    pub yes_votes: Option<i32>,
+    pub no_votes: Option<i32>,
+    /// `BecameLaw` or `Failed`, empty when skipped
+    pub status: Option<BallotMeasureStatus>,
+    pub previous_status: BallotMeasureStatus,
+    pub skipped_reason: Option<String>,
+    pub previously_finalized: bool,
+}
+
+struct RaceRecord {
+    id: uuid::Uuid,
+    title: String,
+    office_id: uuid::Uuid,
+    race_type: RaceType,
+    vote_type: VoteType,
+    state: Option<State>,
+    is_party_primary: bool,
+    is_special_election: bool,
+    num_elect: Option<i32>,
+    winner_ids: Option<Vec<uuid::Uuid>>,
+    election_date: chrono::NaiveDate,
+    has_rounds: bool,
+    previously_finalized: bool,
+}
+
+/// Sets winners on every race in an election with results, counts each candidate's win or loss,
+/// moves primary winners onto the general race for the same office and records the terms won in
+/// general races. Races finalized before are recomputed and only the difference is applied, so
+/// running it again after a recount corrects the counts instead of adding to them. Ranked choice
+/// races keep the winners from their imported rounds. Nothing is written when `dry_run` is set.
+pub async fn finalize_race_results(
+    db_pool: &PgPool,
+    election_id: uuid::Uuid,
+    dry_run: bool,
+) -> Result<Vec<RaceFinalization>, sqlx::Error> {
+    let mut tx = db_pool.begin().await?;
+
+    let races = sqlx::query_as!(
+        RaceRecord,
+        r#"
+            SELECT
+                r.id,
+                r.title,
+                r.office_id,
+                r.race_type AS "race_type:RaceType",
+                r.vote_type AS "vote_type:VoteType",
+                COALESCE(r.state, e.state) AS "state:State",
+                (r.party_id IS NOT NULL OR r.party IS NOT NULL) AS "is_party_primary!",
+                r.is_special_election,
+                r.num_elect,
+                r.winner_ids,
+                e.election_date,
+                EXISTS (SELECT 1 FROM ranked_choice_round WHERE race_id = r.id) AS "has_rounds!",
+                (r.results_finalized_at IS NOT NULL) AS "previously_finalized!"
+            FROM race r
+            JOIN election e ON e.id = r.election_id
+            WHERE r.election_id = $1
+            ORDER BY r.title
+        "#,
+        election_id,
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let candidates = sqlx::query!(
+        r#"
+            SELECT rc.race_id, rc.candidate_id, rc.votes
+            FROM race_candidates rc
+            JOIN race r ON r.id = rc.race_id
+            WHERE r.election_id = $1
+        "#,
+        election_id,
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut finalizations = Vec::with_capacity(races.len());
+    for race in races {
+        let votes: Vec<(uuid::Uuid, Option<i32>)> = candidates
+            .iter()
+            .filter(|c| c.race_id == race.id)
+            .map(|c| (c.candidate_id, c.votes))
+            .collect();
+        let rule = AdvancementRule::for_race(
+            race.state,
+            race.race_type,
+            race.is_party_primary,
+            race.num_elect,
+        );
+
+        let outcome = if race.vote_type == VoteType::RankedChoice {
+            match (race.has_rounds, &race.winner_ids) {
+                (true, Some(winner_ids)) => Ok(Outcome::Decided {
+                    winners: winner_ids.clone(),
+                    losers: votes
+                        .iter()
+                        .map(|(id, _)| *id)
+                        .filter(|id| !winner_ids.contains(id))
+                        .collect(),
+                }),
+                _ => Err("Ranked choice rounds have not been imported".to_string()),
+            }
+        } else if votes.iter().all(|(_, votes)| votes.is_none()) {
+            Err("No results yet".to_string())
+        } else {
+            Ok(advancement::decide(&votes, rule.advancing()))
+        };
+
+        let (winner_ids, loser_ids, skipped_reason) = match outcome {
+            Ok(Outcome::Decided { winners, losers }) => (winners, losers, None),
+            Ok(Outcome::Tied(tied)) => (
+                vec![],
+                vec![],
+                Some(format!(
+                    "{} candidates are tied for the last spot, settle the tie and set winners by hand",
+                    tied.len()
+                )),
+            ),
+            Err(reason) => (vec![], vec![], Some(reason)),
+        };
+
+        let general_race_id = match rule.advances_to_general() && skipped_reason.is_none() {
+            true => {
+                sqlx::query_scalar!(
+                    r#"
+                        SELECT r.id
+                        FROM race r
+                        JOIN election e ON e.id = r.election_id
+                        WHERE r.office_id = $1
+                        AND r.race_type = 'general'
+                        AND r.is_special_election = $2
+                        AND e.election_date > $3
+                        ORDER BY e.election_date
+                        LIMIT 1
+                    "#,
+                    race.office_id,
+                    race.is_special_election,
+                    race.election_date,
+                )
+                .fetch_optional(&mut *tx)
+                .await?
+            }
+            false => None,
+        };
+
+        if !dry_run && skipped_reason.is_none() {
+            apply(&mut tx, race.id, &winner_ids, &loser_ids, general_race_id).await?;
+            if race.race_type == RaceType::General {
+                OfficeTerm::record_race_winners(&mut tx, race.id, &winner_ids).await?;
+            }
+        }
+
+        finalizations.push(RaceFinalization {
+            race_id: race.id,
+            race_title: race.title,
+            rule,
+            winner_ids,
+            loser_ids,
+            general_race_id,
+            skipped_reason,
+            previously_finalized: race.previously_finalized,
+        });
+    }
+
+    if !dry_run {
+        tx.commit().await?;
+    }
+    Ok(finalizations)
+}
+
+async fn apply(
+    tx: &mut sqlx::PgConnection,
+    race_id: uuid::Uuid,
+    winner_ids: &[uuid::Uuid],
+    loser_ids: &[uuid::Uuid],
+    general_race_id: Option<uuid::Uuid>,
+) -> Result<(), sqlx::Error> {
+    // Counts move by the difference from the outcome recorded last time, if any
+    sqlx::query!(
+        r#"
+            WITH outcome AS (
+                SELECT
+                    rc.candidate_id,
+                    rc.is_winner AS was_winner,
+                    rc.candidate_id = ANY($2) AS is_winner
+                FROM race_candidates rc
+                WHERE rc.race_id = $1
+            ),
+            update_politicians AS (
+                UPDATE politician p SET
+                    race_wins = COALESCE(p.race_wins, 0)
+                        + o.is_winner::INTEGER
+                        - COALESCE(o.was_winner, false)::INTEGER,
+                    race_losses = COALESCE(p.race_losses, 0)
+                        + (NOT o.is_winner)::INTEGER
+                        - COALESCE(NOT o.was_winner, false)::INTEGER
+                FROM outcome o
+                WHERE p.id = o.candidate_id
+                AND o.was_winner IS DISTINCT FROM o.is_winner
+            )
+            UPDATE race_candidates rc SET is_winner = o.is_winner
+            FROM outcome o
+            WHERE rc.race_id = $1
+            AND rc.candidate_id = o.candidate_id
+            AND rc.is_winner IS DISTINCT FROM o.is_winner
+        "#,
+        race_id,
+        winner_ids,
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+            UPDATE race SET
+                winner_ids = $2,
+                results_finalized_at = NOW()
+            WHERE id = $1
+        "#,
+        race_id,
+        winner_ids,
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    if let Some(general_race_id) = general_race_id {
+        sqlx::query!(
+            r#"
+                INSERT INTO race_candidates (race_id, candidate_id)
+                SELECT $1, UNNEST($2::uuid[])
+                ON CONFLICT (race_id, candidate_id) DO NOTHING
+            "#,
+            general_race_id,
+            winner_ids,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "DELETE FROM race_candidates WHERE race_id = $1 AND candidate_id = ANY($2)",
+            general_race_id,
+            loser_ids,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+                UPDATE politician SET upcoming_race_id = CASE
+                    WHEN id = ANY($3) THEN $2
+                    ELSE NULL
+                END
+                WHERE (id = ANY($3) OR id = ANY($4))
+                AND (upcoming_race_id IS NULL OR upcoming_race_id IN ($1, $2))
+            "#,
+            race_id,
+            general_race_id,
+            winner_ids,
+            loser_ids,
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Sets `BecameLaw` or `Failed` on every ballot measure in an election with results, using each
+/// measure's passage threshold. Like races, measures can be finalized again after a recount.
+/// Nothing is written when `dry_run` is set.
+pub async fn finalize_ballot_measure_results(
+    db_pool: &PgPool,
+    election_id: uuid::Uuid,
+    dry_run: bool,
+) -> Result<Vec<BallotMeasureFinalization>, sqlx::Error> {
+    let mut tx = db_pool.begin().await?;
+
+    let measures = sqlx::query!(
+        r#"
+            SELECT
+                id,
+                title,
+                ballot_measure_code,
+                passage_threshold AS "passage_threshold:PassageThreshold",
+                status AS "status:BallotMeasureStatus",
+                yes_votes,
+                no_votes,
+                (results_finalized_at IS NOT NULL) AS "previously_finalized!"
+            FROM ballot_measure
+            WHERE election_id = $1
+            ORDER BY ballot_measure_code, title
+        "#,
+        election_id,
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut finalizations = Vec::with_capacity(measures.len());
+    for measure in measures {
+        let (status, skipped_reason) = match (measure.yes_votes, measure.no_votes) {
+            (Some(yes), Some(no)) if yes + no > 0 => {
+                match measure.passage_threshold.passes(yes, no) {
+                    true => (Some(BallotMeasureStatus::BecameLaw), None),
+                    false => (Some(BallotMeasureStatus::Failed), None),
+                }
+            }
+            _ => (None, Some("No results yet".to_string())),
+        };
+
+        if let (Some(status), false) = (status, dry_run) {
+            sqlx::query!(
+                r#"
+                    UPDATE ballot_measure SET
+                        status = $2,
+                        results_finalized_at = NOW()
+                    WHERE id = $1
+                "#,
+                measure.id,
+                status as BallotMeasureStatus,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        finalizations.push(BallotMeasureFinalization {
+            ballot_measure_id: measure.id,
+            title: measure.title,
+            ballot_measure_code: measure.ballot_measure_code,
+            passage_threshold: measure.passage_threshold,
+            yes_votes: measure.yes_votes,
+            no_votes: measure.no_votes,
+            status,
+            previous_status: measure.status,
+            skipped_reason,
+            previously_finalized: measure.previously_finalized,
+        });
+    }
+
+    if !dry_run {
+        tx.commit().await?;
+    }
+    Ok(finalizations)
+}
@@ -0,0 +1,416 @@
+use crate::DateTime;
+use async_graphql::Enum;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection, PgPool};
+use strum_macros::Display;
+
+#[derive(
+    Enum, Display, Debug, Copy, Clone, Eq, PartialEq, sqlx::Type, Serialize, Deserialize, Default,
+)]
+#[sqlx(type_name = "results_status", rename_all = "snake_case")]
+pub enum ResultsStatus {
+    /// Election night and canvass-in-progress counts
+    #[default]
+    Unofficial,
+    /// Counties have finished their canvass
+    Canvassed,
+    /// Certified by the state canvassing board, results files no longer change these numbers
+    Certified,
+    Recount,
+}
+
+#[derive(FromRow, Debug, Clone)]
+pub struct RaceResultsSnapshot {
+    pub id: uuid::Uuid,
+    pub race_id: uuid::Uuid,
+    pub results_status: ResultsStatus,
+    pub total_votes: Option<i32>,
+    pub num_precincts_reporting: Option<i32>,
+    pub total_precincts: Option<i32>,
+    pub source_url: Option<String>,
+    pub source_hash: Option<String>,
+    pub created_by: Option<uuid::Uuid>,
+    pub created_at: DateTime,
+}
+
+#[derive(FromRow, Debug, Clone)]
+pub struct RaceResultsSnapshotCandidate {
+    pub snapshot_id: uuid::Uuid,
+    pub candidate_id: uuid::Uuid,
+    pub votes: Option<i32>,
+}
+
+impl RaceResultsSnapshot {
+    /// Snapshots a race's current vote counts, reporting and status if any of them changed
+    /// since its latest snapshot, and returns the new snapshot. Races that have never had
+    /// results aren't snapshotted.
+    pub async fn record(
+        conn: &mut PgConnection,
+        race_id: uuid::Uuid,
+        source_url: Option<&str>,
+        source_hash: Option<&str>,
+        created_by: Option<uuid::Uuid>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let snapshot = sqlx::query_as!(
+            RaceResultsSnapshot,
+            r#"
+                WITH current AS (
+                    SELECT
+                        r.results_status,
+                        r.total_votes,
+                        r.num_precincts_reporting,
+                        r.total_precincts,
+                        (
+                            SELECT jsonb_object_agg(candidate_id, votes)
+                            FROM race_candidates
+                            WHERE race_id = r.id AND votes IS NOT NULL
+                        ) AS votes
+                    FROM race r
+                    WHERE r.id = $1
+                ),
+                latest AS (
+                    SELECT
+                        s.results_status,
+                        s.total_votes,
+                        s.num_precincts_reporting,
+                        s.total_precincts,
+                        (
+                            SELECT jsonb_object_agg(candidate_id, votes)
+                            FROM race_results_snapshot_candidate
+                            WHERE snapshot_id = s.id AND votes IS NOT NULL
+                        ) AS votes
+                    FROM race_results_snapshot s
+                    WHERE s.race_id = $1
+                    ORDER BY s.created_at DESC
+                    LIMIT 1
+                )
+                INSERT INTO race_results_snapshot (
+                    race_id,
+                    results_status,
+                    total_votes,
+                    num_precincts_reporting,
+                    total_precincts,
+                    source_url,
+                    source_hash,
+                    created_by
+                )
+                SELECT $1, c.results_status, c.total_votes, c.num_precincts_reporting,
+                    c.total_precincts, $2, $3, $4
+                FROM current c
+                WHERE NOT EXISTS (
+                    SELECT 1 FROM latest l
+                    WHERE (l.results_status, l.total_votes, l.num_precincts_reporting,
+                        l.total_precincts, l.votes)
+                    IS NOT DISTINCT FROM (c.results_status, c.total_votes,
+                        c.num_precincts_reporting, c.total_precincts, c.votes)
+                )
+                AND (
+                    EXISTS (SELECT 1 FROM latest)
+                    OR c.votes IS NOT NULL
+                    OR c.total_votes IS NOT NULL
+                    OR c.results_status <> 'unofficial'
+                )
+                RETURNING
+                    id,
+                    race_id,
+                    results_status AS "results_status:ResultsStatus",
+                    total_votes,
+                    num_precincts_reporting,
+                    total_precincts,
+                    source_url,
+                    source_hash,
+                    created_by,
+                    created_at
+            "#,
+            race_id,
+            source_url,
+            source_hash,
+            created_by,
+        )
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        if let Some(snapshot) = &snapshot {
+            sqlx::query!(
+                r#"
+                    INSERT INTO race_results_snapshot_candidate (snapshot_id, candidate_id, votes)
+                    SELECT $1, candidate_id, votes
+                    FROM race_candidates
+                    WHERE race_id = $2
+                "#,
+                snapshot.id,
+                race_id,
+            )
+            .execute(&mut *conn)
+            .await?;
+
+            sqlx::query!(
+                "UPDATE race SET results_updated_at = $2 WHERE id = $1",
+                race_id,
+                snapshot.created_at,
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Locks a race whose vote counts are about to change. Certified races are refused
+    /// unless `force` is set, e.g. to enter a court-ordered correction.
+    pub async fn lock_for_votes(
+        conn: &mut PgConnection,
+        race_id: uuid::Uuid,
+        force: bool,
+    ) -> Result<(), sqlx::Error> {
+        let results_status = sqlx::query_scalar!(
+            r#"SELECT results_status AS "results_status:ResultsStatus" FROM race WHERE id = $1 FOR UPDATE"#,
+            race_id
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+        if results_status == ResultsStatus::Certified && !force {
+            return Err(sqlx::Error::AnyDriverError(
+                format!(
+                    "Race {} has certified results, force the update to change its votes",
+                    race_id
+                )
+                .into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Sets a race's total and candidate votes and snapshots the change. Every vote count
+    /// written outside of results files goes through here, see `lock_for_votes` for races
+    /// with certified results.
+    pub async fn update_votes(
+        conn: &mut PgConnection,
+        race_id: uuid::Uuid,
+        total_votes: Option<i32>,
+        candidate_votes: &[(uuid::Uuid, i32)],
+        created_by: Option<uuid::Uuid>,
+        force: bool,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        Self::lock_for_votes(conn, race_id, force).await?;
+
+        if let Some(total_votes) = total_votes {
+            sqlx::query!(
+                "UPDATE race SET total_votes = $2 WHERE id = $1",
+                race_id,
+                total_votes,
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+        let (candidate_ids, votes): (Vec<uuid::Uuid>, Vec<i32>) =
+            candidate_votes.iter().copied().unzip();
+        sqlx::query!(
+            r#"
+                UPDATE race_candidates rc SET votes = v.votes
+                FROM UNNEST($2::uuid[], $3::int[]) AS v(candidate_id, votes)
+                WHERE rc.race_id = $1 AND rc.candidate_id = v.candidate_id
+            "#,
+            race_id,
+            &candidate_ids,
+            &votes,
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        Self::record(conn, race_id, None, None, created_by).await
+    }
+
+    /// Sets a race's results status, e.g. to certify it, and snapshots the change
+    pub async fn set_status(
+        db_pool: &PgPool,
+        race_id: uuid::Uuid,
+        status: ResultsStatus,
+        created_by: Option<uuid::Uuid>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let mut tx = db_pool.begin().await?;
+        let updated = sqlx::query!(
+            "UPDATE race SET results_status = $2 WHERE id = $1",
+            race_id,
+            status as ResultsStatus,
+        )
+        .execute(&mut *tx)
+        .await?;
+        if updated.rows_affected() == 0 {
+            return Err(sqlx::Error::RowNotFound);
+        }
+
+        let snapshot = Self::record(&mut tx, race_id, None, None, created_by).await?;
+        tx.commit().await?;
+        Ok(snapshot)
+    }
+
+    /// Certifies every race in an election that has results and returns their snapshots
+    pub async fn certify_election(
+        db_pool: &PgPool,
+        election_id: uuid::Uuid,
+        created_by: Option<uuid::Uuid>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let mut tx = db_pool.begin().await?;
+        let race_ids = sqlx::query_scalar!(
+            r#"
+                UPDATE race r SET results_status = 'certified'
+                WHERE r.election_id = $1
+                AND r.results_status <> 'certified'
+                AND (
+                    r.total_votes IS NOT NULL
+                    OR EXISTS (
+                        SELECT 1 FROM race_candidates
+                        WHERE race_id = r.id AND votes IS NOT NULL
+                    )
+                )
+                RETURNING r.id
+            "#,
+            election_id,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut snapshots = Vec::with_capacity(race_ids.len());
+        for race_id in race_ids {
+            if let Some(snapshot) = Self::record(&mut tx, race_id, None, None, created_by).await? {
+                snapshots.push(snapshot);
+            }
+        }
+        tx.commit().await?;
+        Ok(snapshots)
+    }
+
+    /// A race's snapshots, newest first
+    pub async fn find_by_race_id(
+        db_pool: &PgPool,
+        race_id: uuid::Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RaceResultsSnapshot,
+            r#"
+                SELECT
+                    id,
+                    race_id,
+                    results_status AS "results_status:ResultsStatus",
+                    total_votes,
+                    num_precincts_reporting,
+                    total_precincts,
+                    source_url,
+                    source_hash,
+                    created_by,
+                    created_at
+                FROM race_results_snapshot
+                WHERE race_id = $1
+                ORDER BY created_at DESC
+            "#,
+            race_id,
+        )
+        .fetch_all(db_pool)
+        .await
+    }
+}
+
+impl RaceResultsSnapshotCandidate {
+    /// Candidate votes in every snapshot of a race
+    pub async fn find_by_race_id(
+        db_pool: &PgPool,
+        race_id: uuid::Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RaceResultsSnapshotCandidate,
+            r#"
+                SELECT sc.snapshot_id, sc.candidate_id, sc.votes
+                FROM race_results_snapshot_candidate sc
+                JOIN race_results_snapshot s ON s.id = sc.snapshot_id
+                WHERE s.race_id = $1
+            "#,
+            race_id,
+        )
+        .fetch_all(db_pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_update_votes_snapshots_and_refuses_certified_races() {
+        let db_pool = crate::DatabasePool::new().await.unwrap().connection;
+        let mut tx = db_pool.begin().await.unwrap();
+        let race = sqlx::query!(
+            r#"
+                WITH o AS (
+                    INSERT INTO office (slug, title, political_scope)
+                    VALUES ('test-update-votes-office', 'Mayor', 'local')
+                    RETURNING id
+                ),
+                p AS (
+                    INSERT INTO politician (slug, full_name, first_name, last_name)
+                    VALUES ('test-update-votes-candidate', 'Test Candidate', 'Test', 'Candidate')
+                    RETURNING id
+                ),
+                r AS (
+                    INSERT INTO race (slug, title, office_id)
+                    SELECT 'test-update-votes-race', 'Mayor', o.id FROM o
+                    RETURNING id
+                )
+                INSERT INTO race_candidates (race_id, candidate_id)
+                SELECT r.id, p.id FROM r, p
+                RETURNING race_id, candidate_id
+            "#
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .unwrap();
+
+        let snapshot = RaceResultsSnapshot::update_votes(
+            &mut tx,
+            race.race_id,
+            Some(120),
+            &[(race.candidate_id, 100)],
+            None,
+            false,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(snapshot.total_votes, Some(120));
+
+        sqlx::query!(
+            "UPDATE race SET results_status = 'certified' WHERE id = $1",
+            race.race_id
+        )
+        .execute(&mut *tx)
+        .await
+        .unwrap();
+        assert!(RaceResultsSnapshot::update_votes(
+            &mut tx,
+            race.race_id,
+            Some(130),
+            &[],
+            None,
+            false
+        )
+        .await
+        .is_err());
+        let snapshot = RaceResultsSnapshot::update_votes(
+            &mut tx,
+            race.race_id,
+            Some(130),
+            &[(race.candidate_id, 110)],
+            None,
+            true,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(snapshot.total_votes, Some(130));
+        assert_eq!(snapshot.results_status, ResultsStatus::Certified);
+
+        tx.rollback().await.unwrap();
+    }
+}
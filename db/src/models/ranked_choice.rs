@@ -0,0 +1,192 @@
+use crate::{util::rcv::TabulatedRound, DateTime, RaceResultsSnapshot};
+use async_graphql::Enum;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use strum_macros::Display;
+
+#[derive(Enum, Display, Debug, Copy, Clone, Eq, PartialEq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "ranked_choice_status", rename_all = "snake_case")]
+pub enum RankedChoiceStatus {
+    Continuing,
+    Elected,
+    Eliminated,
+}
+
+#[derive(FromRow, Debug, Clone)]
+pub struct RankedChoiceRound {
+    pub id: uuid::Uuid,
+    pub race_id: uuid::Uuid,
+    pub round: i32,
+    pub threshold: Option<f64>,
+    pub exhausted: f64,
+    pub source: String,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(FromRow, Debug, Clone)]
+pub struct RankedChoiceTally {
+    pub round_id: uuid::Uuid,
+    pub candidate_id: Option<uuid::Uuid>,
+    pub candidate_name: String,
+    pub votes: f64,
+    pub transfer: f64,
+    pub status: RankedChoiceStatus,
+}
+
+impl RankedChoiceRound {
+    /// Replaces a race's rounds. Candidate names are matched to the race's candidates by slug,
+    /// first-round tallies become the candidates' votes and everyone elected in the last round
+    /// becomes the race's winners, and the new counts are snapshotted. Races with certified
+    /// results are refused unless `force` is set.
+    pub async fn replace_for_race(
+        db_pool: &PgPool,
+        race_id: uuid::Uuid,
+        source: &str,
+        rounds: &[TabulatedRound],
+        force: bool,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let mut tx = db_pool.begin().await?;
+
+        RaceResultsSnapshot::lock_for_votes(&mut tx, race_id, force).await?;
+
+        sqlx::query!(
+            "DELETE FROM ranked_choice_round WHERE race_id = $1",
+            race_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let mut records = Vec::with_capacity(rounds.len());
+        for round in rounds {
+            let record = sqlx::query_as!(
+                RankedChoiceRound,
+                r#"
+                    INSERT INTO ranked_choice_round (race_id, round, threshold, exhausted, source)
+                    VALUES ($1, $2, $3, $4, $5)
+                    RETURNING id, race_id, round, threshold, exhausted, source, created_at, updated_at
+                "#,
+                race_id,
+                round.round,
+                round.threshold,
+                round.exhausted,
+                source,
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+
+            for tally in &round.tallies {
+                sqlx::query!(
+                    r#"
+                        INSERT INTO ranked_choice_round_candidate (round_id, candidate_id, candidate_name, votes, transfer, status)
+                        VALUES ($1, (
+                            SELECT p.id FROM race_candidates rc
+                            JOIN politician p ON p.id = rc.candidate_id
+                            WHERE rc.race_id = $2
+                            AND SLUGIFY($3) IN (
+                                p.slug,
+                                SLUGIFY(p.full_name),
+                                SLUGIFY(COALESCE(p.preferred_name, p.first_name) || ' ' || p.last_name)
+                            )
+                            LIMIT 1
+                        ), $3, $4, $5, $6)
+                    "#,
+                    record.id,
+                    race_id,
+                    tally.candidate,
+                    tally.votes,
+                    tally.transfer,
+                    tally.status as RankedChoiceStatus,
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+            records.push(record);
+        }
+
+        if let (Some(first), Some(last)) = (records.first(), records.last()) {
+            sqlx::query!(
+                r#"
+                    UPDATE race SET winner_ids = COALESCE((
+                        SELECT ARRAY_AGG(candidate_id ORDER BY votes DESC)
+                        FROM ranked_choice_round_candidate
+                        WHERE round_id = $1 AND status = 'elected' AND candidate_id IS NOT NULL
+                    ), race.winner_ids)
+                    WHERE id = $2
+                "#,
+                last.id,
+                race_id,
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            let first_round = sqlx::query!(
+                r#"
+                    SELECT candidate_id, ROUND(votes)::INTEGER AS "votes!"
+                    FROM ranked_choice_round_candidate
+                    WHERE round_id = $1
+                "#,
+                first.id,
+            )
+            .fetch_all(&mut *tx)
+            .await?;
+            let total_votes = first_round.iter().map(|t| t.votes).sum();
+            let candidate_votes: Vec<(uuid::Uuid, i32)> = first_round
+                .iter()
+                .filter_map(|t| Some((t.candidate_id?, t.votes)))
+                .collect();
+            RaceResultsSnapshot::update_votes(
+                &mut tx,
+                race_id,
+                Some(total_votes),
+                &candidate_votes,
+                None,
+                force,
+            )
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(records)
+    }
+
+    pub async fn find_by_race_id(
+        db_pool: &PgPool,
+        race_id: uuid::Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RankedChoiceRound,
+            r#"
+                SELECT id, race_id, round, threshold, exhausted, source, created_at, updated_at
+                FROM ranked_choice_round
+                WHERE race_id = $1
+                ORDER BY round
+            "#,
+            race_id,
+        )
+        .fetch_all(db_pool)
+        .await
+    }
+}
+
+impl RankedChoiceTally {
+    /// Every round's tallies for a race, most votes first within a round
+    pub async fn find_by_race_id(
+        db_pool: &PgPool,
+        race_id: uuid::Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RankedChoiceTally,
+            r#"
+                SELECT t.round_id, t.candidate_id, t.candidate_name, t.votes, t.transfer, t.status AS "status:RankedChoiceStatus"
+                FROM ranked_choice_round_candidate t
+                JOIN ranked_choice_round r ON r.id = t.round_id
+                WHERE r.race_id = $1
+                ORDER BY r.round, t.votes DESC, t.candidate_name
+            "#,
+            race_id,
+        )
+        .fetch_all(db_pool)
+        .await
+    }
+}
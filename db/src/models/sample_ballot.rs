@@ -0,0 +1,375 @@
+use crate::{
+    models::{candidate_guide::CandidateGuide, voting_guide::VotingGuide},
+    Ballot, BallotMeasure, BallotSection, Election, Office, Organization, Question, Race,
+};
+use sqlx::PgPool;
+
+/// A candidate as printed on a sample ballot or candidate guide
+#[derive(Debug, Clone)]
+pub struct PrintedCandidate {
+    pub politician_id: uuid::Uuid,
+    pub name: String,
+    pub party: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SampleBallotCandidate {
+    pub candidate: PrintedCandidate,
+    /// The voter's pick, from `voting_guide_candidates.is_endorsement`
+    pub is_selected: bool,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SampleBallotRace {
+    pub race: Race,
+    pub office: Office,
+    pub section: BallotSection,
+    pub candidates: Vec<SampleBallotCandidate>,
+}
+
+/// A voter's voting guide laid out as their ballot, to print and take to the polls
+#[derive(Debug, Clone)]
+pub struct SampleBallot {
+    pub voting_guide: VotingGuide,
+    pub election: Election,
+    /// Preferred or first name of the guide's author
+    pub author_name: Option<String>,
+    /// Whether races were picked by the author's address. Without an address only races
+    /// the author made a selection or note in are printed.
+    pub is_personalized: bool,
+    pub races: Vec<SampleBallotRace>,
+    pub ballot_measures: Vec<BallotMeasure>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CandidateGuideSheetCandidate {
+    pub candidate: PrintedCandidate,
+    /// Responses in the order of the sheet's questions, `None` where the candidate didn't answer
+    pub responses: Vec<Option<String>>,
+}
+
+/// An organization's candidate guide answers for one race, with the organization's branding
+#[derive(Debug, Clone)]
+pub struct CandidateGuideSheet {
+    pub candidate_guide: CandidateGuide,
+    pub organization: Organization,
+    pub race: Race,
+    pub questions: Vec<Question>,
+    pub candidates: Vec<CandidateGuideSheetCandidate>,
+}
+
+struct CandidateRow {
+    race_id: uuid::Uuid,
+    politician_id: uuid::Uuid,
+    first_name: String,
+    preferred_name: Option<String>,
+    last_name: String,
+    suffix: Option<String>,
+    party: Option<String>,
+}
+
+impl CandidateRow {
+    fn printed(&self) -> PrintedCandidate {
+        PrintedCandidate {
+            politician_id: self.politician_id,
+            name: display_name(
+                &self.first_name,
+                self.preferred_name.as_deref(),
+                &self.last_name,
+                self.suffix.as_deref(),
+            ),
+            party: self.party.clone(),
+        }
+    }
+}
+
+impl SampleBallot {
+    pub async fn for_voting_guide(
+        db_pool: &PgPool,
+        voting_guide_id: uuid::Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let voting_guide = VotingGuide::find_by_id(db_pool, voting_guide_id).await?;
+        let author = sqlx::query!(
+            r#"
+                SELECT first_name, preferred_name, address_id
+                FROM user_profile
+                WHERE user_id = $1
+            "#,
+            voting_guide.user_id,
+        )
+        .fetch_optional(db_pool)
+        .await?;
+        let author_name = author
+            .as_ref()
+            .and_then(|a| a.preferred_name.clone().or(a.first_name.clone()));
+
+        let (election, mut races, ballot_measures, is_personalized) = match author
+            .and_then(|a| a.address_id)
+        {
+            Some(address_id) => {
+                let ballot = Ballot::build(db_pool, &voting_guide.election_id, &address_id).await?;
+                let races = ballot
+                    .races()
+                    .map(|r| SampleBallotRace {
+                        race: r.race.clone(),
+                        office: r.office.clone(),
+                        section: r.section,
+                        candidates: vec![],
+                    })
+                    .collect();
+                let ballot_measures = ballot.ballot_measures().cloned().collect();
+                (ballot.election, races, ballot_measures, true)
+            }
+            None => {
+                let election = Election::find_by_id(db_pool, voting_guide.election_id).await?;
+                let races = Self::races_with_selections(db_pool, &voting_guide).await?;
+                (election, races, vec![], false)
+            }
+        };
+
+        let race_ids: Vec<uuid::Uuid> = races.iter().map(|r| r.race.id).collect();
+        let selections = sqlx::query!(
+            r#"
+                SELECT candidate_id, is_endorsement, note
+                FROM voting_guide_candidates
+                WHERE voting_guide_id = $1
+            "#,
+            voting_guide.id,
+        )
+        .fetch_all(db_pool)
+        .await?;
+        for row in candidates_for_races(db_pool, &race_ids).await? {
+            let Some(race) = races.iter_mut().find(|r| r.race.id == row.race_id) else {
+                continue;
+            };
+            let selection = selections
+                .iter()
+                .find(|s| s.candidate_id == row.politician_id);
+            race.candidates.push(SampleBallotCandidate {
+                candidate: row.printed(),
+                is_selected: selection.map(|s| s.is_endorsement).unwrap_or(false),
+                note: selection
+                    .and_then(|s| s.note.clone())
+                    .filter(|note| !note.trim().is_empty()),
+            });
+        }
+
+        Ok(Self {
+            voting_guide,
+            election,
+            author_name,
+            is_personalized,
+            races,
+            ballot_measures,
+        })
+    }
+
+    /// Races of the guide's election in which the author picked or noted a candidate, in
+    /// ballot order
+    async fn races_with_selections(
+        db_pool: &PgPool,
+        voting_guide: &VotingGuide,
+    ) -> Result<Vec<SampleBallotRace>, sqlx::Error> {
+        let race_ids = sqlx::query_scalar!(
+            r#"
+                SELECT DISTINCT rc.race_id
+                FROM voting_guide_candidates vgc
+                JOIN race_candidates rc ON rc.candidate_id = vgc.candidate_id
+                JOIN race r ON r.id = rc.race_id
+                WHERE vgc.voting_guide_id = $1
+                AND r.election_id = $2
+                AND (vgc.is_endorsement OR vgc.note IS NOT NULL)
+            "#,
+            voting_guide.id,
+            voting_guide.election_id,
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        let mut races = Vec::with_capacity(race_ids.len());
+        for race_id in race_ids {
+            let race = Race::find_by_id(db_pool, race_id).await?;
+            let office = Office::find_by_id(db_pool, race.office_id).await?;
+            races.push(SampleBallotRace {
+                section: BallotSection::of_office(&office),
+                race,
+                office,
+                candidates: vec![],
+            });
+        }
+        races.sort_by(|a, b| {
+            a.section
+                .cmp(&b.section)
+                .then(
+                    a.office
+                        .priority
+                        .unwrap_or(i32::MAX)
+                        .cmp(&b.office.priority.unwrap_or(i32::MAX)),
+                )
+                .then(b.race.title.cmp(&a.race.title))
+        });
+        Ok(races)
+    }
+}
+
+impl CandidateGuideSheet {
+    /// Fails with `RowNotFound` when the race isn't part of the candidate guide
+    pub async fn for_race(
+        db_pool: &PgPool,
+        candidate_guide_id: uuid::Uuid,
+        race_id: uuid::Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let candidate_guide = CandidateGuide::find_by_id(db_pool, candidate_guide_id).await?;
+        sqlx::query!(
+            "SELECT race_id FROM candidate_guide_races WHERE candidate_guide_id = $1 AND race_id = $2",
+            candidate_guide_id,
+            race_id,
+        )
+        .fetch_one(db_pool)
+        .await?;
+        let organization =
+            Organization::find_by_id(db_pool, candidate_guide.organization_id).await?;
+        let race = Race::find_by_id(db_pool, race_id).await?;
+
+        let questions = sqlx::query_as!(
+            Question,
+            r#"
+                SELECT
+                  id,
+                  prompt,
+                  translations,
+                  response_char_limit,
+                  response_placeholder_text,
+                  allow_anonymous_responses,
+                  embed_id,
+                  organization_id,
+                  created_at,
+                  updated_at
+                FROM question
+                JOIN candidate_guide_questions ON question.id = candidate_guide_questions.question_id
+                WHERE candidate_guide_id = $1
+                ORDER BY question.created_at
+            "#,
+            candidate_guide_id,
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        let question_ids: Vec<uuid::Uuid> = questions.iter().map(|q| q.id).collect();
+        let responses = sqlx::query!(
+            r#"
+                SELECT qs.question_id, qs.candidate_id AS "candidate_id!", qs.response
+                FROM question_submission qs
+                JOIN race_candidates rc ON rc.candidate_id = qs.candidate_id
+                WHERE rc.race_id = $1 AND qs.question_id = ANY($2)
+            "#,
+            race_id,
+            &question_ids,
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        let candidates = candidates_for_races(db_pool, &[race_id])
+            .await?
+            .into_iter()
+            .map(|row| CandidateGuideSheetCandidate {
+                responses: questions
+                    .iter()
+                    .map(|q| {
+                        responses
+                            .iter()
+                            .find(|r| r.question_id == q.id && r.candidate_id == row.politician_id)
+                            .map(|r| r.response.clone())
+                            .filter(|response| !response.trim().is_empty())
+                    })
+                    .collect(),
+                candidate: row.printed(),
+            })
+            .collect();
+
+        Ok(Self {
+            candidate_guide,
+            organization,
+            race,
+            questions,
+            candidates,
+        })
+    }
+}
+
+/// Candidates still running in the races, alphabetical by last name as most states print them
+async fn candidates_for_races(
+    db_pool: &PgPool,
+    race_ids: &[uuid::Uuid],
+) -> Result<Vec<CandidateRow>, sqlx::Error> {
+    sqlx::query_as!(
+        CandidateRow,
+        r#"
+            SELECT
+                rc.race_id,
+                p.id AS politician_id,
+                p.first_name,
+                p.preferred_name,
+                p.last_name,
+                p.suffix,
+                pa.name AS "party?"
+            FROM race_candidates rc
+            JOIN politician p ON p.id = rc.candidate_id
+            LEFT JOIN party pa ON pa.id = p.party_id
+            WHERE rc.race_id = ANY($1) AND rc.is_running
+            ORDER BY p.last_name, p.first_name
+        "#,
+        race_ids,
+    )
+    .fetch_all(db_pool)
+    .await
+}
+
+/// Name as it appears on the ballot, e.g. "Tim Walz" or "Bob Smith Jr."
+pub fn display_name(
+    first_name: &str,
+    preferred_name: Option<&str>,
+    last_name: &str,
+    suffix: Option<&str>,
+) -> String {
+    [
+        Some(
+            preferred_name
+                .filter(|n| !n.trim().is_empty())
+                .unwrap_or(first_name),
+        ),
+        Some(last_name),
+        suffix.filter(|s| !s.trim().is_empty()),
+    ]
+    .into_iter()
+    .flatten()
+    .map(str::trim)
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/// Logo to brand an organization's printouts with, preferring the larger uploaded asset
+pub fn organization_logo_url(organization: &Organization) -> Option<String> {
+    ["thumbnailImage400", "thumbnailImage160"]
+        .iter()
+        .find_map(|key| organization.assets.get(*key)?.as_str().map(str::to_string))
+        .or_else(|| organization.thumbnail_image_url.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_name() {
+        assert_eq!(
+            display_name("Timothy", Some("Tim"), "Walz", None),
+            "Tim Walz"
+        );
+        assert_eq!(
+            display_name("Robert", Some(" "), "Smith", Some("Jr.")),
+            "Robert Smith Jr."
+        );
+    }
+}
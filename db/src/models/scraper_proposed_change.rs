@@ -0,0 +1,326 @@
+use crate::{DateTime, ProposedChangeKind, ProposedChangeStatus, ScraperEntityType};
+use async_graphql::InputObject;
+use serde_json::Value as JSON;
+use sqlx::{postgres::PgPool, FromRow, PgConnection};
+
+/// A change a staged scraper run would have made, held until staff approve or reject it
+#[derive(FromRow, Debug, Clone)]
+pub struct ScraperProposedChange {
+    pub id: uuid::Uuid,
+    pub scraper_run_id: uuid::Uuid,
+    pub position: i32,
+    pub entity_type: ScraperEntityType,
+    pub entity_key: JSON,
+    pub change_kind: ProposedChangeKind,
+    pub record: JSON,
+    pub changes: JSON,
+    pub status: ProposedChangeStatus,
+    pub rejection_reason: Option<String>,
+    pub apply_error: Option<String>,
+    pub reviewed_by: Option<uuid::Uuid>,
+    pub reviewed_at: Option<DateTime>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateProposedChangeInput {
+    pub entity_type: ScraperEntityType,
+    pub entity_key: JSON,
+    pub change_kind: ProposedChangeKind,
+    pub record: JSON,
+    pub changes: JSON,
+}
+
+#[derive(InputObject, Default, Debug)]
+pub struct ProposedChangeFilter {
+    pub scraper_run_id: Option<uuid::Uuid>,
+    pub entity_type: Option<ScraperEntityType>,
+    pub status: Option<ProposedChangeStatus>,
+}
+
+const COLUMNS: &str = r#"
+    id, scraper_run_id, position, entity_type, entity_key, change_kind, record, changes, status,
+    rejection_reason, apply_error, reviewed_by, reviewed_at, created_at, updated_at
+"#;
+
+impl ScraperProposedChange {
+    pub async fn create_many(
+        db_pool: &PgPool,
+        scraper_run_id: uuid::Uuid,
+        changes: &[CreateProposedChangeInput],
+    ) -> Result<(), sqlx::Error> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        let positions: Vec<i32> = (0..changes.len() as i32).collect();
+        let entity_types: Vec<String> = changes.iter().map(|c| c.entity_type.to_string()).collect();
+        let entity_keys: Vec<JSON> = changes.iter().map(|c| c.entity_key.clone()).collect();
+        let change_kinds: Vec<String> = changes.iter().map(|c| c.change_kind.to_string()).collect();
+        let records: Vec<JSON> = changes.iter().map(|c| c.record.clone()).collect();
+        let field_changes: Vec<JSON> = changes.iter().map(|c| c.changes.clone()).collect();
+
+        sqlx::query(
+            r#"
+            INSERT INTO scraper_proposed_change (scraper_run_id, position, entity_type, entity_key, change_kind, record, changes)
+            SELECT $1, position, entity_type::scraper_entity_type, entity_key, change_kind::proposed_change_kind, record, changes
+            FROM UNNEST($2::int[], $3::text[], $4::jsonb[], $5::text[], $6::jsonb[], $7::jsonb[])
+                AS c(position, entity_type, entity_key, change_kind, record, changes)
+            "#,
+        )
+        .bind(scraper_run_id)
+        .bind(positions)
+        .bind(entity_types)
+        .bind(entity_keys)
+        .bind(change_kinds)
+        .bind(records)
+        .bind(field_changes)
+        .execute(db_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn filter(
+        db_pool: &PgPool,
+        filter: &ProposedChangeFilter,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as(&format!(
+            r#"
+            SELECT {COLUMNS}
+            FROM scraper_proposed_change
+            WHERE ($1::uuid IS NULL OR scraper_run_id = $1)
+            AND ($2::scraper_entity_type IS NULL OR entity_type = $2)
+            AND ($3::proposed_change_status IS NULL OR status = $3)
+            ORDER BY created_at DESC, scraper_run_id, position
+            "#
+        ))
+        .bind(filter.scraper_run_id)
+        .bind(filter.entity_type)
+        .bind(filter.status)
+        .fetch_all(db_pool)
+        .await
+    }
+
+    pub async fn find_by_ids(
+        db_pool: &PgPool,
+        ids: &[uuid::Uuid],
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as(&format!(
+            r#"
+            SELECT {COLUMNS}
+            FROM scraper_proposed_change
+            WHERE id = ANY($1)
+            ORDER BY scraper_run_id, position
+            "#
+        ))
+        .bind(ids)
+        .fetch_all(db_pool)
+        .await
+    }
+
+    /// Approves every pending change from a staged scraper run
+    pub async fn approve_run(
+        db_pool: &PgPool,
+        scraper_run_id: uuid::Uuid,
+        reviewed_by: Option<uuid::Uuid>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let ids = sqlx::query_scalar!(
+            r#"
+            SELECT id FROM scraper_proposed_change
+            WHERE scraper_run_id = $1 AND status = 'pending'
+            "#,
+            scraper_run_id
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        Self::approve(db_pool, &ids, reviewed_by).await
+    }
+
+    /// Applies pending changes in the order the scraper produced them. Each change is applied in
+    /// its own transaction, a change that fails to apply stays pending with `apply_error` set.
+    pub async fn approve(
+        db_pool: &PgPool,
+        ids: &[uuid::Uuid],
+        reviewed_by: Option<uuid::Uuid>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let changes = Self::find_by_ids(db_pool, ids).await?;
+        for change in changes
+            .iter()
+            .filter(|c| c.status == ProposedChangeStatus::Pending)
+        {
+            let mut tx = db_pool.begin().await?;
+            match change.apply(&mut tx).await {
+                Ok(()) => {
+                    sqlx::query!(
+                        r#"
+                        UPDATE scraper_proposed_change
+                        SET status = 'approved', apply_error = NULL, reviewed_by = $2, reviewed_at = CURRENT_TIMESTAMP
+                        WHERE id = $1
+                        "#,
+                        change.id,
+                        reviewed_by
+                    )
+                    .execute(&mut *tx)
+                    .await?;
+                    tx.commit().await?;
+                }
+                Err(err) => {
+                    tx.rollback().await?;
+                    sqlx::query!(
+                        r#"
+                        UPDATE scraper_proposed_change SET apply_error = $2 WHERE id = $1
+                        "#,
+                        change.id,
+                        err.to_string()
+                    )
+                    .execute(db_pool)
+                    .await?;
+                }
+            }
+        }
+
+        Self::find_by_ids(db_pool, ids).await
+    }
+
+    pub async fn reject(
+        db_pool: &PgPool,
+        ids: &[uuid::Uuid],
+        reason: Option<String>,
+        reviewed_by: Option<uuid::Uuid>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE scraper_proposed_change
+            SET status = 'rejected', rejection_reason = $2, reviewed_by = $3, reviewed_at = CURRENT_TIMESTAMP
+            WHERE id = ANY($1) AND status = 'pending'
+            "#,
+            ids,
+            reason,
+            reviewed_by
+        )
+        .execute(db_pool)
+        .await?;
+
+        Self::find_by_ids(db_pool, ids).await
+    }
+
+    /// Writes the proposed record. Updates only touch the changed fields and fail if the row
+    /// was edited after the scraper ran.
+    async fn apply(&self, conn: &mut PgConnection) -> Result<(), sqlx::Error> {
+        let table = self.entity_type.table();
+        let result = match self.change_kind {
+            ProposedChangeKind::Insert => {
+                let sql = format!(
+                    "INSERT INTO {table} SELECT * FROM jsonb_populate_record(NULL::{table}, $1)"
+                );
+                sqlx::query(&sql).bind(&self.record).execute(conn).await?
+            }
+            ProposedChangeKind::Update => {
+                let fields = column_names(&self.changes, |c| c.get("field"))?;
+                let keys = column_names(&self.entity_key, |_| None)?;
+                let assignments = fields
+                    .iter()
+                    .map(|f| format!("{f} = r.{f}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let predicate = keys
+                    .iter()
+                    .map(|k| format!("{table}.{k} = r.{k}"))
+                    .collect::<Vec<_>>()
+                    .join(" AND ");
+                let sql = format!(
+                    r#"
+                    UPDATE {table} SET {assignments}
+                    FROM jsonb_populate_record(NULL::{table}, $1) r
+                    WHERE {predicate} AND to_jsonb({table}) @> $2
+                    "#
+                );
+
+                // Values the scraper saw before its update, if the row no longer matches them
+                // someone has edited it since and the change is stale
+                let previous = self
+                    .changes
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|c| {
+                        Some((c.get("field")?.as_str()?.to_string(), c.get("old")?.clone()))
+                    })
+                    .collect::<serde_json::Map<_, _>>();
+
+                sqlx::query(&sql)
+                    .bind(&self.record)
+                    .bind(JSON::Object(previous))
+                    .execute(conn)
+                    .await?
+            }
+        };
+
+        if result.rows_affected() == 0 {
+            return Err(sqlx::Error::AnyDriverError(
+                "Row has changed since the scraper ran, re-run the scraper to refresh this change"
+                    .into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Column names from a proposed change's `changes` array or `entity_key` object. Only plain
+/// identifiers are accepted since they are interpolated into SQL.
+fn column_names(
+    value: &JSON,
+    field: impl Fn(&JSON) -> Option<&JSON>,
+) -> Result<Vec<String>, sqlx::Error> {
+    let names = match value {
+        JSON::Array(items) => items
+            .iter()
+            .filter_map(|item| field(item).and_then(JSON::as_str).map(str::to_string))
+            .collect::<Vec<_>>(),
+        JSON::Object(map) => map.keys().cloned().collect(),
+        _ => vec![],
+    };
+
+    let is_identifier = |name: &String| {
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    };
+    if names.is_empty() || !names.iter().all(is_identifier) {
+        return Err(sqlx::Error::AnyDriverError(
+            "Proposed change has invalid column names".into(),
+        ));
+    }
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_column_names() {
+        let changes = json!([{ "field": "title", "old": "A", "new": "B" }]);
+        assert_eq!(
+            column_names(&changes, |c| c.get("field")).unwrap(),
+            vec!["title".to_string()]
+        );
+
+        let key = json!({ "race_id": "a", "candidate_id": "b" });
+        let mut keys = column_names(&key, |_| None).unwrap();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec!["candidate_id".to_string(), "race_id".to_string()]
+        );
+
+        let changes = json!([{ "field": "title = NULL; --", "old": "A", "new": "B" }]);
+        assert!(column_names(&changes, |c| c.get("field")).is_err());
+        assert!(column_names(&json!([]), |c| c.get("field")).is_err());
+    }
+}
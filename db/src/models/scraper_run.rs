@@ -0,0 +1,170 @@
+use crate::{DateTime, ScraperRunStatus};
+use serde_json::Value as JSON;
+use sqlx::{postgres::PgPool, FromRow};
+
+/// A single execution of a scraper, keyed by the scraper's `source_id`
+#[derive(FromRow, Debug, Clone)]
+pub struct ScraperRun {
+    pub id: uuid::Uuid,
+    pub source_id: String,
+    pub status: ScraperRunStatus,
+    pub is_staged: bool,
+    pub stats: JSON,
+    pub error: Option<String>,
+    pub started_at: DateTime,
+    pub finished_at: Option<DateTime>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(FromRow, Debug, Clone)]
+pub struct ScraperRunError {
+    pub id: uuid::Uuid,
+    pub scraper_run_id: uuid::Uuid,
+    pub entity_type: Option<String>,
+    pub source_ref: Option<String>,
+    pub message: String,
+    pub created_at: DateTime,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct CreateScraperRunErrorInput {
+    pub entity_type: Option<String>,
+    pub source_ref: Option<String>,
+    pub message: String,
+}
+
+impl ScraperRun {
+    pub async fn start(
+        db_pool: &PgPool,
+        source_id: &str,
+        is_staged: bool,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ScraperRun,
+            r#"
+                INSERT INTO scraper_run (source_id, is_staged)
+                VALUES ($1, $2)
+                RETURNING id, source_id, status AS "status:ScraperRunStatus", is_staged, stats, error, started_at, finished_at, created_at, updated_at
+            "#,
+            source_id,
+            is_staged
+        )
+        .fetch_one(db_pool)
+        .await
+    }
+
+    pub async fn find_by_id(db_pool: &PgPool, id: uuid::Uuid) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ScraperRun,
+            r#"
+                SELECT id, source_id, status AS "status:ScraperRunStatus", is_staged, stats, error, started_at, finished_at, created_at, updated_at
+                FROM scraper_run
+                WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_one(db_pool)
+        .await
+    }
+
+    /// Most recent runs first, optionally limited to a single scraper or to staged runs
+    pub async fn recent(
+        db_pool: &PgPool,
+        source_id: Option<String>,
+        is_staged: Option<bool>,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ScraperRun,
+            r#"
+                SELECT id, source_id, status AS "status:ScraperRunStatus", is_staged, stats, error, started_at, finished_at, created_at, updated_at
+                FROM scraper_run
+                WHERE ($1::text IS NULL OR source_id = $1)
+                AND ($2::bool IS NULL OR is_staged = $2)
+                ORDER BY started_at DESC
+                LIMIT $3
+            "#,
+            source_id,
+            is_staged,
+            limit
+        )
+        .fetch_all(db_pool)
+        .await
+    }
+
+    pub async fn finish(
+        db_pool: &PgPool,
+        id: uuid::Uuid,
+        status: ScraperRunStatus,
+        stats: JSON,
+        error: Option<String>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ScraperRun,
+            r#"
+                UPDATE scraper_run
+                SET status = $2, stats = $3, error = $4, finished_at = CURRENT_TIMESTAMP
+                WHERE id = $1
+                RETURNING id, source_id, status AS "status:ScraperRunStatus", is_staged, stats, error, started_at, finished_at, created_at, updated_at
+            "#,
+            id,
+            status as ScraperRunStatus,
+            stats,
+            error
+        )
+        .fetch_one(db_pool)
+        .await
+    }
+
+    pub async fn record_errors(
+        db_pool: &PgPool,
+        id: uuid::Uuid,
+        errors: &[CreateScraperRunErrorInput],
+    ) -> Result<(), sqlx::Error> {
+        if errors.is_empty() {
+            return Ok(());
+        }
+
+        let entity_types: Vec<Option<String>> =
+            errors.iter().map(|e| e.entity_type.clone()).collect();
+        let source_refs: Vec<Option<String>> =
+            errors.iter().map(|e| e.source_ref.clone()).collect();
+        let messages: Vec<String> = errors.iter().map(|e| e.message.clone()).collect();
+
+        sqlx::query!(
+            r#"
+                INSERT INTO scraper_run_error (scraper_run_id, entity_type, source_ref, message)
+                SELECT $1, * FROM UNNEST($2::text[], $3::text[], $4::text[])
+            "#,
+            id,
+            &entity_types as &[Option<String>],
+            &source_refs as &[Option<String>],
+            &messages,
+        )
+        .execute(db_pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+impl ScraperRunError {
+    pub async fn find_by_scraper_run_id(
+        db_pool: &PgPool,
+        scraper_run_id: uuid::Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ScraperRunError,
+            r#"
+                SELECT id, scraper_run_id, entity_type, source_ref, message, created_at
+                FROM scraper_run_error
+                WHERE scraper_run_id = $1
+                ORDER BY created_at
+            "#,
+            scraper_run_id
+        )
+        .fetch_all(db_pool)
+        .await
+    }
+}
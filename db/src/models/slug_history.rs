@@ -0,0 +1,59 @@
+use crate::{DateTime, SlugEntityType};
+use sqlx::{postgres::PgPool, FromRow, PgConnection};
+
+/// A slug an entity was previously published under. Rows are written by the
+/// `record_slug_history` trigger whenever a slug changes, and when politicians are merged.
+#[derive(FromRow, Debug, Clone)]
+pub struct SlugHistory {
+    pub id: uuid::Uuid,
+    pub entity_type: SlugEntityType,
+    pub entity_id: uuid::Uuid,
+    pub slug: String,
+    pub created_at: DateTime,
+}
+
+impl SlugHistory {
+    /// Points `slug` at `entity_id`, taking it over if another entity used it before
+    pub async fn record(
+        conn: &mut PgConnection,
+        entity_type: SlugEntityType,
+        entity_id: uuid::Uuid,
+        slug: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+                INSERT INTO slug_history (entity_type, entity_id, slug)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (entity_type, slug) DO UPDATE
+                SET entity_id = EXCLUDED.entity_id, created_at = CURRENT_TIMESTAMP
+            "#,
+            entity_type as SlugEntityType,
+            entity_id,
+            slug,
+        )
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Previous slugs of an entity, most recent first
+    pub async fn find_by_entity(
+        db_pool: &PgPool,
+        entity_type: SlugEntityType,
+        entity_id: uuid::Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            SlugHistory,
+            r#"
+                SELECT id, entity_type AS "entity_type:SlugEntityType", entity_id, slug, created_at
+                FROM slug_history
+                WHERE entity_type = $1 AND entity_id = $2
+                ORDER BY created_at DESC
+            "#,
+            entity_type as SlugEntityType,
+            entity_id,
+        )
+        .fetch_all(db_pool)
+        .await
+    }
+}
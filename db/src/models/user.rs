@@ -2,9 +2,11 @@ use super::{
     address::{Address, AddressInput},
     enums::State,
 };
-use crate::{DateTime, Error};
+use crate::{
+    geocoder::{geocode_address, GeocoderError},
+    DateTime, Error,
+};
 use async_graphql::{Enum, InputObject, SimpleObject};
-use geocodio::GeocodioProxy;
 use pwhash::bcrypt;
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, PgPool, Type};
@@ -194,6 +196,16 @@ impl User {
             input.address.state_house_district,
         ).fetch_one(db_pool).await?;
 
+        let address_id = sqlx::query_scalar!(
+            r#"SELECT address_id FROM user_profile WHERE user_id = $1"#,
+            record.id
+        )
+        .fetch_one(db_pool)
+        .await?;
+        if let Some(address_id) = address_id {
+            Address::refresh_districts(db_pool, &address_id).await?;
+        }
+
         // Need to handle case of existing user
 
         Ok(record)
@@ -440,46 +452,19 @@ impl User {
                 }
             }
             None => {
-                let geocodio = GeocodioProxy::new().unwrap();
-                let address_clone = address.clone();
-                let geocode_result = geocodio
-                    .geocode(
-                        geocodio::AddressParams::AddressInput(geocodio::AddressInput {
-                            line_1: address_clone.line_1,
-                            line_2: address_clone.line_2,
-                            city: address_clone.city,
-                            state: address_clone.state.to_string(),
-                            country: address_clone.country,
-                            postal_code: address_clone.postal_code,
-                        }),
-                        Some(&["cd", "stateleg"]),
-                    )
-                    .await;
-
-                match geocode_result {
-                    Ok(geocodio_data) => {
-                        if geocodio_data.results.is_empty() {
-                            return Err(Error::Custom(
-                                "This is not a valid voting address".to_string(),
-                            ));
-                        }
-                        let coordinates = geocodio_data.results[0].location.clone();
-                        let county = geocodio_data.results[0].address_components.county.clone();
-                        let primary_result = geocodio_data.results[0].fields.as_ref().unwrap();
-                        let congressional_district =
-                            primary_result.congressional_districts.as_ref().unwrap()[0]
-                                .district_number
-                                .to_string();
-                        let state_legislative_districts =
-                            primary_result.state_legislative_districts.as_ref().unwrap();
-                        let state_house_district =
-                            &state_legislative_districts.house[0].district_number;
-                        let state_senate_district =
-                            &state_legislative_districts.senate[0].district_number;
-                        let lat = coordinates.latitude;
-                        let lon = coordinates.longitude;
-
-                        let updated_record_result = sqlx::query_as!(
+                let geocoded = match geocode_address(db_pool, &address).await {
+                    Ok(geocoded) => geocoded,
+                    Err(GeocoderError::NotFound) => {
+                        return Err(Error::Custom(
+                            "This is not a valid voting address".to_string(),
+                        ))
+                    }
+                    Err(err) => return Err(err.into()),
+                };
+                let lat = geocoded.coordinates.latitude;
+                let lon = geocoded.coordinates.longitude;
+
+                let updated_record_result = sqlx::query_as!(
                     Address,
                     r#"
                     INSERT INTO address (id, line_1, line_2, city, county, state, postal_code, country, lon, lat, geog, geom, congressional_district, state_house_district, state_senate_district)
@@ -519,40 +504,36 @@ impl User {
                     address.line_2,
                     address.city,
                     address.state as State,
-                    county,
+                    geocoded.county,
                     address.postal_code,
                     address.country,
                     lon,
                     lat,
                     format!("POINT({} {})", lon, lat), // A string we pass into ST_GeomFromText function
-                    Some(congressional_district),
-                    state_house_district,
-                    state_senate_district,
+                    geocoded.congressional_district,
+                    geocoded.state_house_district,
+                    geocoded.state_senate_district,
                 )
                 .fetch_one(db_pool)
                 .await;
 
-                        match updated_record_result {
-                            Ok(updated_record) => {
-                                let _ = sqlx::query!(
-                                    r#"
+                match updated_record_result {
+                    Ok(updated_record) => {
+                        Address::refresh_districts(db_pool, &updated_record.id).await?;
+                        let _ = sqlx::query!(
+                            r#"
                             UPDATE user_profile
                             SET address_id = $1
                             WHERE user_id = $2
                         "#,
-                                    updated_record.id,
-                                    user_id
-                                )
-                                .execute(db_pool)
-                                .await;
-                                Ok(updated_record)
-                            }
-                            Err(err) => Err(err.into()),
-                        }
+                            updated_record.id,
+                            user_id
+                        )
+                        .execute(db_pool)
+                        .await;
+                        Ok(updated_record)
                     }
-                    Err(_) => Err(Error::Custom(
-                        "This is not a valid voting address".to_string(),
-                    )),
+                    Err(err) => Err(err.into()),
                 }
             }
         }
@@ -0,0 +1,206 @@
+use sqlx::{
+    postgres::{PgArguments, PgRow},
+    query::Query,
+    FromRow, Postgres, Row,
+};
+
+/// Position of a row within a sorted result set. The id breaks ties between rows
+/// that share the same sort key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keyset {
+    pub sort_key: String,
+    pub id: uuid::Uuid,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+/// Slice of a keyset paginated result set to fetch, mirrors the Relay connection arguments
+#[derive(Debug, Clone, Default)]
+pub struct PageRequest {
+    pub after: Option<Keyset>,
+    pub before: Option<Keyset>,
+    pub first: Option<i64>,
+    pub last: Option<i64>,
+    pub default_page_size: i64,
+}
+
+/// A single page of records, each paired with its keyset so cursors can be built from it
+#[derive(Debug)]
+pub struct Page<T> {
+    pub records: Vec<(Keyset, T)>,
+    pub has_previous_page: bool,
+    pub has_next_page: bool,
+}
+
+impl PageRequest {
+    pub fn limit(&self) -> i64 {
+        self.first
+            .or(self.last)
+            .unwrap_or(self.default_page_size)
+            .max(0)
+    }
+
+    fn is_backward(&self) -> bool {
+        self.first.is_none() && self.last.is_some()
+    }
+
+    /// Wraps a filter query so only the requested page is returned. The inner query must select
+    /// an `id` column and a non-null `sort_value` column of type `sort_type`. Cursor values are
+    /// bound as parameters starting at `$first_param`, see `PageRequest::bind`.
+    pub fn paginate(
+        &self,
+        inner: &str,
+        sort_type: &str,
+        direction: SortDirection,
+        first_param: usize,
+    ) -> String {
+        let (after_op, before_op) = match direction {
+            SortDirection::Asc => (">", "<"),
+            SortDirection::Desc => ("<", ">"),
+        };
+
+        let mut param = first_param;
+        let mut predicates = vec![];
+        for (keyset, op) in [(&self.after, after_op), (&self.before, before_op)] {
+            if keyset.is_some() {
+                predicates.push(format!(
+                    "(sort_value, id) {op} (${sort}::text::{sort_type}, ${id}::uuid)",
+                    sort = param,
+                    id = param + 1,
+                ));
+                param += 2;
+            }
+        }
+
+        let predicate = match predicates.is_empty() {
+            true => "TRUE".to_string(),
+            false => predicates.join(" AND "),
+        };
+
+        // Paging from the end walks the sort in reverse so the LIMIT keeps the rows
+        // closest to the `before` cursor, `Page::from_rows` flips them back
+        let order = match (direction, self.is_backward()) {
+            (SortDirection::Asc, false) | (SortDirection::Desc, true) => "ASC",
+            _ => "DESC",
+        };
+
+        format!(
+            r#"
+            SELECT *, sort_value::text AS sort_key
+            FROM ({inner}) AS keyset_page
+            WHERE {predicate}
+            ORDER BY sort_value {order}, id {order}
+            LIMIT {limit}
+            "#,
+            limit = self.limit() + 1,
+        )
+    }
+
+    /// Binds the cursor values referenced by the SQL generated in `PageRequest::paginate`
+    pub fn bind<'q>(
+        &self,
+        mut query: Query<'q, Postgres, PgArguments>,
+    ) -> Query<'q, Postgres, PgArguments> {
+        for keyset in [&self.after, &self.before].into_iter().flatten() {
+            query = query.bind(keyset.sort_key.clone()).bind(keyset.id);
+        }
+        query
+    }
+}
+
+impl<T> Page<T>
+where
+    T: for<'r> FromRow<'r, PgRow>,
+{
+    /// Builds a page from rows returned by a query generated with `PageRequest::paginate`
+    pub fn from_rows(rows: Vec<PgRow>, request: &PageRequest) -> Result<Self, sqlx::Error> {
+        let limit = request.limit() as usize;
+        let has_more = rows.len() > limit;
+
+        let mut records = rows
+            .iter()
+            .take(limit)
+            .map(|row| {
+                let keyset = Keyset {
+                    sort_key: row.try_get("sort_key")?,
+                    id: row.try_get("id")?,
+                };
+                Ok((keyset, T::from_row(row)?))
+            })
+            .collect::<Result<Vec<_>, sqlx::Error>>()?;
+
+        let (has_previous_page, has_next_page) = if request.is_backward() {
+            records.reverse();
+            (has_more, request.before.is_some())
+        } else {
+            (request.after.is_some(), has_more)
+        };
+
+        Ok(Self {
+            records,
+            has_previous_page,
+            has_next_page,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyset() -> Keyset {
+        Keyset {
+            sort_key: "Smith".to_string(),
+            id: uuid::Uuid::nil(),
+        }
+    }
+
+    #[test]
+    fn test_paginate_forward() {
+        let request = PageRequest {
+            after: Some(keyset()),
+            first: Some(10),
+            default_page_size: 20,
+            ..Default::default()
+        };
+        let sql = request.paginate("SELECT 1", "text", SortDirection::Asc, 3);
+
+        assert_eq!(request.limit(), 10);
+        assert!(sql.contains("(sort_value, id) > ($3::text::text, $4::uuid)"));
+        assert!(sql.contains("ORDER BY sort_value ASC, id ASC"));
+        assert!(sql.contains("LIMIT 11"));
+    }
+
+    #[test]
+    fn test_paginate_backward_descending() {
+        let request = PageRequest {
+            after: Some(keyset()),
+            before: Some(keyset()),
+            last: Some(5),
+            default_page_size: 20,
+            ..Default::default()
+        };
+        let sql = request.paginate("SELECT 1", "float8", SortDirection::Desc, 1);
+
+        assert!(sql.contains("(sort_value, id) < ($1::text::float8, $2::uuid)"));
+        assert!(sql.contains("(sort_value, id) > ($3::text::float8, $4::uuid)"));
+        assert!(sql.contains("ORDER BY sort_value ASC, id ASC"));
+        assert!(sql.contains("LIMIT 6"));
+    }
+
+    #[test]
+    fn test_paginate_default_page_size() {
+        let request = PageRequest {
+            default_page_size: 20,
+            ..Default::default()
+        };
+        let sql = request.paginate("SELECT 1", "text", SortDirection::Asc, 1);
+
+        assert!(sql.contains("WHERE TRUE"));
+        assert!(sql.contains("LIMIT 21"));
+    }
+}
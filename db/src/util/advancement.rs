@@ -0,0 +1,137 @@
+//! Who wins a race or advances out of a primary. Generals elect `num_elect` candidates. Party
+//! primaries nominate `num_elect` per party, nonpartisan primaries send twice the open seats on
+//! to the general. California and Washington run every primary top-two and Alaska top-four.
+
+use crate::models::enums::{RaceType, State};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdvancementRule {
+    Elect(usize),
+    PartyNominee(usize),
+    TopTwo(usize),
+    TopFour,
+}
+
+impl AdvancementRule {
+    pub fn for_race(
+        state: Option<State>,
+        race_type: RaceType,
+        is_party_primary: bool,
+        num_elect: Option<i32>,
+    ) -> Self {
+        let seats = num_elect.map_or(1, |n| n.max(1) as usize);
+        match (race_type, state) {
+            (RaceType::General, _) => AdvancementRule::Elect(seats),
+            (RaceType::Primary, Some(State::AK)) => AdvancementRule::TopFour,
+            (RaceType::Primary, Some(State::CA | State::WA)) => AdvancementRule::TopTwo(seats),
+            (RaceType::Primary, _) if is_party_primary => AdvancementRule::PartyNominee(seats),
+            (RaceType::Primary, _) => AdvancementRule::TopTwo(seats),
+        }
+    }
+
+    /// Number of candidates who win or advance
+    pub fn advancing(&self) -> usize {
+        match self {
+            AdvancementRule::Elect(seats) | AdvancementRule::PartyNominee(seats) => *seats,
+            AdvancementRule::TopTwo(seats) => seats * 2,
+            AdvancementRule::TopFour => 4,
+        }
+    }
+
+    pub fn advances_to_general(&self) -> bool {
+        !matches!(self, AdvancementRule::Elect(_))
+    }
+}
+
+impl fmt::Display for AdvancementRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AdvancementRule::Elect(1) => write!(f, "Most votes wins"),
+            AdvancementRule::Elect(n) => write!(f, "Top {} win", n),
+            AdvancementRule::PartyNominee(1) => write!(f, "Most votes is the party's nominee"),
+            AdvancementRule::PartyNominee(n) => write!(f, "Top {} are the party's nominees", n),
+            AdvancementRule::TopTwo(_) | AdvancementRule::TopFour => {
+                write!(f, "Top {} advance to the general", self.advancing())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// Winners, most votes first, and everyone else
+    Decided {
+        winners: Vec<uuid::Uuid>,
+        losers: Vec<uuid::Uuid>,
+    },
+    /// Candidates tied for the last winning spot, left for staff to settle
+    Tied(Vec<uuid::Uuid>),
+}
+
+/// Picks the top candidates by votes, candidates without a count have none
+pub fn decide(votes: &[(uuid::Uuid, Option<i32>)], advancing: usize) -> Outcome {
+    let mut ranked = votes.to_vec();
+    ranked.sort_by_key(|(_, votes)| std::cmp::Reverse(votes.unwrap_or(0)));
+
+    if advancing > 0 && ranked.len() > advancing {
+        let cutoff = ranked[advancing - 1].1.unwrap_or(0);
+        if ranked[advancing].1.unwrap_or(0) == cutoff {
+            return Outcome::Tied(
+                ranked
+                    .iter()
+                    .filter(|(_, votes)| votes.unwrap_or(0) == cutoff)
+                    .map(|(id, _)| *id)
+                    .collect(),
+            );
+        }
+    }
+
+    let losers = ranked.split_off(advancing.min(ranked.len()));
+    Outcome::Decided {
+        winners: ranked.into_iter().map(|(id, _)| id).collect(),
+        losers: losers.into_iter().map(|(id, _)| id).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_advancement_rules() {
+        let rule = |state, race_type, is_party_primary, num_elect| {
+            AdvancementRule::for_race(state, race_type, is_party_primary, num_elect).advancing()
+        };
+        assert_eq!(rule(Some(State::MN), RaceType::General, false, Some(3)), 3);
+        assert_eq!(rule(Some(State::MN), RaceType::Primary, true, None), 1);
+        assert_eq!(rule(Some(State::MN), RaceType::Primary, false, None), 2);
+        assert_eq!(rule(Some(State::MN), RaceType::Primary, false, Some(2)), 4);
+        assert_eq!(rule(Some(State::CA), RaceType::Primary, true, None), 2);
+        assert_eq!(rule(Some(State::AK), RaceType::Primary, false, None), 4);
+    }
+
+    #[test]
+    fn test_decide() {
+        let [a, b, c] = [1, 2, 3].map(uuid::Uuid::from_u128);
+        assert_eq!(
+            decide(&[(b, Some(40)), (a, Some(60)), (c, None)], 2),
+            Outcome::Decided {
+                winners: vec![a, b],
+                losers: vec![c],
+            }
+        );
+        assert_eq!(
+            decide(&[(a, Some(60)), (b, Some(40)), (c, Some(40))], 2),
+            Outcome::Tied(vec![b, c])
+        );
+        // Uncontested, everyone advances
+        assert_eq!(
+            decide(&[(a, Some(10))], 2),
+            Outcome::Decided {
+                winners: vec![a],
+                losers: vec![],
+            }
+        );
+    }
+}
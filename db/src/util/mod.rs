@@ -1 +1,4 @@
+pub mod advancement;
+pub mod rcv;
 pub mod translate;
+pub mod tiger;
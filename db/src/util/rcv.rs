@@ -0,0 +1,374 @@
+//! Round-by-round tabulation of ranked choice ballots. A single seat is an instant runoff: the
+//! candidate with a majority of the votes for continuing candidates wins. More seats use the
+//! single transferable vote with the Droop quota and fractional surplus transfers, the way
+//! Minneapolis tabulates its multi-seat boards.
+
+use crate::RankedChoiceStatus;
+
+/// One ranking on a ballot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mark {
+    Candidate(String),
+    /// Left blank, the ballot moves on to its next ranking
+    Skipped,
+    /// More than one candidate marked, the ballot is exhausted when it gets here
+    Overvote,
+}
+
+/// Ballots ranked identically, `count` of them
+#[derive(Debug, Clone, PartialEq)]
+pub struct CastVoteRecord {
+    pub marks: Vec<Mark>,
+    pub count: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoundTally {
+    pub candidate: String,
+    pub votes: f64,
+    /// Change from the previous round, zero in the first
+    pub transfer: f64,
+    /// Elected or eliminated at the end of this round, or elected in an earlier one
+    pub status: RankedChoiceStatus,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TabulatedRound {
+    pub round: i32,
+    pub threshold: Option<f64>,
+    /// Ballots with no continuing candidate left, cumulative
+    pub exhausted: f64,
+    /// Every candidate not eliminated in an earlier round, most votes first
+    pub tallies: Vec<RoundTally>,
+}
+
+impl TabulatedRound {
+    pub fn elected(&self) -> impl Iterator<Item = &str> {
+        self.tallies
+            .iter()
+            .filter(|t| t.status == RankedChoiceStatus::Elected)
+            .map(|t| t.candidate.as_str())
+    }
+}
+
+/// Surplus transfer values are truncated to four decimal places, as Minneapolis does
+fn truncate(value: f64) -> f64 {
+    (value * 10_000.0).floor() / 10_000.0
+}
+
+fn round_votes(value: f64) -> f64 {
+    (value * 10_000.0).round() / 10_000.0
+}
+
+struct BallotState {
+    position: usize,
+    weight: f64,
+    holder: Option<usize>,
+}
+
+/// Tabulates ballots for `seats` winners. Ties for last place are broken by who had fewer votes
+/// in the latest earlier round where they differ, then by name in place of drawing lots.
+pub fn tabulate(ballots: &[CastVoteRecord], seats: usize) -> Vec<TabulatedRound> {
+    let mut candidates: Vec<String> = ballots
+        .iter()
+        .flat_map(|b| b.marks.iter())
+        .filter_map(|m| match m {
+            Mark::Candidate(name) => Some(name.clone()),
+            _ => None,
+        })
+        .collect();
+    candidates.sort();
+    candidates.dedup();
+    if candidates.is_empty() || seats == 0 {
+        return vec![];
+    }
+
+    let index = |name: &str| candidates.binary_search_by(|c| c.as_str().cmp(name)).ok();
+    let mut status = vec![RankedChoiceStatus::Continuing; candidates.len()];
+    let mut eliminated_in: Vec<Option<usize>> = vec![None; candidates.len()];
+    let mut kept = vec![0.0; candidates.len()];
+
+    // Finds the next continuing candidate at or after `from`, `None` once the ballot is exhausted
+    let next_holder = |ballot: &CastVoteRecord, from: usize, status: &[RankedChoiceStatus]| {
+        for (position, mark) in ballot.marks.iter().enumerate().skip(from) {
+            match mark {
+                Mark::Skipped => continue,
+                Mark::Overvote => return None,
+                Mark::Candidate(name) => {
+                    let candidate = index(name)?;
+                    if status[candidate] == RankedChoiceStatus::Continuing {
+                        return Some((candidate, position));
+                    }
+                }
+            }
+        }
+        None
+    };
+
+    let mut states: Vec<BallotState> = ballots
+        .iter()
+        .map(|ballot| {
+            let holder = next_holder(ballot, 0, &status);
+            BallotState {
+                position: holder.map(|(_, p)| p).unwrap_or(0),
+                weight: ballot.count,
+                holder: holder.map(|(c, _)| c),
+            }
+        })
+        .collect();
+
+    let valid: f64 = states
+        .iter()
+        .filter(|s| s.holder.is_some())
+        .map(|s| s.weight)
+        .sum();
+    let droop = (valid / (seats as f64 + 1.0)).floor() + 1.0;
+
+    // Ballots overvoted at their first ranking never count for anyone
+    let mut exhausted: f64 = ballots
+        .iter()
+        .filter(|b| b.marks.iter().find(|m| **m != Mark::Skipped) == Some(&Mark::Overvote))
+        .map(|b| b.count)
+        .sum();
+    let mut history: Vec<Vec<f64>> = vec![];
+    let mut rounds = vec![];
+
+    loop {
+        let mut votes = kept.clone();
+        for state in &states {
+            if let Some(holder) = state.holder {
+                votes[holder] += state.weight;
+            }
+        }
+        let continuing: Vec<usize> = (0..candidates.len())
+            .filter(|&c| status[c] == RankedChoiceStatus::Continuing)
+            .collect();
+        let threshold = if seats == 1 {
+            (continuing.iter().map(|&c| votes[c]).sum::<f64>() / 2.0).floor() + 1.0
+        } else {
+            droop
+        };
+        let elected_count = status
+            .iter()
+            .filter(|&&s| s == RankedChoiceStatus::Elected)
+            .count();
+        let open_seats = seats - elected_count;
+
+        let mut newly_elected: Vec<usize> = continuing
+            .iter()
+            .copied()
+            .filter(|&c| votes[c] >= threshold)
+            .collect();
+        newly_elected.sort_by(|&a, &b| votes[b].total_cmp(&votes[a]));
+        newly_elected.truncate(open_seats);
+        if newly_elected.is_empty() && continuing.len() <= open_seats {
+            newly_elected = continuing.clone();
+        }
+
+        let newly_eliminated = if newly_elected.is_empty() {
+            continuing.iter().copied().min_by(|&a, &b| {
+                votes[a]
+                    .total_cmp(&votes[b])
+                    .then_with(|| {
+                        history
+                            .iter()
+                            .rev()
+                            .map(|round| round[a].total_cmp(&round[b]))
+                            .find(|o| o.is_ne())
+                            .unwrap_or(std::cmp::Ordering::Equal)
+                    })
+                    .then_with(|| candidates[b].cmp(&candidates[a]))
+            })
+        } else {
+            None
+        };
+
+        for &c in &newly_elected {
+            status[c] = RankedChoiceStatus::Elected;
+        }
+        if let Some(c) = newly_eliminated {
+            status[c] = RankedChoiceStatus::Eliminated;
+            eliminated_in[c] = Some(history.len());
+        }
+
+        let round_number = history.len();
+        let mut tallies: Vec<RoundTally> = (0..candidates.len())
+            .filter(|&c| !matches!(eliminated_in[c], Some(r) if r != round_number))
+            .map(|c| RoundTally {
+                candidate: candidates[c].clone(),
+                votes: round_votes(votes[c]),
+                transfer: round_votes(history.last().map_or(0.0, |prev| votes[c] - prev[c])),
+                status: status[c],
+            })
+            .collect();
+        tallies.sort_by(|a, b| {
+            b.votes
+                .total_cmp(&a.votes)
+                .then(a.candidate.cmp(&b.candidate))
+        });
+        rounds.push(TabulatedRound {
+            round: round_number as i32 + 1,
+            threshold: Some(threshold),
+            exhausted: round_votes(exhausted),
+            tallies,
+        });
+        history.push(votes.clone());
+
+        if elected_count + newly_elected.len() >= seats || continuing.is_empty() {
+            break;
+        }
+
+        // Ballots move on from eliminated candidates at full value, and from elected ones at
+        // the value of the candidate's surplus
+        for (ballot, state) in ballots.iter().zip(states.iter_mut()) {
+            let Some(holder) = state.holder else {
+                continue;
+            };
+            let fraction = if Some(holder) == newly_eliminated {
+                1.0
+            } else if newly_elected.contains(&holder) {
+                let received = votes[holder] - kept[holder];
+                truncate(((votes[holder] - threshold) / received).max(0.0))
+            } else {
+                continue;
+            };
+            state.weight *= fraction;
+            match next_holder(ballot, state.position + 1, &status) {
+                Some((next, position)) => {
+                    state.holder = Some(next);
+                    state.position = position;
+                }
+                None => {
+                    exhausted += state.weight;
+                    state.holder = None;
+                }
+            }
+        }
+        for &c in &newly_elected {
+            kept[c] = threshold;
+        }
+    }
+
+    rounds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ballot(count: f64, marks: &[&str]) -> CastVoteRecord {
+        CastVoteRecord {
+            marks: marks
+                .iter()
+                .map(|m| match *m {
+                    "" => Mark::Skipped,
+                    "overvote" => Mark::Overvote,
+                    name => Mark::Candidate(name.to_string()),
+                })
+                .collect(),
+            count,
+        }
+    }
+
+    fn tally<'a>(round: &'a TabulatedRound, candidate: &str) -> &'a RoundTally {
+        round
+            .tallies
+            .iter()
+            .find(|t| t.candidate == candidate)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_instant_runoff() {
+        let ballots = vec![
+            ballot(40.0, &["Alice", "Carol"]),
+            ballot(35.0, &["Bob"]),
+            ballot(20.0, &["Carol", "Alice"]),
+            ballot(5.0, &["", "Carol", "Bob"]),
+            ballot(3.0, &["overvote", "Bob"]),
+        ];
+        let rounds = tabulate(&ballots, 1);
+        assert_eq!(rounds.len(), 2);
+
+        let first = &rounds[0];
+        assert_eq!(first.threshold, Some(51.0));
+        assert_eq!(tally(first, "Carol").votes, 25.0);
+        assert_eq!(tally(first, "Carol").status, RankedChoiceStatus::Eliminated);
+        assert_eq!(first.exhausted, 3.0);
+
+        let second = &rounds[1];
+        assert_eq!(tally(second, "Alice").votes, 60.0);
+        assert_eq!(tally(second, "Alice").transfer, 20.0);
+        assert_eq!(tally(second, "Bob").votes, 40.0);
+        assert_eq!(second.exhausted, 3.0);
+        assert_eq!(second.elected().collect::<Vec<_>>(), vec!["Alice"]);
+        assert!(second.tallies.iter().all(|t| t.candidate != "Carol"));
+    }
+
+    #[test]
+    fn test_exhausted_ballots() {
+        let ballots = vec![
+            ballot(12.0, &["Alice"]),
+            ballot(8.0, &["Bob"]),
+            ballot(5.0, &["Carol"]),
+            ballot(2.0, &["Carol", "Bob"]),
+        ];
+        let rounds = tabulate(&ballots, 1);
+        let last = rounds.last().unwrap();
+        assert_eq!(last.exhausted, 5.0);
+        // A majority of the 22 continuing votes, not of all 27 ballots
+        assert_eq!(last.threshold, Some(12.0));
+        assert_eq!(last.elected().collect::<Vec<_>>(), vec!["Alice"]);
+    }
+
+    #[test]
+    fn test_single_transferable_vote() {
+        let ballots = vec![
+            ballot(60.0, &["Alice", "Bob"]),
+            ballot(25.0, &["Carol"]),
+            ballot(15.0, &["Dave", "Bob"]),
+            ballot(10.0, &["Bob", "Carol"]),
+        ];
+        let rounds = tabulate(&ballots, 2);
+
+        // Droop quota of 110 ballots for 2 seats
+        assert_eq!(rounds[0].threshold, Some(37.0));
+        assert_eq!(rounds[0].elected().collect::<Vec<_>>(), vec!["Alice"]);
+
+        // Alice's 23 vote surplus moves to Bob at 23/60 of a vote per ballot, truncated
+        let second = &rounds[1];
+        assert_eq!(tally(second, "Alice").votes, 37.0);
+        assert_eq!(tally(second, "Bob").votes, 32.998);
+
+        let last = rounds.last().unwrap();
+        assert_eq!(
+            last.tallies
+                .iter()
+                .filter(|t| t.status == RankedChoiceStatus::Elected)
+                .count(),
+            2
+        );
+        assert!(last.elected().any(|c| c == "Bob"));
+    }
+
+    #[test]
+    fn test_tie_for_last_uses_earlier_rounds() {
+        let ballots = vec![
+            ballot(10.0, &["Alice"]),
+            ballot(4.0, &["Bob"]),
+            ballot(3.0, &["Carol"]),
+            ballot(2.0, &["Dave", "Carol"]),
+            ballot(1.0, &["Erin", "Bob"]),
+        ];
+        let rounds = tabulate(&ballots, 1);
+        // Erin is out first, then Bob and Carol are tied at 5 but Carol had fewer in round 1
+        assert_eq!(
+            tally(&rounds[0], "Erin").status,
+            RankedChoiceStatus::Eliminated
+        );
+        assert_eq!(
+            tally(&rounds[2], "Carol").status,
+            RankedChoiceStatus::Eliminated
+        );
+    }
+}
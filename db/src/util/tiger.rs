@@ -0,0 +1,210 @@
+//! Conventions of the Census TIGER/Line shapefiles, so every state's districts can be imported
+//! the same way. Files are named `tl_{year}_{state fips or "us"}_{layer}`, e.g. `tl_2024_27_sldu`.
+
+use crate::{models::enums::State, DistrictType};
+
+/// Where to find a district's code and name in a TIGER layer's attribute table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TigerLayer {
+    pub layer: String,
+    pub district_type: DistrictType,
+    pub code_field: String,
+    pub name_field: &'static str,
+    /// Legislative district numbers are zero padded, `07A` is stored as `7A`
+    pub trim_code: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TigerFile {
+    pub vintage: i32,
+    /// `None` for national files, features carry their state in `STATEFP`
+    pub state: Option<State>,
+    pub layer: TigerLayer,
+}
+
+impl TigerLayer {
+    pub fn from_layer(layer: &str) -> Option<Self> {
+        let layer = layer.to_lowercase();
+        let (district_type, code_field, name_field, trim_code) = match layer.as_str() {
+            "sldu" => (DistrictType::StateSenate, "SLDUST".into(), "NAMELSAD", true),
+            "sldl" => (DistrictType::StateHouse, "SLDLST".into(), "NAMELSAD", true),
+            // Full county names ("Hennepin County") match what geocoders return for an address
+            "county" => (DistrictType::County, "GEOID".into(), "NAMELSAD", false),
+            // Townships and other minor civil divisions elect officers as well as places do
+            "place" | "cousub" => (DistrictType::City, "GEOID".into(), "NAME", false),
+            "unsd" | "elsd" | "scsd" => (DistrictType::School, "GEOID".into(), "NAME", false),
+            // Congressional layers are numbered by Congress, e.g. `cd118` with `CD118FP`
+            cd if cd.starts_with("cd")
+                && cd.len() > 2
+                && cd[2..].chars().all(|c| c.is_ascii_digit()) =>
+            {
+                (
+                    DistrictType::UsCongressional,
+                    format!("CD{}FP", &cd[2..]),
+                    "NAMELSAD",
+                    true,
+                )
+            }
+            _ => return None,
+        };
+        Some(Self {
+            layer,
+            district_type,
+            code_field,
+            name_field,
+            trim_code,
+        })
+    }
+
+    /// The layer without its Congress number, so `cd118` and `cd119` are vintages of one map
+    pub fn base_layer(&self) -> &str {
+        match self.district_type {
+            DistrictType::UsCongressional => "cd",
+            _ => &self.layer,
+        }
+    }
+}
+
+impl TigerFile {
+    /// Parses a TIGER/Line file name or path, with or without its `.zip` or `.shp` extension
+    pub fn from_path(path: &str) -> Option<Self> {
+        let file_name = path.rsplit(['/', '\\']).next()?;
+        let stem = file_name.split('.').next()?;
+        let mut parts = stem.splitn(4, '_');
+        if parts.next()? != "tl" {
+            return None;
+        }
+        let vintage = parts.next()?.parse().ok()?;
+        let state = match parts.next()? {
+            "us" => None,
+            fips => Some(state_from_fips(fips)?),
+        };
+        let layer = TigerLayer::from_layer(parts.next()?)?;
+        Some(Self {
+            vintage,
+            state,
+            layer,
+        })
+    }
+}
+
+/// Strips the zero padding from a district number, keeping at-large `00` as `0`
+pub fn normalize_code(code: &str) -> String {
+    let trimmed = code.trim().trim_start_matches('0');
+    if trimmed.is_empty() && !code.trim().is_empty() {
+        "0".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// TIGER fills areas outside any district (mostly water) with `ZZ`, `ZZZ` and so on
+pub fn is_undefined_code(code: &str) -> bool {
+    let code = code.trim();
+    code.is_empty() || code.chars().all(|c| c == 'Z')
+}
+
+pub fn state_from_fips(fips: &str) -> Option<State> {
+    let state = match fips {
+        "01" => State::AL,
+        "02" => State::AK,
+        "04" => State::AZ,
+        "05" => State::AR,
+        "06" => State::CA,
+        "08" => State::CO,
+        "09" => State::CT,
+        "10" => State::DE,
+        "11" => State::DC,
+        "12" => State::FL,
+        "13" => State::GA,
+        "15" => State::HI,
+        "16" => State::ID,
+        "17" => State::IL,
+        "18" => State::IN,
+        "19" => State::IA,
+        "20" => State::KS,
+        "21" => State::KY,
+        "22" => State::LA,
+        "23" => State::ME,
+        "24" => State::MD,
+        "25" => State::MA,
+        "26" => State::MI,
+        "27" => State::MN,
+        "28" => State::MS,
+        "29" => State::MO,
+        "30" => State::MT,
+        "31" => State::NE,
+        "32" => State::NV,
+        "33" => State::NH,
+        "34" => State::NJ,
+        "35" => State::NM,
+        "36" => State::NY,
+        "37" => State::NC,
+        "38" => State::ND,
+        "39" => State::OH,
+        "40" => State::OK,
+        "41" => State::OR,
+        "42" => State::PA,
+        "44" => State::RI,
+        "45" => State::SC,
+        "46" => State::SD,
+        "47" => State::TN,
+        "48" => State::TX,
+        "49" => State::UT,
+        "50" => State::VT,
+        "51" => State::VA,
+        "53" => State::WA,
+        "54" => State::WV,
+        "55" => State::WI,
+        "56" => State::WY,
+        "60" => State::AS,
+        "64" => State::FM,
+        "66" => State::GU,
+        "68" => State::MH,
+        "69" => State::MP,
+        "70" => State::PW,
+        "72" => State::PR,
+        "78" => State::VI,
+        _ => return None,
+    };
+    Some(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tiger_file_from_path() {
+        let file = TigerFile::from_path("/data/tiger/tl_2024_27_sldl.zip").unwrap();
+        assert_eq!(file.vintage, 2024);
+        assert_eq!(file.state, Some(State::MN));
+        assert_eq!(file.layer.district_type, DistrictType::StateHouse);
+        assert_eq!(file.layer.code_field, "SLDLST");
+
+        let file = TigerFile::from_path("tl_2023_us_cd118.shp").unwrap();
+        assert_eq!(file.state, None);
+        assert_eq!(file.layer.district_type, DistrictType::UsCongressional);
+        assert_eq!(file.layer.code_field, "CD118FP");
+        assert_eq!(file.layer.base_layer(), "cd");
+
+        let file = TigerFile::from_path("tl_2024_08_unsd").unwrap();
+        assert_eq!(file.state, Some(State::CO));
+        assert_eq!(file.layer.district_type, DistrictType::School);
+        assert!(!file.layer.trim_code);
+
+        assert_eq!(TigerFile::from_path("tl_2024_us_rails.zip"), None);
+        assert_eq!(TigerFile::from_path("tl_2024_99_sldu.zip"), None);
+        assert_eq!(TigerFile::from_path("mn_wards_2024.shp"), None);
+    }
+
+    #[test]
+    fn test_normalize_code() {
+        assert_eq!(normalize_code("07A"), "7A");
+        assert_eq!(normalize_code("012"), "12");
+        assert_eq!(normalize_code("00"), "0");
+        assert_eq!(normalize_code(""), "");
+        assert!(is_undefined_code("ZZZ"));
+        assert!(!is_undefined_code("007"));
+    }
+}
@@ -1,7 +1,16 @@
 use async_graphql::dataloader::{DataLoader, LruCache, NoCache};
 use db::loaders::{
-    issue_tag::IssueTagLoader, office::OfficeLoader, organization::OrganizationLoader,
-    politician::PoliticianLoader, race::RaceLoader,
+    bill::BillLoader,
+    candidate_guide::CandidateGuideLoader,
+    election::ElectionLoader,
+    issue_tag::IssueTagLoader,
+    office::OfficeLoader,
+    organization::OrganizationLoader,
+    party::PartyLoader,
+    politician::PoliticianLoader,
+    poll::PollLoader,
+    question::QuestionLoader,
+    race::{RaceCandidatesLoader, RaceLoader},
 };
 use sqlx::PgPool;
 
@@ -15,7 +24,14 @@ pub struct DataLoaders {
     pub politician_loader: DataLoader<PoliticianLoader, NoCache>,
     pub office_loader: DataLoader<OfficeLoader, LruCache>,
     pub race_loader: DataLoader<RaceLoader, LruCache>,
+    pub race_candidates_loader: DataLoader<RaceCandidatesLoader, NoCache>,
     pub issue_tag_loader: DataLoader<IssueTagLoader, LruCache>,
+    pub election_loader: DataLoader<ElectionLoader, LruCache>,
+    pub bill_loader: DataLoader<BillLoader, LruCache>,
+    pub party_loader: DataLoader<PartyLoader, LruCache>,
+    pub poll_loader: DataLoader<PollLoader, LruCache>,
+    pub question_loader: DataLoader<QuestionLoader, LruCache>,
+    pub candidate_guide_loader: DataLoader<CandidateGuideLoader, LruCache>,
 }
 
 impl DataLoaders {
@@ -41,11 +57,46 @@ impl DataLoaders {
                 tokio::task::spawn,
                 LruCache::new(64),
             ),
+            race_candidates_loader: DataLoader::with_cache(
+                RaceCandidatesLoader::new(pool.clone()),
+                tokio::task::spawn,
+                NoCache,
+            ),
             issue_tag_loader: DataLoader::with_cache(
-                IssueTagLoader::new(pool),
+                IssueTagLoader::new(pool.clone()),
+                tokio::task::spawn,
+                LruCache::new(128),
+            ),
+            election_loader: DataLoader::with_cache(
+                ElectionLoader::new(pool.clone()),
+                tokio::task::spawn,
+                LruCache::new(64),
+            ),
+            bill_loader: DataLoader::with_cache(
+                BillLoader::new(pool.clone()),
+                tokio::task::spawn,
+                LruCache::new(64),
+            ),
+            party_loader: DataLoader::with_cache(
+                PartyLoader::new(pool.clone()),
                 tokio::task::spawn,
                 LruCache::new(128),
             ),
+            poll_loader: DataLoader::with_cache(
+                PollLoader::new(pool.clone()),
+                tokio::task::spawn,
+                LruCache::new(64),
+            ),
+            question_loader: DataLoader::with_cache(
+                QuestionLoader::new(pool.clone()),
+                tokio::task::spawn,
+                LruCache::new(64),
+            ),
+            candidate_guide_loader: DataLoader::with_cache(
+                CandidateGuideLoader::new(pool),
+                tokio::task::spawn,
+                LruCache::new(64),
+            ),
         }
     }
 }
@@ -58,3 +109,119 @@ impl ApiContext {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::new_schema;
+    use auth::AccessTokenClaims;
+    use jsonwebtoken::TokenData;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use tracing_subscriber::{layer::SubscriberExt, Layer};
+
+    /// Counts the statements sqlx logs under its `sqlx::query` target
+    struct QueryCounter(Arc<AtomicUsize>);
+
+    impl<S: tracing::Subscriber> Layer<S> for QueryCounter {
+        fn on_event(
+            &self,
+            event: &tracing::Event<'_>,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            if event.metadata().target() == "sqlx::query" {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    }
+
+    /// Inserts an election with two races of two candidates each, returning its slug
+    async fn seed_election(pool: &PgPool, key: &str) -> String {
+        sqlx::query_scalar::<_, String>(
+            r#"
+            WITH e AS (
+                INSERT INTO election (slug, title, election_date)
+                VALUES ('test-election-' || $1, 'Test Election', '2024-11-05')
+                RETURNING id, slug
+            ), o AS (
+                INSERT INTO office (slug, title, political_scope)
+                VALUES ('test-office-' || $1, 'Test Office', 'local')
+                RETURNING id
+            ), pa AS (
+                INSERT INTO party (slug, name)
+                VALUES ('test-party-' || $1, 'Test Party')
+                RETURNING id
+            ), r AS (
+                INSERT INTO race (slug, title, office_id, election_id)
+                SELECT 'test-race-' || $1 || '-' || n, 'Test Race', o.id, e.id
+                FROM generate_series(1, 2) n, o, e
+                RETURNING id, slug
+            ), p AS (
+                INSERT INTO politician (slug, first_name, last_name, full_name, party_id)
+                SELECT 'test-politician-' || $1 || '-' || n, 'Test', 'Candidate', 'Test Candidate', pa.id
+                FROM generate_series(1, 4) n, pa
+                RETURNING id, slug
+            ), rc AS (
+                INSERT INTO race_candidates (race_id, candidate_id)
+                SELECT r.id, p.id FROM r
+                JOIN p ON right(p.slug, 1)::int % 2 = right(r.slug, 1)::int % 2
+            )
+            SELECT slug FROM e
+            "#,
+        )
+        .bind(key)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    async fn delete_seeded_election(pool: &PgPool, key: &str) {
+        for statement in [
+            "DELETE FROM race_candidates WHERE race_id IN (SELECT id FROM race WHERE slug LIKE 'test-race-' || $1 || '-%')",
+            "DELETE FROM race WHERE slug LIKE 'test-race-' || $1 || '-%'",
+            "DELETE FROM politician WHERE slug LIKE 'test-politician-' || $1 || '-%'",
+            "DELETE FROM party WHERE slug = 'test-party-' || $1",
+            "DELETE FROM office WHERE slug = 'test-office-' || $1",
+            "DELETE FROM election WHERE slug = 'test-election-' || $1",
+        ] {
+            sqlx::query(statement).bind(key).execute(pool).await.unwrap();
+        }
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    #[ignore]
+    async fn test_election_races_candidates_query_count() {
+        db::init_pool().await.unwrap();
+        let pool = db::pool().await.connection.clone();
+
+        let key = uuid::Uuid::new_v4().simple().to_string();
+        let slug = seed_election(&pool, &key).await;
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let subscriber = tracing_subscriber::registry().with(
+            QueryCounter(count.clone()).with_filter(tracing::level_filters::LevelFilter::TRACE),
+        );
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let schema = new_schema()
+            .data(ApiContext::new(pool))
+            .data(None::<TokenData<AccessTokenClaims>>)
+            .finish();
+        let query = format!(
+            r#"{{ electionBySlug(slug: "{slug}") {{ races {{ candidates {{ party {{ name }} }} }} }} }}"#
+        );
+        let response = schema.execute(query).await;
+
+        drop(_guard);
+        delete_seeded_election(&pool, &key).await;
+
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let races = response.data.into_json().unwrap()["electionBySlug"]["races"].clone();
+        assert_eq!(races.as_array().map(Vec::len), Some(2));
+        assert_eq!(races[0]["candidates"][0]["party"]["name"], "Test Party");
+        // One query each for the election, its races, all race candidates and all parties
+        assert!(count.load(Ordering::SeqCst) <= 4);
+    }
+}
@@ -10,10 +10,10 @@ use auth::{
     create_temporary_username, format_auth_cookie, AccessTokenClaims,
 };
 use db::{
-    AddressInput, Coordinates, CreateUserInput, CreateUserWithProfileInput, OrganizationRole,
+    geocoder::{geocode_address, GeocoderError},
+    AddressInput, CreateUserInput, CreateUserWithProfileInput, OrganizationRole,
     OrganizationRoleType, SystemRoleType, User,
 };
-use geocodio::GeocodioProxy;
 use jsonwebtoken::TokenData;
 use mailers::EmailClient;
 use pwhash::bcrypt;
@@ -233,56 +233,19 @@ impl AuthMutation {
 
         let new_user_result = match input.address {
             Some(address) => {
-                let address_clone = address.clone();
-                let geocodio = GeocodioProxy::new().unwrap();
-                let geocode_result = geocodio
-                    .geocode(
-                        geocodio::AddressParams::AddressInput(geocodio::AddressInput {
-                            line_1: address_clone.line_1,
-                            line_2: address_clone.line_2,
-                            city: address_clone.city,
-                            state: address_clone.state.to_string(),
-                            country: address_clone.country,
-                            postal_code: address_clone.postal_code,
-                        }),
-                        Some(&["cd118", "stateleg-next"]),
-                    )
-                    .await;
-
-                let t = match geocode_result {
-                    Ok(geocodio_data) => {
-                        let city = geocodio_data.results[0]
-                            .address_components
-                            .city
-                            .clone()
-                            .unwrap_or(address.city);
-                        let coordinates = geocodio_data.results[0].location.clone();
-                        let county = geocodio_data.results[0].address_components.county.clone();
-                        let primary_result = geocodio_data.results[0].fields.as_ref().unwrap();
-                        let congressional_district =
-                            &primary_result.congressional_districts.as_ref().unwrap()[0]
-                                .district_number;
-                        let state_legislative_districts =
-                            primary_result.state_legislative_districts.as_ref().unwrap();
-                        let state_house_district =
-                            &state_legislative_districts.house[0].district_number;
-                        let state_senate_district =
-                            &state_legislative_districts.senate[0].district_number;
-
+                let t = match geocode_address(&db_pool, &address).await {
+                    Ok(geocoded) => {
                         let new_user_input = CreateUserWithProfileInput {
                             email: input.email.clone(),
                             username: temp_username,
                             password: input.password,
                             address: AddressInput {
-                                coordinates: Some(Coordinates {
-                                    latitude: coordinates.latitude,
-                                    longitude: coordinates.longitude,
-                                }),
-                                city,
-                                county,
-                                congressional_district: Some(congressional_district.to_string()),
-                                state_house_district: Some(state_house_district.to_string()),
-                                state_senate_district: Some(state_senate_district.to_string()),
+                                coordinates: Some(geocoded.coordinates),
+                                city: geocoded.city.unwrap_or(address.city),
+                                county: geocoded.county,
+                                congressional_district: geocoded.congressional_district,
+                                state_house_district: geocoded.state_house_district,
+                                state_senate_district: geocoded.state_senate_district,
                                 ..address
                             },
                             confirmation_token: confirmation_token.clone(),
@@ -290,10 +253,8 @@ impl AuthMutation {
 
                         Ok(User::create_with_profile(&db_pool, &new_user_input).await?)
                     }
-                    Err(err) => match err {
-                        geocodio::Error::BadAddress(_err) => Err(Error::BadAddress),
-                        _ => Err(err.into()),
-                    },
+                    Err(GeocoderError::NotFound) => Err(Error::BadAddress),
+                    Err(err) => Err(err.into()),
                 };
                 t
             }
@@ -1,6 +1,19 @@
-use crate::{context::ApiContext, guard::StaffOnly, is_admin, types::ElectionResult};
+use crate::{
+    context::ApiContext,
+    guard::StaffOnly,
+    is_admin,
+    types::{
+        BallotMeasureFinalizationResult, ElectionDeadlineResult, ElectionResult,
+        RaceFinalizationResult, RaceResult,
+    },
+};
 use async_graphql::*;
-use db::{Election, UpsertElectionInput};
+use auth::AccessTokenClaims;
+use db::{
+    finalize_ballot_measure_results, finalize_race_results, Election, ElectionDeadline, Race,
+    RaceResultsSnapshot, UpsertElectionDeadlineInput, UpsertElectionInput,
+};
+use jsonwebtoken::TokenData;
 
 #[derive(Default)]
 pub struct ElectionMutation;
@@ -29,4 +42,88 @@ impl ElectionMutation {
         Election::delete(&db_pool, uuid::Uuid::parse_str(&id)?).await?;
         Ok(DeleteElectionResult { id })
     }
+
+    /// Sets winners, counts wins and losses and advances primary winners for every race in the
+    /// election with results. Safe to run again after results change.
+    #[graphql(guard = "StaffOnly", visible = "is_admin")]
+    async fn finalize_race_results(
+        &self,
+        ctx: &Context<'_>,
+        election_id: ID,
+    ) -> Result<Vec<RaceFinalizationResult>> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let records =
+            finalize_race_results(&db_pool, uuid::Uuid::parse_str(&election_id)?, false).await?;
+        Ok(records
+            .into_iter()
+            .map(RaceFinalizationResult::from)
+            .collect())
+    }
+
+    /// Marks each ballot measure in the election with results as passed or failed, using its
+    /// passage threshold. Safe to run again after results change.
+    #[graphql(guard = "StaffOnly", visible = "is_admin")]
+    async fn finalize_ballot_measure_results(
+        &self,
+        ctx: &Context<'_>,
+        election_id: ID,
+    ) -> Result<Vec<BallotMeasureFinalizationResult>> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let records =
+            finalize_ballot_measure_results(&db_pool, uuid::Uuid::parse_str(&election_id)?, false)
+                .await?;
+        Ok(records
+            .into_iter()
+            .map(BallotMeasureFinalizationResult::from)
+            .collect())
+    }
+
+    /// Certifies every race in the election with results and returns the races that changed.
+    /// Certified results are no longer changed by results files.
+    #[graphql(guard = "StaffOnly", visible = "is_admin")]
+    async fn certify_election_results(
+        &self,
+        ctx: &Context<'_>,
+        election_id: ID,
+    ) -> Result<Vec<RaceResult>> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let user_id = ctx
+            .data::<Option<TokenData<AccessTokenClaims>>>()?
+            .as_ref()
+            .map(|token| token.claims.sub);
+        let snapshots = RaceResultsSnapshot::certify_election(
+            &db_pool,
+            uuid::Uuid::parse_str(&election_id)?,
+            user_id,
+        )
+        .await?;
+
+        let mut races = Vec::with_capacity(snapshots.len());
+        for snapshot in snapshots {
+            races.push(Race::find_by_id(&db_pool, snapshot.race_id).await?.into());
+        }
+        Ok(races)
+    }
+
+    #[graphql(guard = "StaffOnly", visible = "is_admin")]
+    async fn upsert_election_deadline(
+        &self,
+        ctx: &Context<'_>,
+        input: UpsertElectionDeadlineInput,
+    ) -> Result<ElectionDeadlineResult> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let record = ElectionDeadline::upsert(&db_pool, &input).await?;
+        Ok(ElectionDeadlineResult::from(record))
+    }
+
+    #[graphql(guard = "StaffOnly", visible = "is_admin")]
+    async fn delete_election_deadline(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+    ) -> Result<DeleteElectionResult> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        ElectionDeadline::delete(&db_pool, uuid::Uuid::parse_str(&id)?).await?;
+        Ok(DeleteElectionResult { id })
+    }
 }
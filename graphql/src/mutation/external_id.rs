@@ -0,0 +1,30 @@
+use async_graphql::{Context, Object, Result, ID};
+use db::{ExternalId, UpsertExternalIdInput};
+
+use crate::{context::ApiContext, guard::StaffOnly, is_admin, types::ExternalIdResult};
+
+#[derive(Default)]
+pub struct ExternalIdMutation;
+
+#[Object]
+impl ExternalIdMutation {
+    /// Records an id another system uses for one of our records. If the id was attached to a
+    /// different record it moves to this one.
+    #[graphql(guard = "StaffOnly", visible = "is_admin")]
+    async fn upsert_external_id(
+        &self,
+        ctx: &Context<'_>,
+        input: UpsertExternalIdInput,
+    ) -> Result<ExternalIdResult> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let record = ExternalId::upsert(&db_pool, &input).await?;
+        Ok(record.into())
+    }
+
+    #[graphql(guard = "StaffOnly", visible = "is_admin")]
+    async fn delete_external_id(&self, ctx: &Context<'_>, id: ID) -> Result<bool> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        ExternalId::delete(&db_pool, uuid::Uuid::parse_str(&id)?).await?;
+        Ok(true)
+    }
+}
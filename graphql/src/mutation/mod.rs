@@ -5,6 +5,7 @@ mod bill;
 mod candidate_guide;
 mod election;
 mod embed;
+mod external_id;
 mod issue_tag;
 #[allow(clippy::module_inception)]
 mod mutation;
@@ -14,6 +15,7 @@ mod politician;
 mod poll;
 mod question;
 mod race;
+mod scraper;
 mod user;
 mod voting_guide;
 pub use mutation::*;
@@ -6,6 +6,7 @@ use super::{
     candidate_guide::CandidateGuideMutation,
     election::ElectionMutation,
     embed::EmbedMutation,
+    external_id::ExternalIdMutation,
     issue_tag::IssueTagMutation,
     office::OfficeMutation,
     organization::OrganizationMutation,
@@ -13,6 +14,7 @@ use super::{
     poll::PollMutation,
     question::{QuestionMutation, QuestionSubmissionMutation},
     race::RaceMutation,
+    scraper::ScraperMutation,
     user::UserMutation,
     voting_guide::VotingGuideMutation,
 };
@@ -27,10 +29,12 @@ pub struct Mutation(
     CandidateGuideMutation,
     ElectionMutation,
     EmbedMutation,
+    ExternalIdMutation,
     IssueTagMutation,
     AuthMutation,
     OfficeMutation,
     RaceMutation,
+    ScraperMutation,
     VotingGuideMutation,
     UserMutation,
     PollMutation,
@@ -1,6 +1,11 @@
-use crate::{context::ApiContext, guard::StaffOnly, is_admin, types::OfficeResult};
+use crate::{
+    context::ApiContext,
+    guard::StaffOnly,
+    is_admin,
+    types::{OfficeResult, OfficeTermResult},
+};
 use async_graphql::{Context, Object, Result, SimpleObject};
-use db::{Office, UpsertOfficeInput};
+use db::{Office, OfficeTerm, UpsertOfficeInput, UpsertOfficeTermInput};
 
 #[derive(Default)]
 pub struct OfficeMutation;
@@ -30,4 +35,27 @@ impl OfficeMutation {
         Office::delete(&db_pool, uuid::Uuid::parse_str(&id)?).await?;
         Ok(DeleteOfficeResult { id })
     }
+
+    /// Adds or corrects a term in office, e.g. for an appointment or a term that ended early
+    #[graphql(guard = "StaffOnly", visible = "is_admin")]
+    async fn upsert_office_term(
+        &self,
+        ctx: &Context<'_>,
+        input: UpsertOfficeTermInput,
+    ) -> Result<OfficeTermResult> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let term = OfficeTerm::upsert(&db_pool, &input).await?;
+        Ok(term.into())
+    }
+
+    #[graphql(guard = "StaffOnly", visible = "is_admin")]
+    async fn delete_office_term(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+    ) -> Result<DeleteOfficeResult> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        OfficeTerm::delete(&db_pool, uuid::Uuid::parse_str(&id)?).await?;
+        Ok(DeleteOfficeResult { id })
+    }
 }
@@ -228,6 +228,25 @@ impl PoliticianMutation {
         Ok(DeletePoliticianResult { id })
     }
 
+    /// Folds `merge_id` into `keep_id` and deletes it. Candidacies, endorsements, submissions,
+    /// notes and bill sponsorships move to the kept politician and the old slug redirects to it.
+    #[graphql(guard = "StaffOnly", visible = "is_admin")]
+    async fn merge_politicians(
+        &self,
+        ctx: &Context<'_>,
+        keep_id: ID,
+        merge_id: ID,
+    ) -> Result<PoliticianResult> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let record = Politician::merge(
+            &db_pool,
+            uuid::Uuid::parse_str(&keep_id)?,
+            uuid::Uuid::parse_str(&merge_id)?,
+        )
+        .await?;
+        Ok(PoliticianResult::from(record))
+    }
+
     #[graphql(
         guard = "IntakeTokenGuard::new(&_intake_token, &slug)",
         visible = "is_admin"
@@ -1,6 +1,16 @@
-use crate::{context::ApiContext, guard::StaffOnly, is_admin, types::RaceResult};
-use async_graphql::{Context, Object, Result, SimpleObject};
-use db::{Race, UpsertRaceInput};
+use crate::{
+    context::ApiContext,
+    guard::StaffOnly,
+    is_admin,
+    types::{RaceCandidacyResult, RaceResult},
+};
+use async_graphql::{Context, Object, Result, SimpleObject, ID};
+use auth::AccessTokenClaims;
+use db::{
+    Politician, Race, RaceCandidate, RaceResultsSnapshot, ResultsStatus, UpsertRaceCandidateInput,
+    UpsertRaceInput,
+};
+use jsonwebtoken::TokenData;
 
 #[derive(Default)]
 pub struct RaceMutation;
@@ -26,4 +36,37 @@ impl RaceMutation {
         Race::delete(&db_pool, uuid::Uuid::parse_str(&id)?).await?;
         Ok(DeleteRaceResult { id })
     }
+
+    /// Marks a race's results as canvassed, certified or in a recount. Certified results are no
+    /// longer changed by results files. The change is added to the race's results history.
+    #[graphql(guard = "StaffOnly", visible = "is_admin")]
+    async fn update_race_results_status(
+        &self,
+        ctx: &Context<'_>,
+        race_id: ID,
+        status: ResultsStatus,
+    ) -> Result<RaceResult> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let user_id = ctx
+            .data::<Option<TokenData<AccessTokenClaims>>>()?
+            .as_ref()
+            .map(|token| token.claims.sub);
+        let race_id = uuid::Uuid::parse_str(&race_id)?;
+        RaceResultsSnapshot::set_status(&db_pool, race_id, status, user_id).await?;
+        Ok(Race::find_by_id(&db_pool, race_id).await?.into())
+    }
+
+    /// Updates a candidate's status or filing details in a race. Withdrawing or disqualifying
+    /// a candidate hides them from the race's candidates, scrapers won't put them back.
+    #[graphql(guard = "StaffOnly", visible = "is_admin")]
+    async fn update_race_candidacy(
+        &self,
+        ctx: &Context<'_>,
+        input: UpsertRaceCandidateInput,
+    ) -> Result<RaceCandidacyResult> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let candidacy = RaceCandidate::update(&db_pool, &input).await?;
+        let politician = Politician::find_by_id(&db_pool, candidacy.candidate_id).await?;
+        Ok((candidacy, politician).into())
+    }
 }
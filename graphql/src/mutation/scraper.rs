@@ -0,0 +1,75 @@
+use async_graphql::{Context, Object, Result, ID};
+use auth::AccessTokenClaims;
+use db::ScraperProposedChange;
+use jsonwebtoken::TokenData;
+
+use crate::{context::ApiContext, guard::StaffOnly, is_admin, types::ProposedChangeResult};
+
+#[derive(Default)]
+pub struct ScraperMutation;
+
+fn reviewer_id(ctx: &Context<'_>) -> Option<uuid::Uuid> {
+    ctx.data_unchecked::<Option<TokenData<AccessTokenClaims>>>()
+        .as_ref()
+        .map(|token| token.claims.sub)
+}
+
+fn parse_ids(ids: &[ID]) -> Result<Vec<uuid::Uuid>, uuid::Error> {
+    ids.iter().map(|id| uuid::Uuid::parse_str(id)).collect()
+}
+
+#[Object]
+impl ScraperMutation {
+    /// Applies every pending change from a staged scraper run. Changes that fail to apply stay
+    /// pending with `applyError` set.
+    #[graphql(guard = "StaffOnly", visible = "is_admin")]
+    async fn approve_all_proposed_changes(
+        &self,
+        ctx: &Context<'_>,
+        scraper_run_id: ID,
+    ) -> Result<Vec<ProposedChangeResult>> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let records = ScraperProposedChange::approve_run(
+            &db_pool,
+            uuid::Uuid::parse_str(&scraper_run_id)?,
+            reviewer_id(ctx),
+        )
+        .await?;
+        Ok(records
+            .into_iter()
+            .map(ProposedChangeResult::from)
+            .collect())
+    }
+
+    #[graphql(guard = "StaffOnly", visible = "is_admin")]
+    async fn approve_proposed_changes(
+        &self,
+        ctx: &Context<'_>,
+        ids: Vec<ID>,
+    ) -> Result<Vec<ProposedChangeResult>> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let records =
+            ScraperProposedChange::approve(&db_pool, &parse_ids(&ids)?, reviewer_id(ctx)).await?;
+        Ok(records
+            .into_iter()
+            .map(ProposedChangeResult::from)
+            .collect())
+    }
+
+    #[graphql(guard = "StaffOnly", visible = "is_admin")]
+    async fn reject_proposed_changes(
+        &self,
+        ctx: &Context<'_>,
+        ids: Vec<ID>,
+        reason: Option<String>,
+    ) -> Result<Vec<ProposedChangeResult>> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let records =
+            ScraperProposedChange::reject(&db_pool, &parse_ids(&ids)?, reason, reviewer_id(ctx))
+                .await?;
+        Ok(records
+            .into_iter()
+            .map(ProposedChangeResult::from)
+            .collect())
+    }
+}
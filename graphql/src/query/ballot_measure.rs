@@ -25,19 +25,20 @@ impl BallotMeasureQuery {
         before: Option<String>,
         first: Option<i32>,
         last: Option<i32>,
-    ) -> relay::ConnectionResult<BallotMeasureResult> {
+    ) -> relay::KeysetConnectionResult<BallotMeasureResult> {
         let db_pool = ctx.data::<ApiContext>()?.pool.clone();
-        let records = BallotMeasure::filter(
-            &db_pool,
-            &filter.unwrap_or_default(),
-            &sort.unwrap_or_default(),
-        )
-        .await?;
+        let filter = filter.unwrap_or_default();
+        let sort = sort.unwrap_or_default();
+        let total_count = match ctx.look_ahead().field("totalCount").exists() {
+            true => Some(BallotMeasure::count(&db_pool, &filter).await?),
+            false => None,
+        };
 
-        relay::query(
-            records.into_iter().map(BallotMeasureResult::from),
+        relay::keyset_query(
             relay::Params::new(after, before, first, last),
             10,
+            total_count,
+            |page| async move { BallotMeasure::filter(&db_pool, &filter, &sort, &page).await },
         )
         .await
     }
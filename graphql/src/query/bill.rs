@@ -1,7 +1,7 @@
-use async_graphql::{Context, Object, ID};
+use async_graphql::{Context, Object, Result, ID};
 use db::{
     models::{committee::Committee, enums::State},
-    Bill, BillFilter, BillSort, IssueTag,
+    Bill, BillFilter, BillSort, ExternalIdSource, IssueTag,
 };
 
 use crate::{
@@ -25,21 +25,20 @@ impl BillQuery {
         before: Option<String>,
         first: Option<i32>,
         last: Option<i32>,
-    ) -> relay::ConnectionResult<BillResult> {
+    ) -> relay::KeysetConnectionResult<BillResult> {
         let db_pool = ctx.data::<ApiContext>()?.pool.clone();
-        let records = Bill::filter(
-            &db_pool,
-            &filter.unwrap_or_default(),
-            &sort.unwrap_or_default(),
-        )
-        .await?;
+        let filter = filter.unwrap_or_default();
+        let sort = sort.unwrap_or_default();
+        let total_count = match ctx.look_ahead().field("totalCount").exists() {
+            true => Some(Bill::count(&db_pool, &filter).await?),
+            false => None,
+        };
 
-        println!("records.len() = {:?}", records.len());
-
-        relay::query(
-            records.into_iter().map(BillResult::from),
+        relay::keyset_query(
             relay::Params::new(after, before, first, last),
             10,
+            total_count,
+            |page| async move { Bill::filter(&db_pool, &filter, &sort, &page).await },
         )
         .await
     }
@@ -71,6 +70,18 @@ impl BillQuery {
         Some(BillResult::from(record))
     }
 
+    /// Looks up a bill by an id another system uses for it, e.g. a Legiscan bill id
+    async fn bill_by_external_id(
+        &self,
+        ctx: &Context<'_>,
+        source: ExternalIdSource,
+        value: String,
+    ) -> Result<Option<BillResult>> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let record = Bill::find_by_external_id(&db_pool, source, &value).await?;
+        Ok(record.map(BillResult::from))
+    }
+
     /// Returns all issue tags that have an associated bill
     async fn bill_issue_tags(&self, ctx: &Context<'_>) -> Vec<IssueTagResult> {
         let db_pool = ctx.data::<ApiContext>().unwrap().pool.clone();
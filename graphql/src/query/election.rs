@@ -1,11 +1,19 @@
 use async_graphql::{Context, FieldResult, Object, Result, ID};
 use auth::AccessTokenClaims;
-use db::{models::enums::State, Election, ElectionFilter};
+use db::{
+    finalize_ballot_measure_results, finalize_race_results, models::enums::State, Address,
+    AddressInput, Ballot, Election, ElectionFilter, PollingPlace,
+};
 use jsonwebtoken::TokenData;
 
 use crate::{
     context::ApiContext,
-    types::{ElectionResult, Error},
+    guard::StaffOnly,
+    is_admin,
+    types::{
+        process_address, BallotMeasureFinalizationResult, BallotResult, ElectionResult, Error,
+        PollingPlaceResult, RaceFinalizationResult,
+    },
 };
 
 #[derive(Default)]
@@ -133,4 +141,104 @@ impl ElectionQuery {
         .await?;
         Ok(record.into())
     }
+
+    /// What `finalizeRaceResults` would do to the election's races, without changing anything
+    #[graphql(guard = "StaffOnly", visible = "is_admin")]
+    async fn finalize_race_results_preview(
+        &self,
+        ctx: &Context<'_>,
+        election_id: ID,
+    ) -> Result<Vec<RaceFinalizationResult>> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let records =
+            finalize_race_results(&db_pool, uuid::Uuid::parse_str(&election_id)?, true).await?;
+        Ok(records
+            .into_iter()
+            .map(RaceFinalizationResult::from)
+            .collect())
+    }
+
+    /// What `finalizeBallotMeasureResults` would set each measure's status to
+    #[graphql(guard = "StaffOnly", visible = "is_admin")]
+    async fn finalize_ballot_measure_results_preview(
+        &self,
+        ctx: &Context<'_>,
+        election_id: ID,
+    ) -> Result<Vec<BallotMeasureFinalizationResult>> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let records =
+            finalize_ballot_measure_results(&db_pool, uuid::Uuid::parse_str(&election_id)?, true)
+                .await?;
+        Ok(records
+            .into_iter()
+            .map(BallotMeasureFinalizationResult::from)
+            .collect())
+    }
+
+    /// The races and measures on the ballot at an address, in ballot order and with why each
+    /// race is on it. Pass either a new `address` or the `addressId` of your saved address.
+    async fn ballot(
+        &self,
+        ctx: &Context<'_>,
+        election_id: ID,
+        address: Option<AddressInput>,
+        address_id: Option<ID>,
+    ) -> Result<BallotResult, Error> {
+        let db_pool = ctx.data::<ApiContext>().unwrap().pool.clone();
+        let election_id = uuid::Uuid::parse_str(&election_id)?;
+        let address_id = match (address, address_id) {
+            (Some(address), None) => process_address(&db_pool, address).await?,
+            (None, Some(address_id)) => authorized_address_id(ctx, &db_pool, &address_id).await?,
+            _ => {
+                return Err(Error::BadInput {
+                    field: "address".to_string(),
+                    message: "Provide either an address or an addressId".to_string(),
+                })
+            }
+        };
+
+        let ballot = Ballot::build(&db_pool, &election_id, &address_id).await?;
+        Ok(ballot.into())
+    }
+
+    /// Where the voter at a saved address votes in an election, found through the precinct
+    /// containing the address: their election day polling place and the early voting locations
+    /// open to them, with hours
+    async fn polling_place(
+        &self,
+        ctx: &Context<'_>,
+        address_id: ID,
+        election_id: ID,
+    ) -> Result<PollingPlaceResult, Error> {
+        let db_pool = ctx.data::<ApiContext>().unwrap().pool.clone();
+        let address_id = authorized_address_id(ctx, &db_pool, &address_id).await?;
+        let election_id = uuid::Uuid::parse_str(&election_id)?;
+        let polling_place = PollingPlace::for_address(&db_pool, election_id, address_id).await?;
+        Ok(polling_place.into())
+    }
+}
+
+/// Parses a saved address's id, only staff and the user whose address it is can look it up
+async fn authorized_address_id(
+    ctx: &Context<'_>,
+    db_pool: &sqlx::PgPool,
+    address_id: &ID,
+) -> Result<uuid::Uuid, Error> {
+    let address_id = uuid::Uuid::parse_str(address_id)?;
+    let token = ctx.data::<Option<TokenData<AccessTokenClaims>>>().unwrap();
+    let allowed = match token {
+        Some(token_data) => {
+            matches!(
+                token_data.claims.system_role,
+                db::SystemRoleType::Staff | db::SystemRoleType::Superuser
+            ) || Address::find_by_user_id(db_pool, &token_data.claims.sub)
+                .await?
+                .is_some_and(|a| a.id == address_id)
+        }
+        None => false,
+    };
+    if !allowed {
+        return Err(Error::Unauthorized);
+    }
+    Ok(address_id)
 }
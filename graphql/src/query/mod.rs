@@ -12,6 +12,7 @@ mod politician;
 mod question;
 mod race;
 mod respondent;
+mod scraper;
 mod user;
 mod voting_guide;
 
@@ -1,7 +1,12 @@
 use async_graphql::{Context, FieldResult, Object};
-use db::{models::enums::State, Office, OfficeFilter};
+use db::{models::enums::State, transition_office_holders, ExternalIdSource, Office, OfficeFilter};
 
-use crate::{context::ApiContext, relay, types::OfficeResult};
+use crate::{
+    context::ApiContext,
+    guard::StaffOnly,
+    is_admin, relay,
+    types::{OfficeResult, OfficeTransitionsResult},
+};
 
 #[derive(Default)]
 pub struct OfficeQuery;
@@ -43,6 +48,19 @@ impl OfficeQuery {
         Ok(record.into())
     }
 
+    /// Looks up an office by an id another system uses for it, e.g. an OCD id
+    async fn office_by_external_id(
+        &self,
+        ctx: &Context<'_>,
+        source: ExternalIdSource,
+        value: String,
+    ) -> FieldResult<Option<OfficeResult>> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let record = Office::find_by_external_id(&db_pool, source, &value).await?;
+
+        Ok(record.map(OfficeResult::from))
+    }
+
     async fn counties_by_state(&self, ctx: &Context<'_>, state: State) -> FieldResult<Vec<String>> {
         let db_pool = ctx.data::<ApiContext>()?.pool.clone();
 
@@ -61,4 +79,16 @@ impl OfficeQuery {
 
         Ok(counties)
     }
+    /// Office holder changes the daily transition job has yet to make, including those waiting
+    /// on certification or for their term to begin
+    #[graphql(guard = "StaffOnly", visible = "is_admin")]
+    async fn office_transitions_preview(
+        &self,
+        ctx: &Context<'_>,
+    ) -> FieldResult<OfficeTransitionsResult> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let today = chrono::Local::now().date_naive();
+        let transitions = transition_office_holders(&db_pool, today, true).await?;
+        Ok(transitions.into())
+    }
 }
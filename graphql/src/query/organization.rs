@@ -1,5 +1,5 @@
 use async_graphql::{Context, Object, Result, ID};
-use db::{Organization, OrganizationSearch};
+use db::{ExternalIdSource, Organization, OrganizationSearch};
 
 use crate::context::ApiContext;
 use crate::relay;
@@ -37,6 +37,19 @@ impl OrganizationQuery {
         Ok(record.into())
     }
 
+    /// Looks up an organization by an id another system uses for it, e.g. a Votesmart SIG id
+    async fn organization_by_external_id(
+        &self,
+        ctx: &Context<'_>,
+        source: ExternalIdSource,
+        value: String,
+    ) -> Result<Option<OrganizationResult>> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let record = Organization::find_by_external_id(&db_pool, source, &value).await?;
+
+        Ok(record.map(OrganizationResult::from))
+    }
+
     async fn organization_by_id(&self, ctx: &Context<'_>, id: ID) -> Result<OrganizationResult> {
         let db_pool = ctx.data::<ApiContext>()?.pool.clone();
         let record = Organization::find_by_id(&db_pool, uuid::Uuid::parse_str(&id)?).await?;
@@ -1,13 +1,14 @@
 use crate::{
     context::ApiContext,
-    relay,
-    types::{PoliticalParty, PoliticianResult},
+    guard::StaffOnly,
+    is_admin, relay,
+    types::{PoliticalParty, PoliticianMatchResult, PoliticianResult},
 };
 use async_graphql::{Context, Object, Result, ID};
 use db::{
     loaders::politician::{PoliticianId, PoliticianSlug},
     models::enums::State,
-    Politician, PoliticianFilter,
+    ExternalIdSource, Politician, PoliticianFilter, PoliticianMatchInput,
 };
 
 #[derive(Default, Debug)]
@@ -46,6 +47,37 @@ impl PoliticianQuery {
         Ok(politician.map(PoliticianResult::from))
     }
 
+    /// Looks up a politician by an id another system uses for them, e.g. an FEC candidate id
+    async fn politician_by_external_id(
+        &self,
+        ctx: &Context<'_>,
+        source: ExternalIdSource,
+        value: String,
+    ) -> Result<Option<PoliticianResult>> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let record = Politician::find_by_external_id(&db_pool, source, &value).await?;
+        Ok(record.map(PoliticianResult::from))
+    }
+
+    /// Other politician records that look like the same person, for review before merging
+    #[graphql(guard = "StaffOnly", visible = "is_admin")]
+    async fn politician_matches(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        #[graphql(default = 10)] limit: usize,
+    ) -> Result<Vec<PoliticianMatchResult>> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let politician = Politician::find_by_id(&db_pool, uuid::Uuid::parse_str(&id)?).await?;
+        let matches =
+            Politician::find_matches(&db_pool, &PoliticianMatchInput::from(&politician), limit)
+                .await?;
+        Ok(matches
+            .into_iter()
+            .map(PoliticianMatchResult::from)
+            .collect())
+    }
+
     async fn politician_by_intake_token(
         &self,
         ctx: &Context<'_>,
@@ -60,7 +92,6 @@ impl PoliticianQuery {
         }
     }
 
-    #[allow(clippy::needless_collect)]
     async fn politicians(
         &self,
         ctx: &Context<'_>,
@@ -69,16 +100,19 @@ impl PoliticianQuery {
         before: Option<String>,
         first: Option<i32>,
         last: Option<i32>,
-    ) -> relay::ConnectionResult<PoliticianResult> {
+    ) -> relay::KeysetConnectionResult<PoliticianResult> {
         let db_pool = ctx.data::<ApiContext>()?.pool.clone();
-        let records = Politician::filter(&db_pool, &filter.unwrap_or_default()).await?;
-        let results: Vec<PoliticianResult> =
-            records.into_iter().map(PoliticianResult::from).collect();
+        let filter = filter.unwrap_or_default();
+        let total_count = match ctx.look_ahead().field("totalCount").exists() {
+            true => Some(Politician::count(&db_pool, &filter).await?),
+            false => None,
+        };
 
-        relay::query(
-            results.into_iter(),
+        relay::keyset_query(
             relay::Params::new(after, before, first, last),
             10,
+            total_count,
+            |page| async move { Politician::filter(&db_pool, &filter, &page).await },
         )
         .await
     }
@@ -15,6 +15,7 @@ use super::{
     question::{QuestionQuery, QuestionSubmissionQuery},
     race::RaceQuery,
     respondent::RespondentQuery,
+    scraper::ScraperQuery,
     user::UserQuery,
     voting_guide::VotingGuideQuery,
 };
@@ -45,6 +46,7 @@ pub struct Query(
     PoliticianQuery,
     RaceQuery,
     RespondentQuery,
+    ScraperQuery,
     AuthQuery,
     VotingGuideQuery,
     UserQuery,
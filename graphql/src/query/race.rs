@@ -1,5 +1,5 @@
 use async_graphql::{Context, FieldResult, Object};
-use db::{Race, RaceFilter};
+use db::{ExternalIdSource, Race, RaceFilter};
 
 use crate::{context::ApiContext, relay, types::RaceResult};
 
@@ -42,4 +42,17 @@ impl RaceQuery {
 
         Ok(record.into())
     }
+
+    /// Looks up a race by an id another system uses for it, e.g. a Ballotpedia id
+    async fn race_by_external_id(
+        &self,
+        ctx: &Context<'_>,
+        source: ExternalIdSource,
+        value: String,
+    ) -> FieldResult<Option<RaceResult>> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let record = Race::find_by_external_id(&db_pool, source, &value).await?;
+
+        Ok(record.map(RaceResult::from))
+    }
 }
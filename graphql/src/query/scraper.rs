@@ -0,0 +1,51 @@
+use async_graphql::{Context, Object, Result, ID};
+use db::{ProposedChangeFilter, ScraperProposedChange, ScraperRun};
+
+use crate::{
+    context::ApiContext,
+    guard::StaffOnly,
+    is_admin,
+    types::{ProposedChangeResult, ScraperRunResult},
+};
+
+#[derive(Default)]
+pub struct ScraperQuery;
+
+#[Object]
+impl ScraperQuery {
+    /// Most recent scraper runs, newest first
+    #[graphql(guard = "StaffOnly", visible = "is_admin")]
+    async fn scraper_runs(
+        &self,
+        ctx: &Context<'_>,
+        source_id: Option<String>,
+        is_staged: Option<bool>,
+        #[graphql(default = 25)] limit: i64,
+    ) -> Result<Vec<ScraperRunResult>> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let records = ScraperRun::recent(&db_pool, source_id, is_staged, limit).await?;
+        Ok(records.into_iter().map(ScraperRunResult::from).collect())
+    }
+
+    #[graphql(guard = "StaffOnly", visible = "is_admin")]
+    async fn scraper_run_by_id(&self, ctx: &Context<'_>, id: ID) -> Result<ScraperRunResult> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let record = ScraperRun::find_by_id(&db_pool, uuid::Uuid::parse_str(&id)?).await?;
+        Ok(record.into())
+    }
+
+    /// Changes proposed by staged scraper runs
+    #[graphql(guard = "StaffOnly", visible = "is_admin")]
+    async fn proposed_changes(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<ProposedChangeFilter>,
+    ) -> Result<Vec<ProposedChangeResult>> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let records = ScraperProposedChange::filter(&db_pool, &filter.unwrap_or_default()).await?;
+        Ok(records
+            .into_iter()
+            .map(ProposedChangeResult::from)
+            .collect())
+    }
+}
@@ -1,15 +1,16 @@
 // Credit: https://github.com/vectordotdev/vector/blob/master/src/api/schema/relay.rs
-use std::convert::Infallible;
+use std::{convert::Infallible, future::Future};
 
 use async_graphql::{
     connection::{self, Connection, CursorType, Edge, EmptyFields},
-    Result, SimpleObject,
+    OutputType, Result, SimpleObject,
 };
+use db::{Keyset, Page, PageRequest};
 
 /// Base64 invalid states, used by `Base64Cursor`.
 pub enum Base64CursorError {
     /// Invalid cursor. This can happen if the base64 string is valid, but its contents don't
-    /// conform to the `name:index` pattern.
+    /// conform to the `name:index` or `id:sort_key` pattern.
     Invalid,
     /// Decoding error. If this happens, the string isn't valid base64.
     DecodeError(base64::DecodeError),
@@ -85,6 +86,34 @@ impl CursorType for Base64Cursor {
     }
 }
 
+/// Keyset cursor implementation, encodes the id and sort key of the row it points at
+pub struct KeysetCursor(Keyset);
+
+impl CursorType for KeysetCursor {
+    type Error = Base64CursorError;
+
+    fn decode_cursor(s: &str) -> Result<Self, Self::Error> {
+        let bytes = base64::decode_config(s, base64::URL_SAFE_NO_PAD)
+            .map_err(Base64CursorError::DecodeError)?;
+
+        let cursor = String::from_utf8(bytes).map_err(|_| Base64CursorError::Invalid)?;
+        let (id, sort_key) = cursor.split_once(':').ok_or(Base64CursorError::Invalid)?;
+        let id = uuid::Uuid::parse_str(id).map_err(|_| Base64CursorError::Invalid)?;
+
+        Ok(Self(Keyset {
+            sort_key: sort_key.to_string(),
+            id,
+        }))
+    }
+
+    fn encode_cursor(&self) -> String {
+        base64::encode_config(
+            format!("{}:{}", self.0.id, self.0.sort_key),
+            base64::URL_SAFE_NO_PAD,
+        )
+    }
+}
+
 /// Additional fields to attach to the connection
 #[derive(SimpleObject)]
 pub struct ConnectionFields {
@@ -95,6 +124,17 @@ pub struct ConnectionFields {
 /// Relay connection result
 pub type ConnectionResult<T> = Result<Connection<Base64Cursor, T, ConnectionFields, EmptyFields>>;
 
+/// Additional fields to attach to a keyset connection
+#[derive(SimpleObject)]
+pub struct KeysetConnectionFields {
+    /// Total result set count, only computed when selected
+    total_count: Option<i64>,
+}
+
+/// Relay connection result paged with keyset cursors
+pub type KeysetConnectionResult<T> =
+    Result<Connection<KeysetCursor, T, KeysetConnectionFields, EmptyFields>>;
+
 /// Relay-compliant connection parameters to page results by cursor/page size
 pub struct Params {
     after: Option<String>,
@@ -168,3 +208,73 @@ pub async fn query<T: async_graphql::OutputType, I: ExactSizeIterator<Item = T>>
     )
     .await
 }
+
+/// Creates a new Relay-compliant connection from a page fetched by `fetch`. Paging happens in
+/// the database, so only the rows of the requested page are loaded. `total_count` is computed
+/// separately by the caller and may be omitted when it was not selected.
+pub async fn keyset_query<T, R, F, Fut>(
+    p: Params,
+    default_page_size: i64,
+    total_count: Option<i64>,
+    fetch: F,
+) -> KeysetConnectionResult<T>
+where
+    T: OutputType + From<R>,
+    F: FnOnce(PageRequest) -> Fut,
+    Fut: Future<Output = Result<Page<R>, sqlx::Error>>,
+{
+    connection::query::<
+        _,
+        _,
+        KeysetCursor,
+        T,
+        _,
+        KeysetConnectionFields,
+        _,
+        _,
+        _,
+        async_graphql::Error,
+    >(
+        p.after,
+        p.before,
+        p.first,
+        p.last,
+        |after, before, first, last| async move {
+            let page = fetch(PageRequest {
+                after: after.map(|a| a.0),
+                before: before.map(|b| b.0),
+                first: first.map(|f| f as i64),
+                last: last.map(|l| l as i64),
+                default_page_size,
+            })
+            .await?;
+
+            let mut connection = Connection::with_additional_fields(
+                page.has_previous_page,
+                page.has_next_page,
+                KeysetConnectionFields { total_count },
+            );
+            connection.edges.extend(
+                page.records
+                    .into_iter()
+                    .map(|(keyset, record)| Edge::new(KeysetCursor(keyset), T::from(record))),
+            );
+            Ok(connection)
+        },
+    )
+    .await
+}
+
+#[test]
+fn keyset_cursor_round_trip_test() {
+    let cursor = KeysetCursor(Keyset {
+        sort_key: "Van Buren: Jr".to_string(),
+        id: uuid::Uuid::new_v4(),
+    });
+    let decoded = KeysetCursor::decode_cursor(&cursor.encode_cursor())
+        .ok()
+        .unwrap();
+    assert_eq!(decoded.0, cursor.0);
+
+    assert!(KeysetCursor::decode_cursor("not a cursor").is_err());
+}
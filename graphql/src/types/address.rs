@@ -1,5 +1,5 @@
 use async_graphql::SimpleObject;
-use db::{models::enums::State, Address, AddressExtendedMN};
+use db::{models::enums::State, Address, AddressExtendedMN, DistrictBoundary, DistrictType};
 
 #[derive(SimpleObject, Debug, Clone)]
 pub struct AddressResult {
@@ -56,3 +56,28 @@ impl From<AddressExtendedMN> for AddressExtendedMNResult {
         }
     }
 }
+
+#[derive(SimpleObject, Debug, Clone)]
+pub struct DistrictResult {
+    district_type: DistrictType,
+    code: String,
+    name: Option<String>,
+    county: Option<String>,
+    municipality: Option<String>,
+    vintage: i32,
+    geoid: Option<String>,
+}
+
+impl From<DistrictBoundary> for DistrictResult {
+    fn from(boundary: DistrictBoundary) -> Self {
+        Self {
+            district_type: boundary.district_type,
+            code: boundary.code,
+            name: boundary.name,
+            county: boundary.county,
+            municipality: boundary.municipality,
+            vintage: boundary.vintage,
+            geoid: boundary.geoid,
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use async_graphql::{SimpleObject, ID};
+use db::{
+    Ballot, BallotDistrict, BallotMatchReason, BallotRace, BallotSection, BallotSectionEntries,
+    DistrictType,
+};
+
+use super::{BallotMeasureResult, ElectionResult, OfficeResult, RaceResult};
+
+/// A voter's ballot, sectioned federal, state, county, city, school, judicial then measures
+#[derive(SimpleObject, Debug, Clone)]
+pub struct BallotResult {
+    election: ElectionResult,
+    address_id: ID,
+    sections: Vec<BallotSectionResult>,
+}
+
+#[derive(SimpleObject, Debug, Clone)]
+pub struct BallotSectionResult {
+    section: BallotSection,
+    races: Vec<BallotRaceResult>,
+    ballot_measures: Vec<BallotMeasureResult>,
+}
+
+#[derive(SimpleObject, Debug, Clone)]
+pub struct BallotRaceResult {
+    race: RaceResult,
+    office: OfficeResult,
+    /// Why the race is on this ballot
+    reason: BallotMatchReason,
+    /// e.g. "Address is in State House District 59B (2022 map)"
+    explanation: String,
+    /// The district the address matched, for district races
+    district: Option<BallotDistrictResult>,
+}
+
+#[derive(SimpleObject, Debug, Clone)]
+pub struct BallotDistrictResult {
+    district_type: DistrictType,
+    code: String,
+    name: Option<String>,
+    /// Year of the boundary file the district came from
+    vintage: Option<i32>,
+}
+
+impl From<Ballot> for BallotResult {
+    fn from(ballot: Ballot) -> Self {
+        Self {
+            election: ballot.election.into(),
+            address_id: ballot.address_id.into(),
+            sections: ballot.sections.into_iter().map(|s| s.into()).collect(),
+        }
+    }
+}
+
+impl From<BallotSectionEntries> for BallotSectionResult {
+    fn from(entries: BallotSectionEntries) -> Self {
+        Self {
+            section: entries.section,
+            races: entries.races.into_iter().map(|r| r.into()).collect(),
+            ballot_measures: entries
+                .ballot_measures
+                .into_iter()
+                .map(BallotMeasureResult::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<BallotRace> for BallotRaceResult {
+    fn from(ballot_race: BallotRace) -> Self {
+        Self {
+            race: ballot_race.race.into(),
+            office: ballot_race.office.into(),
+            reason: ballot_race.ballot_match.reason,
+            explanation: ballot_race.ballot_match.explanation,
+            district: ballot_race.ballot_match.district.map(|d| d.into()),
+        }
+    }
+}
+
+impl From<BallotDistrict> for BallotDistrictResult {
+    fn from(district: BallotDistrict) -> Self {
+        Self {
+            district_type: district.district_type,
+            code: district.code,
+            name: district.name,
+            vintage: district.vintage,
+        }
+    }
+}
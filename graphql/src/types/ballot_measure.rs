@@ -2,7 +2,7 @@ use async_graphql::{ComplexObject, Context, Result, SimpleObject, ID};
 use db::{
     models::{
         ballot_measure::BallotMeasure,
-        enums::{BallotMeasureStatus, State},
+        enums::{BallotMeasureStatus, PassageThreshold, State},
     },
     Election, PublicVotes,
 };
@@ -24,6 +24,7 @@ pub struct BallotMeasureResult {
     ballot_measure_code: String,
     measure_type: String,
     definitions: String,
+    passage_threshold: PassageThreshold,
     description: Option<String>,
     official_summary: Option<String>,
     populist_summary: Option<String>,
@@ -95,6 +96,7 @@ impl From<BallotMeasure> for BallotMeasureResult {
             ballot_measure_code: b.ballot_measure_code,
             measure_type: b.measure_type,
             definitions: b.definitions,
+            passage_threshold: b.passage_threshold,
             description: b.description,
             official_summary: b.official_summary,
             populist_summary: b.populist_summary,
@@ -55,6 +55,11 @@ pub struct SessionResult {
 
 #[ComplexObject]
 impl BillResult {
+    /// Current slug, differs from the requested one when an old slug was resolved
+    async fn canonical_slug(&self) -> String {
+        self.slug.clone()
+    }
+
     async fn arguments(&self, ctx: &Context<'_>) -> Result<Vec<ArgumentResult>> {
         let db_pool = ctx.data::<ApiContext>()?.pool.clone();
         let records = Bill::arguments(&db_pool, uuid::Uuid::parse_str(&self.id).unwrap()).await?;
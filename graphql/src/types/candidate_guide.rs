@@ -1,9 +1,12 @@
 use async_graphql::{ComplexObject, Context, Result, SimpleObject, ID};
-use db::{models::candidate_guide::CandidateGuide, Embed, EmbedType, Question};
+use auth::PrintKind;
+use db::{
+    models::candidate_guide::CandidateGuide, Embed, EmbedType, OrganizationRoleType, Question,
+};
 
-use crate::context::ApiContext;
+use crate::{context::ApiContext, guard::OrganizationGuard};
 
-use super::{EmbedResult, OrganizationResult, QuestionResult, RaceResult};
+use super::{EmbedResult, Error, OrganizationResult, PrintLinksResult, QuestionResult, RaceResult};
 
 #[derive(SimpleObject)]
 #[graphql(complex)]
@@ -137,6 +140,37 @@ impl CandidateGuideResult {
         };
         Ok(count)
     }
+
+    /// Links to the guide's answers for a race printed with the organization's branding, to
+    /// share by email or text
+    #[graphql(
+        guard = "OrganizationGuard::new(&self.organization_id, &OrganizationRoleType::ReadOnly)"
+    )]
+    async fn print_links(&self, ctx: &Context<'_>, race_id: ID) -> Result<PrintLinksResult> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let candidate_guide_id = uuid::Uuid::parse_str(self.id.as_str())?;
+        let race_id = uuid::Uuid::parse_str(race_id.as_str())?;
+        let in_guide = sqlx::query!(
+            "SELECT race_id FROM candidate_guide_races WHERE candidate_guide_id = $1 AND race_id = $2",
+            candidate_guide_id,
+            race_id,
+        )
+        .fetch_optional(&db_pool)
+        .await?
+        .is_some();
+        if !in_guide {
+            return Err(Error::BadInput {
+                field: "raceId".to_string(),
+                message: "This race is not part of the candidate guide".to_string(),
+            }
+            .into());
+        }
+        Ok(PrintLinksResult::new(
+            PrintKind::CandidateGuide,
+            candidate_guide_id,
+            Some(race_id),
+        )?)
+    }
 }
 
 impl From<CandidateGuide> for CandidateGuideResult {
@@ -0,0 +1,44 @@
+use async_graphql::{SimpleObject, ID};
+use db::{DeadlineType, ElectionDeadline, ElectionScope, State};
+
+#[derive(SimpleObject, Debug, Clone)]
+pub struct ElectionDeadlineResult {
+    id: ID,
+    election_id: ID,
+    deadline_type: DeadlineType,
+    /// Name of the deadline for display, e.g. "Absentee ballot request deadline"
+    label: String,
+    date: chrono::NaiveDate,
+    /// Empty when the deadline applies everywhere the election is held
+    state: Option<State>,
+    election_scope: ElectionScope,
+    county: Option<String>,
+    municipality: Option<String>,
+    description: Option<String>,
+}
+
+impl From<ElectionDeadline> for ElectionDeadlineResult {
+    fn from(d: ElectionDeadline) -> Self {
+        Self {
+            id: ID::from(d.id),
+            election_id: ID::from(d.election_id),
+            deadline_type: d.deadline_type,
+            label: d.deadline_type.label().to_string(),
+            date: d.date,
+            state: d.state,
+            election_scope: d.election_scope,
+            county: d.county,
+            municipality: d.municipality,
+            description: d.description,
+        }
+    }
+}
+
+/// Link to an iCalendar feed served at `path` under `/calendar/`
+pub fn calendar_url(path: &str) -> String {
+    config::Config::default()
+        .api_url
+        .join(&format!("calendar/{}", path))
+        .expect("calendar path is a valid URL")
+        .to_string()
+}
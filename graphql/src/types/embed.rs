@@ -107,9 +107,13 @@ impl EmbedResult {
         let bill_id = self.attributes["billId"].as_str();
         if let Some(bill_id) = bill_id {
             let bill_id = uuid::Uuid::parse_str(bill_id)?;
-            let db_pool = ctx.data::<ApiContext>()?.pool.clone();
-            let record = db::Bill::find_by_id(&db_pool, bill_id).await?;
-            Ok(Some(record.into()))
+            let record = ctx
+                .data::<ApiContext>()?
+                .loaders
+                .bill_loader
+                .load_one(bill_id)
+                .await?;
+            Ok(record.map(|r| r.into()))
         } else {
             Ok(None)
         }
@@ -213,9 +217,13 @@ impl EmbedResult {
         let question_id = self.attributes["questionId"].as_str();
         if let Some(question_id) = question_id {
             let question_id = uuid::Uuid::parse_str(question_id)?;
-            let db_pool = ctx.data::<ApiContext>()?.pool.clone();
-            let record = db::Question::find_by_id(&db_pool, question_id).await?;
-            Ok(Some(record.into()))
+            let record = ctx
+                .data::<ApiContext>()?
+                .loaders
+                .question_loader
+                .load_one(question_id)
+                .await?;
+            Ok(record.map(|r| r.into()))
         } else {
             Ok(None)
         }
@@ -225,9 +233,13 @@ impl EmbedResult {
         let poll_id = self.attributes["pollId"].as_str();
         if let Some(poll_id) = poll_id {
             let poll_id = uuid::Uuid::parse_str(poll_id)?;
-            let db_pool = ctx.data::<ApiContext>()?.pool.clone();
-            let record = db::Poll::find_by_id(&db_pool, poll_id).await?;
-            Ok(Some(record.into()))
+            let record = ctx
+                .data::<ApiContext>()?
+                .loaders
+                .poll_loader
+                .load_one(poll_id)
+                .await?;
+            Ok(record.map(|r| r.into()))
         } else {
             Ok(None)
         }
@@ -237,9 +249,13 @@ impl EmbedResult {
         let candidate_guide_id = self.attributes["candidateGuideId"].as_str();
         if let Some(candidate_guide_id) = candidate_guide_id {
             let candidate_guide_id = uuid::Uuid::parse_str(candidate_guide_id)?;
-            let db_pool = ctx.data::<ApiContext>()?.pool.clone();
-            let record = CandidateGuide::find_by_id(&db_pool, candidate_guide_id).await?;
-            Ok(Some(record.into()))
+            let record = ctx
+                .data::<ApiContext>()?
+                .loaders
+                .candidate_guide_loader
+                .load_one(candidate_guide_id)
+                .await?;
+            Ok(record.map(|r| r.into()))
         } else {
             Ok(None)
         }
@@ -249,9 +265,13 @@ impl EmbedResult {
         let election_id = self.attributes["electionId"].as_str();
         if let Some(election_id) = election_id {
             let election_id = uuid::Uuid::parse_str(election_id)?;
-            let db_pool = ctx.data::<ApiContext>()?.pool.clone();
-            let record = db::Election::find_by_id(&db_pool, election_id).await?;
-            Ok(Some(record.into()))
+            let record = ctx
+                .data::<ApiContext>()?
+                .loaders
+                .election_loader
+                .load_one(election_id)
+                .await?;
+            Ok(record.map(|r| r.into()))
         } else {
             Ok(None)
         }
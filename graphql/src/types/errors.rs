@@ -25,7 +25,7 @@ pub enum Error {
     VarError(#[from] std::env::VarError),
 
     #[error(transparent)]
-    GeocodioError(#[from] geocodio::Error),
+    GeocoderError(#[from] db::geocoder::GeocoderError),
 
     #[error("A user already exists with this email")]
     UserExistsError,
@@ -0,0 +1,23 @@
+use async_graphql::{SimpleObject, ID};
+use db::{ExternalId, ExternalIdEntityType, ExternalIdSource};
+
+#[derive(SimpleObject, Debug, Clone)]
+pub struct ExternalIdResult {
+    id: ID,
+    entity_type: ExternalIdEntityType,
+    entity_id: ID,
+    source: ExternalIdSource,
+    value: String,
+}
+
+impl From<ExternalId> for ExternalIdResult {
+    fn from(e: ExternalId) -> Self {
+        Self {
+            id: ID::from(e.id),
+            entity_type: e.entity_type,
+            entity_id: ID::from(e.entity_id),
+            source: e.source,
+            value: e.value,
+        }
+    }
+}
@@ -1,47 +1,72 @@
 mod address;
 mod argument;
 mod auth;
+mod ballot;
 mod ballot_measure;
 mod bill;
 mod candidate_guide;
 mod committee;
 mod election;
+mod election_deadline;
 mod embed;
 mod errors;
+mod external_id;
 mod health;
 mod issue_tag;
 mod office;
+mod office_term;
 mod organization;
 mod organization_politician_note;
 mod party;
 mod politician;
 mod poll;
+mod polling_location;
+mod precinct;
+mod print;
 mod question;
 mod race;
+mod race_finalization;
+mod race_results_snapshot;
+mod ranked_choice;
+mod scraper;
 mod upload;
 mod user;
 mod votesmart;
 mod voting_guide;
 
 pub use self::auth::{AuthTokenResult, CreateUserResult, LoginResult};
-pub use address::{AddressExtendedMNResult, AddressResult};
+pub use address::{AddressExtendedMNResult, AddressResult, DistrictResult};
 pub use argument::ArgumentResult;
+pub use ballot::{BallotDistrictResult, BallotRaceResult, BallotResult, BallotSectionResult};
 pub use ballot_measure::BallotMeasureResult;
 pub use bill::BillResult;
 pub use candidate_guide::*;
 pub use committee::CommitteeResult;
-pub use election::ElectionResult;
+pub use election::{process_address, ElectionResult};
+pub use election_deadline::{calendar_url, ElectionDeadlineResult};
 pub use embed::*;
 pub use errors::Error;
+pub use external_id::ExternalIdResult;
 pub use health::Heartbeat;
 pub use issue_tag::IssueTagResult;
 pub use office::OfficeResult;
+pub use office_term::{
+    ElectoralHistoryRaceResult, ElectoralHistoryResult, OfficeHistoryResult, OfficeTermResult,
+    OfficeTransitionResult, OfficeTransitionsResult,
+};
 pub use organization::OrganizationResult;
 pub use party::*;
-pub use politician::PoliticianResult;
+pub use politician::{PoliticianMatchResult, PoliticianResult};
 pub use poll::*;
+pub use polling_location::{PollingLocationHoursResult, PollingLocationResult, PollingPlaceResult};
+pub use precinct::{PrecinctCandidateResult, PrecinctResultResult};
+pub use print::PrintLinksResult;
 pub use question::*;
-pub use race::RaceResult;
+pub use race::{RaceCandidacyResult, RaceResult};
+pub use race_finalization::{BallotMeasureFinalizationResult, RaceFinalizationResult};
+pub use race_results_snapshot::{RaceResultsSnapshotCandidateResult, RaceResultsSnapshotResult};
+pub use ranked_choice::{RankedChoiceRoundResult, RankedChoiceTallyResult};
+pub use scraper::*;
 pub use upload::FileInfo;
 pub use user::UserResult;
 pub use voting_guide::{
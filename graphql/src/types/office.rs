@@ -1,4 +1,7 @@
-use crate::{context::ApiContext, types::PoliticianResult};
+use crate::{
+    context::ApiContext,
+    types::{OfficeHistoryResult, PoliticianResult},
+};
 use async_graphql::{ComplexObject, Context, Result, SimpleObject, ID};
 use db::{
     loaders::politician::OfficeId,
@@ -6,7 +9,7 @@ use db::{
         enums::{FullState, PoliticalScope, State},
         office::Office,
     },
-    Chamber, DistrictType, ElectionScope, Politician,
+    Chamber, DistrictType, ElectionScope, OfficeTerm, Politician, Race,
 };
 
 #[derive(SimpleObject, Debug, Clone)]
@@ -25,6 +28,8 @@ pub struct OfficeResult {
     district: Option<String>,
     /// The type of district, used to determine which field is referenced for the district
     district_type: Option<DistrictType>,
+    /// Year of the district map the office is elected from, if it isn't the one in force
+    district_vintage: Option<i32>,
     hospital_district: Option<String>,
     school_district: Option<String>,
     /// Local, State, or Federal
@@ -148,6 +153,11 @@ fn compute_office_subtitle(office: &Office, use_short: bool) -> Option<String> {
 
 #[ComplexObject]
 impl OfficeResult {
+    /// Current slug, differs from the requested one when an old slug was resolved
+    async fn canonical_slug(&self) -> String {
+        self.slug.clone()
+    }
+
     async fn incumbents(&self, ctx: &Context<'_>) -> Result<Vec<PoliticianResult>> {
         let politicians = ctx
             .data::<ApiContext>()?
@@ -165,6 +175,15 @@ impl OfficeResult {
 
         Ok(politician_results)
     }
+
+    /// Past races for the office with their results, and everyone who has held it
+    async fn history(&self, ctx: &Context<'_>) -> Result<OfficeHistoryResult> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let office_id = uuid::Uuid::parse_str(&self.id)?;
+        let races = Race::find_past_by_office_id(&db_pool, office_id).await?;
+        let terms = OfficeTerm::find_by_office_id(&db_pool, office_id).await?;
+        Ok(OfficeHistoryResult::new(races, terms))
+    }
 }
 
 impl From<Office> for OfficeResult {
@@ -191,6 +210,7 @@ impl From<Office> for OfficeResult {
             office_type: o.office_type,
             district: o.district,
             district_type: o.district_type,
+            district_vintage: o.district_vintage,
             hospital_district: o.hospital_district,
             school_district: o.school_district,
             chamber: o.chamber,
@@ -223,6 +243,7 @@ mod tests {
             office_type: None,
             district: Some("1".to_string()),
             district_type: Some(DistrictType::StateSenate),
+            district_vintage: None,
             hospital_district: None,
             school_district: None,
             chamber: Some(Chamber::Senate),
@@ -257,6 +278,7 @@ mod tests {
             office_type: None,
             district: Some("Ward 3".to_string()),
             district_type: Some(DistrictType::City),
+            district_vintage: None,
             hospital_district: None,
             school_district: None,
             chamber: Some(Chamber::Senate),
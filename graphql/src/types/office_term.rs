@@ -0,0 +1,285 @@
+use crate::context::ApiContext;
+use async_graphql::{ComplexObject, Context, Result, SimpleObject, ID};
+use chrono::NaiveDate;
+use db::{
+    loaders::politician::PoliticianId, models::enums::RaceType, CandidacyStatus, DateTime,
+    ElectoralHistory, ElectoralHistoryRace, EndedOfficeTerm, OfficeTerm, OfficeTermSource,
+    OfficeTransition, OfficeTransitions,
+};
+
+use super::{race_finalization::load_politicians, OfficeResult, PoliticianResult, RaceResult};
+
+#[derive(SimpleObject, Debug, Clone)]
+#[graphql(complex)]
+pub struct OfficeTermResult {
+    id: ID,
+    office_id: ID,
+    politician_id: ID,
+    /// The race the term was won in
+    race_id: Option<ID>,
+    obtained_by: OfficeTermSource,
+    /// Empty when we don't know when the term began
+    start_date: Option<NaiveDate>,
+    /// The day the term ends, or ended if it ended early
+    end_date: Option<NaiveDate>,
+    notes: Option<String>,
+    /// When the holder was made the office's incumbent
+    took_office_at: Option<DateTime>,
+    /// For terms without a race, the term that ends when this one begins
+    replaces_term_id: Option<ID>,
+    is_current: bool,
+}
+
+#[ComplexObject]
+impl OfficeTermResult {
+    async fn politician(&self, ctx: &Context<'_>) -> Result<Option<PoliticianResult>> {
+        let politician = ctx
+            .data::<ApiContext>()?
+            .loaders
+            .politician_loader
+            .load_one(PoliticianId(uuid::Uuid::parse_str(&self.politician_id)?))
+            .await?;
+        Ok(politician.map(PoliticianResult::from))
+    }
+
+    async fn office(&self, ctx: &Context<'_>) -> Result<Option<OfficeResult>> {
+        let office = ctx
+            .data::<ApiContext>()?
+            .loaders
+            .office_loader
+            .load_one(uuid::Uuid::parse_str(&self.office_id)?)
+            .await?;
+        Ok(office.map(OfficeResult::from))
+    }
+}
+
+impl From<OfficeTerm> for OfficeTermResult {
+    fn from(t: OfficeTerm) -> Self {
+        Self {
+            id: ID::from(t.id),
+            office_id: ID::from(t.office_id),
+            politician_id: ID::from(t.politician_id),
+            race_id: t.race_id.map(ID::from),
+            obtained_by: t.obtained_by,
+            is_current: t.is_current(chrono::Local::now().date_naive()),
+            start_date: t.start_date,
+            end_date: t.end_date,
+            notes: t.notes,
+            took_office_at: t.took_office_at,
+            replaces_term_id: t.replaces_term_id.map(ID::from),
+        }
+    }
+}
+
+#[derive(SimpleObject, Debug, Clone)]
+pub struct OfficeHistoryResult {
+    /// Races for the office that have been held, latest first
+    races: Vec<RaceResult>,
+    /// Everyone who has held the office, latest term first
+    terms: Vec<OfficeTermResult>,
+}
+
+impl OfficeHistoryResult {
+    pub fn new(races: Vec<db::Race>, terms: Vec<OfficeTerm>) -> Self {
+        Self {
+            races: races.into_iter().map(RaceResult::from).collect(),
+            terms: terms.into_iter().map(OfficeTermResult::from).collect(),
+        }
+    }
+}
+
+#[derive(SimpleObject, Debug, Clone)]
+pub struct ElectoralHistoryResult {
+    /// Races won, counting primaries, from finalized results
+    race_wins: i32,
+    race_losses: i32,
+    /// Whole years in office, from the terms with known start dates. Null when no term has a
+    /// known start.
+    years_in_office: Option<i32>,
+    /// Latest first
+    races: Vec<ElectoralHistoryRaceResult>,
+    /// Latest first
+    terms: Vec<OfficeTermResult>,
+}
+
+#[derive(SimpleObject, Debug, Clone)]
+#[graphql(complex)]
+pub struct ElectoralHistoryRaceResult {
+    race_id: ID,
+    race_title: String,
+    race_type: RaceType,
+    office_id: ID,
+    election_date: Option<NaiveDate>,
+    is_special_election: bool,
+    status: CandidacyStatus,
+    votes: Option<i32>,
+    vote_percentage: Option<f64>,
+    /// Empty until the race is finalized
+    is_winner: Option<bool>,
+}
+
+#[ComplexObject]
+impl ElectoralHistoryRaceResult {
+    async fn race(&self, ctx: &Context<'_>) -> Result<Option<RaceResult>> {
+        let race = ctx
+            .data::<ApiContext>()?
+            .loaders
+            .race_loader
+            .load_one(uuid::Uuid::parse_str(&self.race_id)?)
+            .await?;
+        Ok(race.map(RaceResult::from))
+    }
+}
+
+impl From<ElectoralHistoryRace> for ElectoralHistoryRaceResult {
+    fn from(r: ElectoralHistoryRace) -> Self {
+        let vote_percentage = match (r.votes, r.total_votes) {
+            (Some(votes), Some(total_votes)) if total_votes > 0 => {
+                Some((votes as f64 / total_votes as f64 * 1000.0).round() / 10.0)
+            }
+            _ => None,
+        };
+        Self {
+            race_id: ID::from(r.race_id),
+            race_title: r.race_title,
+            race_type: r.race_type,
+            office_id: ID::from(r.office_id),
+            election_date: r.election_date,
+            is_special_election: r.is_special_election,
+            status: r.status,
+            votes: r.votes,
+            vote_percentage,
+            is_winner: r.is_winner,
+        }
+    }
+}
+
+impl From<ElectoralHistory> for ElectoralHistoryResult {
+    fn from(h: ElectoralHistory) -> Self {
+        Self {
+            race_wins: h.race_wins(),
+            race_losses: h.race_losses(),
+            years_in_office: h.years_in_office(chrono::Local::now().date_naive()),
+            races: h
+                .races
+                .into_iter()
+                .map(ElectoralHistoryRaceResult::from)
+                .collect(),
+            terms: h.terms.into_iter().map(OfficeTermResult::from).collect(),
+        }
+    }
+}
+
+#[derive(SimpleObject, Debug, Clone)]
+pub struct OfficeTransitionsResult {
+    /// Pending transitions by start date, including those that aren't due yet
+    transitions: Vec<OfficeTransitionResult>,
+    /// Holders who would be removed because their terms ran out without a transition
+    /// replacing them, e.g. board members who didn't run again
+    ended_terms: Vec<EndedOfficeTermResult>,
+    /// Candidates whose upcoming race would be cleared because it has been certified
+    finished_race_candidates: i32,
+}
+
+#[derive(SimpleObject, Debug, Clone)]
+#[graphql(complex)]
+pub struct OfficeTransitionResult {
+    office_term_id: ID,
+    office_id: ID,
+    office_title: String,
+    politician_id: ID,
+    race_id: Option<ID>,
+    start_date: NaiveDate,
+    /// Holders whose terms end when this one begins
+    outgoing_politician_ids: Vec<ID>,
+    /// Why the transition can't happen yet, e.g. the race isn't certified
+    waiting_reason: Option<String>,
+    /// Whether the next run would make the transition
+    is_due: bool,
+}
+
+#[ComplexObject]
+impl OfficeTransitionResult {
+    async fn politician(&self, ctx: &Context<'_>) -> Result<Option<PoliticianResult>> {
+        Ok(
+            load_politicians(ctx, std::slice::from_ref(&self.politician_id))
+                .await?
+                .pop(),
+        )
+    }
+
+    async fn outgoing_politicians(&self, ctx: &Context<'_>) -> Result<Vec<PoliticianResult>> {
+        load_politicians(ctx, &self.outgoing_politician_ids).await
+    }
+}
+
+impl From<OfficeTransition> for OfficeTransitionResult {
+    fn from(t: OfficeTransition) -> Self {
+        Self {
+            office_term_id: ID::from(t.office_term_id),
+            office_id: ID::from(t.office_id),
+            office_title: t.office_title,
+            politician_id: ID::from(t.politician_id),
+            race_id: t.race_id.map(ID::from),
+            start_date: t.start_date,
+            outgoing_politician_ids: t
+                .outgoing_politician_ids
+                .into_iter()
+                .map(ID::from)
+                .collect(),
+            waiting_reason: t.waiting_reason,
+            is_due: t.is_applied,
+        }
+    }
+}
+
+#[derive(SimpleObject, Debug, Clone)]
+#[graphql(complex)]
+pub struct EndedOfficeTermResult {
+    office_term_id: ID,
+    office_id: ID,
+    office_title: String,
+    politician_id: ID,
+    end_date: NaiveDate,
+}
+
+#[ComplexObject]
+impl EndedOfficeTermResult {
+    async fn politician(&self, ctx: &Context<'_>) -> Result<Option<PoliticianResult>> {
+        Ok(
+            load_politicians(ctx, std::slice::from_ref(&self.politician_id))
+                .await?
+                .pop(),
+        )
+    }
+}
+
+impl From<EndedOfficeTerm> for EndedOfficeTermResult {
+    fn from(t: EndedOfficeTerm) -> Self {
+        Self {
+            office_term_id: ID::from(t.office_term_id),
+            office_id: ID::from(t.office_id),
+            office_title: t.office_title,
+            politician_id: ID::from(t.politician_id),
+            end_date: t.end_date,
+        }
+    }
+}
+
+impl From<OfficeTransitions> for OfficeTransitionsResult {
+    fn from(t: OfficeTransitions) -> Self {
+        Self {
+            transitions: t
+                .transitions
+                .into_iter()
+                .map(OfficeTransitionResult::from)
+                .collect(),
+            ended_terms: t
+                .ended_terms
+                .into_iter()
+                .map(EndedOfficeTermResult::from)
+                .collect(),
+            finished_race_candidates: t.finished_race_candidates as i32,
+        }
+    }
+}
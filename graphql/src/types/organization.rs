@@ -64,6 +64,11 @@ pub struct PendingInviteResult {
 
 #[ComplexObject]
 impl OrganizationResult {
+    /// Current slug, differs from the requested one when an old slug was resolved
+    async fn canonical_slug(&self) -> String {
+        self.slug.clone()
+    }
+
     async fn issue_tags(&self, ctx: &Context<'_>) -> FieldResult<Vec<IssueTagResult>> {
         let db_pool = ctx.data::<ApiContext>()?.pool.clone();
         let records =
@@ -1,4 +1,5 @@
 use async_graphql::{SimpleObject, ID};
+use db::Party;
 
 #[derive(Clone, SimpleObject, Debug)]
 pub struct PoliticalParty {
@@ -9,3 +10,16 @@ pub struct PoliticalParty {
     pub description: Option<String>,
     pub notes: Option<String>,
 }
+
+impl From<Party> for PoliticalParty {
+    fn from(p: Party) -> Self {
+        Self {
+            id: ID::from(p.id),
+            slug: p.slug,
+            fec_code: p.fec_code,
+            name: p.name,
+            description: p.description,
+            notes: p.notes,
+        }
+    }
+}
@@ -1,15 +1,15 @@
 use super::{
-    party::PoliticalParty, votesmart::VsRating, BillResult, IssueTagResult, OfficeResult,
-    OrganizationResult, RaceResult,
+    party::PoliticalParty, votesmart::VsRating, BillResult, ElectoralHistoryResult,
+    ExternalIdResult, IssueTagResult, OfficeResult, OrganizationResult, RaceResult,
 };
-use crate::{context::ApiContext, relay};
+use crate::{context::ApiContext, is_admin, relay};
 use async_graphql::{ComplexObject, Context, Enum, Result, SimpleObject, ID};
 use db::{
     models::{
         enums::{BillStatus, PoliticalScope, State},
         politician::Politician,
     },
-    Bill, Chamber,
+    Bill, Chamber, ElectoralHistory, ExternalId, ExternalIdEntityType, PoliticianMatch,
 };
 use open_secrets::OpenSecretsProxy;
 use serde::{Deserialize, Serialize};
@@ -60,7 +60,9 @@ pub struct PoliticianResult {
     votesmart_candidate_id: Option<i32>,
     votesmart_candidate_bio: Option<GetCandidateBioResponse>,
     votesmart_candidate_ratings: Vec<VsRating>,
+    #[graphql(deprecation = "Use `electoralHistory.raceWins` instead")]
     race_wins: Option<i32>,
+    #[graphql(deprecation = "Use `electoralHistory.raceLosses` instead")]
     race_losses: Option<i32>,
 }
 
@@ -264,6 +266,12 @@ async fn fetch_donations_by_industry(crp_id: String) -> Result<DonationsByIndust
 
 #[ComplexObject]
 impl PoliticianResult {
+    /// Current slug. When `politicianBySlug` resolved an old slug this differs from the one
+    /// requested and clients should redirect to it.
+    async fn canonical_slug(&self) -> String {
+        self.slug.clone()
+    }
+
     async fn age(&self) -> Option<i64> {
         match self.date_of_birth {
             Some(dob) => calculate_age(dob).ok(),
@@ -272,36 +280,18 @@ impl PoliticianResult {
     }
 
     async fn party(&self, ctx: &Context<'_>) -> Result<Option<PoliticalParty>> {
-        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
         let party = match &self.party_id {
             Some(party_id) => {
-                let record = sqlx::query!(
-                    r#"
-                    SELECT *
-                    FROM party
-                    WHERE id = $1
-                "#,
-                    uuid::Uuid::parse_str(&party_id.to_string()).unwrap()
-                )
-                .fetch_optional(&db_pool)
-                .await?;
-
-                match record {
-                    Some(record) => Some(PoliticalParty {
-                        id: ID::from(record.id),
-                        slug: record.slug,
-                        fec_code: record.fec_code,
-                        name: record.name,
-                        description: record.description,
-                        notes: record.notes,
-                    }),
-                    None => None,
-                }
+                ctx.data::<ApiContext>()?
+                    .loaders
+                    .party_loader
+                    .load_one(uuid::Uuid::parse_str(party_id)?)
+                    .await?
             }
             None => None,
         };
 
-        Ok(party)
+        Ok(party.map(PoliticalParty::from))
     }
 
     /// Leverages Votesmart ratings data for the time being
@@ -352,9 +342,18 @@ impl PoliticianResult {
         .await
     }
 
+    /// Races run with their results and terms in office, latest first
+    async fn electoral_history(&self, ctx: &Context<'_>) -> Result<ElectoralHistoryResult> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let history =
+            ElectoralHistory::for_politician(&db_pool, uuid::Uuid::parse_str(&self.id)?).await?;
+        Ok(history.into())
+    }
+
     /// Calculates the total years a politician has been in office using
     /// the votesmart politicial experience array.  Does not take into account
     /// objects where the politician is considered a 'candidate'
+    #[graphql(deprecation = "Use `electoralHistory.yearsInOffice` instead")]
     async fn years_in_public_office(&self) -> Result<Option<i32>> {
         if let Some(vs_bio) = &self.votesmart_candidate_bio {
             let experience: VotesmartExperience =
@@ -445,6 +444,18 @@ impl PoliticianResult {
         Ok(results)
     }
 
+    /// Ids other systems use for this politician, e.g. FEC, Votesmart or OCD ids
+    async fn external_ids(&self, ctx: &Context<'_>) -> Result<Vec<ExternalIdResult>> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let records = ExternalId::find_by_entity(
+            &db_pool,
+            ExternalIdEntityType::Politician,
+            uuid::Uuid::parse_str(&self.id)?,
+        )
+        .await?;
+        Ok(records.into_iter().map(ExternalIdResult::from).collect())
+    }
+
     async fn sponsored_bills(
         &self,
         ctx: &Context<'_>,
@@ -641,3 +652,22 @@ impl From<Politician> for PoliticianResult {
         }
     }
 }
+
+/// A possible duplicate of a politician, with the signals that matched
+#[derive(SimpleObject, Debug, Clone)]
+#[graphql(visible = "is_admin")]
+pub struct PoliticianMatchResult {
+    politician: PoliticianResult,
+    score: f64,
+    reasons: Vec<String>,
+}
+
+impl From<PoliticianMatch> for PoliticianMatchResult {
+    fn from(m: PoliticianMatch) -> Self {
+        Self {
+            politician: PoliticianResult::from(m.politician),
+            score: m.score,
+            reasons: m.reasons.iter().map(|r| r.to_string()).collect(),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use async_graphql::{SimpleObject, ID};
+use chrono::{NaiveDate, NaiveTime};
+use db::{PollingLocation, PollingLocationHours, PollingLocationType, PollingPlace, State};
+
+#[derive(SimpleObject, Debug, Clone)]
+pub struct PollingPlaceResult {
+    /// Empty when the address isn't geocoded or isn't in a precinct we have boundaries for
+    precinct_id: Option<ID>,
+    county_code: Option<String>,
+    precinct_code: Option<String>,
+    precinct_name: Option<String>,
+    election_day: Option<PollingLocationResult>,
+    early_voting: Vec<PollingLocationResult>,
+}
+
+#[derive(SimpleObject, Debug, Clone)]
+pub struct PollingLocationResult {
+    id: ID,
+    location_type: PollingLocationType,
+    name: String,
+    line_1: String,
+    line_2: Option<String>,
+    city: String,
+    state: State,
+    postal_code: Option<String>,
+    accessibility_notes: Option<String>,
+    /// By date
+    hours: Vec<PollingLocationHoursResult>,
+}
+
+#[derive(SimpleObject, Debug, Clone)]
+pub struct PollingLocationHoursResult {
+    date: NaiveDate,
+    opens_at: NaiveTime,
+    closes_at: NaiveTime,
+}
+
+impl PollingLocationResult {
+    fn new(location: PollingLocation, hours: &[PollingLocationHours]) -> Self {
+        Self {
+            id: ID::from(location.id),
+            location_type: location.location_type,
+            name: location.name,
+            line_1: location.line_1,
+            line_2: location.line_2,
+            city: location.city,
+            state: location.state,
+            postal_code: location.postal_code,
+            accessibility_notes: location.accessibility_notes,
+            hours: hours
+                .iter()
+                .filter(|h| h.polling_location_id == location.id)
+                .map(|h| PollingLocationHoursResult {
+                    date: h.date,
+                    opens_at: h.opens_at,
+                    closes_at: h.closes_at,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<PollingPlace> for PollingPlaceResult {
+    fn from(p: PollingPlace) -> Self {
+        let hours = p.hours;
+        Self {
+            precinct_id: p.precinct.as_ref().map(|p| ID::from(p.id)),
+            county_code: p.precinct.as_ref().map(|p| p.county_code.clone()),
+            precinct_code: p.precinct.as_ref().map(|p| p.precinct_code.clone()),
+            precinct_name: p.precinct.and_then(|p| p.name),
+            election_day: p
+                .election_day
+                .map(|l| PollingLocationResult::new(l, &hours)),
+            early_voting: p
+                .early_voting
+                .into_iter()
+                .map(|l| PollingLocationResult::new(l, &hours))
+                .collect(),
+        }
+    }
+}
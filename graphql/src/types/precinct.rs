@@ -0,0 +1,63 @@
+use async_graphql::{SimpleObject, ID};
+use db::{PrecinctCandidateVotes, PrecinctRaceResult};
+
+#[derive(SimpleObject, Debug, Clone)]
+pub struct PrecinctResultResult {
+    precinct_id: ID,
+    county_code: String,
+    precinct_code: String,
+    name: Option<String>,
+    /// Votes cast in the race in this precinct
+    total_votes: Option<i32>,
+    registered_voters: Option<i32>,
+    /// Voters who cast a ballot in this precinct, in any race
+    total_voted: Option<i32>,
+    turnout_percentage: Option<f64>,
+    /// Most votes first
+    candidates: Vec<PrecinctCandidateResult>,
+}
+
+#[derive(SimpleObject, Debug, Clone)]
+pub struct PrecinctCandidateResult {
+    /// Empty for write-ins and candidates we don't track
+    candidate_id: Option<ID>,
+    candidate_name: String,
+    votes: i32,
+    vote_percentage: Option<f64>,
+}
+
+fn percentage(part: i32, whole: Option<i32>) -> Option<f64> {
+    whole
+        .filter(|&whole| whole > 0)
+        .map(|whole| (part as f64 / whole as f64 * 1000.0).round() / 10.0)
+}
+
+impl From<PrecinctRaceResult> for PrecinctResultResult {
+    fn from(r: PrecinctRaceResult) -> Self {
+        let total_votes = r
+            .total_votes
+            .or_else(|| Some(r.candidates.iter().map(|c| c.votes).sum()));
+        Self {
+            precinct_id: ID::from(r.precinct_id),
+            county_code: r.county_code,
+            precinct_code: r.precinct_code,
+            name: r.name,
+            total_votes,
+            registered_voters: r.registered_voters,
+            total_voted: r.total_voted,
+            turnout_percentage: r
+                .total_voted
+                .and_then(|voted| percentage(voted, r.registered_voters)),
+            candidates: r
+                .candidates
+                .into_iter()
+                .map(|c: PrecinctCandidateVotes| PrecinctCandidateResult {
+                    candidate_id: c.candidate_id.map(ID::from),
+                    vote_percentage: percentage(c.votes, total_votes),
+                    candidate_name: c.candidate_name,
+                    votes: c.votes,
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use async_graphql::SimpleObject;
+use auth::PrintKind;
+
+use super::Error;
+
+/// Signed links to a printout, valid for 120 days without signing in
+#[derive(SimpleObject, Debug, Clone)]
+pub struct PrintLinksResult {
+    html_url: String,
+    pdf_url: String,
+}
+
+impl PrintLinksResult {
+    pub fn new(
+        kind: PrintKind,
+        id: uuid::Uuid,
+        race_id: Option<uuid::Uuid>,
+    ) -> Result<Self, Error> {
+        let token = auth::create_print_token(kind, id, race_id)?;
+        let api_url = config::Config::default().api_url;
+        let html_url = api_url
+            .join(&format!("print/{}", token))
+            .expect("print path is a valid URL");
+        Ok(Self {
+            pdf_url: format!("{}/pdf", html_url),
+            html_url: html_url.to_string(),
+        })
+    }
+}
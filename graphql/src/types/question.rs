@@ -299,14 +299,17 @@ impl QuestionSubmissionResult {
     }
 
     async fn question(&self, ctx: &Context<'_>) -> Result<QuestionResult> {
-        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
-        let question = db::Question::find_by_id(
-            &db_pool,
-            uuid::Uuid::parse_str(self.question_id.as_str()).unwrap(),
-        )
-        .await?;
+        let question = ctx
+            .data::<ApiContext>()?
+            .loaders
+            .question_loader
+            .load_one(uuid::Uuid::parse_str(self.question_id.as_str())?)
+            .await?;
 
-        Ok(question.into())
+        match question {
+            Some(question) => Ok(question.into()),
+            None => Err(sqlx::Error::RowNotFound.into()),
+        }
     }
 
     /// Returns the candidate guide embed associated with the question submission.
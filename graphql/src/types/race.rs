@@ -1,16 +1,20 @@
 use crate::{context::ApiContext, types::OfficeResult};
-use async_graphql::{ComplexObject, Context, Result, SimpleObject, ID};
+use async_graphql::{ComplexObject, Context, InputObject, Result, SimpleObject, ID};
 use db::{
-    loaders::politician::PoliticianId,
+    loaders::{politician::PoliticianId, race::RaceId},
     models::{
         enums::{RaceType, State, VoteType},
         politician::Politician,
-        race::Race,
+        race::{CandidacyStatus, Race, RaceCandidate as Candidacy},
     },
-    Election, Embed, EmbedType,
+    DateTime, Embed, EmbedType, PrecinctRaceResult, RaceResultsSnapshot,
+    RaceResultsSnapshotCandidate, RankedChoiceRound, RankedChoiceTally, ResultsStatus,
 };
 
-use super::{ElectionResult, EmbedResult, PoliticalParty, PoliticianResult};
+use super::{
+    ElectionResult, EmbedResult, PoliticalParty, PoliticianResult, PrecinctResultResult,
+    RaceResultsSnapshotResult, RankedChoiceRoundResult,
+};
 
 #[derive(SimpleObject, Debug, Clone)]
 #[graphql(complex)]
@@ -36,6 +40,7 @@ pub struct RaceCandidate {
     race_id: uuid::Uuid,
     candidate_id: uuid::Uuid,
     votes: Option<i32>,
+    is_write_in: bool,
 }
 
 #[derive(SimpleObject, Debug, Clone)]
@@ -45,6 +50,42 @@ pub struct RaceCandidateResult {
     race_id: ID,
     candidate_id: ID,
     votes: Option<i32>,
+    is_write_in: bool,
+}
+
+/// Candidates still running are returned when no status is given
+#[derive(InputObject, Default, Debug)]
+pub struct CandidacyFilter {
+    status: Option<Vec<CandidacyStatus>>,
+    is_write_in: Option<bool>,
+    is_incumbent: Option<bool>,
+}
+
+impl CandidacyFilter {
+    fn matches(&self, candidacy: &Candidacy) -> bool {
+        let status = match &self.status {
+            Some(status) => status.contains(&candidacy.status),
+            None => candidacy.is_running,
+        };
+        status
+            && self.is_write_in.is_none_or(|w| w == candidacy.is_write_in)
+            && self
+                .is_incumbent
+                .is_none_or(|i| i == candidacy.is_incumbent)
+    }
+}
+
+#[derive(SimpleObject, Debug, Clone)]
+pub struct RaceCandidacyResult {
+    candidate: PoliticianResult,
+    status: CandidacyStatus,
+    is_write_in: bool,
+    is_incumbent: bool,
+    filing_date: Option<chrono::NaiveDate>,
+    ballot_designation: Option<String>,
+    party_on_ballot: Option<String>,
+    /// Position on the ballot, empty where names are rotated and for write-ins
+    ballot_order: Option<i32>,
 }
 
 #[derive(SimpleObject, Debug, Clone)]
@@ -55,10 +96,25 @@ pub struct RaceResultsResult {
     total_precincts: Option<i32>,
     precinct_reporting_percentage: Option<f64>,
     winners: Option<Vec<PoliticianResult>>,
+    /// Votes for write-ins the results don't name
+    write_in_votes: Option<i32>,
+    /// Round by round tabulation of ranked choice races, empty for other races and until
+    /// rounds are imported
+    rounds: Vec<RankedChoiceRoundResult>,
+    status: ResultsStatus,
+    /// When vote counts or status last changed
+    last_updated_at: Option<DateTime>,
+    /// Every change to the results, newest first
+    history: Vec<RaceResultsSnapshotResult>,
 }
 
 #[ComplexObject]
 impl RaceResult {
+    /// Current slug, differs from the requested one when an old slug was resolved
+    async fn canonical_slug(&self) -> String {
+        self.slug.clone()
+    }
+
     async fn office(&self, ctx: &Context<'_>) -> Result<OfficeResult> {
         let office = ctx
             .data::<ApiContext>()?
@@ -71,98 +127,49 @@ impl RaceResult {
     }
 
     async fn party(&self, ctx: &Context<'_>) -> Result<Option<PoliticalParty>> {
-        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
         let party = match &self.party_id {
             Some(party_id) => {
-                let record = sqlx::query!(
-                    r#"
-                    SELECT *
-                    FROM party
-                    WHERE id = $1
-                "#,
-                    uuid::Uuid::parse_str(&party_id.to_string()).unwrap()
-                )
-                .fetch_optional(&db_pool)
-                .await?;
-
-                match record {
-                    Some(record) => Some(PoliticalParty {
-                        id: ID::from(record.id),
-                        slug: record.slug,
-                        fec_code: record.fec_code,
-                        name: record.name,
-                        description: record.description,
-                        notes: record.notes,
-                    }),
-                    None => None,
-                }
+                ctx.data::<ApiContext>()?
+                    .loaders
+                    .party_loader
+                    .load_one(uuid::Uuid::parse_str(party_id)?)
+                    .await?
             }
             None => None,
         };
 
-        Ok(party)
+        Ok(party.map(PoliticalParty::from))
     }
 
-    async fn candidates(&self, ctx: &Context<'_>) -> Result<Vec<PoliticianResult>> {
-        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+    /// Candidates in ballot order, by default those still running
+    async fn candidates(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<CandidacyFilter>,
+    ) -> Result<Vec<PoliticianResult>> {
+        let candidacies = self.candidacies(ctx, filter).await?;
+        Ok(candidacies.into_iter().map(|c| c.candidate).collect())
+    }
 
-        let politician_records = sqlx::query_as!(
-            Politician,
-            r#"
-                SELECT
-                    id,
-                    slug,
-                    ref_key,
-                    first_name,
-                    middle_name,
-                    last_name,
-                    suffix,
-                    preferred_name,
-                    full_name,
-                    biography,
-                    biography_source,
-                    home_state AS "home_state:State",
-                    date_of_birth,
-                    office_id,
-                    party_id,
-                    upcoming_race_id,
-                    thumbnail_image_url,
-                    assets,
-                    official_website_url,
-                    campaign_website_url,
-                    facebook_url,
-                    twitter_url,
-                    instagram_url,
-                    youtube_url,
-                    linkedin_url,
-                    tiktok_url,
-                    email,
-                    phone,
-                    votesmart_candidate_id,
-                    votesmart_candidate_bio,
-                    votesmart_candidate_ratings,
-                    legiscan_people_id,
-                    crp_candidate_id,
-                    fec_candidate_id,
-                    race_wins,
-                    race_losses,
-                    p.created_at,
-                    p.updated_at
-                FROM
-                    politician p
-                    JOIN race_candidates rc ON race_id = $1
-                WHERE
-                    p.id = rc.candidate_id
-                    AND rc.is_running = TRUE
-            "#,
-            uuid::Uuid::parse_str(&self.id).unwrap()
-        )
-        .fetch_all(&db_pool)
-        .await?;
+    /// Candidates with their filing and ballot details, by default those still running
+    async fn candidacies(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<CandidacyFilter>,
+    ) -> Result<Vec<RaceCandidacyResult>> {
+        let records = ctx
+            .data::<ApiContext>()?
+            .loaders
+            .race_candidates_loader
+            .load_one(RaceId(uuid::Uuid::parse_str(&self.id)?))
+            .await?
+            .unwrap_or_default();
 
-        let results = politician_records
+        let filter = filter.unwrap_or_default();
+        let results = records
             .into_iter()
-            .map(PoliticianResult::from)
+            .filter(|(candidacy, _)| filter.matches(candidacy))
+            .map(RaceCandidacyResult::from)
             .collect();
         Ok(results)
     }
@@ -176,7 +183,8 @@ impl RaceResult {
                 SELECT
                     id AS candidate_id,
                     rc.votes,
-                    rc.race_id
+                    rc.race_id,
+                    rc.is_write_in
                 FROM
                     politician p
                     JOIN race_candidates rc ON race_id = $1
@@ -202,7 +210,10 @@ impl RaceResult {
               winner_ids,
               num_precincts_reporting,
               total_precincts,
-              ROUND(CAST(CAST(num_precincts_reporting AS FLOAT) / CAST(NULLIF(total_precincts, 0) AS FLOAT) * 100 AS NUMERIC), 1)::FLOAT AS "precinct_reporting_percentage"
+              ROUND(CAST(CAST(num_precincts_reporting AS FLOAT) / CAST(NULLIF(total_precincts, 0) AS FLOAT) * 100 AS NUMERIC), 1)::FLOAT AS "precinct_reporting_percentage",
+              results_status AS "results_status:ResultsStatus",
+              results_updated_at,
+              write_in_votes
             FROM
               race
             WHERE
@@ -236,6 +247,30 @@ impl RaceResult {
             false => None,
         };
 
+        let rounds = match self.vote_type == VoteType::RankedChoice
+            && ctx.look_ahead().field("rounds").exists()
+        {
+            true => {
+                let race_id = uuid::Uuid::parse_str(&self.id)?;
+                RankedChoiceRoundResult::from_rounds(
+                    RankedChoiceRound::find_by_race_id(&db_pool, race_id).await?,
+                    RankedChoiceTally::find_by_race_id(&db_pool, race_id).await?,
+                )
+            }
+            false => vec![],
+        };
+
+        let history = match ctx.look_ahead().field("history").exists() {
+            true => {
+                let race_id = uuid::Uuid::parse_str(&self.id)?;
+                RaceResultsSnapshotResult::from_snapshots(
+                    RaceResultsSnapshot::find_by_race_id(&db_pool, race_id).await?,
+                    RaceResultsSnapshotCandidate::find_by_race_id(&db_pool, race_id).await?,
+                )
+            }
+            false => vec![],
+        };
+
         Ok(RaceResultsResult {
             votes_by_candidate: race_candidate_results,
             total_votes: race_results.total_votes,
@@ -243,37 +278,54 @@ impl RaceResult {
             total_precincts: race_results.total_precincts,
             precinct_reporting_percentage: race_results.precinct_reporting_percentage,
             winners,
+            write_in_votes: race_results.write_in_votes,
+            rounds,
+            status: race_results.results_status,
+            last_updated_at: race_results.results_updated_at,
+            history,
         })
     }
 
-    async fn election_date(&self, ctx: &Context<'_>) -> Result<Option<chrono::NaiveDate>> {
+    /// Results in each precinct, for result maps. The same data is served as GeoJSON with
+    /// precinct polygons at `/results/:race_id/precincts`.
+    async fn precinct_results(&self, ctx: &Context<'_>) -> Result<Vec<PrecinctResultResult>> {
         let db_pool = ctx.data::<ApiContext>()?.pool.clone();
-        let record = sqlx::query!(
-            r#"
-                SELECT election_date FROM election
-                WHERE id = $1
-            "#,
-            uuid::Uuid::parse_str(self.election_id.clone().unwrap_or_default().as_str()).unwrap()
-        )
-        .fetch_optional(&db_pool)
-        .await?;
+        let records =
+            PrecinctRaceResult::for_race(&db_pool, uuid::Uuid::parse_str(&self.id)?).await?;
+        Ok(records
+            .into_iter()
+            .map(PrecinctResultResult::from)
+            .collect())
+    }
 
-        Ok(record.map(|r| r.election_date))
+    async fn election_date(&self, ctx: &Context<'_>) -> Result<Option<chrono::NaiveDate>> {
+        let election = match &self.election_id {
+            Some(election_id) => {
+                ctx.data::<ApiContext>()?
+                    .loaders
+                    .election_loader
+                    .load_one(uuid::Uuid::parse_str(election_id)?)
+                    .await?
+            }
+            None => None,
+        };
+
+        Ok(election.map(|e| e.election_date))
     }
 
     async fn election(&self, ctx: &Context<'_>) -> Result<ElectionResult> {
-        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
-        let record = sqlx::query_as!(
-            Election,
-            r#"
-            SELECT id, slug, title, description, state AS "state:State", municipality, election_date 
-            FROM election WHERE id = $1"#,
-            uuid::Uuid::parse_str(self.election_id.clone().unwrap_or_default().as_str()).unwrap()
-        )
-        .fetch_one(&db_pool)
-        .await?;
+        let election_id = self.election_id.clone().unwrap_or_default();
+        let election = ctx
+            .data::<ApiContext>()?
+            .loaders
+            .election_loader
+            .load_one(uuid::Uuid::parse_str(election_id.as_str())?)
+            .await?;
 
-        Ok(record.into())
+        match election {
+            Some(election) => Ok(election.into()),
+            None => Err(sqlx::Error::RowNotFound.into()),
+        }
     }
 
     async fn related_embeds(&self, ctx: &Context<'_>) -> Result<Vec<EmbedResult>> {
@@ -367,6 +419,22 @@ impl From<RaceCandidate> for RaceCandidateResult {
             race_id: ID::from(r.race_id),
             candidate_id: ID::from(r.candidate_id),
             votes: r.votes,
+            is_write_in: r.is_write_in,
+        }
+    }
+}
+
+impl From<(Candidacy, Politician)> for RaceCandidacyResult {
+    fn from((candidacy, politician): (Candidacy, Politician)) -> Self {
+        Self {
+            candidate: PoliticianResult::from(politician),
+            status: candidacy.status,
+            is_write_in: candidacy.is_write_in,
+            is_incumbent: candidacy.is_incumbent,
+            filing_date: candidacy.filing_date,
+            ballot_designation: candidacy.ballot_designation,
+            party_on_ballot: candidacy.party_on_ballot,
+            ballot_order: candidacy.ballot_order,
         }
     }
 }
@@ -0,0 +1,103 @@
+use crate::context::ApiContext;
+use async_graphql::{ComplexObject, Context, Result, SimpleObject, ID};
+use db::{
+    loaders::politician::PoliticianId,
+    models::enums::{BallotMeasureStatus, PassageThreshold},
+    BallotMeasureFinalization, RaceFinalization,
+};
+
+use super::PoliticianResult;
+
+#[derive(SimpleObject, Debug, Clone)]
+#[graphql(complex)]
+pub struct RaceFinalizationResult {
+    race_id: ID,
+    race_title: String,
+    /// How winners were picked, e.g. "Top 2 advance to the general"
+    rule: String,
+    winner_ids: Vec<ID>,
+    loser_ids: Vec<ID>,
+    general_race_id: Option<ID>,
+    /// Why the race was left alone, races with no results or a tie for the last spot are skipped
+    skipped_reason: Option<String>,
+    previously_finalized: bool,
+}
+
+#[derive(SimpleObject, Debug, Clone)]
+pub struct BallotMeasureFinalizationResult {
+    ballot_measure_id: ID,
+    title: String,
+    ballot_measure_code: String,
+    passage_threshold: PassageThreshold,
+    yes_votes: Option<i32>,
+    no_votes: Option<i32>,
+    /// `BECAME_LAW` or `FAILED`, empty when the measure was skipped
+    status: Option<BallotMeasureStatus>,
+    previous_status: BallotMeasureStatus,
+    skipped_reason: Option<String>,
+    previously_finalized: bool,
+}
+
+pub(crate) async fn load_politicians(
+    ctx: &Context<'_>,
+    ids: &[ID],
+) -> Result<Vec<PoliticianResult>> {
+    let ids = ids
+        .iter()
+        .map(|id| uuid::Uuid::parse_str(id).map(PoliticianId))
+        .collect::<Result<Vec<_>, _>>()?;
+    let politicians = ctx
+        .data::<ApiContext>()?
+        .loaders
+        .politician_loader
+        .load_many(ids.clone())
+        .await?;
+    Ok(ids
+        .iter()
+        .filter_map(|id| politicians.get(id).cloned())
+        .map(PoliticianResult::from)
+        .collect())
+}
+
+#[ComplexObject]
+impl RaceFinalizationResult {
+    async fn winners(&self, ctx: &Context<'_>) -> Result<Vec<PoliticianResult>> {
+        load_politicians(ctx, &self.winner_ids).await
+    }
+
+    async fn losers(&self, ctx: &Context<'_>) -> Result<Vec<PoliticianResult>> {
+        load_politicians(ctx, &self.loser_ids).await
+    }
+}
+
+impl From<RaceFinalization> for RaceFinalizationResult {
+    fn from(f: RaceFinalization) -> Self {
+        Self {
+            race_id: ID::from(f.race_id),
+            race_title: f.race_title,
+            rule: f.rule.to_string(),
+            winner_ids: f.winner_ids.into_iter().map(ID::from).collect(),
+            loser_ids: f.loser_ids.into_iter().map(ID::from).collect(),
+            general_race_id: f.general_race_id.map(ID::from),
+            skipped_reason: f.skipped_reason,
+            previously_finalized: f.previously_finalized,
+        }
+    }
+}
+
+impl From<BallotMeasureFinalization> for BallotMeasureFinalizationResult {
+    fn from(f: BallotMeasureFinalization) -> Self {
+        Self {
+            ballot_measure_id: ID::from(f.ballot_measure_id),
+            title: f.title,
+            ballot_measure_code: f.ballot_measure_code,
+            passage_threshold: f.passage_threshold,
+            yes_votes: f.yes_votes,
+            no_votes: f.no_votes,
+            status: f.status,
+            previous_status: f.previous_status,
+            skipped_reason: f.skipped_reason,
+            previously_finalized: f.previously_finalized,
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use async_graphql::{SimpleObject, ID};
+use db::{DateTime, RaceResultsSnapshot, RaceResultsSnapshotCandidate, ResultsStatus};
+
+#[derive(SimpleObject, Debug, Clone)]
+pub struct RaceResultsSnapshotResult {
+    id: ID,
+    status: ResultsStatus,
+    total_votes: Option<i32>,
+    /// Change from the previous snapshot, empty for the first one
+    total_votes_change: Option<i32>,
+    num_precincts_reporting: Option<i32>,
+    total_precincts: Option<i32>,
+    /// Results file the numbers came from, empty when staff changed the status
+    source_url: Option<String>,
+    /// SHA-256 of the results file
+    source_hash: Option<String>,
+    created_at: DateTime,
+    votes_by_candidate: Vec<RaceResultsSnapshotCandidateResult>,
+}
+
+#[derive(SimpleObject, Debug, Clone)]
+pub struct RaceResultsSnapshotCandidateResult {
+    candidate_id: ID,
+    votes: Option<i32>,
+    votes_change: Option<i32>,
+}
+
+fn change(current: Option<i32>, previous: Option<i32>) -> i32 {
+    current.unwrap_or(0) - previous.unwrap_or(0)
+}
+
+impl RaceResultsSnapshotResult {
+    /// Pairs each snapshot with its candidates' votes and the change since the snapshot before
+    /// it. Snapshots are expected newest first.
+    pub fn from_snapshots(
+        snapshots: Vec<RaceResultsSnapshot>,
+        candidates: Vec<RaceResultsSnapshotCandidate>,
+    ) -> Vec<Self> {
+        let votes_in = |snapshot: &RaceResultsSnapshot, candidate_id: uuid::Uuid| {
+            candidates
+                .iter()
+                .find(|c| c.snapshot_id == snapshot.id && c.candidate_id == candidate_id)
+                .and_then(|c| c.votes)
+        };
+
+        snapshots
+            .iter()
+            .enumerate()
+            .map(|(index, snapshot)| {
+                let previous = snapshots.get(index + 1);
+                Self {
+                    id: ID::from(snapshot.id),
+                    status: snapshot.results_status,
+                    total_votes: snapshot.total_votes,
+                    total_votes_change: previous
+                        .map(|p| change(snapshot.total_votes, p.total_votes)),
+                    num_precincts_reporting: snapshot.num_precincts_reporting,
+                    total_precincts: snapshot.total_precincts,
+                    source_url: snapshot.source_url.clone(),
+                    source_hash: snapshot.source_hash.clone(),
+                    created_at: snapshot.created_at,
+                    votes_by_candidate: candidates
+                        .iter()
+                        .filter(|c| c.snapshot_id == snapshot.id)
+                        .map(|c| RaceResultsSnapshotCandidateResult {
+                            candidate_id: ID::from(c.candidate_id),
+                            votes: c.votes,
+                            votes_change: previous
+                                .map(|p| change(c.votes, votes_in(p, c.candidate_id))),
+                        })
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+}
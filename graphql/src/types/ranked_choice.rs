@@ -0,0 +1,81 @@
+use crate::context::ApiContext;
+use async_graphql::{ComplexObject, Context, Result, SimpleObject, ID};
+use db::{
+    loaders::politician::PoliticianId, RankedChoiceRound, RankedChoiceStatus, RankedChoiceTally,
+};
+
+use super::PoliticianResult;
+
+#[derive(SimpleObject, Debug, Clone)]
+pub struct RankedChoiceRoundResult {
+    round: i32,
+    /// Votes needed to be elected in this round
+    threshold: Option<f64>,
+    /// Ballots with no continuing candidate left, cumulative
+    exhausted: f64,
+    /// Candidates still in the count this round, most votes first
+    tallies: Vec<RankedChoiceTallyResult>,
+}
+
+#[derive(SimpleObject, Debug, Clone)]
+#[graphql(complex)]
+pub struct RankedChoiceTallyResult {
+    candidate_id: Option<ID>,
+    /// Name as it appears in the results, write-ins have no candidate
+    candidate_name: String,
+    votes: f64,
+    /// Votes gained or lost since the previous round
+    transfer: f64,
+    status: RankedChoiceStatus,
+}
+
+#[ComplexObject]
+impl RankedChoiceTallyResult {
+    async fn candidate(&self, ctx: &Context<'_>) -> Result<Option<PoliticianResult>> {
+        let Some(candidate_id) = &self.candidate_id else {
+            return Ok(None);
+        };
+        let politician = ctx
+            .data::<ApiContext>()?
+            .loaders
+            .politician_loader
+            .load_one(PoliticianId(uuid::Uuid::parse_str(candidate_id)?))
+            .await?;
+        Ok(politician.map(PoliticianResult::from))
+    }
+}
+
+impl RankedChoiceRoundResult {
+    /// Groups a race's tallies under its rounds
+    pub fn from_rounds(
+        rounds: Vec<RankedChoiceRound>,
+        tallies: Vec<RankedChoiceTally>,
+    ) -> Vec<Self> {
+        rounds
+            .into_iter()
+            .map(|round| Self {
+                round: round.round,
+                threshold: round.threshold,
+                exhausted: round.exhausted,
+                tallies: tallies
+                    .iter()
+                    .filter(|t| t.round_id == round.id)
+                    .cloned()
+                    .map(RankedChoiceTallyResult::from)
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+impl From<RankedChoiceTally> for RankedChoiceTallyResult {
+    fn from(t: RankedChoiceTally) -> Self {
+        Self {
+            candidate_id: t.candidate_id.map(ID::from),
+            candidate_name: t.candidate_name,
+            votes: t.votes,
+            transfer: t.transfer,
+            status: t.status,
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use async_graphql::{ComplexObject, Context, Result, SimpleObject, ID};
+use db::{
+    DateTime, ProposedChangeFilter, ProposedChangeKind, ProposedChangeStatus, ScraperEntityType,
+    ScraperProposedChange, ScraperRun, ScraperRunError, ScraperRunStatus,
+};
+use serde_json::Value as JSON;
+
+use crate::{context::ApiContext, is_admin};
+
+#[derive(SimpleObject, Debug, Clone)]
+#[graphql(complex, visible = "is_admin")]
+pub struct ScraperRunResult {
+    id: ID,
+    source_id: String,
+    status: ScraperRunStatus,
+    is_staged: bool,
+    stats: JSON,
+    error: Option<String>,
+    started_at: DateTime,
+    finished_at: Option<DateTime>,
+}
+
+#[ComplexObject]
+impl ScraperRunResult {
+    async fn errors(&self, ctx: &Context<'_>) -> Result<Vec<ScraperRunErrorResult>> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let errors =
+            ScraperRunError::find_by_scraper_run_id(&db_pool, uuid::Uuid::parse_str(&self.id)?)
+                .await?;
+        Ok(errors
+            .into_iter()
+            .map(ScraperRunErrorResult::from)
+            .collect())
+    }
+
+    async fn proposed_changes(
+        &self,
+        ctx: &Context<'_>,
+        status: Option<ProposedChangeStatus>,
+    ) -> Result<Vec<ProposedChangeResult>> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let filter = ProposedChangeFilter {
+            scraper_run_id: Some(uuid::Uuid::parse_str(&self.id)?),
+            status,
+            ..Default::default()
+        };
+        let records = ScraperProposedChange::filter(&db_pool, &filter).await?;
+        Ok(records
+            .into_iter()
+            .map(ProposedChangeResult::from)
+            .collect())
+    }
+}
+
+#[derive(SimpleObject, Debug, Clone)]
+#[graphql(visible = "is_admin")]
+pub struct ScraperRunErrorResult {
+    id: ID,
+    entity_type: Option<String>,
+    source_ref: Option<String>,
+    message: String,
+    created_at: DateTime,
+}
+
+#[derive(SimpleObject, Debug, Clone)]
+#[graphql(visible = "is_admin")]
+pub struct ProposedChangeResult {
+    id: ID,
+    scraper_run_id: ID,
+    position: i32,
+    entity_type: ScraperEntityType,
+    entity_key: JSON,
+    change_kind: ProposedChangeKind,
+    /// The full row as the scraper would write it
+    record: JSON,
+    /// Changed fields for updates, as `{ field, old, new }` objects
+    changes: JSON,
+    status: ProposedChangeStatus,
+    rejection_reason: Option<String>,
+    /// Why the change could not be applied when it was approved
+    apply_error: Option<String>,
+    reviewed_by: Option<ID>,
+    reviewed_at: Option<DateTime>,
+    created_at: DateTime,
+}
+
+impl From<ScraperRun> for ScraperRunResult {
+    fn from(r: ScraperRun) -> Self {
+        Self {
+            id: ID::from(r.id),
+            source_id: r.source_id,
+            status: r.status,
+            is_staged: r.is_staged,
+            stats: r.stats,
+            error: r.error,
+            started_at: r.started_at,
+            finished_at: r.finished_at,
+        }
+    }
+}
+
+impl From<ScraperRunError> for ScraperRunErrorResult {
+    fn from(e: ScraperRunError) -> Self {
+        Self {
+            id: ID::from(e.id),
+            entity_type: e.entity_type,
+            source_ref: e.source_ref,
+            message: e.message,
+            created_at: e.created_at,
+        }
+    }
+}
+
+impl From<ScraperProposedChange> for ProposedChangeResult {
+    fn from(c: ScraperProposedChange) -> Self {
+        Self {
+            id: ID::from(c.id),
+            scraper_run_id: ID::from(c.scraper_run_id),
+            position: c.position,
+            entity_type: c.entity_type,
+            entity_key: c.entity_key,
+            change_kind: c.change_kind,
+            record: c.record,
+            changes: c.changes,
+            status: c.status,
+            rejection_reason: c.rejection_reason,
+            apply_error: c.apply_error,
+            reviewed_by: c.reviewed_by.map(ID::from),
+            reviewed_at: c.reviewed_at,
+            created_at: c.created_at,
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use super::{AddressExtendedMNResult, AddressResult, OrganizationResult};
+use super::{
+    calendar_url, AddressExtendedMNResult, AddressResult, DistrictResult, OrganizationResult,
+};
 use crate::{context::ApiContext, guard::UserGuard, is_admin};
 use async_graphql::{ComplexObject, Context, InputObject, Result, SimpleObject, ID};
 use db::{Address, Organization, UserWithProfile};
@@ -89,6 +91,37 @@ impl UserResult {
             Ok(None)
         }
     }
+
+    /// Every district the user's address falls in, from imported boundary files
+    #[graphql(guard = "UserGuard::new(&self.id)", visible = "is_admin")]
+    async fn districts(&self, ctx: &Context<'_>) -> Result<Vec<DistrictResult>> {
+        let db_pool = ctx.data::<ApiContext>()?.pool.clone();
+        let address_id = sqlx::query_scalar!(
+            r#"
+                SELECT up.address_id FROM user_profile up WHERE up.user_id = $1
+            "#,
+            &uuid::Uuid::try_parse(&self.id).unwrap()
+        )
+        .fetch_optional(&db_pool)
+        .await?
+        .flatten();
+
+        match address_id {
+            Some(address_id) => {
+                let districts = Address::districts(&db_pool, &address_id, None).await?;
+                Ok(districts.boundaries.into_iter().map(|d| d.into()).collect())
+            }
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Private iCalendar feed of deadlines at the user's address and in elections they have
+    /// voting guides for, valid for a year without signing in
+    #[graphql(guard = "UserGuard::new(&self.id)", visible = "is_admin")]
+    async fn calendar_url(&self) -> Result<String> {
+        let token = auth::create_calendar_token(uuid::Uuid::parse_str(&self.id)?)?;
+        Ok(calendar_url(&format!("user/{}.ics", token)))
+    }
 }
 
 impl From<UserWithProfile> for UserResult {
@@ -1,9 +1,10 @@
 use async_graphql::{ComplexObject, Context, InputObject, Result, SimpleObject, ID};
+use auth::PrintKind;
 use db::{models::voting_guide::VotingGuide, Election, Politician};
 
-use crate::context::ApiContext;
+use crate::{context::ApiContext, guard::UserGuard};
 
-use super::{ElectionResult, PoliticianResult, UserResult};
+use super::{ElectionResult, PoliticianResult, PrintLinksResult, UserResult};
 
 #[derive(InputObject)]
 pub struct UpsertVotingGuideInput {
@@ -119,6 +120,16 @@ impl VotingGuideResult {
             .collect();
         Ok(results)
     }
+
+    /// Links to the guide printed as the author's sample ballot, to share by email or text
+    #[graphql(guard = "UserGuard::new(&self.user_id)")]
+    async fn print_links(&self) -> Result<PrintLinksResult> {
+        Ok(PrintLinksResult::new(
+            PrintKind::SampleBallot,
+            uuid::Uuid::parse_str(self.id.as_str())?,
+            None,
+        )?)
+    }
 }
 
 #[ComplexObject]
@@ -1,13 +1,18 @@
-use scrapers::{util::run_with_timer, Scraper, ScraperContext};
+use scrapers::{util::run_with_timer, RunMode, Scraper, ScraperContext};
 
 #[tokio::main]
 async fn main() {
     db::init_pool().await.unwrap();
     let pool = db::pool().await;
-    let context = ScraperContext { db: pool };
+    let context = ScraperContext {
+        db: pool,
+        mode: RunMode::from_args(),
+    };
     let scraper = scrapers::co::sos::general_candidates::Scraper::default();
-    if let Err(err) = run_with_timer("Scraping data from CO SOS".into(), || async {
-        scraper.run_local(&context).await?;
+    let mut report = None;
+    let report_slot = &mut report;
+    if let Err(err) = run_with_timer("Scraping data from CO SOS".into(), || async move {
+        *report_slot = Some(scraper.run_local(&context).await?);
 
         Ok(())
     })
@@ -18,4 +23,8 @@ async fn main() {
             err
         );
     }
+
+    if let Some(report) = report {
+        println!("\n{report}");
+    }
 }
@@ -1,19 +1,45 @@
-use scrapers::mn_sos_candidate_filings_fed_state_county::get_mn_sos_candidate_filings_fed_state_county;
-use scrapers::mn_sos_candidate_filings_local::get_mn_sos_candidate_filings_local;
+use scrapers::{mn::sos, util::run_with_timer, RunMode, Scraper, ScraperContext};
 
 #[tokio::main]
 async fn main() {
     db::init_pool().await.unwrap();
-    if let Err(err) = get_mn_sos_candidate_filings_fed_state_county().await {
-        println!("error scraping data from MN SOS candidate filings: {}", err);
+    let pool = db::pool().await;
+    let context = ScraperContext {
+        db: pool,
+        mode: RunMode::from_args(),
+    };
+    let args = std::env::args().collect::<Vec<_>>();
+    let local = args.iter().any(|arg| arg == "--local");
+    // The general election the filings are for, e.g. `--year 2026`, defaults to this year
+    let election_year = args
+        .windows(2)
+        .find(|pair| pair[0] == "--year")
+        .map(|pair| pair[1].parse::<u16>().expect("--year must be a year"))
+        .unwrap_or_else(sos::current_election_year);
+
+    let mut reports = vec![];
+    let reports_slot = &mut reports;
+    if let Err(err) = run_with_timer("Scraping data from MN SOS".into(), || async move {
+        let scraper = sos::candidate_filings_fed_state_county::Scraper { election_year };
+        reports_slot.push(match local {
+            true => scraper.run_local(&context).await?,
+            false => scraper.run(&context).await?,
+        });
+
+        let scraper = sos::candidate_filings_local::Scraper { election_year };
+        reports_slot.push(match local {
+            true => scraper.run_local(&context).await?,
+            false => scraper.run(&context).await?,
+        });
+
+        Ok(())
+    })
+    .await
+    {
+        println!("Error scraping data from MN SOS candidate filings: {}", err);
     }
-    // if let Err(err) = get_mn_sos_candidate_filings_fed_state_county_primaries().await {
-    //     println!("error scraping data from MN SOS candidate filings: {}", err);
-    // }
-    if let Err(err) = get_mn_sos_candidate_filings_local().await {
-        println!("error running example: {}", err);
+
+    for report in reports {
+        println!("\n{report}");
     }
-    // if let Err(err) = get_mn_sos_candidate_filings_local_primaries().await {
-    //     println!("error running example: {}", err);
-    // }
 }
@@ -0,0 +1,60 @@
+use scrapers::rcv_results::{import_cast_vote_records, import_round_summary};
+use std::error::Error;
+use std::process;
+
+const USAGE: &str = "usage: rcv_results <race id> <file> [--summary] [--seats <n>] [--force]
+
+Tabulates a ranked choice race from a cast vote record CSV, or with --summary imports
+rounds from an RCTab summary.json or a round,candidate,votes[,status] CSV. Races with
+certified results are only changed with --force.";
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let positional = args
+        .iter()
+        .enumerate()
+        .filter(|(i, arg)| !arg.starts_with("--") && (*i == 0 || args[i - 1] != "--seats"))
+        .map(|(_, arg)| arg.as_str())
+        .collect::<Vec<_>>();
+    let [race_id, path] = positional[..] else {
+        return Err(USAGE.into());
+    };
+    let race_id = uuid::Uuid::parse_str(race_id)?;
+    let force = args.iter().any(|arg| arg == "--force");
+    let seats = match args.iter().position(|arg| arg == "--seats") {
+        Some(i) => Some(args.get(i + 1).ok_or(USAGE)?.parse()?),
+        None => None,
+    };
+
+    db::init_pool().await.unwrap();
+    let db_pool = &db::pool().await.connection;
+    let rounds = if args.iter().any(|arg| arg == "--summary") {
+        import_round_summary(db_pool, race_id, path, force).await?
+    } else {
+        import_cast_vote_records(db_pool, race_id, std::fs::File::open(path)?, seats, force).await?
+    };
+
+    for round in &rounds {
+        println!(
+            "Round {} (threshold {}, exhausted {})",
+            round.round,
+            round.threshold.map_or("-".to_string(), |t| t.to_string()),
+            round.exhausted
+        );
+        for tally in &round.tallies {
+            println!(
+                "  {:<32} {:>12} {:>+12} {}",
+                tally.candidate, tally.votes, tally.transfer, tally.status
+            );
+        }
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    if let Err(err) = run().await {
+        println!("error running rcv_results: {}", err);
+        process::exit(1);
+    }
+}
@@ -140,6 +140,61 @@ pub fn extract_office_meta(input: &str) -> Option<OfficeMeta> {
                     election_scope: db::ElectionScope::State,
                 },
             ),
+            (
+                r"(?i:County Commissioner)",
+                OfficeMeta {
+                    name: "County Commissioner".into(),
+                    title: "County Commissioner".into(),
+                    chamber: None,
+                    district_type: Some(db::DistrictType::County),
+                    political_scope: db::PoliticalScope::Local,
+                    election_scope: db::ElectionScope::County,
+                },
+            ),
+            (
+                r"(?i:Soil and Water (?:Conservation District )?Supervisor)",
+                OfficeMeta {
+                    name: "Soil and Water Supervisor".into(),
+                    title: "Soil and Water Supervisor".into(),
+                    chamber: None,
+                    district_type: Some(db::DistrictType::SoilAndWater),
+                    political_scope: db::PoliticalScope::Local,
+                    election_scope: db::ElectionScope::County,
+                },
+            ),
+            (
+                r"(?i:Mayor)",
+                OfficeMeta {
+                    name: "Mayor".into(),
+                    title: "Mayor".into(),
+                    chamber: None,
+                    district_type: None,
+                    political_scope: db::PoliticalScope::Local,
+                    election_scope: db::ElectionScope::City,
+                },
+            ),
+            (
+                r"(?i:Council Member)",
+                OfficeMeta {
+                    name: "City Council".into(),
+                    title: "Council Member".into(),
+                    chamber: None,
+                    district_type: Some(db::DistrictType::City),
+                    political_scope: db::PoliticalScope::Local,
+                    election_scope: db::ElectionScope::City,
+                },
+            ),
+            (
+                r"(?i:School Board Member)",
+                OfficeMeta {
+                    name: "School Board".into(),
+                    title: "School Board Member".into(),
+                    chamber: None,
+                    district_type: Some(db::DistrictType::School),
+                    political_scope: db::PoliticalScope::Local,
+                    election_scope: db::ElectionScope::District,
+                },
+            ),
             (
                 r"(?i:Regional Transportation District Director)",
                 OfficeMeta {
@@ -171,6 +226,7 @@ pub fn extract_office_district(input: &str) -> Option<String> {
     let extractors = DISTRICT_EXTRACTORS.get_or_init(|| {
         [
             r"District (\d+[A-Z]?|[A-Z]+)(?:\W|$)",
+            r"Ward (\d+)(?:\W|$)",
             r"(\d+)(?:st|nd|rd|th) (?:\w+ )?District",
         ]
         .into_iter()
@@ -234,6 +290,10 @@ mod tests {
             ("Appeals", None),
             ("Transportation", None),
             ("Director", None),
+            ("Commissioner", None),
+            ("Soil and Water", None),
+            ("Council", None),
+            ("School Board", None),
             // ----
             ("U.S. Senate", Some("U.S. Senate")),
             ("U.S. Senator", Some("U.S. Senate")),
@@ -259,6 +319,21 @@ mod tests {
                 "Regional Transportation District Director",
                 Some("Regional Transportation District Director"),
             ),
+            (
+                "County Commissioner District 2",
+                Some("County Commissioner"),
+            ),
+            (
+                "Soil and Water Supervisor",
+                Some("Soil and Water Supervisor"),
+            ),
+            (
+                "Soil and Water Conservation District Supervisor",
+                Some("Soil and Water Supervisor"),
+            ),
+            ("Mayor (Minneapolis)", Some("Mayor")),
+            ("Council Member Ward 3 (Minneapolis)", Some("City Council")),
+            ("School Board Member (ISD #625)", Some("School Board")),
         ];
 
         for (input, expected) in tests {
@@ -295,6 +370,10 @@ mod tests {
             ("15th District", Some("15")),
             ("2nd Something District", Some("2")),
             (" 01th Something District ", Some("01")), // TODO weird edge case?
+            // ----
+            ("Ward", None),
+            ("Ward 3", Some("3")),
+            ("Council Member Ward 12 (Minneapolis)", Some("12")),
         ];
 
         for (input, expected) in tests {
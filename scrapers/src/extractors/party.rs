@@ -22,6 +22,14 @@ pub fn extract_party_name(input: &str) -> Option<String> {
             (r"(?i:Unity Party)", "Unity Party"),
             (r"(?i:Forward Party)", "Forward Party"),
             (r"(?i:Approval Voting Party)", "Approval Voting Party"),
+            (r"(?i:Democratic-Farmer-Labor)", "Democratic-Farmer-Labor"),
+            (r"(?i:Green Party)", "Green Party"),
+            (r"(?i:Legal Marijuana Now)", "Legal Marijuana Now"),
+            (
+                r"(?i:Grassroots-Legalize Cannabis)",
+                "Grassroots-Legalize Cannabis",
+            ),
+            (r"(?i:Socialist Workers Party)", "Socialist Workers Party"),
         ]
         .into_iter()
         .map(|t| (Regex::new(t.0).unwrap(), t.1))
@@ -58,6 +66,9 @@ mod tests {
             ("Approval", None),
             ("Voting", None),
             ("Affiliation", None),
+            ("Farmer", None),
+            ("Green", None),
+            ("Cannabis", None),
             // ----
             ("unaffiliated", Some("Unaffiliated")),
             ("no party affiliation", Some("Unaffiliated")),
@@ -72,6 +83,14 @@ mod tests {
             ("unity party", Some("Unity Party")),
             ("forward party", Some("Forward Party")),
             ("approval voting party", Some("Approval Voting Party")),
+            ("democratic-farmer-labor", Some("Democratic-Farmer-Labor")),
+            ("green party", Some("Green Party")),
+            ("legal marijuana now", Some("Legal Marijuana Now")),
+            (
+                "grassroots-legalize cannabis",
+                Some("Grassroots-Legalize Cannabis"),
+            ),
+            ("socialist workers party", Some("Socialist Workers Party")),
         ];
 
         for (input, expected) in tests {
@@ -13,60 +13,207 @@ pub struct PoliticianName {
     pub suffix: Option<String>,
 }
 
-static NAME_EXTRACTORS: OnceLock<Vec<Regex>> = OnceLock::new();
+/// Surname prefixes that belong to the last name, e.g. "De La Cruz" or "Van Buren"
+const SURNAME_PARTICLES: [&str; 20] = [
+    "al", "bin", "da", "das", "de", "del", "della", "den", "der", "di", "dos", "du", "el", "la",
+    "le", "st", "st.", "ter", "van", "von",
+];
 
+/// Parenthesized notes that filings print after a name, which aren't nicknames
+const NAME_ANNOTATIONS: [&str; 4] = ["i", "incumbent", "write-in", "write in"];
+
+static NICKNAME_EXTRACTOR: OnceLock<Regex> = OnceLock::new();
+
+/// Splits a candidate name as printed on a ballot or filing into its parts. Handles quoted and
+/// parenthesized nicknames, generational suffixes, surname particles, all-caps names or words
+/// and "Last, First" ordering.
 pub fn extract_politician_name(input: &str) -> Option<PoliticianName> {
-    let extractors = NAME_EXTRACTORS.get_or_init(|| {
-        // Regular expressions are broken into multiple lines for readability
-        [
-            // Reference: https://regex101.com/r/xKvi7n/3
-            // Adapted from https://regex101.com/library/7zjSTN
-            // FIXME - It's so close!!!
-            [
-                r#"^"#,
-                r#"(?<first>[\w\.']+)"#,
-                r#"(?: *(?<middle1>[\w\.']+)*?)"#,
-                r#"(?: *"(?<preferred>[\w\.' ]+)*")?"#,
-                r#"(?: *(?<middle2>[\w\.']+)*?)"#,
-                r#"(?: *(?<last>[^\s,iIvVxX(?:[jJsS][rR]\.?)]+))"#,
-                r#"(?:,? +(?<suffix>[iIvVxX((?:[jJsS][rR]\.?)]+))?"#,
-                r#"$"#,
-            ],
-        ]
-        .into_iter()
-        .map(|r| Regex::new(&r.join("")).unwrap())
-        .collect()
+    let nickname_extractor = NICKNAME_EXTRACTOR.get_or_init(|| {
+        Regex::new(r#"(?:^|\s)(?:"([^"]+)"|“([^”]+)”|'([^']+)'|\(([^)]+)\))(?:\s|,|$)"#).unwrap()
+    });
+
+    let all_caps = input.chars().any(char::is_alphabetic) && !input.chars().any(char::is_lowercase);
+
+    let mut preferred = None;
+    let input = nickname_extractor.replace_all(input, |captures: &regex::Captures| {
+        if preferred.is_none() {
+            preferred = (1..=4)
+                .find_map(|i| captures.get(i))
+                .map(owned_capture)
+                .map(|nickname| nickname.trim().to_string())
+                .filter(|nickname| !NAME_ANNOTATIONS.contains(&nickname.to_lowercase().as_str()));
+        }
+        // Keep the separator the nickname was attached to
+        match captures
+            .get(0)
+            .map(|m| m.as_str().trim_start().ends_with(','))
+        {
+            Some(true) => ", ",
+            _ => " ",
+        }
     });
 
-    for extractor in extractors {
-        println!("{}", extractor.as_str());
-        if let Some(captures) = extractor.captures(input) {
-            if let Some(first) = captures.name("first").map(owned_capture) {
-                return Some(PoliticianName {
-                    first,
-                    last: captures.name("last").map(owned_capture),
-                    middle: captures
-                        .name("middle2")
-                        .or(captures.name("middle1"))
-                        .map(owned_capture),
-                    preferred: captures.name("preferred").map(owned_capture),
-                    suffix: captures.name("suffix").map(owned_capture),
-                });
-            }
+    let mut parts = input
+        .split(',')
+        .map(|part| part.split_whitespace().collect::<Vec<_>>())
+        .filter(|tokens| !tokens.is_empty())
+        .collect::<Vec<_>>();
+    // A suffix on its own, e.g. "Jr.", isn't a name
+    if parts.iter().flatten().all(|t| is_suffix(t)) {
+        return None;
+    }
+
+    let mut suffix = None;
+    // Trailing comma separated parts made up of suffixes only, e.g. "John Schmidt, Jr."
+    while parts.len() > 1 && parts[parts.len() - 1].iter().all(|t| is_suffix(t)) {
+        let part = parts.pop().unwrap_or_default();
+        suffix = suffix.or(part.first().map(|t| normalize_suffix(t)));
+    }
+
+    // "Schmidt, John J." lists the surname first
+    let (given, surname) = match parts.len() {
+        1 => {
+            let mut tokens = parts.remove(0);
+            suffix = take_suffix(&mut tokens).or(suffix);
+            let split = surname_start(&tokens);
+            let surname = tokens.split_off(split);
+            (tokens, surname)
+        }
+        _ => {
+            let mut surname = parts.remove(0);
+            let mut given = parts.concat();
+            // A lone letter after the given names is a middle initial, e.g. "Smith, John V"
+            let given_suffix = match given.last() {
+                Some(t) if t.trim_end_matches('.').chars().count() == 1 => None,
+                _ => take_suffix(&mut given),
+            };
+            suffix = given_suffix.or(take_suffix(&mut surname)).or(suffix);
+            (given, surname)
+        }
+    };
+
+    // Some filings capitalize only the surname, e.g. "Mary-Kate O'NEIL"
+    let case = |token: &str| match all_caps || is_capitalized_word(token) {
+        true => title_case(token),
+        false => token.to_string(),
+    };
+    let join = |tokens: &[&str]| {
+        (!tokens.is_empty()).then(|| tokens.iter().map(|t| case(t)).collect::<Vec<_>>().join(" "))
+    };
+
+    let (first, middle) = match given.split_first() {
+        Some((first, middle)) => (case(first), join(middle)),
+        // A lone name is treated as a first name
+        None => (join(&surname)?, None),
+    };
+    let last = match given.is_empty() {
+        true => None,
+        false => join(&surname),
+    };
+
+    Some(PoliticianName {
+        first,
+        last,
+        middle,
+        preferred: preferred.map(|p| case(&p)),
+        suffix,
+    })
+}
+
+/// Index of the first surname token, the last token plus any particles in front of it
+fn surname_start(tokens: &[&str]) -> usize {
+    if tokens.len() < 2 {
+        return tokens.len();
+    }
+    let mut start = tokens.len() - 1;
+    while start > 1 && SURNAME_PARTICLES.contains(&tokens[start - 1].to_lowercase().as_str()) {
+        start -= 1;
+    }
+    start
+}
+
+fn take_suffix(tokens: &mut Vec<&str>) -> Option<String> {
+    match tokens.last() {
+        Some(token) if tokens.len() > 1 && is_suffix(token) => {
+            let suffix = normalize_suffix(token);
+            tokens.pop();
+            Some(suffix)
         }
+        _ => None,
     }
-    None
+}
+
+fn is_suffix(token: &str) -> bool {
+    matches!(
+        token.trim_end_matches('.').to_lowercase().as_str(),
+        "jr" | "sr"
+            | "ii"
+            | "iii"
+            | "iv"
+            | "v"
+            | "vi"
+            | "vii"
+            | "viii"
+            | "ix"
+            | "2nd"
+            | "3rd"
+            | "4th"
+    )
+}
+
+fn normalize_suffix(token: &str) -> String {
+    let suffix = token.trim_end_matches('.').to_lowercase();
+    match suffix.as_str() {
+        "jr" => "Jr.".into(),
+        "sr" => "Sr.".into(),
+        "2nd" | "3rd" | "4th" => suffix,
+        _ => suffix.to_uppercase(),
+    }
+}
+
+/// A word in capitals, leaving initials and short acronyms like "JB" alone
+fn is_capitalized_word(token: &str) -> bool {
+    token.chars().filter(|c| c.is_alphabetic()).count() > 2
+        && !token.chars().any(char::is_lowercase)
+}
+
+/// Title cases a word from an all-caps filing, e.g. "O'BRIEN" or "SMITH-MCDONALD"
+fn title_case(token: &str) -> String {
+    let mut word = String::with_capacity(token.len());
+    // The part of the word since the last hyphen or apostrophe
+    let mut segment = String::new();
+    let mut capitalize = true;
+    for c in token.chars() {
+        match capitalize {
+            true => segment.extend(c.to_uppercase()),
+            false => segment.extend(c.to_lowercase()),
+        }
+        capitalize = !c.is_alphabetic() || segment == "Mc";
+        if !c.is_alphabetic() {
+            word.push_str(&segment);
+            segment.clear();
+        }
+    }
+    word + &segment
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[ignore]
     #[test]
     fn extract() {
         let tests: Vec<(&'static str, Option<PoliticianName>)> = vec![
-            //("John", None),
+            ("", None),
+            (" , ", None),
+            ("Jr.", None),
+            (
+                "John",
+                Some(PoliticianName {
+                    first: "John".into(),
+                    ..Default::default()
+                }),
+            ),
             (
                 "John Schmidt",
                 Some(PoliticianName {
@@ -196,7 +343,7 @@ mod tests {
                 Some(PoliticianName {
                     first: "John".into(),
                     last: Some("Schmidt".into()),
-                    suffix: Some("Jr".into()),
+                    suffix: Some("Jr.".into()),
                     ..Default::default()
                 }),
             ),
@@ -205,7 +352,7 @@ mod tests {
                 Some(PoliticianName {
                     first: "John".into(),
                     last: Some("Schmidt".into()),
-                    suffix: Some("sr".into()),
+                    suffix: Some("Sr.".into()),
                     ..Default::default()
                 }),
             ),
@@ -229,4 +376,125 @@ mod tests {
             );
         }
     }
+
+    /// Builds the expected name for a corpus entry, empty strings are treated as missing parts
+    fn name(
+        first: &str,
+        middle: &str,
+        last: &str,
+        preferred: &str,
+        suffix: &str,
+    ) -> PoliticianName {
+        let part = |value: &str| (!value.is_empty()).then(|| value.to_string());
+        PoliticianName {
+            first: first.into(),
+            middle: part(middle),
+            last: part(last),
+            preferred: part(preferred),
+            suffix: part(suffix),
+        }
+    }
+
+    #[test]
+    fn extract_filing_names() {
+        // Names as they appear in MN and CO candidate filings and ballots, followed by the
+        // formats those sources use for the same names
+        #[rustfmt::skip]
+        let tests = vec![
+            // ---- MN SOS candidate filings
+            ("Amy Klobuchar", ("Amy", "", "Klobuchar", "", "")),
+            ("Royce White", ("Royce", "", "White", "", "")),
+            ("Rebecca Whiting", ("Rebecca", "", "Whiting", "", "")),
+            ("Joyce Lacey", ("Joyce", "", "Lacey", "", "")),
+            ("Brad Finstad", ("Brad", "", "Finstad", "", "")),
+            ("Angie Craig", ("Angie", "", "Craig", "", "")),
+            ("Tad Jude", ("Tad", "", "Jude", "", "")),
+            ("Ilhan Omar", ("Ilhan", "", "Omar", "", "")),
+            ("Dalia al-Aqidi", ("Dalia", "", "al-Aqidi", "", "")),
+            ("Betty McCollum", ("Betty", "", "McCollum", "", "")),
+            ("May Lor Xiong", ("May", "Lor", "Xiong", "", "")),
+            ("Tom Emmer", ("Tom", "", "Emmer", "", "")),
+            ("Michelle Fischbach", ("Michelle", "", "Fischbach", "", "")),
+            ("Pete Stauber", ("Pete", "", "Stauber", "", "")),
+            ("Travis \"Bull\" Johnson", ("Travis", "", "Johnson", "Bull", "")),
+            ("Bobby Joe Champion", ("Bobby", "Joe", "Champion", "", "")),
+            ("Tim O'Driscoll", ("Tim", "", "O'Driscoll", "", "")),
+            ("Esther Agbaje", ("Esther", "", "Agbaje", "", "")),
+            ("Fue Lee", ("Fue", "", "Lee", "", "")),
+            ("Robert F. Kennedy Jr.", ("Robert", "F.", "Kennedy", "", "Jr.")),
+            ("Claudia De la Cruz", ("Claudia", "", "De la Cruz", "", "")),
+            ("Kamala D. Harris", ("Kamala", "D.", "Harris", "", "")),
+            ("Donald J. Trump", ("Donald", "J.", "Trump", "", "")),
+            ("J.D. Vance", ("J.D.", "", "Vance", "", "")),
+            ("Timothy James Walz", ("Timothy", "James", "Walz", "", "")),
+            // ---- CO SOS general candidates
+            ("Lauren Boebert", ("Lauren", "", "Boebert", "", "")),
+            ("Trisha Calvarese", ("Trisha", "", "Calvarese", "", "")),
+            ("Gabe Evans", ("Gabe", "", "Evans", "", "")),
+            ("Yadira Caraveo", ("Yadira", "", "Caraveo", "", "")),
+            ("Jeff Hurd", ("Jeff", "", "Hurd", "", "")),
+            ("Diana DeGette", ("Diana", "", "DeGette", "", "")),
+            ("Joe Neguse", ("Joe", "", "Neguse", "", "")),
+            ("Brittany Pettersen", ("Brittany", "", "Pettersen", "", "")),
+            ("Jason Crow", ("Jason", "", "Crow", "", "")),
+            ("Adam Frisch", ("Adam", "", "Frisch", "", "")),
+            ("Chase Oliver", ("Chase", "", "Oliver", "", "")),
+            ("Cornel West", ("Cornel", "", "West", "", "")),
+            // ---- All caps
+            ("AMY KLOBUCHAR", ("Amy", "", "Klobuchar", "", "")),
+            ("BETTY MCCOLLUM", ("Betty", "", "McCollum", "", "")),
+            ("TIM O'DRISCOLL", ("Tim", "", "O'Driscoll", "", "")),
+            ("DALIA AL-AQIDI", ("Dalia", "", "Al-Aqidi", "", "")),
+            ("J.D. VANCE", ("J.D.", "", "Vance", "", "")),
+            ("TRAVIS \"BULL\" JOHNSON", ("Travis", "", "Johnson", "Bull", "")),
+            ("ROBERT F. KENNEDY JR", ("Robert", "F.", "Kennedy", "", "Jr.")),
+            ("CLAUDIA DE LA CRUZ", ("Claudia", "", "De La Cruz", "", "")),
+            // ---- Last, First
+            ("Klobuchar, Amy", ("Amy", "", "Klobuchar", "", "")),
+            ("KLOBUCHAR, AMY", ("Amy", "", "Klobuchar", "", "")),
+            ("MCCOLLUM, BETTY LOUISE", ("Betty", "Louise", "McCollum", "", "")),
+            ("Trump, Donald J.", ("Donald", "J.", "Trump", "", "")),
+            ("KENNEDY, ROBERT F. JR.", ("Robert", "F.", "Kennedy", "", "Jr.")),
+            ("Kennedy Jr., Robert F.", ("Robert", "F.", "Kennedy", "", "Jr.")),
+            ("Kennedy, Robert F., Jr.", ("Robert", "F.", "Kennedy", "", "Jr.")),
+            ("De la Cruz, Claudia", ("Claudia", "", "De la Cruz", "", "")),
+            ("Johnson, Travis \"Bull\"", ("Travis", "", "Johnson", "Bull", "")),
+            // ---- Nicknames
+            ("Robert (Bob) Smith", ("Robert", "", "Smith", "Bob", "")),
+            ("Robert “Bob” Smith", ("Robert", "", "Smith", "Bob", "")),
+            ("Robert 'Bob' O'Brien", ("Robert", "", "O'Brien", "Bob", "")),
+            ("Robert \"Bobby Jo\" Smith", ("Robert", "", "Smith", "Bobby Jo", "")),
+            // ---- Suffixes
+            ("John Smith III", ("John", "", "Smith", "", "III")),
+            ("John Smith, III", ("John", "", "Smith", "", "III")),
+            ("John Smith Sr", ("John", "", "Smith", "", "Sr.")),
+            ("John Smith, SR.", ("John", "", "Smith", "", "Sr.")),
+            ("John Smith 2nd", ("John", "", "Smith", "", "2nd")),
+            ("John Smith iv", ("John", "", "Smith", "", "IV")),
+            // ---- Compound surnames
+            ("Martin Van Buren", ("Martin", "", "Van Buren", "", "")),
+            ("Maria de la Cruz", ("Maria", "", "de la Cruz", "", "")),
+            ("Ludwig von Mises", ("Ludwig", "", "von Mises", "", "")),
+            ("Anna Maria della Rovere", ("Anna", "Maria", "della Rovere", "", "")),
+            ("Maria Garcia-Lopez", ("Maria", "", "Garcia-Lopez", "", "")),
+            ("John St. John", ("John", "", "St. John", "", "")),
+            ("Van Morrison", ("Van", "", "Morrison", "", "")),
+            // ---- Mixed up formatting
+            ("Smith, John V", ("John", "V", "Smith", "", "")),
+            ("John Smith V", ("John", "", "Smith", "", "V")),
+            ("JANE SMITH-MCDONALD", ("Jane", "", "Smith-McDonald", "", "")),
+            ("John Smith (Incumbent)", ("John", "", "Smith", "", "")),
+            ("Mary-Kate O'NEIL", ("Mary-Kate", "", "O'Neil", "", "")),
+            ("JB Pritzker", ("JB", "", "Pritzker", "", "")),
+        ];
+
+        for (input, expected) in tests {
+            let expected = name(expected.0, expected.1, expected.2, expected.3, expected.4);
+            assert_eq!(
+                extract_politician_name(input),
+                Some(expected),
+                "\n\n  Test Case: '{input}'\n"
+            );
+        }
+    }
 }
@@ -3,6 +3,7 @@ use slugify::slugify;
 pub struct OfficeSubtitleGenerator<'a> {
     pub state: &'a db::State,
     pub county: Option<&'a str>,
+    pub municipality: Option<&'a str>,
     pub district: Option<&'a str>,
     pub seat: Option<&'a str>,
 }
@@ -11,6 +12,10 @@ impl<'a> OfficeSubtitleGenerator<'a> {
     pub fn generate(&self) -> (String, String) {
         let mut subtitle = self.state.to_string();
         let mut subtitle_short = self.state.to_string();
+        if let Some(municipality) = self.municipality {
+            subtitle = format!("{}, {}", municipality, subtitle);
+            subtitle_short = format!("{}, {}", municipality, subtitle_short);
+        }
         if let Some(county) = self.county {
             subtitle = format!("{}, {}", county, subtitle);
             subtitle_short = format!("{}, {}", county, subtitle_short);
@@ -35,6 +40,7 @@ pub struct OfficeSlugGenerator<'a> {
     pub state: &'a db::State,
     pub name: &'a str,
     pub county: Option<&'a str>,
+    pub municipality: Option<&'a str>,
     pub district: Option<&'a str>,
     pub seat: Option<&'a str>,
 }
@@ -42,11 +48,12 @@ pub struct OfficeSlugGenerator<'a> {
 impl<'a> OfficeSlugGenerator<'a> {
     pub fn generate(&self) -> String {
         let format = format!(
-            "{} {} {} {} {} {}",
+            "{} {} {} {} {} {} {}",
             self.state.as_ref(),
             self.name.replace(".", ""),
             self.county.unwrap_or_default(),
             self.county.as_ref().map(|_| "county").unwrap_or_default(),
+            self.municipality.unwrap_or_default(),
             self.district.unwrap_or_default(),
             self.seat.unwrap_or_default(),
         );
@@ -67,6 +74,7 @@ mod tests {
                     name: "U.S. Senate",
                     state: &db::State::CO,
                     county: None,
+                    municipality: None,
                     district: Some("1"),
                     seat: None,
                 },
@@ -77,6 +85,7 @@ mod tests {
                     name: "District Something",
                     state: &db::State::CO,
                     county: None,
+                    municipality: None,
                     district: None,
                     seat: Some("At Large"),
                 },
@@ -87,6 +96,7 @@ mod tests {
                     name: "District Something",
                     state: &db::State::CO,
                     county: None,
+                    municipality: None,
                     district: None,
                     seat: None,
                 },
@@ -97,6 +107,7 @@ mod tests {
                     name: "County Judge",
                     state: &db::State::CO,
                     county: Some("Adams"),
+                    municipality: None,
                     district: None,
                     seat: None,
                 },
@@ -107,6 +118,7 @@ mod tests {
                     name: "County Judge",
                     state: &db::State::CO,
                     county: None,
+                    municipality: None,
                     district: None,
                     seat: None,
                 },
@@ -117,6 +129,7 @@ mod tests {
                     name: "Court of Appeals Judge",
                     state: &db::State::CO,
                     county: None,
+                    municipality: None,
                     district: None,
                     seat: None,
                 },
@@ -127,10 +140,22 @@ mod tests {
                     name: "County Commissioner",
                     state: &db::State::CO,
                     county: Some("Adams"),
+                    municipality: None,
                     district: Some("1"),
                     seat: Some("2"),
                 },
             ),
+            (
+                "mn-mayor-minneapolis",
+                OfficeSlugGenerator {
+                    name: "Mayor",
+                    state: &db::State::MN,
+                    county: None,
+                    municipality: Some("Minneapolis"),
+                    district: None,
+                    seat: None,
+                },
+            ),
         ];
 
         for (expected, generator) in tests {
@@ -146,6 +171,7 @@ mod tests {
                 OfficeSubtitleGenerator {
                     state: &db::State::CO,
                     county: None,
+                    municipality: None,
                     district: None,
                     seat: None,
                 },
@@ -155,6 +181,7 @@ mod tests {
                 OfficeSubtitleGenerator {
                     state: &db::State::CO,
                     county: Some("Adams"),
+                    municipality: None,
                     district: None,
                     seat: None,
                 },
@@ -164,6 +191,7 @@ mod tests {
                 OfficeSubtitleGenerator {
                     state: &db::State::CO,
                     county: None,
+                    municipality: None,
                     district: Some("1"),
                     seat: None,
                 },
@@ -173,6 +201,7 @@ mod tests {
                 OfficeSubtitleGenerator {
                     state: &db::State::CO,
                     county: None,
+                    municipality: None,
                     district: None,
                     seat: Some("2"),
                 },
@@ -182,6 +211,7 @@ mod tests {
                 OfficeSubtitleGenerator {
                     state: &db::State::CO,
                     county: None,
+                    municipality: None,
                     district: None,
                     seat: Some("At Large"),
                 },
@@ -191,10 +221,21 @@ mod tests {
                 OfficeSubtitleGenerator {
                     state: &db::State::CO,
                     county: Some("Adams"),
+                    municipality: None,
                     district: Some("1"),
                     seat: Some("2"),
                 },
             ),
+            (
+                ("Minneapolis, MN - District 3", "Minneapolis, MN - 3"),
+                OfficeSubtitleGenerator {
+                    state: &db::State::MN,
+                    county: None,
+                    municipality: Some("Minneapolis"),
+                    district: Some("3"),
+                    seat: None,
+                },
+            ),
         ];
 
         for (expected, generator) in tests {
@@ -12,6 +12,7 @@ pub struct RaceTitleGenerator<'a> {
     pub office_name: Option<&'a str>,
     pub state: Option<&'a db::State>,
     pub county: Option<&'a str>,
+    pub municipality: Option<&'a str>,
     pub district: Option<&'a str>,
     pub seat: Option<&'a str>,
     pub year: i32,
@@ -29,6 +30,7 @@ impl<'a> RaceTitleGenerator<'a> {
             office_name: office.name.as_str(),
             state: office.state.as_ref(),
             county: office.county.as_str(),
+            municipality: office.municipality.as_str(),
             district: office.district.as_str(),
             seat: office.seat.as_str(),
             year: election.election_date.year(),
@@ -36,21 +38,23 @@ impl<'a> RaceTitleGenerator<'a> {
     }
 
     pub fn generate(&self) -> (String, String) {
-        let qualifier = match self.election_scope {
+        let place = match self.election_scope {
             db::ElectionScope::County => (self.county.unwrap_or_default(), "County"),
-            _ => {
-                if self.district.is_some() {
-                    ("District", self.district.unwrap_or_default())
-                } else {
-                    ("", self.seat.unwrap_or_default())
-                }
-            }
+            db::ElectionScope::City => (self.municipality.unwrap_or_default(), ""),
+            _ => ("", ""),
+        };
+        let qualifier = if self.district.is_some() {
+            ("District", self.district.unwrap_or_default())
+        } else {
+            ("", self.seat.unwrap_or_default())
         };
 
         let title = format!(
-            "{} {} {} {} {} {}",
+            "{} {} {} {} {} {} {} {}",
             super::optional_state_str(self.state),
             self.office_name.unwrap_or_default(),
+            place.0,
+            place.1,
             qualifier.0,
             qualifier.1,
             self.race_type,
@@ -83,6 +87,7 @@ mod tests {
                     office_name: Some("Supreme Court Justice"),
                     state: Some(&db::State::CO),
                     county: None,
+                    municipality: None,
                     district: None,
                     seat: None,
                     year: 2024,
@@ -99,6 +104,7 @@ mod tests {
                     office_name: Some("U.S. House"),
                     state: Some(&db::State::CO),
                     county: None,
+                    municipality: None,
                     district: Some("1"),
                     seat: None,
                     year: 2024,
@@ -115,6 +121,7 @@ mod tests {
                     office_name: Some("Board of Regents"),
                     state: Some(&db::State::CO),
                     county: None,
+                    municipality: None,
                     district: None,
                     seat: Some("At Large"),
                     year: 2024,
@@ -131,11 +138,46 @@ mod tests {
                     office_name: Some("County Court Judge"),
                     state: Some(&db::State::CO),
                     county: Some("Adams"),
+                    municipality: None,
                     district: None,
                     seat: None,
                     year: 2024,
                 },
             ),
+            (
+                (
+                    "MN County Commissioner Hennepin County District 2 General 2024",
+                    "mn-county-commissioner-hennepin-county-district-2-general-2024",
+                ),
+                RaceTitleGenerator {
+                    race_type: &db::RaceType::General,
+                    election_scope: &db::ElectionScope::County,
+                    office_name: Some("County Commissioner"),
+                    state: Some(&db::State::MN),
+                    county: Some("Hennepin"),
+                    municipality: None,
+                    district: Some("2"),
+                    seat: None,
+                    year: 2024,
+                },
+            ),
+            (
+                (
+                    "MN City Council Minneapolis District 3 General 2024",
+                    "mn-city-council-minneapolis-district-3-general-2024",
+                ),
+                RaceTitleGenerator {
+                    race_type: &db::RaceType::General,
+                    election_scope: &db::ElectionScope::City,
+                    office_name: Some("City Council"),
+                    state: Some(&db::State::MN),
+                    county: None,
+                    municipality: Some("Minneapolis"),
+                    district: Some("3"),
+                    seat: None,
+                    year: 2024,
+                },
+            ),
         ];
 
         for (expected, generator) in tests {
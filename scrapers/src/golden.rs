@@ -0,0 +1,273 @@
+//! Golden file tests for scrapers. A scraper is run against its checked-in fixture inside a
+//! throwaway schema, and the offices, races, parties and candidates it writes are compared
+//! against a normalized JSON snapshot under `scrapers/golden`. The tests need a migrated
+//! database so they're ignored by default, run them with `cargo test -p scrapers -- --ignored`
+//! and add `UPDATE_GOLDEN=1` to rewrite the snapshots after an intended change.
+
+use std::{collections::BTreeMap, error::Error, fs, sync::OnceLock};
+
+use project_root::get_project_root;
+use regex::Regex;
+use serde_json::{json, Map, Value};
+use sqlx::postgres::{PgPool, PgPoolOptions};
+
+use crate::{RunMode, RunReport, Scraper, ScraperContext};
+
+/// Tables a scraper run writes to, copied empty with their triggers into the ephemeral schema
+const TABLES: [&str; 11] = [
+    "election",
+    "office",
+    "race",
+    "party",
+    "politician",
+    "race_candidates",
+    "external_id",
+    "slug_history",
+    "scraper_run",
+    "scraper_run_error",
+    "scraper_proposed_change",
+];
+
+/// Runs `scraper` against its local fixture and asserts the result matches `golden/{name}.json`
+pub async fn assert_golden(scraper: impl Scraper, name: &str) {
+    let path = get_project_root()
+        .unwrap()
+        .join("scrapers/golden")
+        .join(format!("{name}.json"));
+    let actual = snapshot(scraper).await.unwrap();
+
+    if std::env::var("UPDATE_GOLDEN").is_ok() {
+        fs::write(&path, &actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        expected == actual,
+        "\n\n  Scraper output no longer matches {}\n  Re-run with UPDATE_GOLDEN=1 and review the diff if this change is intended\n\n{}",
+        path.display(),
+        line_diff(&expected, &actual)
+    );
+}
+
+async fn snapshot(scraper: impl Scraper) -> Result<String, Box<dyn Error>> {
+    let admin = db::DatabasePool::new().await?.connection;
+    let schema = format!("scraper_golden_{}", uuid::Uuid::new_v4().simple());
+    create_schema(&admin, &schema).await?;
+
+    let result = async {
+        let options = (*admin.connect_options())
+            .clone()
+            .options([("search_path", format!("{schema},public"))]);
+        let pool = PgPoolOptions::new()
+            .max_connections(2)
+            .connect_with(options)
+            .await?;
+        let context = ScraperContext {
+            db: &db::DatabasePool {
+                connection: pool.clone(),
+            },
+            mode: RunMode::Live,
+        };
+        let report = scraper.run_local(&context).await?;
+        let snapshot = read_snapshot(&pool, &report).await?;
+        pool.close().await;
+        Ok::<_, Box<dyn Error>>(snapshot)
+    }
+    .await;
+
+    sqlx::query(&format!("DROP SCHEMA {schema} CASCADE"))
+        .execute(&admin)
+        .await?;
+
+    Ok(serde_json::to_string_pretty(&result?)? + "\n")
+}
+
+async fn create_schema(pool: &PgPool, schema: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(&format!("CREATE SCHEMA {schema}"))
+        .execute(pool)
+        .await?;
+    for table in TABLES {
+        sqlx::query(&format!(
+            "CREATE TABLE {schema}.{table} (LIKE public.{table} INCLUDING ALL)"
+        ))
+        .execute(pool)
+        .await?;
+    }
+
+    // `LIKE` doesn't copy triggers. The trigger functions use unqualified table names, so with
+    // the ephemeral schema first on the search path they write to its copies, e.g. of
+    // `external_id` and `slug_history`.
+    let triggers = sqlx::query_as::<_, (String, String)>(
+        r#"
+        SELECT c.relname::text, pg_get_triggerdef(t.oid)
+        FROM pg_trigger t
+        JOIN pg_class c ON c.oid = t.tgrelid
+        WHERE c.relnamespace = 'public'::regnamespace
+        AND c.relname = ANY($1)
+        AND NOT t.tgisinternal
+        "#,
+    )
+    .bind(TABLES)
+    .fetch_all(pool)
+    .await?;
+    for (table, definition) in triggers {
+        let definition = definition.replace(
+            &format!(" ON public.{table} "),
+            &format!(" ON {schema}.{table} "),
+        );
+        sqlx::query(&definition).execute(pool).await?;
+    }
+    Ok(())
+}
+
+async fn read_snapshot(pool: &PgPool, report: &RunReport) -> Result<Value, sqlx::Error> {
+    // Foreign keys are swapped for the slugs they point at so snapshots don't depend on ids
+    let queries = [
+        (
+            "elections",
+            "SELECT to_jsonb(e) FROM election e ORDER BY e.slug",
+        ),
+        (
+            "offices",
+            "SELECT to_jsonb(o) FROM office o ORDER BY o.slug",
+        ),
+        ("parties", "SELECT to_jsonb(p) FROM party p ORDER BY p.slug"),
+        (
+            "races",
+            r#"
+            SELECT to_jsonb(r) || jsonb_build_object('office', o.slug, 'election', e.slug)
+            FROM race r
+            LEFT JOIN office o ON o.id = r.office_id
+            LEFT JOIN election e ON e.id = r.election_id
+            ORDER BY r.slug
+            "#,
+        ),
+        (
+            "candidates",
+            r#"
+            SELECT to_jsonb(p) || jsonb_build_object(
+                'party', pa.slug,
+                'candidacies', (
+                    SELECT jsonb_agg(to_jsonb(rc) || jsonb_build_object('race', r.slug) ORDER BY r.slug)
+                    FROM race_candidates rc
+                    JOIN race r ON r.id = rc.race_id
+                    WHERE rc.candidate_id = p.id
+                )
+            )
+            FROM politician p
+            LEFT JOIN party pa ON pa.id = p.party_id
+            ORDER BY p.ref_key, p.slug
+            "#,
+        ),
+    ];
+
+    let mut snapshot = Map::new();
+    for (name, sql) in queries {
+        let records = sqlx::query_scalar::<_, Value>(sql)
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .map(normalize)
+            .collect();
+        snapshot.insert(name.to_string(), Value::Array(records));
+    }
+
+    let errors = report
+        .errors
+        .iter()
+        .map(|e| {
+            json!({
+                "entity_type": e.entity_type.map(|t| t.to_string()),
+                "source_ref": e.source_ref.as_deref().map(mask_uuids),
+                "message": mask_uuids(&e.message),
+            })
+        })
+        .collect();
+    snapshot.insert("errors".to_string(), Value::Array(errors));
+    snapshot.insert("stats".to_string(), report.stats());
+    Ok(normalize(Value::Object(snapshot)))
+}
+
+/// Drops ids, timestamps and null fields and sorts object keys, so only values a scraper
+/// actually wrote show up in a snapshot
+fn normalize(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let fields = map
+                .into_iter()
+                .filter(|(key, value)| {
+                    !(value.is_null()
+                        || key == "id"
+                        || key.ends_with("_at")
+                        || (key.ends_with("_id") && value.as_str().map(is_uuid).unwrap_or(false)))
+                })
+                .map(|(key, value)| (key, normalize(value)))
+                .collect::<BTreeMap<_, _>>();
+            Value::Object(fields.into_iter().collect())
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(normalize).collect()),
+        value => value,
+    }
+}
+
+fn uuid_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r"[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}").unwrap()
+    })
+}
+
+fn is_uuid(value: &str) -> bool {
+    uuid::Uuid::parse_str(value).is_ok()
+}
+
+fn mask_uuids(value: &str) -> String {
+    uuid_regex().replace_all(value, "<uuid>").to_string()
+}
+
+/// Lines that differ between two snapshots, enough to spot the regression in test output
+fn line_diff(expected: &str, actual: &str) -> String {
+    let expected = expected.lines().collect::<Vec<_>>();
+    let actual = actual.lines().collect::<Vec<_>>();
+    let mut diff = vec![];
+    for index in 0..expected.len().max(actual.len()) {
+        match (expected.get(index), actual.get(index)) {
+            (Some(e), Some(a)) if e == a => {}
+            (e, a) => {
+                if let Some(e) = e {
+                    diff.push(format!("{:>5} - {}", index + 1, e));
+                }
+                if let Some(a) = a {
+                    diff.push(format!("{:>5} + {}", index + 1, a));
+                }
+            }
+        }
+        if diff.len() >= 40 {
+            diff.push("  ...".to_string());
+            break;
+        }
+    }
+    diff.join("\n")
+}
+
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let record = json!({
+            "id": "0f0e6a52-3f43-4c1a-8c8e-0d6d9b1f7d0a",
+            "slug": "mn-us-senate",
+            "party_id": "0f0e6a52-3f43-4c1a-8c8e-0d6d9b1f7d0a",
+            "votesmart_candidate_id": 1234,
+            "created_at": "2024-01-01T00:00:00Z",
+            "district": null,
+            "county": "Hennepin",
+        });
+        assert_eq!(
+            serde_json::to_string(&normalize(record)).unwrap(),
+            r#"{"county":"Hennepin","slug":"mn-us-senate","votesmart_candidate_id":1234}"#
+        );
+    }
+}
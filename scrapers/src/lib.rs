@@ -2,17 +2,46 @@ use std::{error::Error, future::Future};
 
 pub mod extractors;
 pub mod generators;
-pub mod mn_sos_candidate_filings_fed_state_county;
-pub mod mn_sos_candidate_filings_local;
+#[cfg(test)]
+mod golden;
 pub mod mn_sos_results;
+pub mod rcv_results;
+pub mod tracking;
 pub mod util;
 
 mod scrapers;
 
 pub use scrapers::*;
+pub use tracking::RunReport;
 
 pub struct ScraperContext<'a> {
     pub db: &'a db::DatabasePool,
+    pub mode: RunMode,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RunMode {
+    /// Write scraped data straight to the database
+    #[default]
+    Live,
+    /// Compute the changes a run would make without writing them
+    DryRun,
+    /// Store the changes a run would make as proposed changes for staff review
+    Staged,
+}
+
+impl RunMode {
+    /// Reads the mode from the `--dry-run` and `--staged` command line flags
+    pub fn from_args() -> Self {
+        let args = std::env::args().collect::<Vec<_>>();
+        if args.iter().any(|arg| arg == "--dry-run") {
+            RunMode::DryRun
+        } else if args.iter().any(|arg| arg == "--staged") {
+            RunMode::Staged
+        } else {
+            RunMode::Live
+        }
+    }
 }
 
 pub trait Scraper {
@@ -20,9 +49,9 @@ pub trait Scraper {
     fn run(
         &self,
         context: &ScraperContext,
-    ) -> impl Future<Output = Result<(), Box<dyn Error>>> + Send;
+    ) -> impl Future<Output = Result<RunReport, Box<dyn Error>>> + Send;
     fn run_local(
         &self,
         context: &ScraperContext,
-    ) -> impl Future<Output = Result<(), Box<dyn Error>>> + Send;
+    ) -> impl Future<Output = Result<RunReport, Box<dyn Error>>> + Send;
 }
@@ -1,8 +1,15 @@
 use csv::ReaderBuilder;
-use reqwest::Client;
+use db::models::enums::State;
+use db::{
+    BallotMeasure, BallotMeasureResultRow, PrecinctRaceResult, PrecinctResultRow,
+    PrecinctStatisticsRow, RaceResultsSnapshot,
+};
+use reqwest::{header, Client, StatusCode};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::error::Error;
 use std::fs::File;
+use std::sync::Mutex;
 
 static HEADER_NAMES: [&str; 16] = [
     "State",
@@ -38,6 +45,25 @@ static PRECINCT_STATS_HEADER_NAMES: [&str; 12] = [
     "Total Number Voted",
 ];
 
+/// Every race on the ballot by precinct. These files use the results layout with the
+/// precinct's code in the "Precinct name" column.
+static PRECINCT_RESULTS_URL: &str =
+    "https://electionresultsfiles.sos.state.mn.us/20241105/allracesbyprecinct.txt";
+static PRECINCT_STATS_URL: &str =
+    "https://electionresultsfiles.sos.state.mn.us/20241105/pctstats.txt";
+static PRECINCT_RESULTS_ELECTION_SLUG: &str = "general-election-2024";
+
+/// Constitutional amendments, with "YES" and "NO" as candidates
+static AMENDMENTS_URL: &str =
+    "https://electionresultsfiles.sos.state.mn.us/20241105/amendments.txt";
+
+/// Files that list ballot questions alongside races, with "YES" and "NO" as candidates
+static QUESTION_FILES: [&str; 3] = [
+    "County Races",
+    "Municipal Races and Questions",
+    "School Board Races",
+];
+
 pub async fn fetch_results() -> Result<(), Box<dyn Error>> {
     let mut results_file_paths: HashMap<&str, &str> = HashMap::new();
     results_file_paths.insert(
@@ -74,8 +100,13 @@ pub async fn fetch_results() -> Result<(), Box<dyn Error>> {
     );
 
     let client = Client::new();
+    let mut question_results = vec![];
+    let mut source_files = SourceFiles::default();
     for (name, url) in results_file_paths {
         let response = client.get(url).send().await?.text().await?;
+        if QUESTION_FILES.contains(&name) {
+            question_results.extend(parse_question_results(&response));
+        }
         let data = convert_text_to_csv(name, &response);
         let csv_data_as_string = String::from_utf8(data.clone())?;
         let table_name = format!(
@@ -84,6 +115,11 @@ pub async fn fetch_results() -> Result<(), Box<dyn Error>> {
                 .replace(' ', "_")
                 .to_lowercase()
         );
+        source_files.table_names.push(table_name.clone());
+        source_files.urls.push(url.to_string());
+        source_files
+            .hashes
+            .push(format!("{:x}", Sha256::digest(response.as_bytes())));
         let copy_query = format!("COPY {} FROM STDIN WITH CSV HEADER;", table_name);
         let pool = db::pool().await;
         sqlx::query(format!(r#"DROP TABLE IF EXISTS {} CASCADE;"#, table_name).as_str())
@@ -101,11 +137,254 @@ pub async fn fetch_results() -> Result<(), Box<dyn Error>> {
         tx_copy.finish().await?;
         // _write_to_csv_file(name, &data)?;
     }
-    update_public_schema_with_results().await;
+    let amendments = client.get(AMENDMENTS_URL).send().await?.text().await?;
+    question_results.extend(parse_question_results(&amendments));
+
+    update_public_schema_with_results(&source_files).await?;
+    update_ballot_measures_with_results(&question_results).await?;
+    fetch_precinct_results().await?;
 
     Ok(())
 }
 
+/// Ballot measures are matched on `ballot_measure_code`, which should be the question's office
+/// name as the SoS reports it, e.g. "City Question 1 (Minneapolis)"
+async fn update_ballot_measures_with_results(
+    question_results: &[BallotMeasureResultRow],
+) -> Result<(), Box<dyn Error>> {
+    let db_pool = &db::pool().await.connection;
+    let election_id = sqlx::query_scalar!(
+        "SELECT id FROM election WHERE slug = $1",
+        PRECINCT_RESULTS_ELECTION_SLUG
+    )
+    .fetch_one(db_pool)
+    .await?;
+
+    let matched = BallotMeasure::update_results(db_pool, election_id, question_results).await?;
+    println!(
+        "Updated results for {} of {} ballot questions",
+        matched.len(),
+        question_results.len()
+    );
+    Ok(())
+}
+
+/// A version of a file this process imported, to tell whether the next poll has changed
+#[derive(Debug, Clone)]
+struct FileVersion {
+    url: &'static str,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    hash: String,
+}
+
+static IMPORTED_FILES: Mutex<Vec<FileVersion>> = Mutex::new(vec![]);
+
+/// Downloads a file unless it's the version last imported. The server is asked with the
+/// version's ETag and Last-Modified, and the contents are compared when it doesn't say.
+async fn fetch_if_changed(
+    client: &Client,
+    url: &'static str,
+) -> Result<Option<(String, FileVersion)>, reqwest::Error> {
+    let imported = IMPORTED_FILES
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|f| f.url == url)
+        .cloned();
+
+    let mut request = client.get(url);
+    if let Some(imported) = &imported {
+        if let Some(etag) = &imported.etag {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &imported.last_modified {
+            request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+    let response = request.send().await?.error_for_status()?;
+    if response.status() == StatusCode::NOT_MODIFIED {
+        return Ok(None);
+    }
+
+    let header_value = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v: &header::HeaderValue| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let etag = header_value(header::ETAG);
+    let last_modified = header_value(header::LAST_MODIFIED);
+    let text = response.text().await?;
+    let hash = format!("{:x}", Sha256::digest(text.as_bytes()));
+    if imported.is_some_and(|f| f.hash == hash) {
+        return Ok(None);
+    }
+    Ok(Some((
+        text,
+        FileVersion {
+            url,
+            etag,
+            last_modified,
+            hash,
+        },
+    )))
+}
+
+/// Remembers a file's version once it's been imported
+fn imported(version: FileVersion) {
+    let mut files = IMPORTED_FILES.lock().unwrap();
+    files.retain(|f| f.url != version.url);
+    files.push(version);
+}
+
+/// Stores results and turnout for every precinct, for `Race.precinctResults` and result maps.
+/// Files unchanged since they were last imported are skipped.
+pub async fn fetch_precinct_results() -> Result<(), Box<dyn Error>> {
+    let client = Client::new();
+    let db_pool = &db::pool().await.connection;
+    let election_id = sqlx::query_scalar!(
+        "SELECT id FROM election WHERE slug = $1",
+        PRECINCT_RESULTS_ELECTION_SLUG
+    )
+    .fetch_one(db_pool)
+    .await?;
+
+    match fetch_if_changed(&client, PRECINCT_STATS_URL).await? {
+        Some((statistics, version)) => {
+            let statistics = parse_precinct_statistics(&statistics);
+            PrecinctRaceResult::import_statistics(db_pool, election_id, State::MN, &statistics)
+                .await?;
+            imported(version);
+            println!("Stored turnout for {} precincts", statistics.len());
+        }
+        None => println!("Precinct turnout is unchanged"),
+    }
+
+    match fetch_if_changed(&client, PRECINCT_RESULTS_URL).await? {
+        Some((results, version)) => {
+            let results = parse_precinct_results(&results);
+            let stored =
+                PrecinctRaceResult::import(db_pool, election_id, State::MN, &results).await?;
+            imported(version);
+            println!("Stored {} precinct results", stored);
+        }
+        None => println!("Precinct results are unchanged"),
+    }
+    Ok(())
+}
+
+fn semicolon_records(text: &str, fields: usize) -> Vec<csv::StringRecord> {
+    ReaderBuilder::new()
+        .has_headers(false)
+        .delimiter(b';')
+        .flexible(true)
+        .from_reader(text.as_bytes())
+        .records()
+        .filter_map(Result::ok)
+        .filter(|record| record.len() == fields)
+        .collect()
+}
+
+fn count(value: &str) -> Option<i32> {
+    value.trim().parse().ok()
+}
+
+pub fn parse_precinct_results(text: &str) -> Vec<PrecinctResultRow> {
+    semicolon_records(text, HEADER_NAMES.len())
+        .into_iter()
+        .filter_map(|record| {
+            let office_name = record[4].trim();
+            // Ranked choice offices repeat for every choice, first choices are the votes cast
+            let is_later_choice = office_name.to_lowercase().contains("choice")
+                && !office_name.to_lowercase().contains("first choice");
+            if record[2].trim().is_empty() || is_later_choice {
+                return None;
+            }
+            Some(PrecinctResultRow {
+                county_code: record[1].trim().to_string(),
+                precinct_code: record[2].trim().to_string(),
+                office_name: office_name.to_string(),
+                candidate_name: record[7].trim().to_string(),
+                votes: count(&record[13])?,
+                total_votes: count(&record[15]),
+            })
+        })
+        .collect()
+}
+
+/// Totals yes and no votes for each question or constitutional amendment in a results file.
+/// Questions voted on in several counties are reported once per county, so their precincts
+/// are added up across counties.
+pub fn parse_question_results(text: &str) -> Vec<BallotMeasureResultRow> {
+    let mut results: Vec<BallotMeasureResultRow> = vec![];
+    let mut counted_counties: Vec<(String, String)> = vec![];
+    for record in semicolon_records(text, HEADER_NAMES.len()) {
+        let office_name = record[4].trim();
+        let lowercase_name = office_name.to_lowercase();
+        if !lowercase_name.contains("question") && !lowercase_name.contains("amendment") {
+            continue;
+        }
+        let votes = count(&record[13]).unwrap_or(0);
+        let index = match results
+            .iter()
+            .position(|r| r.ballot_measure_code == office_name)
+        {
+            Some(index) => index,
+            None => {
+                results.push(BallotMeasureResultRow {
+                    ballot_measure_code: office_name.to_string(),
+                    yes_votes: 0,
+                    no_votes: 0,
+                    num_precincts_reporting: None,
+                    total_precincts: None,
+                });
+                results.len() - 1
+            }
+        };
+        let result = &mut results[index];
+
+        // The yes and no rows for a county repeat its precinct counts
+        let county = (office_name.to_string(), record[1].trim().to_string());
+        if !counted_counties.contains(&county) {
+            counted_counties.push(county);
+            let add = |total: Option<i32>, value: Option<i32>| match (total, value) {
+                (Some(total), Some(value)) => Some(total + value),
+                (total, value) => total.or(value),
+            };
+            result.num_precincts_reporting =
+                add(result.num_precincts_reporting, count(&record[11]));
+            result.total_precincts = add(result.total_precincts, count(&record[12]));
+        }
+        match record[7].trim().to_uppercase().as_str() {
+            "YES" => result.yes_votes += votes,
+            "NO" => result.no_votes += votes,
+            _ => {}
+        }
+    }
+    results
+}
+
+pub fn parse_precinct_statistics(text: &str) -> Vec<PrecinctStatisticsRow> {
+    semicolon_records(text, PRECINCT_STATS_HEADER_NAMES.len())
+        .into_iter()
+        .map(|record| PrecinctStatisticsRow {
+            county_code: record[1].trim().to_string(),
+            precinct_code: record[2].trim().to_string(),
+            precinct_name: Some(record[3].trim().to_string()).filter(|n| !n.is_empty()),
+            has_reported: record[4].trim() == "1",
+            registered_voters: count(&record[5]),
+            election_day_registrations: count(&record[6]),
+            roster_signatures: count(&record[7]),
+            military_overseas_absentee_ballots: count(&record[8]),
+            federal_only_absentee_ballots: count(&record[9]),
+            president_only_absentee_ballots: count(&record[10]),
+            total_voted: count(&record[11]),
+        })
+        .collect()
+}
+
 fn get_create_table_query(name: &str, table_name: &str) -> String {
     if name == "Precinct Reporting Statistics" {
         return format!(
@@ -154,8 +433,13 @@ fn convert_text_to_csv(name: &str, text: &str) -> Vec<u8> {
                 continue;
             }
             let record = result.unwrap();
-            // test to ensure record has 16 parts
-            if record.len() != 16 {
+            // test to ensure record has every column
+            let expected = if name == "Precinct Reporting Statistics" {
+                PRECINCT_STATS_HEADER_NAMES.len()
+            } else {
+                HEADER_NAMES.len()
+            };
+            if record.len() != expected {
                 continue;
             }
             wtr.write_record(&record)
@@ -167,31 +451,46 @@ fn convert_text_to_csv(name: &str, text: &str) -> Vec<u8> {
     csv_string
 }
 
-async fn update_public_schema_with_results() {
+/// Where each results table was loaded from, for race results snapshots
+#[derive(Default)]
+struct SourceFiles {
+    table_names: Vec<String>,
+    urls: Vec<String>,
+    hashes: Vec<String>,
+}
+
+/// Updates votes and reporting on races we cover and snapshots every race whose numbers
+/// changed, in one transaction so counts never change without a snapshot. Certified races are
+/// left alone.
+async fn update_public_schema_with_results(source_files: &SourceFiles) -> Result<(), sqlx::Error> {
     let db_pool = db::pool().await;
     let query = r#"
-        WITH source AS (
-            SELECT * FROM p6t_state_mn.results_2024_county_races
+        WITH source_files AS (
+            SELECT * FROM UNNEST($1::text[], $2::text[], $3::text[]) AS f(table_name, url, hash)
+        ),
+        source AS (
+            SELECT *, 'p6t_state_mn.results_2024_county_races' AS table_name FROM p6t_state_mn.results_2024_county_races
             UNION ALL
-            SELECT * FROM p6t_state_mn.results_2024_municipal_races_and_questions
+            SELECT *, 'p6t_state_mn.results_2024_municipal_races_and_questions' FROM p6t_state_mn.results_2024_municipal_races_and_questions
             UNION ALL
-            SELECT * FROM p6t_state_mn.results_2024_school_board_races
+            SELECT *, 'p6t_state_mn.results_2024_school_board_races' FROM p6t_state_mn.results_2024_school_board_races
             UNION ALL
-            SELECT * FROM p6t_state_mn.results_2024_state_senator_by_district
+            SELECT *, 'p6t_state_mn.results_2024_state_senator_by_district' FROM p6t_state_mn.results_2024_state_senator_by_district
             UNION ALL
-            SELECT * FROM p6t_state_mn.results_2024_us_representative_by_district
+            SELECT *, 'p6t_state_mn.results_2024_us_representative_by_district' FROM p6t_state_mn.results_2024_us_representative_by_district
             UNION ALL
-            SELECT * FROM p6t_state_mn.results_2024_us_senator_statewide
+            SELECT *, 'p6t_state_mn.results_2024_us_senator_statewide' FROM p6t_state_mn.results_2024_us_senator_statewide
             UNION ALL
-            SELECT * FROM p6t_state_mn.results_2024_state_representative_by_district
+            SELECT *, 'p6t_state_mn.results_2024_state_representative_by_district' FROM p6t_state_mn.results_2024_state_representative_by_district
             UNION ALL
-            SELECT * FROM p6t_state_mn.results_2024_district_court_judges
+            SELECT *, 'p6t_state_mn.results_2024_district_court_judges' FROM p6t_state_mn.results_2024_district_court_judges
         ),
         results AS (
             SELECT DISTINCT ON (office_name,
                 candidate_name)
                 office_name,
                 candidate_name,
+                incumbent_code,
                 votes_for_candidate,
                 total_number_of_votes_for_office_in_area,
                 number_of_precincts_reporting,
@@ -202,6 +501,9 @@ async fn update_public_schema_with_results() {
                 r.vote_type AS vote_type,
                 rc.votes AS race_candidate_votes,
                 r.total_votes AS race_total_votes,
+                r.results_status,
+                sf.url AS source_url,
+                sf.hash AS source_hash,
                 CASE WHEN office_name ILIKE '%first choice%' THEN
                     votes_for_candidate::int
                 ELSE
@@ -214,7 +516,11 @@ async fn update_public_schema_with_results() {
                 END AS total_first_choice_votes
             FROM
                 source
+            LEFT JOIN source_files sf ON sf.table_name = source.table_name
+            LEFT JOIN slug_history sh ON sh.entity_type = 'politician'
+                AND sh.slug = SLUGIFY (source.candidate_name)
             LEFT JOIN politician p ON p.slug = SLUGIFY (source.candidate_name)
+                OR p.id = sh.entity_id
             LEFT JOIN race_candidates rc ON rc.candidate_id = p.id
                 AND rc.race_id IN(
                     SELECT
@@ -223,30 +529,41 @@ async fn update_public_schema_with_results() {
                 WHERE
                     e.slug = 'minnesota-primaries-2024')
                 LEFT JOIN race r ON r.id = rc.race_id
+            -- Ranked choice offices are reported once per choice, only first choices are
+            -- vote totals. Later rounds come from the ranked choice results import.
+            WHERE
+                office_name NOT ILIKE '%choice%'
+                OR office_name ILIKE '%first choice%'
             ORDER BY
                 office_name,
-                candidate_name,
-                CASE WHEN office_name LIKE '%First Choice%' THEN
-                    1
-                WHEN office_name LIKE '%Second Choice%' THEN
-                    2
-                WHEN office_name LIKE '%Third Choice%' THEN
-                    3
-                ELSE
-                    4 -- You can add more conditions if needed
-                END
+                candidate_name
+        ),
+        -- Write-ins are reported as one line per office, only named write-ins we cover are
+        -- race candidates
+        write_ins AS (
+            SELECT
+                office_name,
+                MAX(votes_for_candidate::integer) AS votes
+            FROM
+                source
+            WHERE
+                candidate_name ILIKE 'write-in%'
+            GROUP BY
+                office_name
         ),
         update_race_candidates AS (
             UPDATE
                 race_candidates rc
             SET
                 votes = COALESCE(first_choice_votes,
-                    results.votes_for_candidate::integer)
+                    results.votes_for_candidate::integer),
+                is_incumbent = results.incumbent_code = 'I'
             FROM
                 results
             WHERE
                 rc.race_id = results.race_id
                 AND rc.candidate_id = results.politician_id
+                AND results.results_status <> 'certified'
             RETURNING
                 *
         ),
@@ -258,31 +575,53 @@ async fn update_public_schema_with_results() {
                     NULLIF(results.total_number_of_votes_for_office_in_area::integer,
                         0)),
                 num_precincts_reporting = results.number_of_precincts_reporting::integer,
-                total_precincts = results.total_number_of_precincts_voting_for_the_office::integer
+                total_precincts = results.total_number_of_precincts_voting_for_the_office::integer,
+                write_in_votes = write_ins.votes
             FROM
                 results
+                LEFT JOIN write_ins ON write_ins.office_name = results.office_name
             WHERE
                 race.id = results.race_id
+                AND results.results_status <> 'certified'
         )
-        SELECT
-            *
+        SELECT DISTINCT ON (race_id)
+            race_id,
+            source_url,
+            source_hash
         FROM
             results
         WHERE
-            office_name NOT ILIKE '%question%';
+            race_id IS NOT NULL
+            AND results_status <> 'certified'
+            AND office_name NOT ILIKE '%question%';
     "#;
 
-    let result = sqlx::query(query)
-        .execute(&db_pool.connection)
-        .await
-        .map_err(|e| {
-            println!("Error updating public schema with results: {}", e);
-            e
-        });
+    let mut tx = db_pool.connection.begin().await?;
+    let updated_races = sqlx::query_as::<_, (uuid::Uuid, Option<String>, Option<String>)>(query)
+        .bind(&source_files.table_names)
+        .bind(&source_files.urls)
+        .bind(&source_files.hashes)
+        .fetch_all(&mut *tx)
+        .await?;
 
-    if result.is_ok() {
-        println!("Public schema successfully updated with results");
+    // Snapshots are taken after the update, so they hold the new numbers
+    let mut snapshots = 0;
+    for (race_id, source_url, source_hash) in updated_races {
+        let snapshot = RaceResultsSnapshot::record(
+            &mut tx,
+            race_id,
+            source_url.as_deref(),
+            source_hash.as_deref(),
+            None,
+        )
+        .await?;
+        snapshots += snapshot.is_some() as usize;
     }
+    tx.commit().await?;
+
+    println!("Public schema successfully updated with results");
+    println!("Recorded {} results snapshots", snapshots);
+    Ok(())
 }
 
 fn _write_to_csv_file(name: &str, data: &[u8]) -> Result<(), Box<dyn Error>> {
@@ -290,3 +629,77 @@ fn _write_to_csv_file(name: &str, data: &[u8]) -> Result<(), Box<dyn Error>> {
     std::io::Write::write_all(&mut file, data)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_precinct_results() {
+        let text = "MN;27;0005;0109;U.S. Senator;;0301;Amy Klobuchar;;I;DFL;1;1;812;58.04;1399\n\
+                    MN;27;;0109;U.S. Senator;;0301;Amy Klobuchar;;I;DFL;4102;4102;1792441;56.20;3189329\n\
+                    MN;27;0005;2501;Mayor First Choice Minneapolis;;0101;Jacob Frey;;I;NP;1;1;300;50.00;600\n\
+                    MN;27;0005;2502;Mayor Second Choice Minneapolis;;0101;Jacob Frey;;I;NP;1;1;80;20.00;400\n";
+        let rows = parse_precinct_results(text);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].county_code, "27");
+        assert_eq!(rows[0].precinct_code, "0005");
+        assert_eq!(rows[0].candidate_name, "Amy Klobuchar");
+        assert_eq!(rows[0].votes, 812);
+        assert_eq!(rows[0].total_votes, Some(1399));
+        assert_eq!(rows[1].office_name, "Mayor First Choice Minneapolis");
+    }
+
+    #[test]
+    fn test_parse_question_results() {
+        let text = "MN;27;;1001;City Question 1 (Minneapolis);43000;0901;YES;;;NP;130;136;95210;61.40;155064\n\
+                    MN;27;;1001;City Question 1 (Minneapolis);43000;0902;NO;;;NP;130;136;59854;38.60;155064\n\
+                    MN;27;;0401;Mayor Minneapolis;43000;0101;Jacob Frey;;I;NP;130;136;300;50.00;600\n\
+                    MN;02;;3001;School District Question 1 (ISD #728);;0901;YES;;;NP;20;22;4000;55.00;7300\n\
+                    MN;02;;3001;School District Question 1 (ISD #728);;0902;NO;;;NP;20;22;3300;45.00;7300\n\
+                    MN;71;;3001;School District Question 1 (ISD #728);;0901;YES;;;NP;9;10;2000;50.00;4000\n\
+                    MN;71;;3001;School District Question 1 (ISD #728);;0902;NO;;;NP;9;10;2000;50.00;4000\n\
+                    MN;;;0201;CONSTITUTIONAL AMENDMENT 1;;0901;YES;;;NP;4100;4102;2300000;77.00;2990000\n";
+        let results = parse_question_results(text);
+        assert_eq!(
+            results,
+            vec![
+                BallotMeasureResultRow {
+                    ballot_measure_code: "City Question 1 (Minneapolis)".to_string(),
+                    yes_votes: 95210,
+                    no_votes: 59854,
+                    num_precincts_reporting: Some(130),
+                    total_precincts: Some(136),
+                },
+                BallotMeasureResultRow {
+                    ballot_measure_code: "School District Question 1 (ISD #728)".to_string(),
+                    yes_votes: 6000,
+                    no_votes: 5300,
+                    num_precincts_reporting: Some(29),
+                    total_precincts: Some(32),
+                },
+                BallotMeasureResultRow {
+                    ballot_measure_code: "CONSTITUTIONAL AMENDMENT 1".to_string(),
+                    yes_votes: 2300000,
+                    no_votes: 0,
+                    num_precincts_reporting: Some(4100),
+                    total_precincts: Some(4102),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_precinct_statistics() {
+        let text = "MN;27;0005;Minneapolis W-1 P-5;1;1800;120;1450;3;0;0;1462\n";
+        let rows = parse_precinct_statistics(text);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(
+            rows[0].precinct_name.as_deref(),
+            Some("Minneapolis W-1 P-5")
+        );
+        assert!(rows[0].has_reported);
+        assert_eq!(rows[0].registered_voters, Some(1800));
+        assert_eq!(rows[0].total_voted, Some(1462));
+    }
+}
@@ -0,0 +1,339 @@
+//! Ranked choice results, either tabulated from cast vote records or imported from a round
+//! by round summary. Cast vote records are CSVs with a column per ranking (`1st Choice`,
+//! `Rank 2`, ...) and optionally a `Count` column for identical ballots, as Minneapolis and
+//! St. Paul publish them. Summaries are RCTab `summary.json` files or a CSV of
+//! `round,candidate,votes[,status]` rows with `Exhausted` and `Threshold` as candidates.
+
+use db::util::rcv::{self, CastVoteRecord, Mark, RoundTally, TabulatedRound};
+use db::{RankedChoiceRound, RankedChoiceStatus};
+use serde_json::Value;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::Read;
+
+fn is_choice_column(header: &str) -> bool {
+    let header = header.to_lowercase();
+    header.contains("choice") || header.contains("rank")
+}
+
+fn is_count_column(header: &str) -> bool {
+    matches!(
+        header.trim().to_lowercase().as_str(),
+        "count" | "ballots" | "ballot count" | "number of ballots"
+    )
+}
+
+fn parse_mark(value: &str) -> Mark {
+    let value = value.trim();
+    match value.to_lowercase().as_str() {
+        "" | "undervote" | "skipped" | "blank" => Mark::Skipped,
+        "overvote" => Mark::Overvote,
+        _ => Mark::Candidate(value.to_string()),
+    }
+}
+
+pub fn parse_cast_vote_records<R: Read>(reader: R) -> Result<Vec<CastVoteRecord>, Box<dyn Error>> {
+    let mut rdr = csv::Reader::from_reader(reader);
+    let headers = rdr.headers()?.clone();
+    let choice_columns: Vec<usize> = headers
+        .iter()
+        .enumerate()
+        .filter(|(_, h)| is_choice_column(h))
+        .map(|(i, _)| i)
+        .collect();
+    if choice_columns.is_empty() {
+        return Err("No ranking columns found, expected headers like \"1st Choice\"".into());
+    }
+    let count_column = headers.iter().position(is_count_column);
+
+    let mut records = vec![];
+    for result in rdr.records() {
+        let record = result?;
+        let count = match count_column {
+            Some(column) => record.get(column).unwrap_or("1").trim().parse()?,
+            None => 1.0,
+        };
+        records.push(CastVoteRecord {
+            marks: choice_columns
+                .iter()
+                .map(|&column| parse_mark(record.get(column).unwrap_or_default()))
+                .collect(),
+            count,
+        });
+    }
+    Ok(records)
+}
+
+/// RCTab writes numbers as strings
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn with_transfers(rounds: &mut [TabulatedRound]) {
+    let mut previous: Option<HashMap<String, f64>> = None;
+    for round in rounds.iter_mut() {
+        if let Some(previous) = &previous {
+            for tally in round.tallies.iter_mut() {
+                tally.transfer = tally.votes - previous.get(&tally.candidate).unwrap_or(&0.0);
+            }
+        }
+        previous = Some(
+            round
+                .tallies
+                .iter()
+                .map(|t| (t.candidate.clone(), t.votes))
+                .collect(),
+        );
+        round.tallies.sort_by(|a, b| {
+            b.votes
+                .total_cmp(&a.votes)
+                .then(a.candidate.cmp(&b.candidate))
+        });
+    }
+}
+
+pub fn parse_rctab_summary(json: &str) -> Result<Vec<TabulatedRound>, Box<dyn Error>> {
+    let summary: Value = serde_json::from_str(json)?;
+    let results = summary["results"]
+        .as_array()
+        .ok_or("RCTab summary has no results")?;
+    let config_threshold = number(&summary["config"]["threshold"]);
+
+    let mut rounds = vec![];
+    let mut elected: Vec<String> = vec![];
+    let mut exhausted = 0.0;
+    for (i, result) in results.iter().enumerate() {
+        let tally = result["tally"]
+            .as_object()
+            .ok_or("RCTab round has no tally")?;
+        let tally_results = result["tallyResults"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        let outcome = |key: &str| -> Vec<String> {
+            tally_results
+                .iter()
+                .filter_map(|r| r[key].as_str().map(str::to_string))
+                .collect()
+        };
+        let newly_elected = outcome("elected");
+        let eliminated = outcome("eliminated");
+
+        let round_exhausted = match result["inactiveBallots"].as_object() {
+            Some(inactive) => inactive.values().filter_map(number).sum(),
+            None => exhausted,
+        };
+        rounds.push(TabulatedRound {
+            round: number(&result["round"]).map_or(i as i32 + 1, |r| r as i32),
+            threshold: number(&result["threshold"]).or(config_threshold),
+            exhausted: round_exhausted,
+            tallies: tally
+                .iter()
+                .map(|(candidate, votes)| RoundTally {
+                    candidate: candidate.clone(),
+                    votes: number(votes).unwrap_or_default(),
+                    transfer: 0.0,
+                    status: if newly_elected.contains(candidate) || elected.contains(candidate) {
+                        RankedChoiceStatus::Elected
+                    } else if eliminated.contains(candidate) {
+                        RankedChoiceStatus::Eliminated
+                    } else {
+                        RankedChoiceStatus::Continuing
+                    },
+                })
+                .collect(),
+        });
+
+        // Ballots transferred to "exhausted" after this round count from the next one
+        exhausted = round_exhausted
+            + tally_results
+                .iter()
+                .filter_map(|r| number(&r["transfers"]["exhausted"]))
+                .sum::<f64>();
+        elected.extend(newly_elected);
+    }
+    with_transfers(&mut rounds);
+    Ok(rounds)
+}
+
+pub fn parse_round_summary_csv<R: Read>(reader: R) -> Result<Vec<TabulatedRound>, Box<dyn Error>> {
+    let mut rdr = csv::Reader::from_reader(reader);
+    let headers: Vec<String> = rdr
+        .headers()?
+        .iter()
+        .map(|h| h.trim().to_lowercase())
+        .collect();
+    let column = |name: &str| headers.iter().position(|h| h == name);
+    let round_column = column("round").ok_or("Missing round column")?;
+    let candidate_column = column("candidate").ok_or("Missing candidate column")?;
+    let votes_column = column("votes").ok_or("Missing votes column")?;
+    let status_column = column("status");
+
+    let mut rounds: Vec<TabulatedRound> = vec![];
+    for result in rdr.records() {
+        let record = result?;
+        let round: i32 = record
+            .get(round_column)
+            .unwrap_or_default()
+            .trim()
+            .parse()?;
+        let candidate = record.get(candidate_column).unwrap_or_default().trim();
+        let votes: f64 = record
+            .get(votes_column)
+            .unwrap_or_default()
+            .trim()
+            .replace(',', "")
+            .parse()?;
+        let index = match rounds.iter().position(|r| r.round == round) {
+            Some(index) => index,
+            None => {
+                rounds.push(TabulatedRound {
+                    round,
+                    threshold: None,
+                    exhausted: 0.0,
+                    tallies: vec![],
+                });
+                rounds.len() - 1
+            }
+        };
+        let entry = &mut rounds[index];
+        match candidate.to_lowercase().as_str() {
+            "exhausted" | "exhausted ballots" | "inactive ballots" => entry.exhausted = votes,
+            "threshold" => entry.threshold = Some(votes),
+            _ => entry.tallies.push(RoundTally {
+                candidate: candidate.to_string(),
+                votes,
+                transfer: 0.0,
+                status: match status_column
+                    .and_then(|c| record.get(c))
+                    .map(|s| s.trim().to_lowercase())
+                    .as_deref()
+                {
+                    Some("elected") => RankedChoiceStatus::Elected,
+                    Some("eliminated") | Some("defeated") => RankedChoiceStatus::Eliminated,
+                    _ => RankedChoiceStatus::Continuing,
+                },
+            }),
+        }
+    }
+    rounds.sort_by_key(|r| r.round);
+    with_transfers(&mut rounds);
+    Ok(rounds)
+}
+
+/// Tabulates a race from its cast vote records, for `seats` winners or the race's `num_elect`.
+/// Races with certified results are only changed when `force` is set.
+pub async fn import_cast_vote_records<R: Read>(
+    db_pool: &PgPool,
+    race_id: uuid::Uuid,
+    reader: R,
+    seats: Option<usize>,
+    force: bool,
+) -> Result<Vec<TabulatedRound>, Box<dyn Error>> {
+    let seats = match seats {
+        Some(seats) => seats,
+        None => db::Race::find_by_id(db_pool, race_id)
+            .await?
+            .num_elect
+            .map_or(1, |n| n.max(1) as usize),
+    };
+    let ballots = parse_cast_vote_records(reader)?;
+    let rounds = rcv::tabulate(&ballots, seats);
+    RankedChoiceRound::replace_for_race(db_pool, race_id, "cvr", &rounds, force).await?;
+    Ok(rounds)
+}
+
+/// Imports rounds tabulated elsewhere, from an RCTab `.json` summary or a `.csv`
+pub async fn import_round_summary(
+    db_pool: &PgPool,
+    race_id: uuid::Uuid,
+    path: &str,
+    force: bool,
+) -> Result<Vec<TabulatedRound>, Box<dyn Error>> {
+    let (source, rounds) = if path.to_lowercase().ends_with(".json") {
+        (
+            "rctab",
+            parse_rctab_summary(&std::fs::read_to_string(path)?)?,
+        )
+    } else {
+        (
+            "summary_csv",
+            parse_round_summary_csv(std::fs::File::open(path)?)?,
+        )
+    };
+    RankedChoiceRound::replace_for_race(db_pool, race_id, source, &rounds, force).await?;
+    Ok(rounds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cast_vote_records() {
+        let csv = "Precinct,1st Choice,2nd Choice,3rd Choice,Count\n\
+                   MPLS W-1 P-1,Alice,undervote,Bob,3\n\
+                   MPLS W-1 P-1,overvote,Bob,,1\n";
+        let records = parse_cast_vote_records(csv.as_bytes()).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            records[0].marks,
+            vec![
+                Mark::Candidate("Alice".to_string()),
+                Mark::Skipped,
+                Mark::Candidate("Bob".to_string())
+            ]
+        );
+        assert_eq!(records[0].count, 3.0);
+        assert_eq!(records[1].marks[0], Mark::Overvote);
+        assert_eq!(records[1].marks[2], Mark::Skipped);
+    }
+
+    #[test]
+    fn test_parse_rctab_summary() {
+        let json = r#"{
+            "config": {"contest": "Mayor", "threshold": "51"},
+            "results": [
+                {
+                    "round": 1,
+                    "tally": {"Alice": "40", "Bob": "35", "Carol": "25"},
+                    "tallyResults": [{"eliminated": "Carol", "transfers": {"Alice": "20", "exhausted": "5"}}]
+                },
+                {
+                    "round": 2,
+                    "tally": {"Alice": "60", "Bob": "35"},
+                    "tallyResults": [{"elected": "Alice", "transfers": {}}]
+                }
+            ]
+        }"#;
+        let rounds = parse_rctab_summary(json).unwrap();
+        assert_eq!(rounds.len(), 2);
+        assert_eq!(rounds[0].threshold, Some(51.0));
+        assert_eq!(rounds[0].tallies[2].status, RankedChoiceStatus::Eliminated);
+        assert_eq!(rounds[1].exhausted, 5.0);
+        assert_eq!(rounds[1].tallies[0].candidate, "Alice");
+        assert_eq!(rounds[1].tallies[0].transfer, 20.0);
+        assert_eq!(rounds[1].elected().collect::<Vec<_>>(), vec!["Alice"]);
+    }
+
+    #[test]
+    fn test_parse_round_summary_csv() {
+        let csv = "round,candidate,votes,status\n\
+                   1,Alice,\"40,000\",\n\
+                   1,Carol,25000,eliminated\n\
+                   1,Threshold,50001,\n\
+                   2,Alice,60000,elected\n\
+                   2,Exhausted,5000,\n";
+        let rounds = parse_round_summary_csv(csv.as_bytes()).unwrap();
+        assert_eq!(rounds[0].threshold, Some(50001.0));
+        assert_eq!(rounds[0].tallies.len(), 2);
+        assert_eq!(rounds[1].exhausted, 5000.0);
+        assert_eq!(rounds[1].tallies[0].transfer, 20000.0);
+        assert_eq!(rounds[1].tallies[0].status, RankedChoiceStatus::Elected);
+    }
+}
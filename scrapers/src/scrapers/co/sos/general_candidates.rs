@@ -1,4 +1,4 @@
-use std::{error::Error, sync::OnceLock};
+use std::{collections::HashMap, error::Error, sync::OnceLock};
 
 use regex::Regex;
 use scraper::{Html, Selector};
@@ -6,7 +6,9 @@ use scraper::{Html, Selector};
 use crate::{
     extractors::*,
     generators::*,
+    tracking::{EntityKey, EntityType, RunTracker},
     util::{self, extensions::*},
+    RunReport,
 };
 
 const HTML_PATH: &str = "co/sos/general_candidates.html";
@@ -21,12 +23,15 @@ impl crate::Scraper for Scraper {
         SOURCE_ID
     }
 
-    async fn run(&self, context: &crate::ScraperContext<'_>) -> Result<(), Box<dyn Error>> {
+    async fn run(&self, context: &crate::ScraperContext<'_>) -> Result<RunReport, Box<dyn Error>> {
         let html = reqwest::get(PAGE_URL).await?.text().await?;
         Self::scrape_html(html, context).await
     }
 
-    async fn run_local(&self, context: &crate::ScraperContext<'_>) -> Result<(), Box<dyn Error>> {
+    async fn run_local(
+        &self,
+        context: &crate::ScraperContext<'_>,
+    ) -> Result<RunReport, Box<dyn Error>> {
         let html = util::read_local_html(HTML_PATH)?;
         Self::scrape_html(html, context).await
     }
@@ -36,79 +41,115 @@ impl Scraper {
     pub async fn scrape_html(
         html: String,
         context: &crate::ScraperContext<'_>,
-    ) -> Result<(), Box<dyn Error>> {
+    ) -> Result<RunReport, Box<dyn Error>> {
         let data = Self::scrape_page_data(html)?;
+        let mut run = RunTracker::start(context, SOURCE_ID).await?;
+        let result = Self::upsert_page_data(data, &mut run)
+            .await
+            .map_err(|err| err.to_string());
+        run.finish(context, result).await
+    }
+
+    async fn upsert_page_data(data: PageData, run: &mut RunTracker) -> Result<(), Box<dyn Error>> {
         let election_year = Self::parse_election_year(&data.title)?;
         let election_date = GeneralElectionDateGenerator::new(election_year).generate()?;
         let (election_title, election_slug) =
             ElectionTitleGenerator::new(&db::RaceType::General, election_year).generate();
-        let election = db::Election::upsert_from_source(
-            &context.db.connection,
-            &db::UpsertElectionInput {
-                slug: Some(election_slug),
-                title: Some(election_title),
-                election_date: Some(election_date),
-                ..Default::default()
-            },
-        )
-        .await?;
+        let election = db::UpsertElectionInput {
+            slug: Some(election_slug.clone()),
+            title: Some(election_title),
+            election_date: Some(election_date),
+            ..Default::default()
+        };
+        let election = run
+            .upsert(
+                EntityType::Election,
+                EntityKey::Slug(election_slug),
+                &data.title,
+                move |conn| {
+                    Box::pin(async move { db::Election::upsert_from_source(conn, &election).await })
+                },
+            )
+            .await
+            .ok_or("Failed to upsert Election")?;
 
         for entry in data.candidates {
-            let office = Self::build_office_input(&entry);
-            let office = match db::Office::upsert_from_source(&context.db.connection, &office).await
-            {
+            let source_ref = format!("row {}: {}", entry.index, entry.name);
+
+            let office = match Self::build_office_input(&entry) {
                 Ok(office) => office,
                 Err(err) => {
-                    // TODO - Track/log error
-                    println!("Error upserting Office: {err}");
+                    run.fail(EntityType::Office, &source_ref, err);
                     continue;
                 }
             };
+            let key = EntityKey::Slug(office.slug.clone().unwrap_or_default());
+            let Some(office) = run
+                .upsert(EntityType::Office, key, &source_ref, move |conn| {
+                    Box::pin(async move { db::Office::upsert_from_source(conn, &office).await })
+                })
+                .await
+            else {
+                continue;
+            };
 
             let race = Self::build_race_input(&election, &office);
-            let race = match db::Race::upsert_from_source(&context.db.connection, &race).await {
-                Ok(race) => race,
-                Err(err) => {
-                    // TODO - Track/log error
-                    println!("Error upserting Race: {err}");
-                    continue;
-                }
+            let key = EntityKey::Slug(race.slug.clone().unwrap_or_default());
+            let Some(race) = run
+                .upsert(EntityType::Race, key, &source_ref, move |conn| {
+                    Box::pin(async move { db::Race::upsert_from_source(conn, &race).await })
+                })
+                .await
+            else {
+                continue;
             };
 
-            let party = Self::build_party_input(&entry);
-            let party = if let Some(party) = party {
-                match db::Party::upsert_from_source(&context.db.connection, &party).await {
-                    Ok(party) => Some(party),
-                    Err(err) => {
-                        // TODO - Track/log error
-                        println!("Error upserting Party: {err}");
-                        continue;
+            let party = match Self::build_party_input(&entry) {
+                Ok(Some(party)) => {
+                    let key = EntityKey::Slug(party.slug.clone().unwrap_or_default());
+                    let party = run
+                        .upsert(EntityType::Party, key, &source_ref, move |conn| {
+                            Box::pin(
+                                async move { db::Party::upsert_from_source(conn, &party).await },
+                            )
+                        })
+                        .await;
+                    match party {
+                        Some(party) => Some(party),
+                        None => continue,
                     }
                 }
-            } else {
-                None
+                Ok(None) => None,
+                Err(err) => {
+                    run.fail(EntityType::Party, &source_ref, err);
+                    continue;
+                }
             };
 
             let politician = Self::build_politician_input(&entry, &party);
-            let politician =
-                match db::Politician::upsert_from_source(&context.db.connection, &politician).await
-                {
-                    Ok(politician) => politician,
-                    Err(err) => {
-                        // TODO - Track/log error
-                        println!("Error upserting Politician: {err}");
-                        continue;
-                    }
-                };
-
-            let race_candidate = Self::build_race_candidate_input(&race, &politician);
-            if let Err(err) =
-                db::RaceCandidate::upsert_from_source(&context.db.connection, &race_candidate).await
-            {
-                // TODO - Track/log error
-                println!("Error upserting RaceCandidate: {err}");
+            let key = EntityKey::RefKey(politician.ref_key.clone().unwrap_or_default());
+            let Some(politician) = run
+                .upsert(EntityType::Politician, key, &source_ref, move |conn| {
+                    Box::pin(
+                        async move { db::Politician::upsert_from_source(conn, &politician).await },
+                    )
+                })
+                .await
+            else {
                 continue;
-            }
+            };
+
+            let race_candidate = Self::build_race_candidate_input(&entry, &race, &politician);
+            let key = EntityKey::RaceCandidate {
+                race_id: race.id,
+                candidate_id: politician.id,
+            };
+            run.upsert(EntityType::RaceCandidate, key, &source_ref, move |conn| {
+                Box::pin(async move {
+                    db::RaceCandidate::upsert_from_source(conn, &race_candidate).await
+                })
+            })
+            .await;
         }
         Ok(())
     }
@@ -133,6 +174,7 @@ impl Scraper {
 
     pub fn scrape_candidate_entries(html: &Html) -> Result<Vec<CandidateEntry>, Box<dyn Error>> {
         let mut entries = Vec::new();
+        let mut ballot_positions = HashMap::<String, i32>::new();
         for (index, element) in html
             .select(&Selector::parse("table.w3-cmsTable")?)
             .next()
@@ -156,13 +198,27 @@ impl Scraper {
                     .map(|f| f.none_if_empty())
             };
 
+            let name = next_field("name")?.ok_or("Empty name field")?;
+            let office = next_field("office")?.ok_or("Empty office field")?;
+            let party = next_field("party")?;
+            let is_write_in = next_field("write_in")?.is_some_and(|w| w.trim() == "Y");
+            let website = next_field("website")?;
+
+            // Candidates are listed in ballot order, write-ins aren't on the ballot
+            let ballot_order = (!is_write_in).then(|| {
+                let position = ballot_positions.entry(office.clone()).or_default();
+                *position += 1;
+                *position
+            });
+
             let entry = CandidateEntry {
                 index,
-                name: next_field("name")?.ok_or("Empty name field")?,
-                office: next_field("office")?.ok_or("Empty office field")?,
-                party: next_field("party")?,
-                _write_in: next_field("write_in")?,
-                website: next_field("website")?,
+                name,
+                office,
+                party,
+                website,
+                is_write_in,
+                ballot_order,
             };
 
             if !entry.office.starts_with("President") {
@@ -185,10 +241,9 @@ impl Scraper {
         Ok(year)
     }
 
-    fn build_office_input(entry: &CandidateEntry) -> db::UpsertOfficeInput {
+    fn build_office_input(entry: &CandidateEntry) -> Result<db::UpsertOfficeInput, String> {
         let Some(mut meta) = extract_office_meta(&entry.office) else {
-            // TODO - Track/log failed scrape
-            return db::UpsertOfficeInput::default();
+            return Err(format!("Unrecognized office: {}", entry.office));
         };
 
         if meta.name == "Board of Regents" {
@@ -196,8 +251,7 @@ impl Scraper {
                 meta.name = format!("CU {}", meta.name);
                 meta.title = format!("CU {}", meta.title);
             } else {
-                // TODO - Track/log failed scrape
-                return db::UpsertOfficeInput::default();
+                return Err(format!("Unrecognized Board of Regents: {}", entry.office));
             }
         }
 
@@ -218,6 +272,7 @@ impl Scraper {
         let (subtitle, subtitle_short) = OfficeSubtitleGenerator {
             state: &db::State::CO,
             county: county.as_str(),
+            municipality: None,
             district: district.as_str(),
             seat: seat.as_str(),
         }
@@ -227,12 +282,13 @@ impl Scraper {
             state: &db::State::CO,
             name: meta.name.as_str(),
             county: county.as_str(),
+            municipality: None,
             district: district.as_str(),
             seat: seat.as_str(),
         }
         .generate();
 
-        db::UpsertOfficeInput {
+        Ok(db::UpsertOfficeInput {
             slug: Some(slug),
             name: Some(meta.name),
             title: Some(meta.title),
@@ -247,7 +303,7 @@ impl Scraper {
             political_scope: Some(meta.political_scope),
             election_scope: Some(meta.election_scope),
             ..Default::default()
-        }
+        })
     }
 
     fn build_race_input(election: &db::Election, office: &db::Office) -> db::UpsertRaceInput {
@@ -265,18 +321,19 @@ impl Scraper {
         }
     }
 
-    fn build_party_input(entry: &CandidateEntry) -> Option<db::UpsertPartyInput> {
-        let party = entry.party.as_str()?;
+    fn build_party_input(entry: &CandidateEntry) -> Result<Option<db::UpsertPartyInput>, String> {
+        let Some(party) = entry.party.as_str() else {
+            return Ok(None);
+        };
         let Some(name) = extract_party_name(party) else {
-            // TODO - Track/log failed scrape
-            return None;
+            return Err(format!("Unrecognized party: {party}"));
         };
         let slug = PartySlugGenerator::new(name.as_str()).generate();
-        Some(db::UpsertPartyInput {
+        Ok(Some(db::UpsertPartyInput {
             name: Some(name),
             slug: Some(slug),
             ..Default::default()
-        })
+        }))
     }
 
     fn build_politician_input(
@@ -299,12 +356,19 @@ impl Scraper {
     }
 
     fn build_race_candidate_input(
+        entry: &CandidateEntry,
         race: &db::Race,
         politician: &db::Politician,
     ) -> db::UpsertRaceCandidateInput {
         db::UpsertRaceCandidateInput {
             race_id: race.id,
             candidate_id: politician.id,
+            // The list only has candidates on the ballot and certified write-ins
+            status: Some(db::CandidacyStatus::Qualified),
+            is_write_in: Some(entry.is_write_in),
+            party_on_ballot: entry.party.clone(),
+            ballot_order: entry.ballot_order,
+            ..Default::default()
         }
     }
 }
@@ -320,5 +384,39 @@ pub struct CandidateEntry {
     pub office: String,
     pub party: Option<String>,
     pub website: Option<String>,
-    _write_in: Option<String>,
+    pub is_write_in: bool,
+    /// Position among the office's candidates on the ballot, empty for write-ins
+    pub ballot_order: Option<i32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scrape_ballot_order() {
+        let html = util::read_local_html(HTML_PATH).unwrap();
+        let entries = Scraper::scrape_page_data(html).unwrap().candidates;
+
+        let ballot = entries
+            .iter()
+            .filter(|e| e.office == "State Representative - District 7")
+            .map(|e| (e.name.as_str(), e.ballot_order))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ballot[..4],
+            [
+                ("Jennifer Bacon", Some(1)),
+                ("Tom Swift", Some(2)),
+                ("Raymon Anthony Doane", Some(3)),
+                ("Ben Pope", None),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn golden() {
+        crate::golden::assert_golden(Scraper::default(), "co_sos_general_candidates").await;
+    }
 }
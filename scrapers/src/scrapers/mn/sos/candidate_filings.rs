@@ -0,0 +1,502 @@
+use std::{error::Error, sync::OnceLock};
+
+use chrono::Datelike;
+use csv::{ReaderBuilder, StringRecord};
+use regex::Regex;
+use scraper::{Html, Selector};
+
+use crate::{
+    extractors::*,
+    generators::*,
+    tracking::{EntityKey, EntityType, RunTracker},
+    util::extensions::*,
+};
+
+pub const SOURCE_ID: &str = "MN-SOS";
+const INDEX_URL: &str = "https://candidates.sos.mn.gov";
+
+static COUNTY_NAMES: [&str; 87] = [
+    "Aitkin",
+    "Anoka",
+    "Becker",
+    "Beltrami",
+    "Benton",
+    "Big Stone",
+    "Blue Earth",
+    "Brown",
+    "Carlton",
+    "Carver",
+    "Cass",
+    "Chippewa",
+    "Chisago",
+    "Clay",
+    "Clearwater",
+    "Cook",
+    "Cottonwood",
+    "Crow Wing",
+    "Dakota",
+    "Dodge",
+    "Douglas",
+    "Faribault",
+    "Fillmore",
+    "Freeborn",
+    "Goodhue",
+    "Grant",
+    "Hennepin",
+    "Houston",
+    "Hubbard",
+    "Isanti",
+    "Itasca",
+    "Jackson",
+    "Kanabec",
+    "Kandiyohi",
+    "Kittson",
+    "Koochiching",
+    "Lac qui Parle",
+    "Lake",
+    "Lake of the Woods",
+    "Le Sueur",
+    "Lincoln",
+    "Lyon",
+    "McLeod",
+    "Mahnomen",
+    "Marshall",
+    "Martin",
+    "Meeker",
+    "Mille Lacs",
+    "Morrison",
+    "Mower",
+    "Murray",
+    "Nicollet",
+    "Nobles",
+    "Norman",
+    "Olmsted",
+    "Otter Tail",
+    "Pennington",
+    "Pine",
+    "Pipestone",
+    "Polk",
+    "Pope",
+    "Ramsey",
+    "Red Lake",
+    "Redwood",
+    "Renville",
+    "Rice",
+    "Rock",
+    "Roseau",
+    "St. Louis",
+    "Scott",
+    "Sherburne",
+    "Sibley",
+    "Stearns",
+    "Steele",
+    "Stevens",
+    "Swift",
+    "Todd",
+    "Traverse",
+    "Wabasha",
+    "Wadena",
+    "Waseca",
+    "Washington",
+    "Watonwan",
+    "Wilkin",
+    "Winona",
+    "Wright",
+    "Yellow Medicine",
+];
+
+/// A single candidate filing, normalized across the different filing file layouts
+pub struct FilingEntry {
+    pub index: usize,
+    pub name: String,
+    pub office_title: String,
+    pub county_id: Option<String>,
+    pub school_district: Option<String>,
+    pub party: Option<String>,
+    pub website: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+}
+
+/// Finds the filing file linked from the candidate filings index page and downloads it
+pub async fn fetch_filings(link_text: &str) -> Result<String, Box<dyn Error>> {
+    let index = reqwest::get(INDEX_URL).await?.text().await?;
+    let href = find_link(&index, link_text)?;
+    let url = reqwest::Url::parse(INDEX_URL)?.join(&href)?;
+    let body = reqwest::get(url).await?.text().await?;
+    extract_preformatted(&body)
+}
+
+fn find_link(html: &str, link_text: &str) -> Result<String, Box<dyn Error>> {
+    let html = Html::parse_document(html);
+    let href = html
+        .select(&Selector::parse("a[href]")?)
+        .find(|a| a.text().collect::<String>().trim() == link_text)
+        .and_then(|a| a.value().attr("href"))
+        .ok_or_else(|| format!("Link not found: {link_text}"))?;
+    Ok(href.to_string())
+}
+
+/// Filing files are served as plain text, older versions wrapped them in a `<pre>` block
+fn extract_preformatted(body: &str) -> Result<String, Box<dyn Error>> {
+    if !body.trim_start().starts_with('<') {
+        return Ok(body.to_string());
+    }
+    let html = Html::parse_document(body);
+    let text = html
+        .select(&Selector::parse("pre")?)
+        .next()
+        .ok_or("Filing data not found")?
+        .text()
+        .collect::<String>();
+    Ok(text)
+}
+
+/// Reads the semicolon delimited rows of a filing file
+pub fn read_records(text: &str) -> Result<Vec<StringRecord>, Box<dyn Error>> {
+    let records = ReaderBuilder::new()
+        .has_headers(false)
+        .delimiter(b';')
+        .flexible(true)
+        .from_reader(text.as_bytes())
+        .records()
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(records)
+}
+
+pub fn field(record: &StringRecord, index: usize) -> Option<String> {
+    record
+        .get(index)
+        .map(|f| f.trim().to_string())
+        .and_then(NoneIfEmptyExt::none_if_empty)
+}
+
+/// The filing files only list candidates for the current election cycle, so callers pass the
+/// year of the general election they are for
+pub async fn upsert_filings(
+    entries: Vec<FilingEntry>,
+    election_year: u16,
+    run: &mut RunTracker,
+) -> Result<(), Box<dyn Error>> {
+    let election_date = GeneralElectionDateGenerator::new(election_year).generate()?;
+    let (election_title, election_slug) =
+        ElectionTitleGenerator::new(&db::RaceType::General, election_year).generate();
+    let election = db::UpsertElectionInput {
+        slug: Some(election_slug.clone()),
+        title: Some(election_title.clone()),
+        election_date: Some(election_date),
+        ..Default::default()
+    };
+    let election = run
+        .upsert(
+            EntityType::Election,
+            EntityKey::Slug(election_slug),
+            &election_title,
+            move |conn| {
+                Box::pin(async move { db::Election::upsert_from_source(conn, &election).await })
+            },
+        )
+        .await
+        .ok_or("Failed to upsert Election")?;
+
+    for entry in entries {
+        let source_ref = format!("row {}: {}", entry.index, entry.name);
+
+        let office = match build_office_input(&entry) {
+            Ok(office) => office,
+            Err(err) => {
+                run.fail(EntityType::Office, &source_ref, err);
+                continue;
+            }
+        };
+        let key = EntityKey::Slug(office.slug.clone().unwrap_or_default());
+        let Some(office) = run
+            .upsert(EntityType::Office, key, &source_ref, move |conn| {
+                Box::pin(async move { db::Office::upsert_from_source(conn, &office).await })
+            })
+            .await
+        else {
+            continue;
+        };
+
+        let race = build_race_input(&election, &office);
+        let key = EntityKey::Slug(race.slug.clone().unwrap_or_default());
+        let Some(race) = run
+            .upsert(EntityType::Race, key, &source_ref, move |conn| {
+                Box::pin(async move { db::Race::upsert_from_source(conn, &race).await })
+            })
+            .await
+        else {
+            continue;
+        };
+
+        let party = match build_party_input(&entry) {
+            Ok(Some(party)) => {
+                let key = EntityKey::Slug(party.slug.clone().unwrap_or_default());
+                let party = run
+                    .upsert(EntityType::Party, key, &source_ref, move |conn| {
+                        Box::pin(async move { db::Party::upsert_from_source(conn, &party).await })
+                    })
+                    .await;
+                match party {
+                    Some(party) => Some(party),
+                    None => continue,
+                }
+            }
+            Ok(None) => None,
+            Err(err) => {
+                run.fail(EntityType::Party, &source_ref, err);
+                continue;
+            }
+        };
+
+        let politician = build_politician_input(&entry, &party);
+        let key = EntityKey::RefKey(politician.ref_key.clone().unwrap_or_default());
+        let Some(politician) = run
+            .upsert(EntityType::Politician, key, &source_ref, move |conn| {
+                Box::pin(async move { db::Politician::upsert_from_source(conn, &politician).await })
+            })
+            .await
+        else {
+            continue;
+        };
+
+        // Both files only list candidates on the general election ballot. Minnesota rotates
+        // names between precincts so there's no single ballot order.
+        let race_candidate = db::UpsertRaceCandidateInput {
+            race_id: race.id,
+            candidate_id: politician.id,
+            status: Some(db::CandidacyStatus::Qualified),
+            party_on_ballot: entry.party.clone(),
+            ..Default::default()
+        };
+        let key = EntityKey::RaceCandidate {
+            race_id: race.id,
+            candidate_id: politician.id,
+        };
+        run.upsert(EntityType::RaceCandidate, key, &source_ref, move |conn| {
+            Box::pin(
+                async move { db::RaceCandidate::upsert_from_source(conn, &race_candidate).await },
+            )
+        })
+        .await;
+    }
+    Ok(())
+}
+
+fn county_name(county_id: Option<&str>) -> Option<&'static str> {
+    let index = county_id?.parse::<usize>().ok()?;
+    COUNTY_NAMES.get(index.checked_sub(1)?).copied()
+}
+
+/// Local office titles name their jurisdiction in parentheses, e.g. "Mayor (Minneapolis)"
+fn extract_jurisdictions(title: &str) -> Vec<String> {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    let regex = REGEX.get_or_init(|| Regex::new(r"\(([^)]+)\)").unwrap());
+    regex
+        .captures_iter(title)
+        .filter_map(|c| c.get(1))
+        .map(|m| m.as_str().trim().to_string())
+        .collect()
+}
+
+fn build_office_input(entry: &FilingEntry) -> Result<db::UpsertOfficeInput, String> {
+    let Some(mut meta) = extract_office_meta(&entry.office_title) else {
+        return Err(format!("Unrecognized office: {}", entry.office_title));
+    };
+
+    let jurisdictions = extract_jurisdictions(&entry.office_title);
+
+    let county = match meta.election_scope {
+        db::ElectionScope::County => Some(
+            county_name(entry.county_id.as_str())
+                .ok_or_else(|| format!("Unrecognized county: {:?}", entry.county_id))?
+                .to_string(),
+        ),
+        _ => None,
+    };
+
+    let municipality = match meta.election_scope {
+        db::ElectionScope::City => Some(
+            jurisdictions
+                .last()
+                .cloned()
+                .ok_or_else(|| format!("Missing municipality: {}", entry.office_title))?,
+        ),
+        _ => None,
+    };
+
+    let school_district = if meta.district_type == Some(db::DistrictType::School) {
+        let school_district = jurisdictions
+            .first()
+            .ok_or_else(|| format!("Missing school district: {}", entry.office_title))?;
+        meta.name = format!("{} {}", school_district, meta.name);
+        meta.title = format!("{} {}", school_district, meta.title);
+        entry
+            .school_district
+            .clone()
+            .or_else(|| Some(school_district.clone()))
+    } else {
+        None
+    };
+
+    let district = extract_office_district(&entry.office_title);
+    let seat = extract_office_seat(&entry.office_title);
+
+    let (subtitle, subtitle_short) = OfficeSubtitleGenerator {
+        state: &db::State::MN,
+        county: county.as_str(),
+        municipality: municipality.as_str(),
+        district: district.as_str(),
+        seat: seat.as_str(),
+    }
+    .generate();
+
+    let slug = OfficeSlugGenerator {
+        state: &db::State::MN,
+        name: meta.name.as_str(),
+        county: county.as_str(),
+        municipality: municipality.as_str(),
+        district: district.as_str(),
+        seat: seat.as_str(),
+    }
+    .generate();
+
+    Ok(db::UpsertOfficeInput {
+        slug: Some(slug),
+        name: Some(meta.name),
+        title: Some(meta.title),
+        subtitle: Some(subtitle),
+        subtitle_short: Some(subtitle_short),
+        chamber: meta.chamber,
+        state: Some(db::State::MN),
+        county,
+        municipality,
+        school_district,
+        district,
+        district_type: meta.district_type,
+        seat,
+        political_scope: Some(meta.political_scope),
+        election_scope: Some(meta.election_scope),
+        ..Default::default()
+    })
+}
+
+fn build_race_input(election: &db::Election, office: &db::Office) -> db::UpsertRaceInput {
+    let (title, slug) =
+        RaceTitleGenerator::from_source(&db::RaceType::General, election, office).generate();
+    db::UpsertRaceInput {
+        title: Some(title),
+        slug: Some(slug),
+        office_id: Some(office.id),
+        election_id: Some(election.id),
+        state: Some(db::State::MN),
+        race_type: Some(db::RaceType::General),
+        // Some cities use ranked choice voting, so keep the vote type of the office's races
+        vote_type: None,
+        ..Default::default()
+    }
+}
+
+/// The filing files are for this year's general election unless a scraper is told otherwise
+pub fn current_election_year() -> u16 {
+    chrono::Local::now().year() as u16
+}
+
+/// Party names for the abbreviations used in the filing files
+fn party_name(abbreviation: &str) -> Option<&'static str> {
+    match abbreviation {
+        "DFL" => Some("Democratic-Farmer-Labor"),
+        "R" => Some("Republican Party"),
+        "LIB" => Some("Libertarian Party"),
+        "GP" => Some("Green Party"),
+        "LMN" => Some("Legal Marijuana Now"),
+        "GLC" => Some("Grassroots-Legalize Cannabis"),
+        "SWP" => Some("Socialist Workers Party"),
+        _ => None,
+    }
+}
+
+fn build_party_input(entry: &FilingEntry) -> Result<Option<db::UpsertPartyInput>, String> {
+    let Some(party) = entry.party.as_str() else {
+        return Ok(None);
+    };
+    // Judicial and local offices are nonpartisan
+    if party == "NP" {
+        return Ok(None);
+    }
+    let Some(name) = party_name(party).and_then(extract_party_name) else {
+        return Err(format!("Unrecognized party: {party}"));
+    };
+    let slug = PartySlugGenerator::new(name.as_str()).generate();
+    Ok(Some(db::UpsertPartyInput {
+        name: Some(name),
+        slug: Some(slug),
+        ..Default::default()
+    }))
+}
+
+fn build_politician_input(
+    entry: &FilingEntry,
+    party: &Option<db::Party>,
+) -> db::UpsertPoliticianInput {
+    let slug = PoliticianSlugGenerator::new(entry.name.as_str()).generate();
+    let ref_key = PoliticianRefKeyGenerator::new(SOURCE_ID, entry.name.as_str()).generate();
+    let name = extract_politician_name(&entry.name).unwrap_or_default();
+    db::UpsertPoliticianInput {
+        slug: Some(slug),
+        ref_key: Some(ref_key),
+        full_name: Some(entry.name.clone()),
+        first_name: Some(name.first),
+        middle_name: name.middle,
+        last_name: Some(name.last.unwrap_or_default()),
+        suffix: name.suffix,
+        preferred_name: name.preferred,
+        home_state: Some(db::State::MN),
+        party_id: party.as_ref().map(|p| p.id),
+        campaign_website_url: entry.website.clone(),
+        email: entry.email.clone(),
+        phone: entry.phone.clone(),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preformatted() {
+        let body = "<html><body><pre>0101;Jane Doe;0101</pre></body></html>";
+        assert_eq!(extract_preformatted(body).unwrap(), "0101;Jane Doe;0101");
+        assert_eq!(
+            extract_preformatted("0101;Jane Doe").unwrap(),
+            "0101;Jane Doe"
+        );
+    }
+
+    #[test]
+    fn jurisdictions() {
+        let tests: Vec<(&'static str, Vec<&'static str>)> = vec![
+            ("U.S. Senator", vec![]),
+            ("Mayor (Minneapolis)", vec!["Minneapolis"]),
+            (
+                "School Board Member (ISD #625) (St. Paul)",
+                vec!["ISD #625", "St. Paul"],
+            ),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(extract_jurisdictions(input), expected);
+        }
+    }
+
+    #[test]
+    fn county() {
+        assert_eq!(county_name(Some("27")), Some("Hennepin"));
+        assert_eq!(county_name(Some("62")), Some("Ramsey"));
+        assert_eq!(county_name(Some("0")), None);
+        assert_eq!(county_name(Some("88")), None);
+        assert_eq!(county_name(None), None);
+    }
+}
@@ -0,0 +1,116 @@
+use std::error::Error;
+
+use super::candidate_filings::{self, field, FilingEntry, SOURCE_ID};
+use crate::{tracking::RunTracker, util, RunReport};
+
+const FIXTURE_PATH: &str = "mn/sos/candidate_filings_fed_state_county.txt";
+const LINK_TEXT: &str = "Candidates in the General Election - Federal, State, and County Offices";
+
+// Column positions within the filing file, which has no header row
+const CANDIDATE_NAME: usize = 1;
+const OFFICE_TITLE: usize = 3;
+const COUNTY_ID: usize = 4;
+const PARTY_ABBREVIATION: usize = 6;
+const CAMPAIGN_PHONE: usize = 15;
+const CAMPAIGN_WEBSITE: usize = 16;
+const CAMPAIGN_EMAIL: usize = 17;
+
+pub struct Scraper {
+    /// The general election the filings are for
+    pub election_year: u16,
+}
+
+impl Default for Scraper {
+    fn default() -> Self {
+        Self {
+            election_year: candidate_filings::current_election_year(),
+        }
+    }
+}
+
+impl crate::Scraper for Scraper {
+    fn source_id(&self) -> &'static str {
+        SOURCE_ID
+    }
+
+    async fn run(&self, context: &crate::ScraperContext<'_>) -> Result<RunReport, Box<dyn Error>> {
+        let text = candidate_filings::fetch_filings(LINK_TEXT).await?;
+        self.scrape_text(text, context).await
+    }
+
+    async fn run_local(
+        &self,
+        context: &crate::ScraperContext<'_>,
+    ) -> Result<RunReport, Box<dyn Error>> {
+        let text = util::read_local_html(FIXTURE_PATH)?;
+        self.scrape_text(text, context).await
+    }
+}
+
+impl Scraper {
+    pub async fn scrape_text(
+        &self,
+        text: String,
+        context: &crate::ScraperContext<'_>,
+    ) -> Result<RunReport, Box<dyn Error>> {
+        let entries = Self::parse_entries(&text)?;
+        let mut run = RunTracker::start(context, SOURCE_ID).await?;
+        let result = candidate_filings::upsert_filings(entries, self.election_year, &mut run)
+            .await
+            .map_err(|err| err.to_string());
+        run.finish(context, result).await
+    }
+
+    pub fn parse_entries(text: &str) -> Result<Vec<FilingEntry>, Box<dyn Error>> {
+        let mut entries = Vec::new();
+        for (index, record) in candidate_filings::read_records(text)?.iter().enumerate() {
+            let entry = FilingEntry {
+                index,
+                name: field(record, CANDIDATE_NAME).ok_or("Empty candidate name field")?,
+                office_title: field(record, OFFICE_TITLE).ok_or("Empty office title field")?,
+                county_id: field(record, COUNTY_ID),
+                school_district: None,
+                party: field(record, PARTY_ABBREVIATION),
+                website: field(record, CAMPAIGN_WEBSITE),
+                email: field(record, CAMPAIGN_EMAIL),
+                phone: field(record, CAMPAIGN_PHONE),
+            };
+
+            if !entry.office_title.starts_with("President") {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_fixture() {
+        let text = util::read_local_html(FIXTURE_PATH).unwrap();
+        let entries = Scraper::parse_entries(&text).unwrap();
+
+        assert!(entries
+            .iter()
+            .all(|e| !e.office_title.starts_with("President")));
+        let entry = &entries[0];
+        assert_eq!(entry.office_title, "U.S. Senator");
+        assert_eq!(entry.party.as_deref(), Some("DFL"));
+        assert_eq!(entry.website.as_deref(), Some("www.example.com/harper"));
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn golden() {
+        crate::golden::assert_golden(
+            Scraper {
+                election_year: 2024,
+            },
+            "mn_sos_candidate_filings_fed_state_county",
+        )
+        .await;
+    }
+}
@@ -0,0 +1,5 @@
+mod candidate_filings;
+pub mod candidate_filings_fed_state_county;
+pub mod candidate_filings_local;
+
+pub use candidate_filings::current_election_year;
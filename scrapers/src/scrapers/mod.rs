@@ -1 +1,2 @@
 pub mod co;
+pub mod mn;
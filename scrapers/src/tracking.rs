@@ -0,0 +1,511 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt::{self, Display},
+    future::Future,
+    pin::Pin,
+};
+
+use serde_json::{json, Map, Value};
+use sqlx::{Connection, PgConnection, Postgres, Transaction};
+
+use crate::{RunMode, ScraperContext};
+
+/// Columns that change on every write and would otherwise make every row look updated
+const IGNORED_FIELDS: [&str; 2] = ["created_at", "updated_at"];
+
+pub use db::ScraperEntityType as EntityType;
+
+/// The unique key an upsert conflicts on, used to snapshot the row before and after writing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntityKey {
+    Slug(String),
+    RefKey(String),
+    RaceCandidate {
+        race_id: uuid::Uuid,
+        candidate_id: uuid::Uuid,
+    },
+}
+
+impl EntityKey {
+    /// Key columns and values, as stored on `scraper_proposed_change.entity_key`
+    pub fn to_json(&self) -> Value {
+        match self {
+            EntityKey::Slug(slug) => json!({ "slug": slug }),
+            EntityKey::RefKey(ref_key) => json!({ "ref_key": ref_key }),
+            EntityKey::RaceCandidate {
+                race_id,
+                candidate_id,
+            } => json!({
+                "race_id": race_id.to_string(),
+                "candidate_id": candidate_id.to_string(),
+            }),
+        }
+    }
+}
+
+impl Display for EntityKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntityKey::Slug(slug) => f.write_str(slug),
+            EntityKey::RefKey(ref_key) => f.write_str(ref_key),
+            EntityKey::RaceCandidate {
+                race_id,
+                candidate_id,
+            } => write!(f, "{race_id}/{candidate_id}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Inserted,
+    Updated,
+    Unchanged,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub entity_type: EntityType,
+    pub key: EntityKey,
+    pub kind: ChangeKind,
+    pub fields: Vec<FieldChange>,
+    /// The row as it looks after the upsert
+    pub record: Value,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunError {
+    pub entity_type: Option<EntityType>,
+    pub source_ref: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EntityCounts {
+    pub inserted: i64,
+    pub updated: i64,
+    pub unchanged: i64,
+    pub failed: i64,
+}
+
+/// Everything a scraper run created, changed or failed to write
+#[derive(Debug, Clone)]
+pub struct RunReport {
+    pub source_id: &'static str,
+    pub mode: RunMode,
+    pub counts: BTreeMap<EntityType, EntityCounts>,
+    pub changes: Vec<Change>,
+    pub errors: Vec<RunError>,
+}
+
+impl RunReport {
+    fn new(source_id: &'static str, mode: RunMode) -> Self {
+        Self {
+            source_id,
+            mode,
+            counts: BTreeMap::new(),
+            changes: vec![],
+            errors: vec![],
+        }
+    }
+
+    /// Counts keyed by entity type, as stored on `scraper_run.stats`
+    pub fn stats(&self) -> Value {
+        let stats = self
+            .counts
+            .iter()
+            .map(|(entity_type, counts)| {
+                (
+                    entity_type.to_string(),
+                    json!({
+                        "inserted": counts.inserted,
+                        "updated": counts.updated,
+                        "unchanged": counts.unchanged,
+                        "failed": counts.failed,
+                    }),
+                )
+            })
+            .collect::<Map<_, _>>();
+        Value::Object(stats)
+    }
+
+    fn record_change(&mut self, change: Change) {
+        let counts = self.counts.entry(change.entity_type).or_default();
+        match change.kind {
+            ChangeKind::Inserted => counts.inserted += 1,
+            ChangeKind::Updated => counts.updated += 1,
+            ChangeKind::Unchanged => counts.unchanged += 1,
+        }
+        self.changes.push(change);
+    }
+
+    fn record_error(&mut self, error: RunError) {
+        if let Some(entity_type) = error.entity_type {
+            self.counts.entry(entity_type).or_default().failed += 1;
+        }
+        self.errors.push(error);
+    }
+}
+
+impl Display for RunReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mode = match self.mode {
+            RunMode::Live => "",
+            RunMode::DryRun => " (dry run)",
+            RunMode::Staged => " (staged for review)",
+        };
+        writeln!(f, "{}{}", self.source_id, mode)?;
+        writeln!(
+            f,
+            "  {:<16}{:>10}{:>10}{:>10}{:>10}",
+            "entity", "inserted", "updated", "unchanged", "failed"
+        )?;
+        for (entity_type, counts) in &self.counts {
+            writeln!(
+                f,
+                "  {:<16}{:>10}{:>10}{:>10}{:>10}",
+                entity_type.to_string(),
+                counts.inserted,
+                counts.updated,
+                counts.unchanged,
+                counts.failed
+            )?;
+        }
+
+        let changes = self
+            .changes
+            .iter()
+            .filter(|c| c.kind != ChangeKind::Unchanged)
+            .collect::<Vec<_>>();
+        if !changes.is_empty() {
+            writeln!(f, "\nChanges")?;
+            for change in changes {
+                let marker = match change.kind {
+                    ChangeKind::Inserted => "+",
+                    _ => "~",
+                };
+                writeln!(f, "  {} {} {}", marker, change.entity_type, change.key)?;
+                for field in &change.fields {
+                    writeln!(f, "      {}: {} -> {}", field.field, field.old, field.new)?;
+                }
+            }
+        }
+
+        if !self.errors.is_empty() {
+            writeln!(f, "\nErrors")?;
+            for error in &self.errors {
+                let entity_type = error
+                    .entity_type
+                    .map(|e| e.to_string())
+                    .unwrap_or_else(|| "run".to_string());
+                match &error.source_ref {
+                    Some(source_ref) => {
+                        writeln!(f, "  ! {} ({}): {}", entity_type, source_ref, error.message)?
+                    }
+                    None => writeln!(f, "  ! {}: {}", entity_type, error.message)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub type UpsertFuture<'c, T> = Pin<Box<dyn Future<Output = Result<T, sqlx::Error>> + Send + 'c>>;
+
+/// Tracks a single scraper run. All writes go through one transaction which is committed when
+/// the run finishes, or rolled back if the run fails or isn't live, so dry and staged runs see
+/// the exact diff a live run would produce. Staged runs store that diff as proposed changes
+/// for staff to review.
+pub struct RunTracker {
+    run_id: Option<uuid::Uuid>,
+    tx: Transaction<'static, Postgres>,
+    report: RunReport,
+}
+
+impl RunTracker {
+    pub async fn start(
+        context: &ScraperContext<'_>,
+        source_id: &'static str,
+    ) -> Result<Self, sqlx::Error> {
+        let run_id = match context.mode {
+            RunMode::DryRun => None,
+            RunMode::Live | RunMode::Staged => {
+                let is_staged = context.mode == RunMode::Staged;
+                let run = db::ScraperRun::start(&context.db.connection, source_id, is_staged);
+                Some(run.await?.id)
+            }
+        };
+
+        Ok(Self {
+            run_id,
+            tx: context.db.connection.begin().await?,
+            report: RunReport::new(source_id, context.mode),
+        })
+    }
+
+    /// Runs an upsert inside a savepoint and records whether it inserted, updated or left the
+    /// row unchanged. Failures are recorded rather than returned so the rest of the run can
+    /// continue.
+    pub async fn upsert<T, F>(
+        &mut self,
+        entity_type: EntityType,
+        key: EntityKey,
+        source_ref: &str,
+        upsert: F,
+    ) -> Option<T>
+    where
+        F: for<'c> FnOnce(&'c mut PgConnection) -> UpsertFuture<'c, T>,
+    {
+        match self.try_upsert(entity_type, &key, upsert).await {
+            Ok((record, change)) => {
+                self.report.record_change(change);
+                Some(record)
+            }
+            Err(err) => {
+                self.fail(
+                    entity_type,
+                    source_ref,
+                    format!("Error upserting {entity_type} {key}: {err}"),
+                );
+                None
+            }
+        }
+    }
+
+    async fn try_upsert<T, F>(
+        &mut self,
+        entity_type: EntityType,
+        key: &EntityKey,
+        upsert: F,
+    ) -> Result<(T, Change), sqlx::Error>
+    where
+        F: for<'c> FnOnce(&'c mut PgConnection) -> UpsertFuture<'c, T>,
+    {
+        let mut savepoint = Connection::begin(&mut *self.tx).await?;
+        let before = snapshot(&mut savepoint, entity_type, key).await?;
+        let record = match upsert(&mut savepoint).await {
+            Ok(record) => record,
+            Err(err) => {
+                savepoint.rollback().await?;
+                return Err(err);
+            }
+        };
+        let after = snapshot(&mut savepoint, entity_type, key).await?;
+        savepoint.commit().await?;
+
+        Ok((record, diff(entity_type, key.clone(), before, after)))
+    }
+
+    /// Records a record that could not be scraped or written
+    pub fn fail(&mut self, entity_type: EntityType, source_ref: &str, message: impl ToString) {
+        self.report.record_error(RunError {
+            entity_type: Some(entity_type),
+            source_ref: Some(source_ref.to_string()),
+            message: message.to_string(),
+        });
+    }
+
+    /// Commits the run, or rolls it back for dry, staged and failed runs, and stores the
+    /// `scraper_run` record along with its errors and any proposed changes
+    pub async fn finish(
+        mut self,
+        context: &ScraperContext<'_>,
+        result: Result<(), String>,
+    ) -> Result<RunReport, Box<dyn Error>> {
+        let error = result.err();
+        let status = match &error {
+            None if self.report.mode == RunMode::Live => {
+                self.tx.commit().await?;
+                db::ScraperRunStatus::Succeeded
+            }
+            None => {
+                self.tx.rollback().await?;
+                db::ScraperRunStatus::Succeeded
+            }
+            Some(message) => {
+                self.tx.rollback().await?;
+                self.report.errors.push(RunError {
+                    entity_type: None,
+                    source_ref: None,
+                    message: message.clone(),
+                });
+                db::ScraperRunStatus::Failed
+            }
+        };
+
+        if let Some(run_id) = self.run_id {
+            let errors = self
+                .report
+                .errors
+                .iter()
+                .map(|e| db::CreateScraperRunErrorInput {
+                    entity_type: e.entity_type.map(|t| t.to_string()),
+                    source_ref: e.source_ref.clone(),
+                    message: e.message.clone(),
+                })
+                .collect::<Vec<_>>();
+            db::ScraperRun::record_errors(&context.db.connection, run_id, &errors).await?;
+
+            if self.report.mode == RunMode::Staged && error.is_none() {
+                let changes = self
+                    .report
+                    .changes
+                    .iter()
+                    .filter_map(|c| {
+                        let change_kind = match c.kind {
+                            ChangeKind::Inserted => db::ProposedChangeKind::Insert,
+                            ChangeKind::Updated => db::ProposedChangeKind::Update,
+                            ChangeKind::Unchanged => return None,
+                        };
+                        let changes = c
+                            .fields
+                            .iter()
+                            .map(|f| json!({ "field": f.field, "old": f.old, "new": f.new }))
+                            .collect();
+                        Some(db::CreateProposedChangeInput {
+                            entity_type: c.entity_type,
+                            entity_key: c.key.to_json(),
+                            change_kind,
+                            record: c.record.clone(),
+                            changes: Value::Array(changes),
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                db::ScraperProposedChange::create_many(&context.db.connection, run_id, &changes)
+                    .await?;
+            }
+            db::ScraperRun::finish(
+                &context.db.connection,
+                run_id,
+                status,
+                self.report.stats(),
+                error.clone(),
+            )
+            .await?;
+        }
+
+        match error {
+            Some(message) => Err(message.into()),
+            None => Ok(self.report),
+        }
+    }
+}
+
+async fn snapshot(
+    conn: &mut PgConnection,
+    entity_type: EntityType,
+    key: &EntityKey,
+) -> Result<Option<Value>, sqlx::Error> {
+    let table = entity_type.table();
+    let predicate = match key {
+        EntityKey::Slug(_) => "slug = $1",
+        // Ref keys of merged politicians live on in the external id crosswalk
+        EntityKey::RefKey(_) => {
+            "ref_key = $1 OR id IN (SELECT entity_id FROM external_id WHERE source = 'ref_key' AND value = $1)"
+        }
+        EntityKey::RaceCandidate { .. } => "race_id = $1 AND candidate_id = $2",
+    };
+    let sql = format!("SELECT to_jsonb(t) FROM {table} t WHERE {predicate}");
+    let query = sqlx::query_scalar::<_, Value>(&sql);
+    let query = match key {
+        EntityKey::Slug(value) | EntityKey::RefKey(value) => query.bind(value.clone()),
+        EntityKey::RaceCandidate {
+            race_id,
+            candidate_id,
+        } => query.bind(*race_id).bind(*candidate_id),
+    };
+    query.fetch_optional(conn).await
+}
+
+/// Compares the row as it was before and after an upsert
+fn diff(
+    entity_type: EntityType,
+    key: EntityKey,
+    before: Option<Value>,
+    after: Option<Value>,
+) -> Change {
+    let record = after.unwrap_or(Value::Null);
+    let Some(before) = before else {
+        return Change {
+            entity_type,
+            key,
+            kind: ChangeKind::Inserted,
+            fields: vec![],
+            record,
+        };
+    };
+
+    let empty = Map::new();
+    let before = before.as_object().unwrap_or(&empty);
+    let fields = record
+        .as_object()
+        .unwrap_or(&empty)
+        .iter()
+        .filter(|(field, _)| !IGNORED_FIELDS.contains(&field.as_str()))
+        .filter_map(|(field, new)| {
+            let old = before.get(field).cloned().unwrap_or(Value::Null);
+            (&old != new).then(|| FieldChange {
+                field: field.clone(),
+                old,
+                new: new.clone(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let kind = if fields.is_empty() {
+        ChangeKind::Unchanged
+    } else {
+        ChangeKind::Updated
+    };
+
+    Change {
+        entity_type,
+        key,
+        kind,
+        fields,
+        record,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let key = EntityKey::Slug("co-state-senate-district-1".into());
+        let before = json!({ "title": "State Senator", "district": "1", "updated_at": "a" });
+
+        let change = diff(EntityType::Office, key.clone(), None, Some(before.clone()));
+        assert_eq!(change.kind, ChangeKind::Inserted);
+
+        let after = json!({ "title": "State Senator", "district": "1", "updated_at": "b" });
+        let change = diff(
+            EntityType::Office,
+            key.clone(),
+            Some(before.clone()),
+            Some(after),
+        );
+        assert_eq!(change.kind, ChangeKind::Unchanged);
+
+        let after = json!({ "title": "State Senator", "district": "2", "updated_at": "b" });
+        let change = diff(EntityType::Office, key, Some(before), Some(after));
+        assert_eq!(change.kind, ChangeKind::Updated);
+        assert_eq!(
+            change.fields,
+            vec![FieldChange {
+                field: "district".into(),
+                old: json!("1"),
+                new: json!("2"),
+            }]
+        );
+    }
+}
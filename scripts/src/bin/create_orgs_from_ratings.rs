@@ -1,4 +1,4 @@
-use db::{CreateOrganizationInput, Organization};
+use db::{CreateOrganizationInput, ExternalIdSource, Organization};
 use serde::{Deserialize, Serialize};
 use std::error::Error;
 use std::process;
@@ -62,14 +62,11 @@ async fn create_organizations() -> Result<(), Box<dyn Error>> {
         .collect::<std::collections::HashSet<i32>>();
 
     for sig_id in unique_sig_ids {
-        let existing_org = sqlx::query_as!(
-            Organization,
-            r#"
-                SELECT * FROM organization
-                WHERE votesmart_sig_id = $1"#,
-            sig_id
+        let existing_org = Organization::find_by_external_id(
+            &pool.connection,
+            ExternalIdSource::Votesmart,
+            &sig_id.to_string(),
         )
-        .fetch_optional(&pool.connection)
         .await
         .unwrap();
 
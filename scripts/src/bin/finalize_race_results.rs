@@ -0,0 +1,103 @@
+use clap::Parser;
+use colored::*;
+use db::{finalize_ballot_measure_results, finalize_race_results};
+use std::error::Error;
+use std::process;
+
+/// Sets winners, counts race wins and losses and advances primary winners to the general for
+/// every race in an election with results, then marks ballot measures passed or failed. Safe to
+/// run again after results change.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[arg(long)]
+    election_slug: String,
+    /// Print what would change without writing anything
+    #[arg(long)]
+    dry_run: bool,
+}
+
+async fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    db::init_pool().await.unwrap();
+    let db_pool = &db::pool().await.connection;
+
+    let election_id = sqlx::query_scalar!(
+        "SELECT id FROM election WHERE slug = $1",
+        args.election_slug
+    )
+    .fetch_one(db_pool)
+    .await?;
+    let finalizations = finalize_race_results(db_pool, election_id, args.dry_run).await?;
+
+    let mut finalized = 0;
+    for f in &finalizations {
+        match &f.skipped_reason {
+            Some(reason) => println!("{} {}: {}", "Skipped".yellow(), f.race_title, reason),
+            None => {
+                finalized += 1;
+                println!(
+                    "{} {} ({}): {} winners, {} losers{}",
+                    if f.previously_finalized {
+                        "Refinalized".bright_green()
+                    } else {
+                        "Finalized".bright_green()
+                    },
+                    f.race_title,
+                    f.rule,
+                    f.winner_ids.len(),
+                    f.loser_ids.len(),
+                    match f.general_race_id {
+                        Some(id) => format!(", advancing to race {}", id),
+                        None => String::new(),
+                    }
+                );
+            }
+        }
+    }
+
+    let measures = finalize_ballot_measure_results(db_pool, election_id, args.dry_run).await?;
+    let mut finalized_measures = 0;
+    for m in &measures {
+        match (&m.status, &m.skipped_reason) {
+            (Some(status), None) => {
+                finalized_measures += 1;
+                println!(
+                    "{} {} ({}): {:?}, {} yes to {} no",
+                    "Finalized".bright_green(),
+                    m.ballot_measure_code,
+                    m.passage_threshold,
+                    status,
+                    m.yes_votes.unwrap_or_default(),
+                    m.no_votes.unwrap_or_default()
+                );
+            }
+            (_, reason) => println!(
+                "{} {}: {}",
+                "Skipped".yellow(),
+                m.ballot_measure_code,
+                reason.as_deref().unwrap_or_default()
+            ),
+        }
+    }
+
+    eprintln!(
+        "\n✅ {} {} of {} races and {} of {} ballot measures{}\n",
+        "Finalized".bright_green().bold(),
+        finalized,
+        finalizations.len(),
+        finalized_measures,
+        measures.len(),
+        if args.dry_run { " (dry run)" } else { "" }
+    );
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+
+    if let Err(err) = run(args).await {
+        println!("error running finalize_race_results: {}", err);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,231 @@
+use chrono::NaiveDate;
+use clap::Parser;
+use colored::*;
+use db::models::enums::State;
+use db::util::tiger::{self, TigerFile};
+use db::{DistrictBoundary, DistrictType, DistrictVintage, UpsertDistrictBoundaryInput};
+use serde_json::Value;
+use std::error::Error;
+use std::io::{BufRead, BufReader};
+use std::process::{self, Command, Stdio};
+use std::str::FromStr;
+use std::time::Instant;
+
+/// Imports district polygons from a Census TIGER/Line file or a state shapefile. Any format
+/// ogr2ogr can read works, including zipped shapefiles. TIGER files are recognized by name,
+/// other files need `--district-type`, `--state`, `--vintage` and `--code-field`.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[arg(long)]
+    file: String,
+    #[arg(long, value_parser = parse_district_type)]
+    district_type: Option<DistrictType>,
+    /// Defaults to the file's state, or each feature's `STATEFP` for national files
+    #[arg(long)]
+    state: Option<State>,
+    #[arg(long)]
+    vintage: Option<i32>,
+    /// First day elections use this vintage, e.g. `2022-01-01` for maps drawn after a census.
+    /// Defaults to January 1st of the vintage.
+    #[arg(long)]
+    effective_from: Option<NaiveDate>,
+    #[arg(long)]
+    code_field: Option<String>,
+    #[arg(long)]
+    name_field: Option<String>,
+    #[arg(long)]
+    geoid_field: Option<String>,
+    /// Field holding the county a sub-county district belongs to
+    #[arg(long)]
+    county_field: Option<String>,
+    /// County every district in the file belongs to
+    #[arg(long)]
+    county: Option<String>,
+    /// Field holding the city a sub-city district belongs to
+    #[arg(long)]
+    municipality_field: Option<String>,
+    /// City every district in the file belongs to
+    #[arg(long)]
+    municipality: Option<String>,
+    /// Keep zero padding on district codes
+    #[arg(long)]
+    keep_code_padding: bool,
+    /// Layer name recorded with each boundary, defaults to `tiger:{layer}` or the file name
+    #[arg(long)]
+    source: Option<String>,
+    /// Map the file is a vintage of, when it is named differently from earlier vintages.
+    /// Defaults to the TIGER layer without its Congress number, or `--source`.
+    #[arg(long)]
+    layer: Option<String>,
+}
+
+fn parse_district_type(value: &str) -> Result<DistrictType, String> {
+    DistrictType::from_str(&value.replace('_', "")).map_err(|err| err.to_string())
+}
+
+/// Reads a property as text, shapefile attributes can come back as numbers
+fn property(properties: &Value, field: &str) -> Option<String> {
+    match properties.get(field)? {
+        Value::String(value) => Some(value.trim().to_string()).filter(|v| !v.is_empty()),
+        Value::Number(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+async fn import_district_boundaries(args: Args) -> Result<(), Box<dyn Error>> {
+    let start = Instant::now();
+    let tiger_file = TigerFile::from_path(&args.file);
+    let layer = tiger_file.as_ref().map(|f| &f.layer);
+
+    let district_type = args
+        .district_type
+        .or(layer.map(|l| l.district_type))
+        .ok_or("--district-type is required for files not named like TIGER/Line files")?;
+    let vintage = args
+        .vintage
+        .or(tiger_file.as_ref().map(|f| f.vintage))
+        .ok_or("--vintage is required for files not named like TIGER/Line files")?;
+    let code_field = args
+        .code_field
+        .clone()
+        .or(layer.map(|l| l.code_field.clone()))
+        .ok_or("--code-field is required for files not named like TIGER/Line files")?;
+    let name_field = args
+        .name_field
+        .clone()
+        .or(layer.map(|l| l.name_field.to_string()));
+    let geoid_field = args
+        .geoid_field
+        .clone()
+        .or(tiger_file.as_ref().map(|_| "GEOID".to_string()));
+    let trim_code = !args.keep_code_padding && layer.map(|l| l.trim_code).unwrap_or(true);
+    let file_state = args.state.or(tiger_file.as_ref().and_then(|f| f.state));
+    let source = args.source.clone().unwrap_or_else(|| match layer {
+        Some(layer) => format!("tiger:{}", layer.layer),
+        None => args
+            .file
+            .rsplit(['/', '\\'])
+            .next()
+            .and_then(|name| name.split('.').next())
+            .unwrap_or(&args.file)
+            .to_string(),
+    });
+    let base_layer = args.layer.clone().unwrap_or_else(|| match layer {
+        Some(layer) => format!("tiger:{}", layer.base_layer()),
+        None => source.clone(),
+    });
+
+    let input_path = if args.file.ends_with(".zip") {
+        format!("/vsizip/{}", args.file)
+    } else {
+        args.file.clone()
+    };
+
+    // ogr2ogr reprojects to WGS 84 and streams one GeoJSON feature per line
+    let mut ogr2ogr = Command::new("ogr2ogr")
+        .args(["-f", "GeoJSONSeq", "/vsistdout/", "-t_srs", "EPSG:4326"])
+        .arg(&input_path)
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("Failed to run ogr2ogr, is GDAL installed? {}", err))?;
+    let features = BufReader::new(ogr2ogr.stdout.take().unwrap());
+
+    db::init_pool().await.unwrap();
+    let db_pool = &db::pool().await.connection;
+    let mut tx = db_pool.begin().await?;
+
+    let mut imported = 0;
+    let mut skipped = 0;
+    let mut states = vec![];
+    for line in features.lines() {
+        let line = line?;
+        let line = line.trim_start_matches('\u{1e}').trim();
+        if line.is_empty() {
+            continue;
+        }
+        let feature: Value = serde_json::from_str(line)?;
+        let properties = &feature["properties"];
+
+        let state = file_state.or_else(|| {
+            property(properties, "STATEFP").and_then(|fips| tiger::state_from_fips(&fips))
+        });
+        let code = property(properties, &code_field).filter(|c| !tiger::is_undefined_code(c));
+        let (Some(state), Some(code), false) = (state, code, feature["geometry"].is_null()) else {
+            skipped += 1;
+            continue;
+        };
+
+        let input = UpsertDistrictBoundaryInput {
+            state,
+            district_type,
+            code: if trim_code {
+                tiger::normalize_code(&code)
+            } else {
+                code
+            },
+            name: name_field.as_deref().and_then(|f| property(properties, f)),
+            county: args.county.clone().or_else(|| {
+                args.county_field
+                    .as_deref()
+                    .and_then(|f| property(properties, f))
+            }),
+            municipality: args.municipality.clone().or_else(|| {
+                args.municipality_field
+                    .as_deref()
+                    .and_then(|f| property(properties, f))
+            }),
+            vintage,
+            geoid: geoid_field.as_deref().and_then(|f| property(properties, f)),
+            source: source.clone(),
+            layer: base_layer.clone(),
+            geometry: feature["geometry"].to_string(),
+        };
+        DistrictBoundary::upsert(&mut tx, &input).await?;
+        if !states.contains(&state) {
+            states.push(state);
+        }
+        imported += 1;
+    }
+
+    if !ogr2ogr.wait()?.success() {
+        return Err(format!("ogr2ogr failed to read {}", args.file).into());
+    }
+
+    // Flags the vintage so refresh_address_districts reassigns addresses in these states
+    for state in states {
+        DistrictVintage::upsert(
+            &mut tx,
+            state,
+            &base_layer,
+            district_type,
+            vintage,
+            args.effective_from,
+        )
+        .await?;
+    }
+    tx.commit().await?;
+
+    let duration = start.elapsed();
+    eprintln!(
+        "\n✅ {} {} {} boundaries ({}), skipped {}\n",
+        "Imported".bright_green().bold(),
+        imported,
+        district_type,
+        source,
+        skipped
+    );
+    eprintln!("🕑 {:?}", duration);
+
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+
+    if let Err(err) = import_district_boundaries(args).await {
+        println!("error running import_district_boundaries: {}", err);
+        process::exit(1);
+    }
+}
@@ -85,22 +85,19 @@ async fn import_legiscan_dataset(
 
     let bill_ids = bills_hash_map.keys().map(|&k| k).collect::<Vec<i32>>();
     // Filter down the hashmap to bills that do not yet exist in the database
-    let existing_bills = sqlx::query!(
+    let existing_bills = sqlx::query_scalar!(
         r#"
-                SELECT legiscan_bill_id FROM bill WHERE legiscan_bill_id = ANY($1)
+                SELECT value FROM external_id
+                WHERE entity_type = 'bill' AND source = 'legiscan' AND value = ANY($1)
             "#,
-        bill_ids.as_slice()
+        &bill_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>()
     )
     .fetch_all(db_pool)
     .await?;
 
     let new_bills = bills_hash_map
         .iter()
-        .filter(|(k, _)| {
-            !existing_bills
-                .iter()
-                .any(|x| x.legiscan_bill_id.unwrap() == **k)
-        })
+        .filter(|(k, _)| !existing_bills.contains(&k.to_string()))
         .collect::<HashMap<_, _>>();
 
     println!(
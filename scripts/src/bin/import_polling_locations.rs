@@ -0,0 +1,146 @@
+use chrono::{NaiveDate, NaiveTime};
+use clap::Parser;
+use colored::*;
+use db::models::enums::State;
+use db::{parse_polling_time, PollingLocation, PollingLocationRow, PollingLocationType};
+use serde::Deserialize;
+use std::error::Error;
+use std::process;
+use std::time::Instant;
+
+/// Imports an election's polling places and early voting locations from a CSV with a header row
+/// and these columns, one row per location, precinct served and date open:
+///
+/// location_type (election_day or early_voting), county_code, precinct_code, name, line_1,
+/// line_2, city, postal_code, date, opens_at, closes_at, accessibility_notes
+///
+/// Codes match the state's precinct results and boundary files. Early voting rows without a
+/// precinct_code serve their county, or the whole state without a county_code, rows with a
+/// precinct_code always need its county_code. Rows with a date need both times. Rows that
+/// break these rules are reported and skipped. Importing replaces the election's locations of
+/// the types in the file.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[arg(long)]
+    file: String,
+    #[arg(long)]
+    election_slug: String,
+    #[arg(long, default_value = "MN")]
+    state: State,
+}
+
+#[derive(Debug, Deserialize)]
+struct Row {
+    location_type: PollingLocationType,
+    county_code: Option<String>,
+    precinct_code: Option<String>,
+    name: String,
+    line_1: String,
+    line_2: Option<String>,
+    city: String,
+    postal_code: Option<String>,
+    date: Option<NaiveDate>,
+    opens_at: Option<String>,
+    closes_at: Option<String>,
+    accessibility_notes: Option<String>,
+}
+
+fn time(column: &str, value: &Option<String>) -> Result<Option<NaiveTime>, String> {
+    match value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+        Some(value) => parse_polling_time(value)
+            .map(Some)
+            .ok_or_else(|| format!("unrecognized {} {:?}", column, value)),
+        None => Ok(None),
+    }
+}
+
+/// Checks a row can be imported as written and returns its opening and closing times
+fn validate(row: &Row) -> Result<(Option<NaiveTime>, Option<NaiveTime>), String> {
+    if row.location_type == PollingLocationType::ElectionDay
+        && (row.county_code.is_none() || row.precinct_code.is_none())
+    {
+        return Err("election day locations need a county_code and precinct_code".to_string());
+    }
+    if row.precinct_code.is_some() && row.county_code.is_none() {
+        // Without its county the row would be imported as a statewide location
+        return Err("rows with a precinct_code need a county_code".to_string());
+    }
+
+    let opens_at = time("opens_at", &row.opens_at)?;
+    let closes_at = time("closes_at", &row.closes_at)?;
+    match (row.date, opens_at, closes_at) {
+        (Some(_), Some(_), Some(_)) | (None, None, None) => Ok((opens_at, closes_at)),
+        (Some(_), _, _) => Err("rows with a date need opens_at and closes_at".to_string()),
+        (None, _, _) => Err("rows with hours need a date".to_string()),
+    }
+}
+
+async fn import_polling_locations(args: Args) -> Result<(), Box<dyn Error>> {
+    let start = Instant::now();
+    let mut rdr = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_path(&args.file)?;
+
+    let mut rows = vec![];
+    let mut skipped = 0;
+    for (index, result) in rdr.deserialize().enumerate() {
+        let row: Row = result?;
+        // Line numbers count the header
+        let line = index + 2;
+        let (opens_at, closes_at) = match validate(&row) {
+            Ok(hours) => hours,
+            Err(reason) => {
+                println!("{} line {}, {}", "Skipped".yellow(), line, reason);
+                skipped += 1;
+                continue;
+            }
+        };
+        rows.push(PollingLocationRow {
+            location_type: row.location_type,
+            opens_at,
+            closes_at,
+            county_code: row.county_code,
+            precinct_code: row.precinct_code,
+            name: row.name,
+            line_1: row.line_1,
+            line_2: row.line_2,
+            city: row.city,
+            postal_code: row.postal_code,
+            date: row.date,
+            accessibility_notes: row.accessibility_notes,
+        });
+    }
+
+    db::init_pool().await.unwrap();
+    let db_pool = &db::pool().await.connection;
+    let election_id = sqlx::query_scalar!(
+        "SELECT id FROM election WHERE slug = $1",
+        args.election_slug
+    )
+    .fetch_one(db_pool)
+    .await?;
+    let imported = PollingLocation::import(db_pool, election_id, args.state, &rows).await?;
+
+    let duration = start.elapsed();
+    eprintln!(
+        "\n✅ {} {} polling locations from {} rows, skipped {}\n",
+        "Imported".bright_green().bold(),
+        imported,
+        rows.len(),
+        skipped
+    );
+    eprintln!("🕑 {:?}", duration);
+
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+
+    if let Err(err) = import_polling_locations(args).await {
+        println!("error running import_polling_locations: {}", err);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,122 @@
+use clap::Parser;
+use colored::*;
+use db::models::enums::State;
+use db::Precinct;
+use serde_json::Value;
+use std::error::Error;
+use std::io::{BufRead, BufReader};
+use std::process::{self, Command, Stdio};
+use std::time::Instant;
+
+/// Imports precinct polygons so precinct results can be mapped. Any format ogr2ogr can read
+/// works, including zipped shapefiles. Field defaults match the Minnesota voting precincts
+/// shapefile, where codes are the same ones the Secretary of State's results files use. Each
+/// year's file is kept as its own vintage, elections use the latest one from their year.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[arg(long)]
+    file: String,
+    #[arg(long, default_value = "MN")]
+    state: State,
+    /// Year the precinct maps are for, e.g. 2024 for the maps used in the 2024 elections
+    #[arg(long)]
+    vintage: i32,
+    #[arg(long, default_value = "COUNTYCODE")]
+    county_field: String,
+    #[arg(long, default_value = "PCTCODE")]
+    code_field: String,
+    #[arg(long, default_value = "PCTNAME")]
+    name_field: String,
+}
+
+/// Reads a property as text, shapefile attributes can come back as numbers
+fn property(properties: &Value, field: &str) -> Option<String> {
+    match properties.get(field)? {
+        Value::String(value) => Some(value.trim().to_string()).filter(|v| !v.is_empty()),
+        Value::Number(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+async fn import_precinct_boundaries(args: Args) -> Result<(), Box<dyn Error>> {
+    let start = Instant::now();
+    let input_path = if args.file.ends_with(".zip") {
+        format!("/vsizip/{}", args.file)
+    } else {
+        args.file.clone()
+    };
+
+    // ogr2ogr reprojects to WGS 84 and streams one GeoJSON feature per line
+    let mut ogr2ogr = Command::new("ogr2ogr")
+        .args(["-f", "GeoJSONSeq", "/vsistdout/", "-t_srs", "EPSG:4326"])
+        .arg(&input_path)
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("Failed to run ogr2ogr, is GDAL installed? {}", err))?;
+    let features = BufReader::new(ogr2ogr.stdout.take().unwrap());
+
+    db::init_pool().await.unwrap();
+    let db_pool = &db::pool().await.connection;
+    let mut tx = db_pool.begin().await?;
+
+    let mut imported = 0;
+    let mut skipped = 0;
+    for line in features.lines() {
+        let line = line?;
+        let line = line.trim_start_matches('\u{1e}').trim();
+        if line.is_empty() {
+            continue;
+        }
+        let feature: Value = serde_json::from_str(line)?;
+        let properties = &feature["properties"];
+
+        let (Some(county_code), Some(precinct_code), false) = (
+            property(properties, &args.county_field),
+            property(properties, &args.code_field),
+            feature["geometry"].is_null(),
+        ) else {
+            skipped += 1;
+            continue;
+        };
+
+        Precinct::upsert_geometry(
+            &mut tx,
+            args.state,
+            &county_code,
+            &precinct_code,
+            property(properties, &args.name_field).as_deref(),
+            args.vintage,
+            &feature["geometry"].to_string(),
+        )
+        .await?;
+        imported += 1;
+    }
+
+    if !ogr2ogr.wait()?.success() {
+        return Err(format!("ogr2ogr failed to read {}", args.file).into());
+    }
+    tx.commit().await?;
+
+    let duration = start.elapsed();
+    eprintln!(
+        "\n✅ {} {} precincts ({}), skipped {}\n",
+        "Imported".bright_green().bold(),
+        imported,
+        args.vintage,
+        skipped
+    );
+    eprintln!("🕑 {:?}", duration);
+
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+
+    if let Err(err) = import_precinct_boundaries(args).await {
+        println!("error running import_precinct_boundaries: {}", err);
+        process::exit(1);
+    }
+}
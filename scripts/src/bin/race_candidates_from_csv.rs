@@ -55,8 +55,12 @@ async fn upsert_race_candidates() -> Result<(), Box<dyn Error>> {
         let input: RaceCandidate = result?;
         let _record = sqlx::query!(
             r#"
-            INSERT INTO race_candidates (race_id, candidate_id, is_running, date_announced, date_qualified, date_dropped, reason_dropped, qualification_method, qualification_info, votes) 
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) 
+            INSERT INTO race_candidates (race_id, candidate_id, is_running, date_announced, date_qualified, date_dropped, reason_dropped, qualification_method, qualification_info, votes, status) 
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, CASE
+                WHEN NOT $3 THEN 'withdrawn'::candidacy_status
+                WHEN $5::date IS NOT NULL THEN 'qualified'::candidacy_status
+                ELSE 'filed'::candidacy_status
+            END) 
             ON CONFLICT (race_id, candidate_id) DO UPDATE SET 
                 is_running = EXCLUDED.is_running,
                 date_announced = EXCLUDED.date_announced,
@@ -65,8 +69,12 @@ async fn upsert_race_candidates() -> Result<(), Box<dyn Error>> {
                 reason_dropped = EXCLUDED.reason_dropped,
                 qualification_method = EXCLUDED.qualification_method,
                 qualification_info = EXCLUDED.qualification_info,
-                votes = EXCLUDED.votes
-            RETURNING *
+                votes = EXCLUDED.votes,
+                status = CASE
+                    WHEN race_candidates.status = 'disqualified' AND NOT EXCLUDED.is_running THEN race_candidates.status
+                    ELSE EXCLUDED.status
+                END
+            RETURNING race_id
         "#,
             input.race_id,
             input.candidate_id,
@@ -1,4 +1,5 @@
 use colored::*;
+use db::RaceResultsSnapshot;
 use serde::{Deserialize, Serialize};
 use spinners::{Spinner, Spinners};
 use std::collections::HashMap;
@@ -16,10 +17,14 @@ struct ColoradoSummaryResult {
     politician_id: uuid::Uuid,
 }
 
+/// Reads candidates' vote totals from stdin. Races with certified results are refused unless
+/// `--force` is passed, and nothing is imported then.
 async fn import_race_results_from_csv() -> Result<(), Box<dyn Error>> {
+    let force = std::env::args().any(|arg| arg == "--force");
     let mut sp = Spinner::new(Spinners::Dots5, "Importing race results from CSV".into());
     db::init_pool().await.unwrap();
     let pool = db::pool().await;
+    let mut tx = pool.connection.begin().await?;
 
     let mut total_counts = HashMap::new();
 
@@ -28,26 +33,27 @@ async fn import_race_results_from_csv() -> Result<(), Box<dyn Error>> {
         let candidate: ColoradoSummaryResult = result?;
         let record = sqlx::query!(
             r#"
-            WITH rc AS (
-                SELECT candidate_id, race_id FROM race_candidates
-                JOIN politician ON politician.id = candidate_id
-                JOIN race ON race.id = race_id
-                JOIN election ON election.id = election_id
-                WHERE election.slug = 'general-election-2022' AND
-                      candidate_id = $2
-            )
-            UPDATE race_candidates
-            SET votes = $1
-            FROM rc
-            WHERE race_candidates.candidate_id = rc.candidate_id AND
-                  race_candidates.race_id = rc.race_id
-            RETURNING race_candidates.race_id
+            SELECT race_id FROM race_candidates
+            JOIN race ON race.id = race_id
+            JOIN election ON election.id = election_id
+            WHERE election.slug = 'general-election-2022' AND
+                  candidate_id = $1
         "#,
-            candidate.total_votes,
             candidate.politician_id
         )
-        .fetch_optional(&pool.connection)
+        .fetch_optional(&mut *tx)
         .await?;
+        if let Some(record) = &record {
+            RaceResultsSnapshot::update_votes(
+                &mut tx,
+                record.race_id,
+                None,
+                &[(candidate.politician_id, candidate.total_votes)],
+                None,
+                force,
+            )
+            .await?;
+        }
 
         if record.is_none() {
             eprintln!(
@@ -130,6 +136,7 @@ async fn import_race_results_from_csv() -> Result<(), Box<dyn Error>> {
     // .fetch_optional(&pool.connection)
     // .await;
 
+    tx.commit().await?;
     sp.stop();
     eprintln!("\n✅ {}", "Success".bright_green().bold());
     Ok(())
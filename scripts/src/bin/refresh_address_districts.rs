@@ -0,0 +1,15 @@
+use std::{error::Error, process};
+
+async fn refresh_address_districts() -> Result<(), Box<dyn Error>> {
+    db::init_pool().await.unwrap();
+    server::jobs::refresh_address_districts::run().await?;
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    if let Err(err) = refresh_address_districts().await {
+        println!("error running refresh_address_districts: {}", err);
+        process::exit(1);
+    }
+}
@@ -8,10 +8,10 @@ async fn seed_bill_sponsors_table() -> Result<(), Box<dyn Error>> {
     let query = sqlx::query!(
         r#"
             WITH politician_records AS (
-                SELECT p.id, p.legiscan_people_id, b.id AS bill_id
-                FROM politician AS p
-                LEFT JOIN bill AS b ON b.legiscan_data->'sponsors' @> jsonb_build_array(jsonb_build_object('people_id', p.legiscan_people_id))
-                WHERE p.legiscan_people_id IS NOT NULL AND b.id IS NOT NULL
+                SELECT e.entity_id AS id, b.id AS bill_id
+                FROM external_id AS e
+                JOIN bill AS b ON b.legiscan_data->'sponsors' @> jsonb_build_array(jsonb_build_object('people_id', e.value::integer))
+                WHERE e.entity_type = 'politician' AND e.source = 'legiscan' AND e.value ~ '^[0-9]+$'
             )
             INSERT INTO bill_sponsors (bill_id, politician_id)
             SELECT bill_id, id
@@ -0,0 +1,85 @@
+use chrono::NaiveDate;
+use clap::Parser;
+use colored::*;
+use db::transition_office_holders;
+use std::error::Error;
+use std::process;
+
+/// Makes winners of certified races the incumbents of their offices once their terms begin, ends
+/// the terms they replace and removes holders whose terms ended. Runs daily on the server, use
+/// this to preview or catch up.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Print what would change without writing anything
+    #[arg(long)]
+    dry_run: bool,
+    /// Transition as of this date instead of today, e.g. 2025-01-06
+    #[arg(long)]
+    date: Option<NaiveDate>,
+}
+
+async fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    db::init_pool().await.unwrap();
+    let db_pool = &db::pool().await.connection;
+
+    let today = args
+        .date
+        .unwrap_or_else(|| chrono::Local::now().date_naive());
+    let run = transition_office_holders(db_pool, today, args.dry_run).await?;
+
+    let mut applied = 0;
+    for t in &run.transitions {
+        match &t.waiting_reason {
+            Some(reason) => println!(
+                "{} {} ({}): {}",
+                "Waiting".yellow(),
+                t.office_title,
+                t.politician_id,
+                reason
+            ),
+            None => {
+                applied += 1;
+                println!(
+                    "{} {} ({}) from {}, replacing {} holders",
+                    "Seated".bright_green(),
+                    t.office_title,
+                    t.politician_id,
+                    t.start_date,
+                    t.outgoing_politician_ids.len()
+                );
+            }
+        }
+    }
+
+    for t in &run.ended_terms {
+        println!(
+            "{} {} ({}), term ended {}",
+            "Removed".bright_red(),
+            t.office_title,
+            t.politician_id,
+            t.end_date
+        );
+    }
+
+    eprintln!(
+        "\n✅ {} {} of {} pending terms, removed {} holders whose terms ended and cleared {} upcoming races{}\n",
+        "Started".bright_green().bold(),
+        applied,
+        run.transitions.len(),
+        run.ended_terms.len(),
+        run.finished_race_candidates,
+        if args.dry_run { " (dry run)" } else { "" }
+    );
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+
+    if let Err(err) = run(args).await {
+        println!("error running transition_office_holders: {}", err);
+        process::exit(1);
+    }
+}
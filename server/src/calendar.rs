@@ -0,0 +1,79 @@
+//! iCalendar feeds of election days and voter deadlines, served at
+//! `/calendar/elections/:election_id.ics`, `/calendar/states/:state.ics` and
+//! `/calendar/user/:token.ics`. User feeds are signed links from `auth::create_calendar_token`
+//! since calendar apps can't sign in.
+
+use std::str::FromStr;
+
+use axum::{
+    extract::Path,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use db::{ElectionCalendar, State};
+use tracing::error;
+
+/// Calendar apps poll feeds on their own schedule, an hour of caching is plenty
+const CACHE_CONTROL: &str = "public, max-age=3600";
+
+pub async fn election_calendar(Path(election_id): Path<String>) -> Response {
+    let Ok(election_id) = uuid::Uuid::parse_str(feed_key(&election_id)) else {
+        return (StatusCode::NOT_FOUND, "Election not found").into_response();
+    };
+    let db_pool = &db::pool().await.connection;
+    respond(
+        ElectionCalendar::for_election(db_pool, election_id).await,
+        CACHE_CONTROL,
+    )
+}
+
+pub async fn state_calendar(Path(state): Path<String>) -> Response {
+    let Ok(state) = State::from_str(&feed_key(&state).to_uppercase()) else {
+        return (StatusCode::NOT_FOUND, "State not found").into_response();
+    };
+    let db_pool = &db::pool().await.connection;
+    respond(
+        ElectionCalendar::for_state(db_pool, state).await,
+        CACHE_CONTROL,
+    )
+}
+
+pub async fn user_calendar(Path(token): Path<String>) -> Response {
+    let Ok(token) = auth::validate_calendar_token(feed_key(&token)) else {
+        return (StatusCode::NOT_FOUND, "This link is invalid or has expired").into_response();
+    };
+    let db_pool = &db::pool().await.connection;
+    respond(
+        ElectionCalendar::for_user(db_pool, token.claims.sub).await,
+        "private, max-age=3600",
+    )
+}
+
+/// Feed links end in `.ics` so calendar apps recognize them
+fn feed_key(segment: &str) -> &str {
+    segment.strip_suffix(".ics").unwrap_or(segment)
+}
+
+fn respond(calendar: Result<ElectionCalendar, sqlx::Error>, cache_control: &str) -> Response {
+    match calendar {
+        Ok(calendar) => (
+            [
+                (header::CONTENT_TYPE, "text/calendar; charset=utf-8"),
+                (header::CACHE_CONTROL, cache_control),
+            ],
+            calendar.to_icalendar(),
+        )
+            .into_response(),
+        Err(sqlx::Error::RowNotFound) => {
+            (StatusCode::NOT_FOUND, "Calendar not found").into_response()
+        }
+        Err(err) => {
+            error!("Failed to load calendar: {}", err);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Something went wrong loading this calendar",
+            )
+                .into_response()
+        }
+    }
+}
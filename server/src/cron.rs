@@ -1,7 +1,7 @@
 use tokio_cron_scheduler::{Job, JobScheduler};
 use tracing::{error, info, warn};
 
-use crate::update_legiscan_bill_data;
+use crate::{refresh_address_districts, transition_office_holders, update_legiscan_bill_data};
 
 // Creates a new job scheduler and adds an async job to update legiscan bill data
 pub async fn init_job_schedule() {
@@ -40,6 +40,42 @@ pub async fn init_job_schedule() {
     })
     .unwrap();
 
+    // Reassign addresses to newly loaded district maps every hour
+    let refresh_address_districts_job = Job::new_async("0 15 * * * *", |uuid, mut l| {
+        Box::pin(async move {
+            tracing::warn!("Running refresh_address_districts job");
+            refresh_address_districts::run()
+                .await
+                .map_err(|e| error!("Failed to refresh address districts: {}", e))
+                .ok();
+
+            let next_tick = l.next_tick_for_job(uuid).await;
+            match next_tick {
+                Ok(Some(ts)) => info!("Next time for refresh_address_districts is {:?}", ts),
+                _ => warn!("Could not get next tick for refresh_address_districts job"),
+            }
+        })
+    })
+    .unwrap();
+
+    // Seat winners of certified races once their terms begin, early each morning
+    let transition_office_holders_job = Job::new_async("0 30 5 * * *", |uuid, mut l| {
+        Box::pin(async move {
+            tracing::warn!("Running transition_office_holders job");
+            transition_office_holders::run()
+                .await
+                .map_err(|e| error!("Failed to transition office holders: {}", e))
+                .ok();
+
+            let next_tick = l.next_tick_for_job(uuid).await;
+            match next_tick {
+                Ok(Some(ts)) => info!("Next time for transition_office_holders is {:?}", ts),
+                _ => warn!("Could not get next tick for transition_office_holders job"),
+            }
+        })
+    })
+    .unwrap();
+
     // Run job every 10 minutes on the load test Fridays for MN Sos results
     let update_mn_results_job = Job::new_async("0 1/10 * 4/11/18/25 Oct * 2024", |uuid, mut l| {
         Box::pin(async move {
@@ -62,11 +98,15 @@ pub async fn init_job_schedule() {
         config::Environment::Production => {
             info!("Running cron jobs in production environment");
             sched.add(update_legiscan_bills_job).await.unwrap();
+            sched.add(refresh_address_districts_job).await.unwrap();
+            sched.add(transition_office_holders_job).await.unwrap();
             // sched.add(update_mn_results_job).await.unwrap(); // Uncomment after load tests are completed Nov 1, 2024
         }
         config::Environment::Staging => {
             info!("Running cron jobs in staging environment");
             sched.add(update_legiscan_bills_job).await.unwrap();
+            sched.add(refresh_address_districts_job).await.unwrap();
+            sched.add(transition_office_holders_job).await.unwrap();
             sched.add(update_mn_results_job).await.unwrap();
         }
         _ => {
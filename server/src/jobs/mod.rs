@@ -1 +1,3 @@
+pub mod refresh_address_districts;
+pub mod transition_office_holders;
 pub mod update_legiscan_bill_data;
@@ -0,0 +1,17 @@
+use tracing::info;
+
+/// Reassigns addresses to the districts of any vintage loaded or taken effect since the last run
+pub async fn run() -> anyhow::Result<()> {
+    let pool = db::pool().await;
+    let vintages = db::DistrictVintage::pending_refresh(&pool.connection).await?;
+
+    for vintage in vintages.iter() {
+        let assigned = vintage.refresh_addresses(&pool.connection).await?;
+        info!(
+            "Assigned {} addresses to {} {} districts ({})",
+            assigned, vintage.state, vintage.layer, vintage.vintage
+        );
+    }
+
+    Ok(())
+}
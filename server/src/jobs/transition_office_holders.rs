@@ -0,0 +1,30 @@
+use tracing::info;
+
+/// Makes winners of certified races the incumbents of their offices once their terms begin
+pub async fn run() -> anyhow::Result<()> {
+    let pool = db::pool().await;
+    let today = chrono::Local::now().date_naive();
+    let run = db::transition_office_holders(&pool.connection, today, false).await?;
+
+    for transition in run.transitions.iter().filter(|t| t.is_applied) {
+        info!(
+            "{} took office as {} on {}, replacing {} holders",
+            transition.politician_id,
+            transition.office_title,
+            transition.start_date,
+            transition.outgoing_politician_ids.len()
+        );
+    }
+    for ended in &run.ended_terms {
+        info!(
+            "{} left office as {}, their term ended {}",
+            ended.politician_id, ended.office_title, ended.end_date
+        );
+    }
+    info!(
+        "Cleared the upcoming race of {} candidates in certified races",
+        run.finished_race_candidates
+    );
+
+    Ok(())
+}
@@ -9,9 +9,12 @@ use tower_http::cors::CorsLayer;
 use tracing::info;
 use tracing_subscriber::EnvFilter;
 
+pub mod calendar;
 mod cron;
 pub mod jobs;
+pub mod maps;
 mod postgres;
+pub mod print;
 pub use cron::init_job_schedule;
 pub use jobs::*;
 mod handlers;
@@ -52,6 +55,18 @@ pub async fn run() {
 
     let app = axum::Router::new()
         .route("/", get(graphql_playground).post(graphql_handler))
+        .route("/print/:token", get(print::print_html))
+        .route("/print/:token/pdf", get(print::print_pdf))
+        .route(
+            "/results/:race_id/precincts",
+            get(maps::precinct_results_geojson),
+        )
+        .route(
+            "/calendar/elections/:election_id",
+            get(calendar::election_calendar),
+        )
+        .route("/calendar/states/:state", get(calendar::state_calendar))
+        .route("/calendar/user/:token", get(calendar::user_calendar))
         .with_state(schema)
         .layer(CorsLayer::very_permissive())
         .layer(CookieManagerLayer::new());
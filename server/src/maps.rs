@@ -0,0 +1,44 @@
+//! GeoJSON for result maps, served at `/results/:race_id/precincts`. Responses are public and
+//! cached briefly so embedded maps from partner sites don't hit the database on every view.
+
+use axum::{
+    extract::Path,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use db::{PrecinctRaceResult, Race};
+use tracing::error;
+
+pub async fn precinct_results_geojson(Path(race_id): Path<String>) -> Response {
+    let Ok(race_id) = uuid::Uuid::parse_str(&race_id) else {
+        return (StatusCode::NOT_FOUND, "Race not found").into_response();
+    };
+    let db_pool = &db::pool().await.connection;
+
+    let geojson = match Race::find_by_id(db_pool, race_id).await {
+        Ok(_) => PrecinctRaceResult::geojson_for_race(db_pool, race_id).await,
+        Err(err) => Err(err),
+    };
+    match geojson {
+        Ok(geojson) => (
+            [
+                (header::CONTENT_TYPE, "application/geo+json"),
+                (header::CACHE_CONTROL, "public, max-age=60"),
+            ],
+            geojson.to_string(),
+        )
+            .into_response(),
+        Err(sqlx::Error::RowNotFound) => (StatusCode::NOT_FOUND, "Race not found").into_response(),
+        Err(err) => {
+            error!(
+                "Failed to load precinct results for race {}: {}",
+                race_id, err
+            );
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Something went wrong loading precinct results",
+            )
+                .into_response()
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! Print views of sample ballots and candidate guides. Pages are self contained, styles are
+//! inlined and images are the only external resources, so the same markup renders in a
+//! browser's print dialog and through the PDF renderer.
+
+use db::{BallotSection, CandidateGuideSheet, SampleBallot};
+use std::fmt::Write;
+
+const STYLES: &str = r#"
+    @page { size: letter; margin: 0.5in; }
+    body { font-family: Helvetica, Arial, sans-serif; font-size: 11pt; color: #111; margin: 0 auto; max-width: 7.5in; }
+    header { border-bottom: 2px solid #111; margin-bottom: 12pt; padding-bottom: 6pt; }
+    header img { max-height: 48pt; max-width: 2in; float: right; }
+    h1 { font-size: 18pt; margin: 0 0 4pt; }
+    h2 { font-size: 12pt; text-transform: uppercase; letter-spacing: 1pt; background: #111; color: #fff; padding: 3pt 6pt; margin: 14pt 0 6pt; }
+    h3 { font-size: 11pt; margin: 0 0 2pt; }
+    .subtitle, .instructions, footer { color: #444; font-size: 9pt; }
+    .race, .measure, .candidate { page-break-inside: avoid; break-inside: avoid; }
+    .race, .measure { border: 1px solid #999; padding: 6pt; margin-bottom: 6pt; }
+    .choice { display: flex; align-items: baseline; margin: 3pt 0; }
+    .oval { display: inline-block; width: 16pt; height: 9pt; border: 1.5px solid #111; border-radius: 50%; margin-right: 6pt; flex-shrink: 0; }
+    .oval.selected { background: #111; }
+    .party { color: #444; font-size: 9pt; margin-left: 4pt; }
+    .note { font-style: italic; font-size: 9pt; margin: 0 0 4pt 22pt; }
+    .candidate { border-top: 1px solid #ccc; padding-top: 6pt; margin-top: 6pt; }
+    .question { font-weight: bold; margin: 6pt 0 2pt; }
+    .response { white-space: pre-wrap; margin: 0; }
+    .no-response { color: #777; font-style: italic; margin: 0; }
+    footer { border-top: 1px solid #999; margin-top: 14pt; padding-top: 4pt; }
+    @media screen { body { padding: 24px; } }
+"#;
+
+/// Escapes text for use in element content and double quoted attributes
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<meta name=\"robots\" content=\"noindex\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape(title),
+        STYLES,
+        body
+    )
+}
+
+fn section_heading(section: BallotSection) -> &'static str {
+    match section {
+        BallotSection::Federal => "Federal Offices",
+        BallotSection::State => "State Offices",
+        BallotSection::County => "County Offices",
+        BallotSection::City => "City Offices",
+        BallotSection::School => "School District",
+        BallotSection::Judicial => "Judicial Offices",
+        BallotSection::Measures => "Ballot Measures",
+    }
+}
+
+fn vote_for(num_elect: Option<i32>) -> String {
+    match num_elect {
+        Some(n) if n > 1 => format!("Vote for up to {}", n),
+        _ => "Vote for one".to_string(),
+    }
+}
+
+pub fn sample_ballot(ballot: &SampleBallot) -> String {
+    let election_date = ballot.election.election_date.format("%B %-d, %Y");
+    let title = ballot
+        .voting_guide
+        .title
+        .clone()
+        .unwrap_or_else(|| match &ballot.author_name {
+            Some(name) => format!("{}'s Voting Guide", name),
+            None => "My Voting Guide".to_string(),
+        });
+
+    let mut body = String::new();
+    let _ = write!(
+        body,
+        "<header>\n<h1>{}</h1>\n<div class=\"subtitle\">{} &middot; {}</div>\n",
+        escape(&title),
+        escape(&ballot.election.title),
+        election_date
+    );
+    if let Some(description) = &ballot.voting_guide.description {
+        let _ = writeln!(body, "<p>{}</p>", escape(description));
+    }
+    let _ = write!(
+        body,
+        "<div class=\"instructions\">{}</div>\n</header>\n",
+        if ballot.is_personalized {
+            "Sample ballot for your address with your picks filled in. This is not an official ballot."
+        } else {
+            "Your picks and notes. Add your address to see everything on your ballot. This is not an official ballot."
+        }
+    );
+
+    let mut section = None;
+    for race in &ballot.races {
+        if section != Some(race.section) {
+            section = Some(race.section);
+            let _ = writeln!(body, "<h2>{}</h2>", section_heading(race.section));
+        }
+        let _ = write!(
+            body,
+            "<div class=\"race\">\n<h3>{}</h3>\n",
+            escape(&race.race.title)
+        );
+        if let Some(subtitle) = &race.office.subtitle {
+            let _ = writeln!(body, "<div class=\"subtitle\">{}</div>", escape(subtitle));
+        }
+        let _ = writeln!(
+            body,
+            "<div class=\"instructions\">{}</div>",
+            vote_for(race.race.num_elect)
+        );
+        if race.candidates.is_empty() {
+            body.push_str("<div class=\"subtitle\">No candidates listed yet</div>\n");
+        }
+        for candidate in &race.candidates {
+            let _ = write!(
+                body,
+                "<div class=\"choice\"><span class=\"oval{}\"></span><span>{}</span>",
+                if candidate.is_selected {
+                    " selected"
+                } else {
+                    ""
+                },
+                escape(&candidate.candidate.name)
+            );
+            if let Some(party) = &candidate.candidate.party {
+                let _ = write!(body, "<span class=\"party\">{}</span>", escape(party));
+            }
+            body.push_str("</div>\n");
+            if let Some(note) = &candidate.note {
+                let _ = writeln!(body, "<p class=\"note\">{}</p>", escape(note));
+            }
+        }
+        body.push_str("</div>\n");
+    }
+
+    if !ballot.ballot_measures.is_empty() {
+        let _ = writeln!(
+            body,
+            "<h2>{}</h2>",
+            section_heading(BallotSection::Measures)
+        );
+    }
+    for measure in &ballot.ballot_measures {
+        let _ = write!(
+            body,
+            "<div class=\"measure\">\n<h3>{} {}</h3>\n",
+            escape(&measure.ballot_measure_code),
+            escape(&measure.title)
+        );
+        if let Some(summary) = measure
+            .populist_summary
+            .as_ref()
+            .or(measure.official_summary.as_ref())
+        {
+            let _ = writeln!(body, "<p class=\"response\">{}</p>", escape(summary));
+        }
+        body.push_str("<div class=\"choice\"><span class=\"oval\"></span><span>Yes</span></div>\n<div class=\"choice\"><span class=\"oval\"></span><span>No</span></div>\n</div>\n");
+    }
+
+    body.push_str("<footer>Made with Populist &middot; populist.us</footer>\n");
+    page(&title, &body)
+}
+
+/// `logo` is the organization's logo as a URL or data URI
+pub fn candidate_guide_sheet(sheet: &CandidateGuideSheet, logo: Option<&str>) -> String {
+    let title = format!("{}: {}", sheet.organization.name, sheet.race.title);
+
+    let mut body = String::from("<header>\n");
+    if let Some(logo) = logo {
+        let _ = writeln!(
+            body,
+            "<img src=\"{}\" alt=\"{}\">",
+            escape(logo),
+            escape(&sheet.organization.name)
+        );
+    }
+    let _ = write!(
+        body,
+        "<h1>{}</h1>\n<div class=\"subtitle\">{}</div>\n</header>\n",
+        escape(&sheet.race.title),
+        escape(
+            sheet
+                .candidate_guide
+                .name
+                .as_deref()
+                .unwrap_or(&sheet.organization.name)
+        )
+    );
+
+    if sheet.candidates.is_empty() {
+        body.push_str("<p class=\"no-response\">No candidates listed yet</p>\n");
+    }
+    for candidate in &sheet.candidates {
+        let _ = write!(
+            body,
+            "<div class=\"candidate\">\n<h3>{}",
+            escape(&candidate.candidate.name)
+        );
+        if let Some(party) = &candidate.candidate.party {
+            let _ = write!(body, "<span class=\"party\">{}</span>", escape(party));
+        }
+        body.push_str("</h3>\n");
+        for (question, response) in sheet.questions.iter().zip(&candidate.responses) {
+            let _ = writeln!(
+                body,
+                "<div class=\"question\">{}</div>",
+                escape(&question.prompt)
+            );
+            match response {
+                Some(response) => {
+                    let _ = writeln!(body, "<p class=\"response\">{}</p>", escape(response));
+                }
+                None => body.push_str("<p class=\"no-response\">Did not respond</p>\n"),
+            }
+        }
+        body.push_str("</div>\n");
+    }
+
+    let _ = write!(body, "<footer>{}", escape(&sheet.organization.name));
+    if let Some(website_url) = &sheet.organization.website_url {
+        let _ = write!(body, " &middot; {}", escape(website_url));
+    }
+    body.push_str(" &middot; Candidate responses are printed as submitted</footer>\n");
+    page(&title, &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape(r#"<script>alert("Tom & Jerry's")</script>"#),
+            "&lt;script&gt;alert(&quot;Tom &amp; Jerry&#39;s&quot;)&lt;/script&gt;"
+        );
+        assert_eq!(escape("Tim Walz"), "Tim Walz");
+    }
+}
@@ -0,0 +1,109 @@
+//! Printable sample ballots and candidate guides, served at `/print/:token` as HTML and at
+//! `/print/:token/pdf` as a PDF. Tokens are signed print links from `auth::create_print_token`,
+//! so a printout can be shared by email or text without the recipient signing in.
+
+pub mod html;
+pub mod pdf;
+
+use auth::{jwt, PrintKind, PrintTokenClaims};
+use axum::{
+    extract::Path,
+    http::{header, StatusCode},
+    response::{Html, IntoResponse, Response},
+};
+use db::{organization_logo_url, CandidateGuideSheet, SampleBallot};
+use tracing::error;
+
+#[derive(thiserror::Error, Debug)]
+pub enum PrintError {
+    #[error("This link is invalid or has expired")]
+    InvalidLink,
+
+    #[error(transparent)]
+    DbError(#[from] sqlx::Error),
+
+    #[error("Failed to render PDF: {0}")]
+    PdfError(#[source] std::io::Error),
+}
+
+impl IntoResponse for PrintError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            PrintError::InvalidLink | PrintError::DbError(sqlx::Error::RowNotFound) => {
+                StatusCode::NOT_FOUND
+            }
+            _ => {
+                error!("{}", self);
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+        let message = match status {
+            StatusCode::NOT_FOUND => PrintError::InvalidLink.to_string(),
+            _ => "Something went wrong preparing this printout".to_string(),
+        };
+        (status, message).into_response()
+    }
+}
+
+/// Logos are only shown from our own uploads bucket, so a printout never points at an
+/// arbitrary address
+fn is_uploaded_asset(url: &str) -> bool {
+    std::env::var("S3_BUCKET_BASE_URL").is_ok_and(|base| {
+        base.starts_with("https://") && url.starts_with(&format!("{}/", base.trim_end_matches('/')))
+    })
+}
+
+/// The printout's HTML and a file name for its PDF. For PDFs images are inlined, since
+/// wkhtmltopdf isn't trusted to load anything itself.
+async fn render(token: &str, inline_images: bool) -> Result<(String, String), PrintError> {
+    let PrintTokenClaims {
+        sub, kind, race_id, ..
+    } = jwt::validate_print_token(token)
+        .map_err(|_| PrintError::InvalidLink)?
+        .claims;
+    let db_pool = &db::pool().await.connection;
+
+    match kind {
+        PrintKind::SampleBallot => {
+            let ballot = SampleBallot::for_voting_guide(db_pool, sub).await?;
+            let file_name = format!("{}-sample-ballot.pdf", ballot.election.slug);
+            Ok((html::sample_ballot(&ballot), file_name))
+        }
+        PrintKind::CandidateGuide => {
+            let race_id = race_id.ok_or(PrintError::InvalidLink)?;
+            let sheet = CandidateGuideSheet::for_race(db_pool, sub, race_id).await?;
+            let logo = match organization_logo_url(&sheet.organization)
+                .filter(|url| is_uploaded_asset(url))
+            {
+                Some(url) if inline_images => pdf::inline_image(&url).await,
+                logo => logo,
+            };
+            let file_name = format!("{}-{}.pdf", sheet.organization.slug, sheet.race.slug);
+            Ok((
+                html::candidate_guide_sheet(&sheet, logo.as_deref()),
+                file_name,
+            ))
+        }
+    }
+}
+
+pub async fn print_html(Path(token): Path<String>) -> Result<Html<String>, PrintError> {
+    let (html, _) = render(&token, false).await?;
+    Ok(Html(html))
+}
+
+pub async fn print_pdf(Path(token): Path<String>) -> Result<Response, PrintError> {
+    let (html, file_name) = render(&token, true).await?;
+    let pdf = pdf::render(&html).await.map_err(PrintError::PdfError)?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("inline; filename=\"{}\"", file_name),
+            ),
+        ],
+        pdf,
+    )
+        .into_response())
+}
@@ -0,0 +1,99 @@
+//! PDFs are rendered from the print view's HTML by wkhtmltopdf, run as a subprocess. The
+//! binary is `wkhtmltopdf` on the `PATH` unless `WKHTMLTOPDF` points elsewhere. The page is
+//! rendered without file access or JavaScript, and images must be inlined by the caller.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::{process::Stdio, time::Duration};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+
+/// Renders at once, each wkhtmltopdf process holds a full browser engine in memory
+static RENDERS: Semaphore = Semaphore::const_new(4);
+
+const RENDER_TIMEOUT: Duration = Duration::from_secs(30);
+
+const MAX_IMAGE_BYTES: u64 = 2 * 1024 * 1024;
+
+/// Fetches an image as a data URI to inline in a page, or `None` if it isn't a small bitmap
+pub async fn inline_image(url: &str) -> Option<String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .ok()?;
+    let response = client.get(url).send().await.ok()?.error_for_status().ok()?;
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)?
+        .to_str()
+        .ok()?
+        .to_string();
+    if !["image/png", "image/jpeg", "image/gif", "image/webp"].contains(&content_type.as_str())
+        || response.content_length().unwrap_or(0) > MAX_IMAGE_BYTES
+    {
+        return None;
+    }
+    let bytes = response.bytes().await.ok()?;
+    if bytes.len() as u64 > MAX_IMAGE_BYTES {
+        return None;
+    }
+    Some(format!(
+        "data:{};base64,{}",
+        content_type,
+        STANDARD.encode(&bytes)
+    ))
+}
+
+pub async fn render(html: &str) -> Result<Vec<u8>, std::io::Error> {
+    let _permit = RENDERS.acquire().await.map_err(std::io::Error::other)?;
+    let program = std::env::var("WKHTMLTOPDF").unwrap_or_else(|_| "wkhtmltopdf".to_string());
+    let mut child = Command::new(program)
+        .args([
+            "--quiet",
+            "--disable-local-file-access",
+            "--disable-javascript",
+            "--print-media-type",
+            "--encoding",
+            "utf-8",
+            "--page-size",
+            "Letter",
+            "-",
+            "-",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    // Write from a separate task so a large page can't deadlock against a full stdout pipe
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let html = html.to_string();
+    let writer = tokio::spawn(async move {
+        stdin.write_all(html.as_bytes()).await?;
+        stdin.shutdown().await
+    });
+
+    // Dropping the child on timeout kills it
+    let output = tokio::time::timeout(RENDER_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                format!("wkhtmltopdf took longer than {:?}", RENDER_TIMEOUT),
+            )
+        })??;
+    let written = writer.await.map_err(std::io::Error::other)?;
+
+    // A renderer that fails early closes stdin, its own error explains more than the broken pipe
+    if !output.status.success() {
+        return Err(std::io::Error::other(format!(
+            "wkhtmltopdf exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    written?;
+    Ok(output.stdout)
+}